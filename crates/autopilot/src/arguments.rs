@@ -1,5 +1,8 @@
 use {
-    crate::{domain::fee::FeeFactor, infra},
+    crate::{
+        domain::fee::{FeeFactor, SurplusTier},
+        infra,
+    },
     anyhow::{Context, anyhow, ensure},
     clap::ValueEnum,
     primitive_types::{H160, U256},
@@ -14,6 +17,7 @@ use {
         fmt::{Display, Formatter},
         net::SocketAddr,
         num::NonZeroUsize,
+        path::PathBuf,
         str::FromStr,
         time::Duration,
     },
@@ -197,6 +201,16 @@ pub struct Arguments {
     #[clap(long, env, default_value = "0.01")]
     pub fee_policy_max_partner_fee: FeeFactor,
 
+    /// Path to a TOML file with rules selecting the fee policies of orders
+    /// based on their tokens, owner, size and app data. Orders not matching
+    /// any rule get the `fee_policies`.
+    #[clap(long, env)]
+    pub fee_policy_rules: Option<PathBuf>,
+
+    /// How often the fee policy rules file gets reloaded.
+    #[clap(long, env, default_value = "1m", value_parser = humantime::parse_duration)]
+    pub fee_policy_rules_reload_interval: Duration,
+
     /// Arguments for uploading information to S3.
     #[clap(flatten)]
     pub s3: infra::persistence::cli::S3,
@@ -237,6 +251,10 @@ pub struct Arguments {
     /// to settle their winning orders at the same time.
     pub max_winners_per_auction: usize,
 
+    /// The strategy used for selecting the winners of an auction.
+    #[clap(long, env, default_value = "greedy", value_enum)]
+    pub winner_selection: WinnerSelection,
+
     #[clap(long, env, default_value = "3")]
     /// The maximum allowed number of solutions to be proposed from a single
     /// solver, per auction.
@@ -362,6 +380,8 @@ impl std::fmt::Display for Arguments {
             solve_deadline,
             fee_policies,
             fee_policy_max_partner_fee,
+            fee_policy_rules,
+            fee_policy_rules_reload_interval,
             order_events_cleanup_interval,
             order_events_cleanup_threshold,
             db_url,
@@ -373,6 +393,7 @@ impl std::fmt::Display for Arguments {
             max_run_loop_delay,
             run_loop_native_price_timeout,
             max_winners_per_auction,
+            winner_selection,
             archive_node_url,
             max_solutions_per_solver,
             db_based_solver_participation_guard,
@@ -423,6 +444,16 @@ impl std::fmt::Display for Arguments {
             "fee_policy_max_partner_fee: {:?}",
             fee_policy_max_partner_fee
         )?;
+        display_option(
+            f,
+            "fee_policy_rules",
+            &fee_policy_rules.as_ref().map(|path| path.display()),
+        )?;
+        writeln!(
+            f,
+            "fee_policy_rules_reload_interval: {:?}",
+            fee_policy_rules_reload_interval
+        )?;
         writeln!(
             f,
             "order_events_cleanup_interval: {:?}",
@@ -453,6 +484,7 @@ impl std::fmt::Display for Arguments {
             run_loop_native_price_timeout
         )?;
         writeln!(f, "max_winners_per_auction: {:?}", max_winners_per_auction)?;
+        writeln!(f, "winner_selection: {:?}", winner_selection)?;
         writeln!(f, "archive_node_url: {:?}", archive_node_url)?;
         writeln!(
             f,
@@ -468,6 +500,18 @@ impl std::fmt::Display for Arguments {
     }
 }
 
+/// Strategy for selecting the winners of an auction.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum WinnerSelection {
+    /// Select winners one by one in order of their score. A solution wins if
+    /// it does not swap any token that was swapped by a better solution. In
+    /// shadow mode, only the tokens of better winning solutions count.
+    Greedy,
+    /// Select the set of solutions without overlapping tokens that has the
+    /// highest total score.
+    MaxScore,
+}
+
 /// External solver driver configuration
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Solver {
@@ -565,6 +609,10 @@ impl FromStr for Solver {
 ///   price_improvement:0.5:0.06:limit
 ///
 /// - Volume based fee for any order class: volume:0.1:any
+///
+/// - Tiered surplus taking 50% of the first 0.01 ETH of surplus and 10% of the
+///   rest with a cap for any order class:
+///   tieredSurplus:0=0.5|10000000000000000=0.1:0.01:any
 #[derive(Debug, Clone)]
 pub struct FeePolicy {
     pub fee_policy_kind: FeePolicyKind,
@@ -587,6 +635,13 @@ pub enum FeePolicyKind {
     },
     /// How much of the order's volume should be taken as a protocol fee.
     Volume { factor: FeeFactor },
+    /// How much of the order's surplus should be taken as a protocol fee
+    /// depending on the size of the surplus. Tier thresholds are denominated
+    /// in the native token.
+    TieredSurplus {
+        tiers: Vec<SurplusTier>,
+        max_volume_factor: FeeFactor,
+    },
 }
 
 #[derive(clap::Parser, clap::ValueEnum, Clone, Debug)]
@@ -650,6 +705,26 @@ impl FromStr for FeePolicy {
                     factor: factor.try_into()?,
                 })
             }
+            "tieredSurplus" => {
+                let tiers = parts
+                    .next()
+                    .context("missing surplus tiers")?
+                    .split('|')
+                    .map(SurplusTier::from_str)
+                    .collect::<Result<Vec<_>, _>>()?;
+                SurplusTier::validate(&tiers)?;
+                let max_volume_factor = parts
+                    .next()
+                    .context("missing tiered surplus max volume factor")?
+                    .parse::<f64>()
+                    .map_err(|e| {
+                        anyhow::anyhow!("invalid tiered surplus max volume factor: {}", e)
+                    })?;
+                Ok(FeePolicyKind::TieredSurplus {
+                    tiers,
+                    max_volume_factor: max_volume_factor.try_into()?,
+                })
+            }
             _ => Err(anyhow::anyhow!("invalid fee policy kind: {}", kind)),
         }?;
         let fee_policy_order_class = FeePolicyOrderClass::from_str(
@@ -738,6 +813,46 @@ mod test {
         }
     }
 
+    #[test]
+    fn parse_tiered_surplus_fee_policy() {
+        let policy =
+            FeePolicy::from_str("tieredSurplus:0=0.5|10000000000000000=0.1:0.01:any").unwrap();
+        let FeePolicyKind::TieredSurplus {
+            tiers,
+            max_volume_factor,
+        } = policy.fee_policy_kind
+        else {
+            panic!("unexpected fee policy kind");
+        };
+        assert_eq!(
+            tiers,
+            vec![
+                SurplusTier {
+                    threshold: 0.into(),
+                    factor: FeeFactor::try_from(0.5).unwrap(),
+                },
+                SurplusTier {
+                    threshold: U256::exp10(16),
+                    factor: FeeFactor::try_from(0.1).unwrap(),
+                },
+            ]
+        );
+        assert_eq!(max_volume_factor, FeeFactor::try_from(0.01).unwrap());
+        assert!(matches!(
+            policy.fee_policy_order_class,
+            FeePolicyOrderClass::Any
+        ));
+
+        for policy in [
+            "tieredSurplus:1=0.5:0.01:any",
+            "tieredSurplus:0=0.5|0=0.1:0.01:any",
+            "tieredSurplus:0=0.5|10=1.0:0.01:any",
+            "tieredSurplus:0:0.01:any",
+        ] {
+            assert!(FeePolicy::from_str(policy).is_err(), "{policy}");
+        }
+    }
+
     #[test]
     fn parse_driver_submission_account_address() {
         let argument = "name1|http://localhost:8080|0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
@@ -0,0 +1,4 @@
+#[tokio::main]
+async fn main() {
+    autopilot::replay(std::env::args()).await;
+}
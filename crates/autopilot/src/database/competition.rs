@@ -6,6 +6,7 @@ use {
         auction_participants::Participant,
         auction_prices::AuctionPrice,
         byte_array::ByteArray,
+        reference_scores,
         settlement_scores::Score,
         surplus_capturing_jit_order_owners,
     },
@@ -13,15 +14,18 @@ use {
     model::solver_competition::SolverCompetitionDB,
     number::conversions::u256_to_big_decimal,
     primitive_types::{H160, U256},
-    std::collections::{BTreeMap, HashSet},
+    std::collections::{BTreeMap, HashMap, HashSet},
 };
 
 #[derive(Clone, Default, Debug)]
 pub struct Competition {
     pub auction_id: AuctionId,
+    /// The best winning solver of the auction.
     pub winner: H160,
     pub winning_score: U256,
     pub reference_score: U256,
+    /// Reference scores of all winning solvers of the auction.
+    pub reference_scores: HashMap<H160, U256>,
     /// Addresses to which the CIP20 participation rewards will be payed out.
     /// Usually the same as the solver addresses.
     pub participants: HashSet<H160>,
@@ -73,6 +77,22 @@ impl super::Postgres {
         .await
         .context("settlement_scores::insert")?;
 
+        database::reference_scores::insert(
+            &mut ex,
+            competition
+                .reference_scores
+                .iter()
+                .map(|(solver, reference_score)| reference_scores::Score {
+                    auction_id: competition.auction_id,
+                    solver: ByteArray(solver.0),
+                    reference_score: u256_to_big_decimal(reference_score),
+                })
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+        .await
+        .context("reference_scores::insert")?;
+
         database::auction_participants::insert(
             &mut ex,
             competition
@@ -1125,6 +1125,8 @@ mod test {
             sell_amount,
             buy_amount,
             fee_amount,
+            suggested_slippage_bps: None,
+            simulation_trace: None,
         };
         let cloned_quote = quote.clone();
         order_quoter
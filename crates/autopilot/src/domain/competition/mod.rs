@@ -7,10 +7,12 @@ use {
 
 mod participant;
 mod participation_guard;
+pub mod winner_selection;
 
 pub use {
     participant::{Participant, Ranked, Unranked},
     participation_guard::SolverParticipationGuard,
+    winner_selection::WinnerSelection,
 };
 
 type SolutionId = u64;
@@ -0,0 +1,290 @@
+//! Strategies for selecting the winners of a solver competition.
+
+use {crate::domain::eth, primitive_types::U256, std::collections::HashSet};
+
+/// A solution competing for the right to be executed, reduced to the
+/// information that is relevant for selecting the winners of an auction.
+#[derive(Debug, Clone)]
+pub struct Bid {
+    score: eth::Ether,
+    tokens: HashSet<eth::TokenAddress>,
+}
+
+impl Bid {
+    pub fn new(score: eth::Ether, tokens: impl IntoIterator<Item = eth::TokenAddress>) -> Self {
+        Self {
+            score,
+            tokens: tokens.into_iter().collect(),
+        }
+    }
+
+    pub fn score(&self) -> eth::Ether {
+        self.score
+    }
+
+    /// The tokens swapped by the solution.
+    pub fn tokens(&self) -> &HashSet<eth::TokenAddress> {
+        &self.tokens
+    }
+}
+
+/// A strategy for selecting the winners of an auction.
+pub trait WinnerSelection: Send + Sync {
+    /// Selects the winners among the given bids, which are expected to be
+    /// sorted by score (best to worst). Returns whether each bid is a winner,
+    /// in the same order as the input.
+    fn select_winners(&self, bids: &[Bid]) -> Vec<bool>;
+}
+
+/// Selects winners one by one, starting from the best bid, until
+/// `max_winners` are selected. A bid is a winner if it swaps tokens that are
+/// not yet swapped by any previously processed bid (or only by any previous
+/// winner, see [`Greedy::excluding_winner_tokens`]).
+#[derive(Debug, Clone, Copy)]
+pub struct Greedy {
+    max_winners: usize,
+    only_winner_tokens: bool,
+}
+
+impl Greedy {
+    pub fn new(max_winners: usize) -> Self {
+        Self {
+            max_winners,
+            only_winner_tokens: false,
+        }
+    }
+
+    /// Creates a greedy selection where bids only lose if they swap tokens
+    /// that are swapped by a better winning bid. Tokens of better bids that
+    /// did not win themselves don't matter.
+    pub fn excluding_winner_tokens(max_winners: usize) -> Self {
+        Self {
+            max_winners,
+            only_winner_tokens: true,
+        }
+    }
+}
+
+impl WinnerSelection for Greedy {
+    fn select_winners(&self, bids: &[Bid]) -> Vec<bool> {
+        let mut already_swapped_tokens = HashSet::new();
+        let mut winners = 0;
+        bids.iter()
+            .map(|bid| {
+                let is_winner =
+                    bid.tokens.is_disjoint(&already_swapped_tokens) && winners < self.max_winners;
+
+                if is_winner || !self.only_winner_tokens {
+                    already_swapped_tokens.extend(bid.tokens.iter().copied());
+                }
+                winners += usize::from(is_winner);
+
+                is_winner
+            })
+            .collect()
+    }
+}
+
+/// Selects the set of at most `max_winners` bids with pairwise disjoint
+/// tokens that has the highest total score.
+///
+/// This is a weighted set packing problem, which is NP-hard in general. The
+/// number of bids per auction is small, though, and the search is pruned with
+/// an upper bound on the score that is still achievable, which keeps it fast
+/// in practice. In case the search still exceeds [`MAX_SEARCH_STEPS`], the
+/// best selection found so far is returned.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxScore {
+    max_winners: usize,
+}
+
+/// The maximum number of search steps before [`MaxScore`] gives up on finding
+/// the optimal selection.
+const MAX_SEARCH_STEPS: usize = 100_000;
+
+impl MaxScore {
+    pub fn new(max_winners: usize) -> Self {
+        Self { max_winners }
+    }
+}
+
+impl WinnerSelection for MaxScore {
+    fn select_winners(&self, bids: &[Bid]) -> Vec<bool> {
+        let mut search = Search {
+            bids,
+            max_winners: self.max_winners,
+            selected: Vec::new(),
+            used_tokens: HashSet::new(),
+            score: U256::zero(),
+            best: Vec::new(),
+            best_score: U256::zero(),
+            steps: 0,
+        };
+        search.run(0);
+
+        let mut winners = vec![false; bids.len()];
+        for index in search.best {
+            winners[index] = true;
+        }
+        winners
+    }
+}
+
+/// Depth-first branch and bound search for [`MaxScore`].
+///
+/// Bids are visited in order of their score and including a bid is explored
+/// before excluding it, so on ties the selection with the better individual
+/// scores is preferred.
+struct Search<'a> {
+    bids: &'a [Bid],
+    max_winners: usize,
+    selected: Vec<usize>,
+    used_tokens: HashSet<eth::TokenAddress>,
+    score: U256,
+    best: Vec<usize>,
+    best_score: U256,
+    steps: usize,
+}
+
+impl Search<'_> {
+    fn run(&mut self, next: usize) {
+        self.steps += 1;
+        if self.score > self.best_score {
+            self.best_score = self.score;
+            self.best = self.selected.clone();
+        }
+        if next == self.bids.len()
+            || self.selected.len() >= self.max_winners
+            || self.steps > MAX_SEARCH_STEPS
+        {
+            return;
+        }
+
+        // Since bids are sorted by score, the best we can still do is to add
+        // the next best bids until we reach the maximum number of winners.
+        let bound = self.bids[next..]
+            .iter()
+            .take(self.max_winners - self.selected.len())
+            .fold(self.score, |bound, bid| bound.saturating_add(bid.score.0));
+        if bound <= self.best_score {
+            return;
+        }
+
+        let bid = &self.bids[next];
+        if bid.tokens.is_disjoint(&self.used_tokens) {
+            self.selected.push(next);
+            self.used_tokens.extend(bid.tokens.iter().copied());
+            self.score += bid.score.0;
+
+            self.run(next + 1);
+
+            self.score -= bid.score.0;
+            // The bid's tokens were disjoint from the used tokens, so removing
+            // them restores the previous state.
+            for token in &bid.tokens {
+                self.used_tokens.remove(token);
+            }
+            self.selected.pop();
+        }
+        self.run(next + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, primitive_types::H160};
+
+    fn token(i: u8) -> eth::TokenAddress {
+        H160([i; 20]).into()
+    }
+
+    fn bid(score: u64, tokens: &[u8]) -> Bid {
+        Bid::new(eth::Ether(score.into()), tokens.iter().copied().map(token))
+    }
+
+    #[test]
+    fn greedy_selects_best_disjoint_bids() {
+        let bids = [
+            bid(100, &[1, 2]),
+            bid(80, &[1, 3]),
+            bid(60, &[3, 4]),
+            bid(40, &[5, 6]),
+        ];
+
+        // The second bid overlaps with the first and blocks token 3 for the
+        // third bid even though it did not win.
+        assert_eq!(
+            Greedy::new(10).select_winners(&bids),
+            [true, false, false, true]
+        );
+        assert_eq!(
+            Greedy::new(1).select_winners(&bids),
+            [true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn greedy_excluding_winner_tokens_ignores_losing_bids() {
+        let bids = [
+            bid(100, &[1, 2]),
+            bid(80, &[1, 3]),
+            bid(60, &[3, 4]),
+            bid(40, &[4, 5]),
+        ];
+
+        // The second bid lost, so its tokens don't block the third bid.
+        assert_eq!(
+            Greedy::excluding_winner_tokens(10).select_winners(&bids),
+            [true, false, true, false]
+        );
+        assert_eq!(
+            Greedy::excluding_winner_tokens(1).select_winners(&bids),
+            [true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn max_score_prefers_multiple_smaller_bids() {
+        let bids = [bid(100, &[1, 2]), bid(60, &[1, 3]), bid(50, &[2, 4])];
+
+        assert_eq!(Greedy::new(10).select_winners(&bids), [true, false, false]);
+        assert_eq!(MaxScore::new(10).select_winners(&bids), [false, true, true]);
+        // With a single winner, the best bid wins.
+        assert_eq!(MaxScore::new(1).select_winners(&bids), [true, false, false]);
+    }
+
+    #[test]
+    fn max_score_prefers_better_bids_on_ties() {
+        let bids = [bid(100, &[1, 2]), bid(50, &[1]), bid(50, &[2])];
+
+        assert_eq!(
+            MaxScore::new(10).select_winners(&bids),
+            [true, false, false]
+        );
+    }
+
+    #[test]
+    fn max_score_respects_max_winners() {
+        let bids = [
+            bid(100, &[1, 2, 3]),
+            bid(40, &[1]),
+            bid(40, &[2]),
+            bid(40, &[3]),
+        ];
+
+        assert_eq!(
+            MaxScore::new(3).select_winners(&bids),
+            [false, true, true, true]
+        );
+        assert_eq!(
+            MaxScore::new(2).select_winners(&bids),
+            [true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn no_bids() {
+        assert!(Greedy::new(1).select_winners(&[]).is_empty());
+        assert!(MaxScore::new(1).select_winners(&[]).is_empty());
+    }
+}
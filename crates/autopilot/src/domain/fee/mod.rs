@@ -5,6 +5,7 @@
 //! parameters.
 
 mod policy;
+mod rules;
 
 use {
     crate::{
@@ -16,10 +17,19 @@ use {
     derive_more::Into,
     primitive_types::{H160, U256},
     prometheus::core::Number,
-    std::{collections::HashSet, str::FromStr},
+    serde::Deserialize,
+    std::{
+        collections::{BTreeMap, HashSet},
+        path::PathBuf,
+        str::FromStr,
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+    tracing::Instrument,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum OrderClass {
     Market,
     Limit,
@@ -55,6 +65,8 @@ pub type ProtocolFeeExemptAddresses = HashSet<H160>;
 
 pub struct ProtocolFees {
     fee_policies: Vec<ProtocolFee>,
+    /// Rules taking precedence over `fee_policies` for the orders they match.
+    rules: Arc<RwLock<Arc<rules::Rules>>>,
     max_partner_fee: FeeFactor,
 }
 
@@ -69,10 +81,45 @@ impl ProtocolFees {
                 .cloned()
                 .map(ProtocolFee::from)
                 .collect(),
+            rules: Default::default(),
             max_partner_fee: fee_policy_max_partner_fee,
         }
     }
 
+    /// Loads the fee policy rules from the given file and spawns a background
+    /// task reloading them periodically. If a reload fails the previous rules
+    /// stay in place.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the initial rules can't be loaded.
+    pub async fn with_rules(mut self, path: PathBuf, reload_interval: Duration) -> Self {
+        let rules = rules::Rules::load(&path)
+            .await
+            .unwrap_or_else(|err| panic!("failed to load fee policy rules: {err:?}"));
+        self.rules = Arc::new(RwLock::new(Arc::new(rules)));
+
+        let current = Arc::downgrade(&self.rules);
+        tokio::task::spawn(
+            async move {
+                let mut interval = tokio::time::interval(reload_interval);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    let Some(current) = current.upgrade() else {
+                        break;
+                    };
+                    match rules::Rules::load(&path).await {
+                        Ok(rules) => *current.write().unwrap() = Arc::new(rules),
+                        Err(err) => tracing::warn!(?err, "failed to reload fee policy rules"),
+                    }
+                }
+            }
+            .instrument(tracing::info_span!("fee_policy_rules")),
+        );
+        self
+    }
+
     /// Converts an order from the boundary layer to the domain layer, applying
     /// protocol fees if necessary.
     pub fn apply(
@@ -80,6 +127,7 @@ impl ProtocolFees {
         order: boundary::Order,
         quote: Option<domain::Quote>,
         surplus_capturing_jit_order_owners: &[eth::Address],
+        prices: &BTreeMap<H160, U256>,
     ) -> domain::Order {
         let partner_fee = order
             .metadata
@@ -128,7 +176,7 @@ impl ProtocolFees {
             fee: quote.fee.into(),
         };
 
-        self.apply_policies(order, quote, order_, quote_, partner_fee)
+        self.apply_policies(order, quote, order_, quote_, partner_fee, prices)
     }
 
     fn apply_policies(
@@ -138,14 +186,16 @@ impl ProtocolFees {
         order_: boundary::Amounts,
         quote_: boundary::Amounts,
         partner_fees: Vec<Policy>,
+        prices: &BTreeMap<H160, U256>,
     ) -> domain::Order {
-        let protocol_fees = self
-            .fee_policies
+        let rules = self.rules.read().unwrap().clone();
+        let fee_policies = rules.policies(&order, prices).unwrap_or(&self.fee_policies);
+        let protocol_fees = fee_policies
             .iter()
             .filter_map(|fee_policy| {
                 Self::protocol_fee_into_policy(&order, &order_, &quote_, fee_policy)
             })
-            .flat_map(|policy| Self::variant_fee_apply(&order, &quote, policy))
+            .flat_map(|policy| Self::variant_fee_apply(&order, &quote, policy, prices))
             .chain(partner_fees)
             .collect::<Vec<_>>();
         boundary::order::to_domain(order, protocol_fees, Some(quote))
@@ -155,11 +205,13 @@ impl ProtocolFees {
         order: &boundary::Order,
         quote: &domain::Quote,
         policy: &policy::Policy,
+        prices: &BTreeMap<H160, U256>,
     ) -> Option<Policy> {
         match policy {
             policy::Policy::Surplus(variant) => variant.apply(order),
             policy::Policy::PriceImprovement(variant) => variant.apply(order, quote),
             policy::Policy::Volume(variant) => variant.apply(order),
+            policy::Policy::TieredSurplus(variant) => variant.apply(order, prices),
         }
     }
 
@@ -180,7 +232,7 @@ impl ProtocolFees {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Policy {
     /// If the order receives more than limit price, take the protocol fee as a
     /// percentage of the difference. The fee is taken in `sell` token for
@@ -213,9 +265,65 @@ pub enum Policy {
         /// fee.
         factor: FeeFactor,
     },
+    /// Like `Surplus` but the factor depends on the size of the surplus. Each
+    /// tier's factor only applies to the part of the surplus within that tier.
+    ///
+    /// E.g. tiers of 50% from 0 and 10% from 100USDC charge 50USDC for the
+    /// first 100USDC of surplus and 10% of any surplus beyond that.
+    TieredSurplus {
+        /// Tiers denominated in the surplus token, sorted by threshold and
+        /// starting at 0. Thresholds refer to fully executed orders and get
+        /// scaled down for partial fills.
+        tiers: Vec<SurplusTier>,
+        /// Cap protocol fee with a percentage of the order's volume.
+        max_volume_factor: FeeFactor,
+    },
+}
+
+/// A tier of a tiered surplus fee.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurplusTier {
+    /// The surplus from which on the tier's factor applies.
+    pub threshold: U256,
+    /// Factor of the surplus within the tier the protocol charges as a fee.
+    pub factor: FeeFactor,
+}
+
+impl SurplusTier {
+    /// Checks that the tiers start at 0 and are sorted by threshold.
+    pub fn validate(tiers: &[Self]) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            tiers.first().is_some_and(|tier| tier.threshold.is_zero()),
+            "first surplus tier must start at 0"
+        );
+        anyhow::ensure!(
+            tiers
+                .windows(2)
+                .all(|pair| pair[0].threshold < pair[1].threshold),
+            "surplus tier thresholds must be strictly increasing"
+        );
+        Ok(())
+    }
+}
+
+/// Parses a tier of the form `<threshold>=<factor>`.
+impl FromStr for SurplusTier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (threshold, factor) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("surplus tier must be <threshold>=<factor>"))?;
+        Ok(Self {
+            threshold: U256::from_dec_str(threshold)
+                .map_err(|e| anyhow::anyhow!("invalid surplus tier threshold: {:?}", e))?,
+            factor: factor.parse()?,
+        })
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Into)]
+#[derive(Debug, Clone, Copy, PartialEq, Into, Deserialize)]
+#[serde(try_from = "f64")]
 pub struct FeeFactor(f64);
 
 impl FeeFactor {
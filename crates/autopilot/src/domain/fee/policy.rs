@@ -1,16 +1,21 @@
-use crate::{
-    arguments,
-    boundary,
-    domain::{
-        self,
-        fee::{FeeFactor, Quote},
+use {
+    crate::{
+        arguments,
+        boundary,
+        domain::{
+            self,
+            fee::{FeeFactor, Quote, SurplusTier},
+        },
     },
+    primitive_types::{H160, U256},
+    std::collections::BTreeMap,
 };
 
 pub enum Policy {
     Surplus(Surplus),
     PriceImprovement(PriceImprovement),
     Volume(Volume),
+    TieredSurplus(TieredSurplus),
 }
 
 pub struct Surplus {
@@ -27,6 +32,12 @@ pub struct Volume {
     factor: FeeFactor,
 }
 
+pub struct TieredSurplus {
+    /// Tiers denominated in the native token.
+    tiers: Vec<SurplusTier>,
+    max_volume_factor: FeeFactor,
+}
+
 impl From<arguments::FeePolicyKind> for Policy {
     fn from(policy_arg: arguments::FeePolicyKind) -> Self {
         match policy_arg {
@@ -45,6 +56,13 @@ impl From<arguments::FeePolicyKind> for Policy {
                 max_volume_factor,
             }),
             arguments::FeePolicyKind::Volume { factor } => Policy::Volume(Volume { factor }),
+            arguments::FeePolicyKind::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => Policy::TieredSurplus(TieredSurplus {
+                tiers,
+                max_volume_factor,
+            }),
         }
     }
 }
@@ -94,3 +112,50 @@ impl Volume {
         }
     }
 }
+
+impl TieredSurplus {
+    /// Converts the tier thresholds into the surplus token of the order so
+    /// that the driver and the settlement observer compute the same fee
+    /// regardless of later native price changes.
+    pub fn apply(
+        &self,
+        order: &boundary::Order,
+        prices: &BTreeMap<H160, U256>,
+    ) -> Option<domain::fee::Policy> {
+        match order.metadata.class {
+            boundary::OrderClass::Market => None,
+            boundary::OrderClass::Liquidity => None,
+            boundary::OrderClass::Limit => {
+                let surplus_token = match order.data.kind {
+                    boundary::OrderKind::Sell => order.data.buy_token,
+                    boundary::OrderKind::Buy => order.data.sell_token,
+                };
+                let Some(price) = prices.get(&surplus_token).filter(|price| !price.is_zero())
+                else {
+                    tracing::debug!(
+                        uid = ?order.metadata.uid,
+                        ?surplus_token,
+                        "missing price for tiered surplus fee"
+                    );
+                    return None;
+                };
+                let tiers = self
+                    .tiers
+                    .iter()
+                    .map(|tier| SurplusTier {
+                        threshold: tier
+                            .threshold
+                            .checked_mul(U256::exp10(18))
+                            .map(|threshold| threshold / *price)
+                            .unwrap_or(U256::MAX),
+                        factor: tier.factor,
+                    })
+                    .collect();
+                Some(domain::fee::Policy::TieredSurplus {
+                    tiers,
+                    max_volume_factor: self.max_volume_factor,
+                })
+            }
+        }
+    }
+}
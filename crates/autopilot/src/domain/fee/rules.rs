@@ -0,0 +1,408 @@
+//! Rules selecting the protocol fee policies of an order based on its tokens,
+//! owner, size and app data.
+//!
+//! Rules are read from a TOML file of the form:
+//!
+//! ```toml
+//! [[rule]]
+//! name = "stablecoins"
+//! sell-tokens = ["0x6b175474e89094c44da98b954eedeac495271d0f"]
+//! buy-tokens = ["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"]
+//! policies = [{ kind = "volume", factor = 0.0001, order-class = "any" }]
+//!
+//! [[rule]]
+//! name = "partner"
+//! app-data = { appCode = ["Partner"] }
+//! min-volume = "1000000000000000000"
+//! policies = []
+//! ```
+//!
+//! The first rule matching an order determines its fee policies. Orders that
+//! don't match any rule get the default fee policies.
+
+use {
+    super::{FeeFactor, OrderClass, ProtocolFee, SurplusTier},
+    crate::{arguments, boundary},
+    anyhow::{Context, Result},
+    number::serialization::HexOrDecimalU256,
+    primitive_types::{H160, U256},
+    serde::Deserialize,
+    serde_with::serde_as,
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        path::Path,
+    },
+};
+
+#[derive(Default)]
+pub struct Rules(Vec<Rule>);
+
+impl Rules {
+    /// Reads the rules from a TOML file.
+    pub async fn load(path: &Path) -> Result<Self> {
+        let data = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read {path:?}"))?;
+        data.parse()
+    }
+
+    /// Returns the fee policies of the first rule matching the order.
+    pub fn policies<'a>(
+        &'a self,
+        order: &boundary::Order,
+        prices: &BTreeMap<H160, U256>,
+    ) -> Option<&'a [ProtocolFee]> {
+        let app_data = order
+            .metadata
+            .full_app_data
+            .as_deref()
+            .and_then(|app_data| serde_json::from_str(app_data).ok());
+        let rule = self
+            .0
+            .iter()
+            .find(|rule| rule.matches(order, prices, app_data.as_ref()))?;
+        tracing::trace!(uid = ?order.metadata.uid, rule = %rule.name, "matched fee policy rule");
+        Some(&rule.policies)
+    }
+}
+
+impl std::str::FromStr for Rules {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let config: Config = toml::de::from_str(s).context("invalid fee policy rules")?;
+        let rules = config
+            .rule
+            .into_iter()
+            .map(Rule::try_from)
+            .collect::<Result<_>>()?;
+        Ok(Self(rules))
+    }
+}
+
+struct Rule {
+    name: String,
+    sell_tokens: Option<HashSet<H160>>,
+    buy_tokens: Option<HashSet<H160>>,
+    owners: Option<HashSet<H160>>,
+    excluded_owners: HashSet<H160>,
+    min_volume: Option<U256>,
+    max_volume: Option<U256>,
+    app_data: HashMap<String, HashSet<String>>,
+    policies: Vec<ProtocolFee>,
+}
+
+impl Rule {
+    fn matches(
+        &self,
+        order: &boundary::Order,
+        prices: &BTreeMap<H160, U256>,
+        app_data: Option<&serde_json::Value>,
+    ) -> bool {
+        let owner = order.metadata.owner;
+        let within = |set: &Option<HashSet<H160>>, value: H160| {
+            set.as_ref().is_none_or(|set| set.contains(&value))
+        };
+        if !within(&self.sell_tokens, order.data.sell_token)
+            || !within(&self.buy_tokens, order.data.buy_token)
+            || !within(&self.owners, owner)
+            || self.excluded_owners.contains(&owner)
+        {
+            return false;
+        }
+
+        if self.min_volume.is_some() || self.max_volume.is_some() {
+            let Some(volume) = native_volume(order, prices) else {
+                return false;
+            };
+            if self.min_volume.is_some_and(|min| volume < min)
+                || self.max_volume.is_some_and(|max| volume > max)
+            {
+                return false;
+            }
+        }
+
+        self.app_data.iter().all(|(field, values)| {
+            app_data
+                .and_then(|app_data| field_value(app_data, field))
+                .is_some_and(|value| values.contains(value))
+        })
+    }
+}
+
+/// The sell amount of the order denominated in the native token.
+fn native_volume(order: &boundary::Order, prices: &BTreeMap<H160, U256>) -> Option<U256> {
+    let price = prices.get(&order.data.sell_token)?;
+    Some(
+        order
+            .data
+            .sell_amount
+            .checked_mul(*price)
+            .map(|volume| volume / U256::exp10(18))
+            .unwrap_or(U256::MAX),
+    )
+}
+
+/// Looks up a string field of the app data. Nested fields are separated by
+/// dots (e.g. `metadata.orderClass.orderClass`).
+fn field_value<'a>(app_data: &'a serde_json::Value, field: &str) -> Option<&'a String> {
+    match field
+        .split('.')
+        .try_fold(app_data, |value, key| value.get(key))?
+    {
+        serde_json::Value::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    #[serde(default)]
+    rule: Vec<RuleConfig>,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RuleConfig {
+    /// Identifies the rule in logs.
+    name: String,
+    /// The order's sell token has to be one of these tokens.
+    sell_tokens: Option<HashSet<H160>>,
+    /// The order's buy token has to be one of these tokens.
+    buy_tokens: Option<HashSet<H160>>,
+    /// The order's owner has to be one of these accounts.
+    owners: Option<HashSet<H160>>,
+    /// The order's owner must not be one of these accounts.
+    #[serde(default)]
+    excluded_owners: HashSet<H160>,
+    /// The minimum sell amount of the order denominated in the native token.
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    #[serde(default)]
+    min_volume: Option<U256>,
+    /// The maximum sell amount of the order denominated in the native token.
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    #[serde(default)]
+    max_volume: Option<U256>,
+    /// Maps app data fields to the values one of which the order's app data
+    /// has to contain.
+    #[serde(default)]
+    app_data: HashMap<String, HashSet<String>>,
+    /// The fee policies applied to matching orders. An empty list exempts
+    /// matching orders from protocol fees.
+    policies: Vec<PolicyConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PolicyConfig {
+    #[serde(flatten)]
+    kind: PolicyKindConfig,
+    order_class: OrderClass,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum PolicyKindConfig {
+    #[serde(rename_all = "kebab-case")]
+    Surplus {
+        factor: FeeFactor,
+        max_volume_factor: FeeFactor,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PriceImprovement {
+        factor: FeeFactor,
+        max_volume_factor: FeeFactor,
+    },
+    Volume {
+        factor: FeeFactor,
+    },
+    #[serde(rename_all = "kebab-case")]
+    TieredSurplus {
+        /// Tiers with thresholds denominated in the native token.
+        tiers: Vec<TierConfig>,
+        max_volume_factor: FeeFactor,
+    },
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct TierConfig {
+    #[serde_as(as = "HexOrDecimalU256")]
+    threshold: U256,
+    factor: FeeFactor,
+}
+
+impl TryFrom<RuleConfig> for Rule {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RuleConfig) -> Result<Self> {
+        let policies = value
+            .policies
+            .into_iter()
+            .map(ProtocolFee::try_from)
+            .collect::<Result<_>>()
+            .with_context(|| format!("invalid policies of rule {}", value.name))?;
+        Ok(Self {
+            name: value.name,
+            sell_tokens: value.sell_tokens,
+            buy_tokens: value.buy_tokens,
+            owners: value.owners,
+            excluded_owners: value.excluded_owners,
+            min_volume: value.min_volume,
+            max_volume: value.max_volume,
+            app_data: value.app_data,
+            policies,
+        })
+    }
+}
+
+impl TryFrom<PolicyConfig> for ProtocolFee {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PolicyConfig) -> Result<Self> {
+        let kind = match value.kind {
+            PolicyKindConfig::Surplus {
+                factor,
+                max_volume_factor,
+            } => arguments::FeePolicyKind::Surplus {
+                factor,
+                max_volume_factor,
+            },
+            PolicyKindConfig::PriceImprovement {
+                factor,
+                max_volume_factor,
+            } => arguments::FeePolicyKind::PriceImprovement {
+                factor,
+                max_volume_factor,
+            },
+            PolicyKindConfig::Volume { factor } => arguments::FeePolicyKind::Volume { factor },
+            PolicyKindConfig::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => {
+                let tiers = tiers
+                    .into_iter()
+                    .map(|tier| SurplusTier {
+                        threshold: tier.threshold,
+                        factor: tier.factor,
+                    })
+                    .collect::<Vec<_>>();
+                SurplusTier::validate(&tiers)?;
+                arguments::FeePolicyKind::TieredSurplus {
+                    tiers,
+                    max_volume_factor,
+                }
+            }
+        };
+        Ok(Self {
+            policy: kind.into(),
+            order_class: value.order_class,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        maplit::btreemap,
+        model::order::{OrderData, OrderMetadata},
+    };
+
+    const RULES: &str = r#"
+[[rule]]
+name = "stablecoins"
+sell-tokens = ["0x0101010101010101010101010101010101010101"]
+buy-tokens = ["0x0202020202020202020202020202020202020202"]
+policies = [{ kind = "volume", factor = 0.0001, order-class = "any" }]
+
+[[rule]]
+name = "partner"
+excluded-owners = ["0x0303030303030303030303030303030303030303"]
+min-volume = "1000000000000000000"
+app-data = { appCode = ["Partner"], "metadata.orderClass.orderClass" = ["market"] }
+policies = [
+    { kind = "surplus", factor = 0.5, max-volume-factor = 0.01, order-class = "limit" },
+    { kind = "priceImprovement", factor = 0.5, max-volume-factor = 0.01, order-class = "market" },
+    { kind = "tieredSurplus", tiers = [{ threshold = "0", factor = 0.5 }, { threshold = "10000000000000000", factor = 0.1 }], max-volume-factor = 0.01, order-class = "any" },
+]
+
+[[rule]]
+name = "exempt"
+owners = ["0x0404040404040404040404040404040404040404"]
+policies = []
+"#;
+
+    fn order(sell_token: u8, buy_token: u8, owner: u8, app_data: Option<&str>) -> boundary::Order {
+        boundary::Order {
+            data: OrderData {
+                sell_token: H160([sell_token; 20]),
+                buy_token: H160([buy_token; 20]),
+                sell_amount: U256::exp10(18),
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                owner: H160([owner; 20]),
+                full_app_data: app_data.map(ToString::to_string),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn matched(rules: &Rules, order: &boundary::Order) -> Option<usize> {
+        let prices = btreemap! {
+            H160([1; 20]) => U256::exp10(18),
+            H160([5; 20]) => U256::exp10(17),
+        };
+        rules
+            .policies(order, &prices)
+            .map(|policies| policies.len())
+    }
+
+    #[test]
+    fn selects_first_matching_rule() {
+        let rules: Rules = RULES.parse().unwrap();
+        let partner = r#"{"appCode":"Partner","metadata":{"orderClass":{"orderClass":"market"}}}"#;
+
+        assert_eq!(matched(&rules, &order(1, 2, 0, None)), Some(1));
+        assert_eq!(matched(&rules, &order(1, 6, 0, Some(partner))), Some(3));
+        // excluded owner
+        assert_eq!(matched(&rules, &order(1, 6, 3, Some(partner))), None);
+        // volume too small
+        assert_eq!(matched(&rules, &order(5, 6, 0, Some(partner))), None);
+        // missing price
+        assert_eq!(matched(&rules, &order(6, 6, 0, Some(partner))), None);
+        // app data field mismatch
+        assert_eq!(
+            matched(&rules, &order(1, 6, 0, Some(r#"{"appCode":"Partner"}"#))),
+            None
+        );
+        assert_eq!(matched(&rules, &order(1, 6, 4, None)), Some(0));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rules in [
+            r#"[[rule]]
+name = "unknown field"
+sell-token = ["0x0101010101010101010101010101010101010101"]
+policies = []"#,
+            r#"[[rule]]
+name = "invalid factor"
+policies = [{ kind = "volume", factor = 1.0, order-class = "any" }]"#,
+            r#"[[rule]]
+name = "unsorted tiers"
+policies = [{ kind = "tieredSurplus", tiers = [{ threshold = "0", factor = 0.5 }, { threshold = "0", factor = 0.1 }], max-volume-factor = 0.01, order-class = "any" }]"#,
+            r#"[[rule]]
+name = "missing order class"
+policies = [{ kind = "volume", factor = 0.1 }]"#,
+        ] {
+            assert!(rules.parse::<Rules>().is_err(), "{rules}");
+        }
+        assert!("".parse::<Rules>().unwrap().0.is_empty());
+    }
+}
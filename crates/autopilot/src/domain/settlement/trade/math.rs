@@ -179,7 +179,7 @@ impl Trade {
             // Do not need to calculate the last custom prices because in the last iteration
             // the prices are not used anymore to calculate the protocol fee
             fees.push(ExecutedProtocolFee {
-                policy: *policy,
+                policy: policy.clone(),
                 fee,
             });
             total += fee.amount;
@@ -284,6 +284,16 @@ impl Trade {
                 )
             }
             fee::Policy::Volume { factor } => self.volume_fee((*factor).into())?.amount,
+            fee::Policy::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => {
+                let surplus = self.surplus_over_limit_price()?;
+                std::cmp::min(
+                    self.tiered_surplus_fee(surplus, tiers)?.amount,
+                    self.volume_fee((*max_volume_factor).into())?.amount,
+                )
+            }
         };
         Ok(eth::Asset {
             token: self.surplus_token(),
@@ -370,6 +380,82 @@ impl Trade {
         })
     }
 
+    /// Protocol fee as a cut of surplus where the factor depends on the size
+    /// of the surplus, denominated in SURPLUS token
+    fn tiered_surplus_fee(
+        &self,
+        surplus: eth::Asset,
+        tiers: &[fee::SurplusTier],
+    ) -> Result<eth::Asset, Error> {
+        // Like for `surplus_fee` the protocol fee has to be derived from the
+        // surplus after fee. The fee of each tier is linear in the surplus within
+        // that tier, so every tier covers a range of surplus after fee:
+        //    [threshold - fee(threshold), next_threshold - fee(next_threshold))
+        // Within that range the fee is derived like for `surplus_fee`:
+        //    fee = fee(threshold) + (surplus_after_fee - (threshold -
+        // fee(threshold))) * factor / (1 - factor)
+        //
+        // Thresholds refer to the fully executed order so they get scaled down to
+        // the executed amount to treat partial fills consistently.
+        let full = match self.side {
+            order::Side::Buy => self.buy.amount.0,
+            order::Side::Sell => self.sell.amount.0,
+        };
+        let scale = |threshold: eth::U256| {
+            threshold
+                .checked_mul(self.executed.0)
+                .ok_or(error::Math::Overflow)?
+                .checked_div(full)
+                .ok_or(error::Math::DivisionByZero)
+        };
+        let tier_fee = |amount: eth::U256, factor: f64| {
+            eth::TokenAmount(amount)
+                .apply_factor(factor)
+                .ok_or(error::Math::Overflow)
+        };
+
+        let surplus_after_fee = surplus.amount.0;
+        let mut fee_at_threshold = eth::TokenAmount::default();
+        for (i, tier) in tiers.iter().enumerate() {
+            let threshold = scale(tier.threshold)?;
+            let factor = f64::from(tier.factor);
+            let fee_at_next_threshold = match tiers.get(i + 1) {
+                Some(next) => {
+                    let next_threshold = scale(next.threshold)?;
+                    let fee = fee_at_threshold
+                        .checked_add(&tier_fee(next_threshold.saturating_sub(threshold), factor)?)
+                        .ok_or(error::Math::Overflow)?;
+                    Some((next_threshold, fee))
+                }
+                None => None,
+            };
+            let within_tier = fee_at_next_threshold.is_none_or(|(next_threshold, fee)| {
+                surplus_after_fee < next_threshold.saturating_sub(fee.0)
+            });
+            if within_tier {
+                let tier_start = threshold.saturating_sub(fee_at_threshold.0);
+                let fee = fee_at_threshold
+                    .checked_add(&tier_fee(
+                        surplus_after_fee.saturating_sub(tier_start),
+                        factor / (1.0 - factor),
+                    )?)
+                    .ok_or(error::Math::Overflow)?;
+                return Ok(eth::Asset {
+                    token: surplus.token,
+                    amount: fee,
+                });
+            }
+            if let Some((_, fee)) = fee_at_next_threshold {
+                fee_at_threshold = fee;
+            }
+        }
+
+        Ok(eth::Asset {
+            token: surplus.token,
+            amount: Default::default(),
+        })
+    }
+
     /// Protocol fee as a cut of the trade volume, denominated in SURPLUS token
     fn volume_fee(&self, factor: f64) -> Result<eth::Asset, Error> {
         // Volume fee is specified as a `factor` from raw volume (before fee). Since
@@ -579,3 +665,69 @@ pub mod error {
         Negative,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ether(amount: u64) -> eth::U256 {
+        eth::U256::from(amount) * eth::U256::exp10(18)
+    }
+
+    #[test]
+    fn tiered_surplus_fee_from_surplus_after_fee() {
+        let token = eth::TokenAddress(eth::H160([1; 20]));
+        let prices = ClearingPrices {
+            sell: 1.into(),
+            buy: 1.into(),
+        };
+        // half filled sell order so the tier thresholds get halved
+        let trade = Trade {
+            uid: domain::OrderUid([0; 56]),
+            sell: eth::Asset {
+                token: eth::TokenAddress(eth::H160([2; 20])),
+                amount: ether(100).into(),
+            },
+            buy: eth::Asset {
+                token,
+                amount: ether(100).into(),
+            },
+            side: order::Side::Sell,
+            executed: ether(50).into(),
+            prices: Prices {
+                uniform: prices,
+                custom: prices,
+            },
+        };
+        let tiers = [
+            fee::SurplusTier {
+                threshold: 0.into(),
+                factor: 0.5.try_into().unwrap(),
+            },
+            fee::SurplusTier {
+                threshold: ether(10),
+                factor: 0.1.try_into().unwrap(),
+            },
+        ];
+        let fee = |surplus_after_fee| {
+            trade
+                .tiered_surplus_fee(
+                    eth::Asset {
+                        token,
+                        amount: surplus_after_fee,
+                    },
+                    &tiers,
+                )
+                .unwrap()
+                .amount
+                .0
+        };
+
+        // 2 ETH surplus before fee all within the first tier
+        assert_eq!(fee(ether(1).into()), ether(1));
+        // 50 ETH surplus before fee: 50% of 5 ETH + 10% of 45 ETH
+        let fee = fee(ether(43).into());
+        let error = std::cmp::max(fee, ether(7)) - std::cmp::min(fee, ether(7));
+        assert!(error < 1_000_000.into(), "{fee}");
+    }
+}
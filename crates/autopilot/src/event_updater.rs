@@ -1,6 +1,6 @@
 use {
     anyhow::Result,
-    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving},
+    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving, Reorgs},
     shared::{
         event_handling::{EventHandler, EventRetrieving, EventStoring},
         maintenance::Maintaining,
@@ -61,6 +61,12 @@ where
             .await?,
         )))
     }
+
+    /// Makes the event updater resync events of reorged blocks as soon as the
+    /// reorg is detected by the block stream.
+    pub fn with_reorgs(self, reorgs: &Reorgs) -> Self {
+        Self(Mutex::new(self.0.into_inner().with_reorgs(reorgs)))
+    }
 }
 
 #[async_trait::async_trait]
@@ -3,9 +3,11 @@ use {
     crate::{boundary, domain::eth},
     chain::Chain,
     ethcontract::dyns::DynWeb3,
-    ethrpc::{block_stream::CurrentBlockWatcher, extensions::DebugNamespace},
+    ethrpc::{
+        block_stream::{CurrentBlockWatcher, Reorgs},
+        extensions::DebugNamespace,
+    },
     primitive_types::U256,
-    std::time::Duration,
     thiserror::Error,
     url::Url,
 };
@@ -60,6 +62,7 @@ pub struct Ethereum {
     unbuffered_web3: DynWeb3,
     chain: Chain,
     current_block: CurrentBlockWatcher,
+    reorgs: Reorgs,
     contracts: Contracts,
 }
 
@@ -76,14 +79,17 @@ impl Ethereum {
         chain: &Chain,
         url: Url,
         addresses: contracts::Addresses,
-        poll_interval: Duration,
+        current_block_args: &shared::current_block::Arguments,
     ) -> Self {
         let contracts = Contracts::new(&web3, chain, addresses).await;
+        let (current_block, reorgs) = current_block_args
+            .stream_with_reorgs(url)
+            .await
+            .expect("couldn't initialize current block stream");
 
         Self {
-            current_block: ethrpc::block_stream::current_block_stream(url, poll_interval)
-                .await
-                .expect("couldn't initialize current block stream"),
+            current_block,
+            reorgs,
             web3,
             unbuffered_web3,
             chain: *chain,
@@ -101,6 +107,12 @@ impl Ethereum {
         &self.current_block
     }
 
+    /// Returns a handle to get notified about reorgs of the blocks observed by
+    /// the current block stream.
+    pub fn reorgs(&self) -> &Reorgs {
+        &self.reorgs
+    }
+
     pub fn contracts(&self) -> &Contracts {
         &self.contracts
     }
@@ -2,6 +2,7 @@ use {
     crate::{boundary, domain},
     anyhow::Context,
     database::fee_policies::{FeePolicy, FeePolicyKind},
+    number::conversions::{big_decimal_to_u256, u256_to_big_decimal},
 };
 
 pub fn from_domain(
@@ -22,6 +23,9 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
         },
         domain::fee::Policy::Volume { factor } => FeePolicy {
             auction_id,
@@ -32,6 +36,9 @@ pub fn from_domain(
             volume_factor: Some(factor.into()),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
         },
         domain::fee::Policy::PriceImprovement {
             factor,
@@ -46,6 +53,30 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: Some(factor.into()),
             price_improvement_max_volume_factor: Some(max_volume_factor.into()),
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
+        },
+        domain::fee::Policy::TieredSurplus {
+            tiers,
+            max_volume_factor,
+        } => FeePolicy {
+            auction_id,
+            order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
+            kind: FeePolicyKind::TieredSurplus,
+            surplus_factor: None,
+            surplus_max_volume_factor: None,
+            volume_factor: None,
+            price_improvement_factor: None,
+            price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: Some(
+                tiers
+                    .iter()
+                    .map(|tier| u256_to_big_decimal(&tier.threshold))
+                    .collect(),
+            ),
+            tiered_surplus_factors: Some(tiers.iter().map(|tier| tier.factor.into()).collect()),
+            tiered_surplus_max_volume_factor: Some(max_volume_factor.into()),
         },
     }
 }
@@ -90,6 +121,37 @@ pub fn try_into_domain(
                 }
             },
         },
+        FeePolicyKind::TieredSurplus => {
+            let thresholds = policy
+                .tiered_surplus_thresholds
+                .context("missing tiered_surplus_thresholds")?;
+            let factors = policy
+                .tiered_surplus_factors
+                .context("missing tiered_surplus_factors")?;
+            if thresholds.len() != factors.len() {
+                return Err(anyhow::anyhow!(
+                    "tiered_surplus_thresholds and tiered_surplus_factors differ in length"
+                )
+                .into());
+            }
+            domain::fee::Policy::TieredSurplus {
+                tiers: thresholds
+                    .iter()
+                    .zip(factors)
+                    .map(|(threshold, factor)| {
+                        Ok(domain::fee::SurplusTier {
+                            threshold: big_decimal_to_u256(threshold)
+                                .context("invalid tiered_surplus_thresholds")?,
+                            factor: factor.try_into()?,
+                        })
+                    })
+                    .collect::<anyhow::Result<_>>()?,
+                max_volume_factor: policy
+                    .tiered_surplus_max_volume_factor
+                    .context("missing tiered_surplus_max_volume_factor")?
+                    .try_into()?,
+            }
+        }
     };
     Ok(policy)
 }
@@ -268,6 +268,20 @@ pub enum FeePolicy {
     },
     #[serde(rename_all = "camelCase")]
     Volume { factor: f64 },
+    #[serde(rename_all = "camelCase")]
+    TieredSurplus {
+        tiers: Vec<SurplusTier>,
+        max_volume_factor: f64,
+    },
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurplusTier {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub threshold: U256,
+    pub factor: f64,
 }
 
 impl FeePolicy {
@@ -297,6 +311,19 @@ impl FeePolicy {
             domain::fee::Policy::Volume { factor } => Self::Volume {
                 factor: factor.into(),
             },
+            domain::fee::Policy::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => Self::TieredSurplus {
+                tiers: tiers
+                    .into_iter()
+                    .map(|tier| SurplusTier {
+                        threshold: tier.threshold,
+                        factor: tier.factor.into(),
+                    })
+                    .collect(),
+                max_volume_factor: max_volume_factor.into(),
+            },
         }
     }
 
@@ -326,6 +353,19 @@ impl FeePolicy {
             Self::Volume { factor } => domain::fee::Policy::Volume {
                 factor: FeeFactor::try_from(factor).unwrap(),
             },
+            Self::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => domain::fee::Policy::TieredSurplus {
+                tiers: tiers
+                    .into_iter()
+                    .map(|tier| domain::fee::SurplusTier {
+                        threshold: tier.threshold,
+                        factor: FeeFactor::try_from(tier.factor).unwrap(),
+                    })
+                    .collect(),
+                max_volume_factor: FeeFactor::try_from(max_volume_factor).unwrap(),
+            },
         }
     }
 }
@@ -440,6 +440,63 @@ impl Persistence {
         })
     }
 
+    /// Reconstructs a past competition auction so that it can be solved again.
+    ///
+    /// The database only stores which orders were part of the auction, so the
+    /// orders themselves are loaded in their current state. Their executed
+    /// amounts are rebuilt from the trades that happened before the auction's
+    /// block.
+    pub async fn get_competition_auction(
+        &self,
+        auction_id: domain::auction::Id,
+    ) -> anyhow::Result<domain::Auction> {
+        let auction = self.get_auction(auction_id).await?;
+
+        let block = i64::try_from(auction.block.0).context("block overflow")?;
+        let mut ex = self.postgres.pool.acquire().await?;
+        let mut orders = Vec::with_capacity(auction.orders.len());
+        for (uid, protocol_fees) in auction.orders {
+            let (mut order, quote) =
+                database::orders::single_full_order_with_quote(&mut ex, &ByteArray(uid.0))
+                    .await?
+                    .with_context(|| format!("order {uid} not found"))?
+                    .into_order_and_quote();
+            let executed =
+                database::trades::executed_amounts_before_block(&mut ex, &order.uid, block).await?;
+            order.sum_sell = executed.sum_sell;
+            order.sum_buy = executed.sum_buy;
+            order.sum_fee = executed.sum_fee;
+            order.executed_fee = executed.executed_fee;
+            let order = full_order_into_model_order(order)?;
+            let quote = quote
+                .map(dto::quote::into_domain)
+                .transpose()
+                .with_context(|| format!("invalid quote for order {uid}"))?;
+            orders.push(boundary::order::to_domain(order, protocol_fees, quote));
+        }
+
+        Ok(domain::Auction {
+            id: auction_id,
+            block: auction.block.0,
+            orders,
+            prices: auction.prices,
+            surplus_capturing_jit_order_owners: auction
+                .surplus_capturing_jit_order_owners
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    /// Returns the solutions that participated in the competition of the given
+    /// auction, as they were stored at the time.
+    pub async fn get_competition_solutions(
+        &self,
+        auction_id: domain::auction::Id,
+    ) -> anyhow::Result<Vec<Solution>> {
+        let mut ex = self.postgres.pool.acquire().await?;
+        Ok(database::solver_competition::fetch(&mut ex, auction_id).await?)
+    }
+
     /// Computes solvable orders based on the latest observed block number,
     /// order creation timestamp, and minimum validity period.
     pub async fn solvable_orders_after(
@@ -0,0 +1,8 @@
+use {primitive_types::H256, serde::Deserialize};
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub number: u64,
+    pub hash: H256,
+}
@@ -1,6 +1,7 @@
 //! Types for communicating with drivers as defined in
 //! `crates/driver/openapi.yml`.
 
+pub mod block;
 pub mod notify;
 pub mod reveal;
 pub mod settle;
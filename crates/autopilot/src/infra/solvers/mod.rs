@@ -1,5 +1,5 @@
 use {
-    self::dto::{reveal, settle, solve},
+    self::dto::{block, reveal, settle, solve},
     crate::{arguments::Account, domain::eth, infra::solvers::dto::notify, util},
     anyhow::{Context, Result, anyhow},
     chrono::{DateTime, Utc},
@@ -121,6 +121,18 @@ impl Driver {
         self.request_response("notify", request, None).await
     }
 
+    /// Returns the block the driver's node is currently at.
+    pub async fn block(&self) -> Result<block::Response> {
+        let url = util::join(&self.url, "block");
+        let response = self.client.get(url).send().await.context("send")?;
+        let status = response.status();
+        if status != StatusCode::OK {
+            let text = response.text().await.context("read error response body")?;
+            return Err(anyhow!("bad status {status}: {text}"));
+        }
+        response.json().await.context("bad json")
+    }
+
     async fn request_response<Response>(
         &self,
         path: &str,
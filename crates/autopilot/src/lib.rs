@@ -6,10 +6,14 @@ pub mod event_updater;
 pub mod infra;
 mod maintenance;
 pub mod periodic_db_cleanup;
+pub mod replay;
 pub mod run;
 pub mod run_loop;
 pub mod shadow;
 pub mod solvable_orders;
 pub mod util;
 
-pub use self::run::{run, start};
+pub use self::{
+    replay::replay,
+    run::{run, start},
+};
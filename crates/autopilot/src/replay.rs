@@ -0,0 +1,350 @@
+//! Replays a past solver competition. The auction is reconstructed from the
+//! database and sent to the configured drivers, which are expected to be
+//! connected to a node forked at the auction block (e.g. `anvil --fork-url
+//! <ARCHIVE_NODE> --fork-block-number <BLOCK>`). The proposed solutions are
+//! then ranked exactly like the run loop does and compared to the outcome of
+//! the original competition.
+
+use {
+    crate::{
+        arguments,
+        database::Postgres,
+        domain::{
+            self,
+            competition::{Participant, Unranked},
+        },
+        infra::{self, Persistence, solvers::dto::solve},
+        run::winner_selection,
+        run_loop::RunLoop,
+    },
+    anyhow::{Context, Result, ensure},
+    clap::Parser,
+    number::conversions::big_decimal_to_u256,
+    primitive_types::{H160, U256},
+    std::{
+        collections::{BTreeMap, BTreeSet, HashSet},
+        num::NonZeroUsize,
+        sync::Arc,
+        time::Duration,
+    },
+    url::Url,
+    web3::types::BlockNumber,
+};
+
+#[derive(clap::Parser)]
+struct Args {
+    /// The log filter.
+    #[clap(long, env, default_value = "warn,autopilot=info")]
+    log: String,
+
+    /// Url of the Postgres database the auction is loaded from.
+    #[clap(long, env, default_value = "postgresql://")]
+    db_url: Url,
+
+    /// The id of the auction to replay.
+    #[clap(long)]
+    auction_id: domain::auction::Id,
+
+    /// The drivers to replay the auction with, in the same format as for the
+    /// autopilot: `<NAME>|<URL>|<SUBMISSION_ADDRESS>|<FAIRNESS_THRESHOLD>`.
+    /// The drivers need to be connected to `fork_url`.
+    #[clap(long, env, use_value_delimiter = true)]
+    drivers: Vec<arguments::Solver>,
+
+    /// The node forked at the auction block, used to make sure that the
+    /// drivers see the same state as during the original competition.
+    #[clap(long, env)]
+    fork_url: Url,
+
+    #[clap(flatten)]
+    ethrpc: shared::ethrpc::Arguments,
+
+    /// The address of the wrapped native token. Defaults to the canonical
+    /// deployment of the chain.
+    #[clap(long, env)]
+    native_token_address: Option<H160>,
+
+    /// Tokens that are trusted by the drivers.
+    #[clap(long, env, use_value_delimiter = true)]
+    trusted_tokens: Option<Vec<H160>>,
+
+    /// The time the drivers get for solving the auction.
+    #[clap(long, env, default_value = "15s", value_parser = humantime::parse_duration)]
+    solve_deadline: Duration,
+
+    /// The maximum allowed number of solutions to be proposed from a single
+    /// solver, per auction.
+    #[clap(long, env, default_value = "3")]
+    max_solutions_per_solver: usize,
+
+    /// The maximum number of winners per auction.
+    #[clap(long, env, default_value = "1")]
+    max_winners_per_auction: usize,
+
+    /// The strategy used for selecting the winners of the auction.
+    #[clap(long, env, default_value = "greedy")]
+    winner_selection: arguments::WinnerSelection,
+}
+
+/// Entry-point of the replay command.
+pub async fn replay(args: impl Iterator<Item = String>) {
+    observe::panic_hook::install();
+    let args = Args::parse_from(args);
+    observe::tracing::initialize(&args.log, tracing::Level::ERROR.into());
+
+    if let Err(err) = run(args).await {
+        tracing::error!(?err, "failed to replay auction");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<()> {
+    let postgres = Postgres::new(args.db_url.as_str(), NonZeroUsize::new(1).unwrap())
+        .await
+        .context("failed to connect to the database")?;
+    let persistence = Persistence::new(None, Arc::new(postgres)).await;
+    let auction = persistence
+        .get_competition_auction(args.auction_id)
+        .await
+        .context("failed to load auction")?;
+
+    let rpc = infra::blockchain::Rpc::new(&args.fork_url, &args.ethrpc)
+        .await
+        .context("failed to connect to the fork")?;
+    let block = rpc
+        .web3()
+        .eth()
+        .block(BlockNumber::Latest.into())
+        .await?
+        .context("fork has no latest block")?;
+    let (number, hash) = (
+        block.number.context("latest block is pending")?.as_u64(),
+        block.hash.context("latest block is pending")?,
+    );
+    ensure!(
+        number == auction.block,
+        "fork is at block {number} but auction {} was created at block {}; start the fork with \
+         `anvil --fork-url <ARCHIVE_NODE> --fork-block-number {}`",
+        auction.id,
+        auction.block,
+        auction.block,
+    );
+    let wrapped_native_token = match args.native_token_address {
+        Some(address) => address,
+        None => contracts::WETH9::deployed(rpc.web3())
+            .await
+            .context("unknown wrapped native token")?
+            .address(),
+    };
+
+    let request = solve::Request::new(
+        &auction,
+        &args
+            .trusted_tokens
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        args.solve_deadline,
+    );
+    let drivers =
+        futures::future::try_join_all(args.drivers.into_iter().map(|driver| async move {
+            infra::Driver::try_new(
+                driver.url,
+                driver.name.clone(),
+                driver.fairness_threshold.map(Into::into),
+                driver.submission_account,
+                driver.requested_timeout_on_problems,
+            )
+            .await
+            .map(Arc::new)
+            .with_context(|| format!("failed to load driver {}", driver.name))
+        }))
+        .await?;
+    // Drivers that are not connected to the fork would solve the auction on a
+    // different state than the original competition.
+    for driver in &drivers {
+        let block = driver
+            .block()
+            .await
+            .with_context(|| format!("failed to get block of driver {}", driver.name))?;
+        ensure!(
+            block.number == number && block.hash == hash,
+            "driver {} is at block {} ({:?}) but the fork is at block {number} ({hash:?}); \
+             connect the driver to the fork",
+            driver.name,
+            block.number,
+            block.hash,
+        );
+    }
+    let solutions = futures::future::join_all(
+        drivers
+            .into_iter()
+            .map(|driver| solve(driver, &request, args.solve_deadline)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let replayed = RunLoop::rank(
+        solutions,
+        &auction,
+        args.max_solutions_per_solver,
+        winner_selection(args.winner_selection, args.max_winners_per_auction).as_ref(),
+        wrapped_native_token.into(),
+    );
+    let original = persistence
+        .get_competition_solutions(args.auction_id)
+        .await
+        .context("failed to load original competition")?;
+
+    let diff = diff(
+        &outcomes(original.iter().map(|solution| {
+            (
+                H160(solution.solver.0),
+                big_decimal_to_u256(&solution.score).unwrap_or_default(),
+                solution.is_winner,
+            )
+        })),
+        &outcomes(replayed.iter().map(|participant| {
+            (
+                participant.solution().solver().0,
+                participant.solution().score().get().0,
+                participant.is_winner(),
+            )
+        })),
+    );
+    if diff.is_empty() {
+        println!("replayed competition matches the original one");
+    }
+    for line in diff {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Sends the `/solve` request to the driver. Unlike the run loop, errors are
+/// only logged as the solver is simply treated as not participating.
+async fn solve(
+    driver: Arc<infra::Driver>,
+    request: &solve::Request,
+    deadline: Duration,
+) -> Vec<Participant<Unranked>> {
+    let response = match tokio::time::timeout(deadline, driver.solve(request)).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(err)) => {
+            tracing::warn!(?err, driver = %driver.name, "solve error");
+            return vec![];
+        }
+        Err(_) => {
+            tracing::warn!(driver = %driver.name, "solve timeout");
+            return vec![];
+        }
+    };
+    response
+        .into_domain()
+        .into_iter()
+        .filter_map(|solution| match solution {
+            Ok(solution) => Some(Participant::new(solution, driver.clone())),
+            Err(err) => {
+                tracing::warn!(?err, driver = %driver.name, "invalid proposed solution");
+                None
+            }
+        })
+        .collect()
+}
+
+/// The outcome of a competition for a single solver.
+#[derive(Debug, Default, PartialEq)]
+struct Outcome {
+    /// The score of the solver's best solution.
+    score: U256,
+    solutions: usize,
+    won: bool,
+}
+
+fn outcomes(solutions: impl Iterator<Item = (H160, U256, bool)>) -> BTreeMap<H160, Outcome> {
+    let mut outcomes = BTreeMap::<_, Outcome>::new();
+    for (solver, score, is_winner) in solutions {
+        let outcome = outcomes.entry(solver).or_default();
+        outcome.score = outcome.score.max(score);
+        outcome.solutions += 1;
+        outcome.won |= is_winner;
+    }
+    outcomes
+}
+
+/// Compares the per solver outcomes of the original and the replayed
+/// competition. Returns one line per difference.
+fn diff(original: &BTreeMap<H160, Outcome>, replayed: &BTreeMap<H160, Outcome>) -> Vec<String> {
+    let solvers = original
+        .keys()
+        .chain(replayed.keys())
+        .collect::<BTreeSet<_>>();
+    let mut lines = Vec::new();
+    for solver in solvers {
+        match (original.get(solver), replayed.get(solver)) {
+            (Some(original), Some(replayed)) => {
+                if original.score != replayed.score {
+                    lines.push(format!(
+                        "solver {solver:?}: score {} -> {}",
+                        original.score, replayed.score
+                    ));
+                }
+                if original.solutions != replayed.solutions {
+                    lines.push(format!(
+                        "solver {solver:?}: solutions {} -> {}",
+                        original.solutions, replayed.solutions
+                    ));
+                }
+                if original.won != replayed.won {
+                    lines.push(format!(
+                        "solver {solver:?}: winner {} -> {}",
+                        original.won, replayed.won
+                    ));
+                }
+            }
+            (Some(_), None) => lines.push(format!("solver {solver:?}: missing in replay")),
+            (None, Some(_)) => lines.push(format!("solver {solver:?}: new in replay")),
+            (None, None) => unreachable!(),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_competitions() {
+        let (a, b, c) = (H160([1; 20]), H160([2; 20]), H160([3; 20]));
+        let original = outcomes(
+            [
+                (a, 10.into(), true),
+                (a, 5.into(), false),
+                (b, 8.into(), false),
+            ]
+            .into_iter(),
+        );
+        let replayed = outcomes(
+            [
+                (a, 7.into(), false),
+                (a, 5.into(), false),
+                (c, 9.into(), true),
+            ]
+            .into_iter(),
+        );
+
+        assert_eq!(
+            diff(&original, &replayed),
+            vec![
+                format!("solver {a:?}: score 10 -> 7"),
+                format!("solver {a:?}: winner true -> false"),
+                format!("solver {b:?}: missing in replay"),
+                format!("solver {c:?}: new in replay"),
+            ]
+        );
+        assert!(diff(&original, &original).is_empty());
+    }
+}
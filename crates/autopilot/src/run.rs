@@ -1,6 +1,6 @@
 use {
     crate::{
-        arguments::Arguments,
+        arguments::{self, Arguments},
         boundary,
         database::{
             Postgres,
@@ -15,7 +15,10 @@ use {
                 event_retriever::CoWSwapOnchainOrdersContract,
             },
         },
-        domain::{self, competition::SolverParticipationGuard},
+        domain::{
+            self,
+            competition::{SolverParticipationGuard, WinnerSelection, winner_selection},
+        },
         event_updater::EventUpdater,
         infra,
         maintenance::Maintenance,
@@ -112,9 +115,32 @@ async fn ethereum(
     chain: &Chain,
     url: Url,
     contracts: infra::blockchain::contracts::Addresses,
-    poll_interval: Duration,
+    current_block_args: &shared::current_block::Arguments,
 ) -> infra::Ethereum {
-    infra::Ethereum::new(web3, unbuffered_web3, chain, url, contracts, poll_interval).await
+    infra::Ethereum::new(
+        web3,
+        unbuffered_web3,
+        chain,
+        url,
+        contracts,
+        current_block_args,
+    )
+    .await
+}
+
+/// Creates the configured winner selection strategy.
+pub(crate) fn winner_selection(
+    kind: arguments::WinnerSelection,
+    max_winners_per_auction: usize,
+) -> Arc<dyn WinnerSelection> {
+    match kind {
+        arguments::WinnerSelection::Greedy => {
+            Arc::new(winner_selection::Greedy::new(max_winners_per_auction))
+        }
+        arguments::WinnerSelection::MaxScore => {
+            Arc::new(winner_selection::MaxScore::new(max_winners_per_auction))
+        }
+    }
 }
 
 pub async fn start(args: impl Iterator<Item = String>) {
@@ -186,7 +212,7 @@ pub async fn run(args: Arguments) {
         &chain,
         url,
         contracts.clone(),
-        args.shared.current_block.block_stream_poll_interval,
+        &args.shared.current_block,
     )
     .await;
 
@@ -414,7 +440,8 @@ pub async fn run(args: Arguments) {
         ),
         block_retriever.clone(),
         skip_event_sync_start,
-    );
+    )
+    .with_reorgs(eth.reorgs());
 
     let archive_node_web3 = args.archive_node_url.as_ref().map_or(web3.clone(), |url| {
         boundary::web3_client(url, &args.shared.ethrpc)
@@ -450,6 +477,16 @@ pub async fn run(args: Arguments) {
         args.price_estimation.quote_verification,
     ));
 
+    let protocol_fees =
+        domain::ProtocolFees::new(&args.fee_policies, args.fee_policy_max_partner_fee);
+    let protocol_fees = match args.fee_policy_rules {
+        Some(path) => {
+            protocol_fees
+                .with_rules(path, args.fee_policy_rules_reload_interval)
+                .await
+        }
+        None => protocol_fees,
+    };
     let solvable_orders_cache = SolvableOrdersCache::new(
         args.min_order_validity_period,
         persistence.clone(),
@@ -465,11 +502,12 @@ pub async fn run(args: Arguments) {
         args.limit_order_price_factor
             .try_into()
             .expect("limit order price factor can't be converted to BigDecimal"),
-        domain::ProtocolFees::new(&args.fee_policies, args.fee_policy_max_partner_fee),
+        protocol_fees,
         cow_amm_registry.clone(),
         args.run_loop_native_price_timeout,
         eth.contracts().settlement().address(),
     );
+    solvable_orders_cache.subscribe_to_reorgs(eth.reorgs());
 
     let liveness = Arc::new(Liveness::new(args.max_auction_age));
     observe::metrics::serve_metrics(liveness.clone(), args.metrics_address);
@@ -522,7 +560,8 @@ pub async fn run(args: Arguments) {
             ethflow_refund_start_block,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .with_reorgs(eth.reorgs());
 
         let custom_ethflow_order_parser = EthFlowOnchainOrderParser {};
         let onchain_order_event_parser = OnchainOrderParser::new(
@@ -551,7 +590,8 @@ pub async fn run(args: Arguments) {
             ethflow_start_block,
         )
         .await
-        .expect("Should be able to initialize event updater. Database read issues?");
+        .expect("Should be able to initialize event updater. Database read issues?")
+        .with_reorgs(eth.reorgs());
 
         maintenance.with_ethflow(onchain_order_indexer);
         // refunds are not critical for correctness and can therefore be indexed
@@ -567,8 +607,8 @@ pub async fn run(args: Arguments) {
         max_settlement_transaction_wait: args.max_settlement_transaction_wait,
         solve_deadline: args.solve_deadline,
         max_run_loop_delay: args.max_run_loop_delay,
-        max_winners_per_auction: args.max_winners_per_auction,
         max_solutions_per_solver: args.max_solutions_per_solver,
+        winner_selection: winner_selection(args.winner_selection, args.max_winners_per_auction),
     };
 
     let drivers_futures = args
@@ -680,12 +720,21 @@ async fn shadow_mode(args: Arguments) -> ! {
     let liveness = Arc::new(Liveness::new(args.max_auction_age));
     observe::metrics::serve_metrics(liveness.clone(), args.metrics_address);
 
-    let current_block = ethrpc::block_stream::current_block_stream(
-        args.shared.node_url,
-        args.shared.current_block.block_stream_poll_interval,
-    )
-    .await
-    .expect("couldn't initialize current block stream");
+    let current_block = args
+        .shared
+        .current_block
+        .stream(args.shared.node_url)
+        .await
+        .expect("couldn't initialize current block stream");
+
+    // Unlike the run loop, the shadow competition only excludes solutions
+    // that swap tokens of better solutions that actually won.
+    let shadow_winner_selection: Arc<dyn WinnerSelection> = match args.winner_selection {
+        arguments::WinnerSelection::Greedy => Arc::new(
+            winner_selection::Greedy::excluding_winner_tokens(args.max_winners_per_auction),
+        ),
+        kind => winner_selection(kind, args.max_winners_per_auction),
+    };
 
     let shadow = shadow::RunLoop::new(
         orderbook,
@@ -694,7 +743,7 @@ async fn shadow_mode(args: Arguments) -> ! {
         args.solve_deadline,
         liveness.clone(),
         current_block,
-        args.max_winners_per_auction,
+        shadow_winner_selection,
     );
     shadow.run_forever().await;
 }
@@ -12,6 +12,8 @@ use {
                 SolverParticipationGuard,
                 TradedOrder,
                 Unranked,
+                WinnerSelection,
+                winner_selection::Bid,
             },
             eth::{self, TxId},
             settlement::{ExecutionEnded, ExecutionStarted},
@@ -58,8 +60,9 @@ pub struct Config {
     /// allowed to start before it has to re-synchronize to the blockchain
     /// by waiting for the next block to appear.
     pub max_run_loop_delay: Duration,
-    pub max_winners_per_auction: usize,
     pub max_solutions_per_solver: usize,
+    /// Strategy used for selecting the winners of an auction.
+    pub winner_selection: Arc<dyn WinnerSelection>,
 }
 
 pub struct RunLoop {
@@ -365,8 +368,6 @@ impl RunLoop {
         block_deadline: u64,
     ) -> Result<()> {
         let start = Instant::now();
-        // TODO: Support multiple winners
-        // https://github.com/cowprotocol/services/issues/3021
         let Some(winning_solution) = solutions
             .iter()
             .find(|participant| participant.is_winner())
@@ -376,10 +377,14 @@ impl RunLoop {
         };
         let winner = winning_solution.solver().into();
         let winning_score = winning_solution.score().get().0;
-        let reference_score = solutions
-            // todo multiple winners per auction
-            .get(1)
-            .map(|participant| participant.solution().score().get().0)
+        let reference_scores = Self::compute_reference_scores(
+            self.config.winner_selection.as_ref(),
+            self.eth.contracts().wrapped_native_token(),
+            solutions,
+        );
+        let reference_score = reference_scores
+            .get(&winning_solution.solver())
+            .map(|score| score.0)
             .unwrap_or_default();
         let participants = solutions
             .iter()
@@ -447,6 +452,11 @@ impl RunLoop {
                         .map(|(token, price)| (token.0, price.get().into()))
                         .collect(),
                     is_winner: participant.is_winner(),
+                    reference_score: participant
+                        .is_winner()
+                        .then(|| reference_scores.get(&participant.solution().solver()))
+                        .flatten()
+                        .map(|score| score.0),
                 })
                 .collect(),
         };
@@ -455,6 +465,10 @@ impl RunLoop {
             winner,
             winning_score,
             reference_score,
+            reference_scores: reference_scores
+                .into_iter()
+                .map(|(solver, score)| (solver.into(), score.0))
+                .collect(),
             participants,
             prices: auction
                 .prices
@@ -519,7 +533,7 @@ impl RunLoop {
         );
         let request = &request;
 
-        let mut solutions = futures::future::join_all(
+        let solutions = futures::future::join_all(
             self.drivers
                 .iter()
                 .map(|driver| self.solve(driver.clone(), request)),
@@ -529,6 +543,27 @@ impl RunLoop {
         .flatten()
         .collect::<Vec<_>>();
 
+        Self::rank(
+            solutions,
+            auction,
+            self.config.max_solutions_per_solver,
+            self.config.winner_selection.as_ref(),
+            self.eth.contracts().wrapped_native_token(),
+        )
+    }
+
+    /// Ranks the solutions proposed for an auction: solutions that don't come
+    /// from their driver's submission address, exceed the number of solutions
+    /// allowed per solver or are unfair are discarded, and the winners are
+    /// selected among the remaining ones. Returns all fair solutions sorted by
+    /// their score (best to worst).
+    pub fn rank(
+        mut solutions: Vec<competition::Participant<Unranked>>,
+        auction: &domain::Auction,
+        max_solutions_per_solver: usize,
+        winner_selection: &dyn WinnerSelection,
+        wrapped_native_token: eth::WrappedNativeToken,
+    ) -> Vec<competition::Participant> {
         // Shuffle so that sorting randomly splits ties.
         solutions.shuffle(&mut rand::thread_rng());
         solutions.sort_unstable_by_key(|participant| {
@@ -560,7 +595,7 @@ impl RunLoop {
             let driver = participant.driver().name.clone();
             let count = counter.entry(driver).or_insert(0);
             *count += 1;
-            *count <= self.config.max_solutions_per_solver
+            *count <= max_solutions_per_solver
         });
 
         // Filter out solutions that are not fair
@@ -579,39 +614,73 @@ impl RunLoop {
                 }
             });
 
-        // Winners are selected one by one, starting from the best solution,
-        // until `max_winners_per_auction` are selected. The solution is a winner
-        // if it swaps tokens that are not yet swapped by any previously processed
-        // solution.
-        let wrapped_native_token = self.eth.contracts().wrapped_native_token();
-        let mut already_swapped_tokens = HashSet::new();
-        let mut winners = 0;
-        let solutions = solutions
-            .cloned()
+        let solutions = solutions.cloned().collect::<Vec<_>>();
+        let winners = Self::select_winners(winner_selection, wrapped_native_token, &solutions);
+        solutions
+            .into_iter()
+            .zip(winners)
+            .map(|(participant, is_winner)| participant.rank(is_winner))
+            .collect()
+    }
+
+    /// Selects the winners among the given solutions, which are expected to be
+    /// sorted by score (best to worst), using the specified winner selection
+    /// strategy. Returns whether each solution is a winner, in the same order
+    /// as the input.
+    fn select_winners<'a, T: 'a>(
+        winner_selection: &dyn WinnerSelection,
+        wrapped_native_token: eth::WrappedNativeToken,
+        solutions: impl IntoIterator<Item = &'a competition::Participant<T>>,
+    ) -> Vec<bool> {
+        let bids = solutions
+            .into_iter()
             .map(|participant| {
-                let swapped_tokens = participant
-                    .solution()
-                    .orders()
-                    .iter()
-                    .flat_map(|(_, order)| {
+                Bid::new(
+                    *participant.solution().score().get(),
+                    participant.solution().orders().values().flat_map(|order| {
                         [
                             order.sell.token.as_erc20(wrapped_native_token),
                             order.buy.token.as_erc20(wrapped_native_token),
                         ]
-                    })
-                    .collect::<HashSet<_>>();
-
-                let is_winner = swapped_tokens.is_disjoint(&already_swapped_tokens)
-                    && winners < self.config.max_winners_per_auction;
-
-                already_swapped_tokens.extend(swapped_tokens);
-                winners += usize::from(is_winner);
-
-                participant.rank(is_winner)
+                    }),
+                )
             })
-            .collect();
+            .collect::<Vec<_>>();
+        winner_selection.select_winners(&bids)
+    }
 
+    /// Computes the reference score of every winning solver. The reference
+    /// score of a solver is the total score of the winners that would have
+    /// been selected if the solver had not participated in the auction.
+    fn compute_reference_scores(
+        winner_selection: &dyn WinnerSelection,
+        wrapped_native_token: eth::WrappedNativeToken,
+        solutions: &[competition::Participant],
+    ) -> HashMap<eth::Address, eth::Ether> {
         solutions
+            .iter()
+            .filter(|participant| participant.is_winner())
+            .map(|participant| participant.solution().solver())
+            .unique()
+            .map(|solver| {
+                let others = solutions
+                    .iter()
+                    .filter(|participant| participant.solution().solver() != solver)
+                    .collect::<Vec<_>>();
+                let winners = Self::select_winners(
+                    winner_selection,
+                    wrapped_native_token,
+                    others.iter().copied(),
+                );
+                let reference_score = others
+                    .iter()
+                    .zip(winners)
+                    .filter(|(_, is_winner)| *is_winner)
+                    .map(|(participant, _)| *participant.solution().score().get())
+                    .sum();
+                (solver, reference_score)
+            })
+            .collect()
     }
 
     /// Returns true if solution is fair to other solutions
@@ -1217,3 +1286,82 @@ pub mod observe {
         super::Metrics::matched_unsettled(winner.driver(), non_winning_orders);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            arguments::Account,
+            domain::{auction::order, competition::winner_selection::Greedy},
+        },
+        maplit::hashmap,
+        primitive_types::H160,
+    };
+
+    async fn participant(
+        solver: u8,
+        score: u64,
+        tokens: [u8; 2],
+        is_winner: bool,
+    ) -> competition::Participant {
+        let token = |i: u8| eth::TokenAddress(H160([i; 20]));
+        let asset = |i: u8| eth::Asset {
+            amount: eth::TokenAmount(1.into()),
+            token: token(i),
+        };
+        let order = TradedOrder {
+            side: order::Side::Sell,
+            sell: asset(tokens[0]),
+            buy: asset(tokens[1]),
+            executed_sell: eth::TokenAmount(1.into()),
+            executed_buy: eth::TokenAmount(1.into()),
+        };
+        let solution = Solution::new(
+            solver.into(),
+            eth::Address(H160([solver; 20])),
+            competition::Score::try_new(eth::Ether(score.into())).unwrap(),
+            hashmap! { OrderUid([solver; 56]) => order },
+            Default::default(),
+        );
+        let driver = infra::Driver::try_new(
+            "http://localhost".parse().unwrap(),
+            format!("solver{solver}"),
+            None,
+            Account::Address(H160([solver; 20])),
+            false,
+        )
+        .await
+        .unwrap();
+        competition::Participant::new(solution, Arc::new(driver)).rank(is_winner)
+    }
+
+    #[tokio::test]
+    async fn reference_scores() {
+        // Solver 1 wins with the best solution, which makes solver 2 lose
+        // since it swaps an overlapping token. Solver 3 wins without any
+        // competing solution for its tokens.
+        let solutions = [
+            participant(1, 100, [1, 2], true).await,
+            participant(2, 80, [1, 3], false).await,
+            participant(3, 50, [4, 5], true).await,
+        ];
+
+        let reference_scores = RunLoop::compute_reference_scores(
+            &Greedy::new(10),
+            H160([0xee; 20]).into(),
+            &solutions,
+        );
+
+        assert_eq!(
+            reference_scores,
+            hashmap! {
+                // Without solver 1, both other solutions would have won.
+                eth::Address(H160([1; 20])) => eth::Ether(130.into()),
+                // Without solver 3, only solver 1 would have won, so the
+                // reward of solver 3 is its whole score.
+                eth::Address(H160([3; 20])) => eth::Ether(100.into()),
+            }
+        );
+    }
+}
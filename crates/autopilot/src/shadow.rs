@@ -9,7 +9,11 @@
 
 use {
     crate::{
-        domain::{self, competition::TradedOrder},
+        domain::{
+            self,
+            competition::{TradedOrder, WinnerSelection, winner_selection::Bid},
+            eth,
+        },
         infra::{
             self,
             solvers::dto::{reveal, solve},
@@ -23,12 +27,7 @@ use {
     primitive_types::{H160, U256},
     rand::seq::SliceRandom,
     shared::token_list::AutoUpdatingTokenList,
-    std::{
-        cmp,
-        collections::{HashMap, HashSet},
-        sync::Arc,
-        time::Duration,
-    },
+    std::{cmp, collections::HashMap, sync::Arc, time::Duration},
     tracing::Instrument,
 };
 
@@ -41,7 +40,7 @@ pub struct RunLoop {
     solve_deadline: Duration,
     liveness: Arc<Liveness>,
     current_block: CurrentBlockWatcher,
-    max_winners_per_auction: usize,
+    winner_selection: Arc<dyn WinnerSelection>,
 }
 
 impl RunLoop {
@@ -52,7 +51,7 @@ impl RunLoop {
         solve_deadline: Duration,
         liveness: Arc<Liveness>,
         current_block: CurrentBlockWatcher,
-        max_winners_per_auction: usize,
+        winner_selection: Arc<dyn WinnerSelection>,
     ) -> Self {
         Self {
             orderbook,
@@ -63,7 +62,7 @@ impl RunLoop {
             solve_deadline,
             liveness,
             current_block,
-            max_winners_per_auction,
+            winner_selection,
         }
     }
 
@@ -213,34 +212,33 @@ impl RunLoop {
         participants
     }
 
-    /// Chooses the winners from the given participants.
+    /// Chooses the winners from the given participants using the configured
+    /// winner selection strategy.
     ///
     /// Participants are already sorted by their score (best to worst).
-    ///
-    /// Winners are selected one by one, starting from the best solution,
-    /// until `max_winners_per_auction` are selected. The solution is a winner
-    /// if it swaps tokens that are not yet swapped by any other already
-    /// selected winner.
     fn select_winners<'a>(&self, participants: &'a [Participant<'a>]) -> Vec<&'a Participant<'a>> {
-        let mut winners = Vec::new();
-        let mut already_swapped_tokens = HashSet::new();
-        for participant in participants.iter() {
-            if let Ok(solution) = &participant.solution {
-                let swapped_tokens = solution
-                    .orders()
-                    .iter()
-                    .flat_map(|(_, order)| [order.sell.token, order.buy.token])
-                    .collect::<HashSet<_>>();
-                if swapped_tokens.is_disjoint(&already_swapped_tokens) {
-                    winners.push(participant);
-                    already_swapped_tokens.extend(swapped_tokens);
-                    if winners.len() >= self.max_winners_per_auction {
-                        break;
-                    }
-                }
-            }
-        }
-        winners
+        let candidates = participants
+            .iter()
+            .filter_map(|participant| Some((participant, participant.solution.as_ref().ok()?)))
+            .collect::<Vec<_>>();
+        let bids = candidates
+            .iter()
+            .map(|(_, solution)| {
+                Bid::new(
+                    eth::Ether(solution.score.get()),
+                    solution
+                        .orders()
+                        .values()
+                        .flat_map(|order| [order.sell.token, order.buy.token]),
+                )
+            })
+            .collect::<Vec<_>>();
+        self.winner_selection
+            .select_winners(&bids)
+            .into_iter()
+            .zip(candidates)
+            .filter_map(|(is_winner, (participant, _))| is_winner.then_some(participant))
+            .collect()
     }
 
     /// Computes a driver's solutions in the shadow competition.
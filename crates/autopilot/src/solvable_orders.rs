@@ -7,6 +7,7 @@ use {
     anyhow::{Context, Result},
     bigdecimal::BigDecimal,
     database::order_events::OrderEventLabel,
+    ethrpc::block_stream::Reorgs,
     futures::{FutureExt, future::join_all},
     indexmap::IndexSet,
     itertools::{Either, Itertools},
@@ -35,7 +36,11 @@ use {
         time::Duration,
     },
     strum::VariantNames,
-    tokio::{sync::Mutex, time::Instant},
+    tokio::{
+        sync::{Mutex, broadcast::error::RecvError},
+        time::Instant,
+    },
+    tracing::Instrument,
 };
 
 #[derive(prometheus_metric_storage::MetricStorage)]
@@ -146,6 +151,52 @@ impl SolvableOrdersCache {
         self_
     }
 
+    /// Makes the cache rebuild its state from scratch on the next update
+    /// whenever a reorg invalidates the blocks it was built from.
+    pub fn subscribe_to_reorgs(self: &Arc<Self>, reorgs: &Reorgs) {
+        let cache = Arc::downgrade(self);
+        let mut reorgs = reorgs.subscribe();
+        tokio::task::spawn(
+            async move {
+                loop {
+                    let common_ancestor = match reorgs.recv().await {
+                        // A reorg deeper than the tracked blocks invalidates
+                        // everything.
+                        Ok(reorg) => reorg.common_ancestor.map_or(0, |block| block.0),
+                        Err(RecvError::Lagged(missed)) => {
+                            // We don't know how deep the missed reorgs were so
+                            // always resync.
+                            tracing::warn!(missed, "missed reorg notifications");
+                            0
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
+                    let Some(cache) = cache.upgrade() else {
+                        break;
+                    };
+                    cache.handle_reorg(common_ancestor).await;
+                }
+            }
+            .instrument(tracing::info_span!("solvable_orders_reorgs")),
+        );
+    }
+
+    /// Drops the cached state if it was derived from blocks after the common
+    /// ancestor of a reorg. The incremental order query relies on the cached
+    /// state being consistent with the chain, so the next update fetches all
+    /// solvable orders again.
+    async fn handle_reorg(&self, common_ancestor: u64) {
+        let mut cache = self.cache.lock().await;
+        let reorged = cache.as_ref().is_some_and(|inner| {
+            inner.auction.block > common_ancestor
+                || inner.solvable_orders.latest_settlement_block > common_ancestor
+        });
+        if reorged {
+            tracing::debug!(common_ancestor, "dropping solvable orders after reorg");
+            *cache = None;
+        }
+    }
+
     pub async fn current_auction(&self) -> Option<domain::RawAuctionData> {
         self.cache
             .lock()
@@ -277,8 +328,12 @@ impl SolvableOrdersCache {
                         .quotes
                         .get(&order.metadata.uid.into())
                         .cloned();
-                    self.protocol_fees
-                        .apply(order, quote, &surplus_capturing_jit_order_owners)
+                    self.protocol_fees.apply(
+                        order,
+                        quote,
+                        &surplus_capturing_jit_order_owners,
+                        &prices,
+                    )
                 })
                 .collect(),
             prices: prices
@@ -873,6 +928,7 @@ mod tests {
         },
         primitive_types::H160,
         shared::{
+            account_balances::MockBalanceFetching,
             bad_token::list_based::ListBasedDetector,
             price_estimation::{PriceEstimationError, native::MockNativePriceEstimating},
             signature_validator::{MockSignatureValidating, SignatureValidationError},
@@ -1480,4 +1536,63 @@ mod tests {
             token(5), // coming from limit order (part of 1 orders)
         ]));
     }
+
+    #[tokio::test]
+    async fn drops_cache_after_reorg() {
+        let postgres = crate::database::Postgres {
+            pool: sqlx::PgPool::connect_lazy("postgresql://").unwrap(),
+            config: crate::database::Config {
+                insert_batch_size: std::num::NonZeroUsize::new(1).unwrap(),
+            },
+        };
+        let native_price_estimator = CachingNativePriceEstimator::new(
+            Box::new(MockNativePriceEstimating::new()),
+            Duration::from_secs(10),
+            Duration::MAX,
+            None,
+            Default::default(),
+            1,
+            Default::default(),
+        );
+        let web3 = ethrpc::Web3::new(ethcontract::dyns::DynTransport::new(
+            ethrpc::mock::MockTransport::new(),
+        ));
+        let cache = SolvableOrdersCache::new(
+            Duration::ZERO,
+            infra::Persistence::new(None, Arc::new(postgres)).await,
+            banned::Users::none(),
+            Arc::new(MockBalanceFetching::new()),
+            Arc::new(ListBasedDetector::deny_list(vec![])),
+            Arc::new(native_price_estimator),
+            Arc::new(MockSignatureValidating::new()),
+            H160::zero(),
+            BigDecimal::from(1),
+            domain::ProtocolFees::new(&[], 0.0.try_into().unwrap()),
+            cow_amm::Registry::new(web3),
+            Duration::from_secs(1),
+            H160::zero(),
+        );
+        *cache.cache.lock().await = Some(Inner {
+            auction: domain::RawAuctionData {
+                block: 10,
+                orders: vec![],
+                prices: Default::default(),
+                surplus_capturing_jit_order_owners: vec![],
+            },
+            solvable_orders: SolvableOrders {
+                orders: Default::default(),
+                quotes: Default::default(),
+                latest_settlement_block: 8,
+                fetched_from_db: chrono::Utc::now(),
+            },
+        });
+
+        // The cache was built from blocks that are still canonical.
+        cache.handle_reorg(10).await;
+        assert!(cache.current_auction().await.is_some());
+
+        // The auction block got reorged.
+        cache.handle_reorg(9).await;
+        assert!(cache.current_auction().await.is_none());
+    }
 }
@@ -84,6 +84,16 @@ fn main() {
     generate_contract_with_config("CoWSwapOnchainOrders", |builder| {
         builder.contract_mod_override("cowswap_onchain_orders")
     });
+    generate_contract_with_config("ComposableCoW", |builder| {
+        // <https://github.com/cowprotocol/composable-cow/blob/main/networks.json>
+        builder
+            .contract_mod_override("composable_cow")
+            .add_network_str(MAINNET, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(GNOSIS, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(SEPOLIA, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(ARBITRUM_ONE, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(BASE, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+    });
     generate_contract_with_config("BalancerV2Authorizer", |builder| {
         builder.contract_mod_override("balancer_v2_authorizer")
     });
@@ -814,6 +824,15 @@ fn main() {
     generate_contract("IUniswapLikePair");
     // EIP-1271 contract - SignatureValidator
     generate_contract("ERC1271SignatureValidator");
+    generate_contract_with_config("ExtensibleFallbackHandler", |builder| {
+        // <https://github.com/cowprotocol/composable-cow/blob/main/networks.json>
+        builder
+            .add_network_str(MAINNET, "0x2f55e8b20D0B9FEFA187AA7d00B6Cbe563605bF5")
+            .add_network_str(GNOSIS, "0x2f55e8b20D0B9FEFA187AA7d00B6Cbe563605bF5")
+            .add_network_str(SEPOLIA, "0x2f55e8b20D0B9FEFA187AA7d00B6Cbe563605bF5")
+            .add_network_str(ARBITRUM_ONE, "0x2f55e8b20D0B9FEFA187AA7d00B6Cbe563605bF5")
+            .add_network_str(BASE, "0x2f55e8b20D0B9FEFA187AA7d00B6Cbe563605bF5")
+    });
     generate_contract_with_config("PancakeRouter", |builder| {
         builder
             .add_network_str(MAINNET, "0xEfF92A263d31888d860bD50809A8D171709b7b1c")
@@ -885,13 +904,53 @@ fn main() {
     generate_contract_with_config("IUniswapV3Factory", |builder| {
         // <https://github.com/Uniswap/v3-periphery/blob/697c2474757ea89fec12a4e6db16a574fe259610/deploys.md>
         builder
-            .add_network_str(MAINNET, "0x1F98431c8aD98523631AE4a59f267346ea31F984")
+            .add_network(
+                MAINNET,
+                Network {
+                    address: addr("0x1F98431c8aD98523631AE4a59f267346ea31F984"),
+                    deployment_information: Some(DeploymentInformation::BlockNumber(12369621)),
+                },
+            )
             .add_network_str(GOERLI, "0x1F98431c8aD98523631AE4a59f267346ea31F984")
             .add_network_str(SEPOLIA, "0x1F98431c8aD98523631AE4a59f267346ea31F984")
             .add_network_str(ARBITRUM_ONE, "0x1F98431c8aD98523631AE4a59f267346ea31F984")
             .add_network_str(BASE, "0x33128a8fC17869897dcE68Ed026d694621f6FDfD")
         // Not available on Gnosis Chain
     });
+    generate_contract_with_config("UniswapV4PoolManager", |builder| {
+        // <https://docs.uniswap.org/contracts/v4/deployments>
+        builder
+            .add_network(
+                MAINNET,
+                Network {
+                    address: addr("0x000000000004444c5dc75cB358380D2e3dE08A90"),
+                    deployment_information: Some(DeploymentInformation::BlockNumber(21688329)),
+                },
+            )
+            .add_network(
+                ARBITRUM_ONE,
+                Network {
+                    address: addr("0x360e68faccca8ca495c1b759fd9eee466db9fb32"),
+                    deployment_information: Some(DeploymentInformation::BlockNumber(297842872)),
+                },
+            )
+            .add_network(
+                BASE,
+                Network {
+                    address: addr("0x498581ff718922c3f8e6a244956af099b2652b2b"),
+                    deployment_information: Some(DeploymentInformation::BlockNumber(25350988)),
+                },
+            )
+        // Not available on Gnosis Chain
+    });
+    generate_contract_with_config("UniswapV4UniversalRouter", |builder| {
+        // <https://docs.uniswap.org/contracts/v4/deployments>
+        builder
+            .add_network_str(MAINNET, "0x66a9893cc07d91d95644aedd05d03f95e1dba8af")
+            .add_network_str(ARBITRUM_ONE, "0xa51afafe0263b40edaef0df8781ea9aa03e381a3")
+            .add_network_str(BASE, "0x6ff5693b99212da76ad316178a184ab56d299b43")
+        // Not available on Gnosis Chain
+    });
     generate_contract_with_config("IZeroEx", |builder| {
         // <https://docs.0xprotocol.org/en/latest/basics/addresses.html?highlight=contracts#addresses>
         // <https://github.com/0xProject/protocol/blob/652d4226229c97895ae9350bbf276370ebb38c5e/packages/contract-addresses/addresses.json>
@@ -993,6 +1052,12 @@ fn main() {
             )
     });
     generate_contract("CowAmmUniswapV2PriceOracle");
+    generate_contract_with_config("CurveMetaRegistry", |builder| {
+        // <https://docs.curve.fi/references/deployed-contracts/#metaregistry>
+        builder.add_network_str(MAINNET, "0xF98B45FA17DE75FB1aD0e7aFD971b0ca00e379fC")
+    });
+    generate_contract("CurveStableSwapPool");
+    generate_contract("CurveCryptoSwapPool");
 
     // Support contracts used for trade and token simulations.
     generate_contract("Solver");
@@ -172,6 +172,15 @@ fn run() -> Result<()> {
             "ERC1271SignatureValidator",
             "Manually vendored ABI for ERC-1271 signature validation",
         )
+        .manual(
+            "ComposableCoW",
+            "Manually vendored ABI for the parts of ComposableCoW used for polling \
+             conditional orders",
+        )
+        .manual(
+            "ExtensibleFallbackHandler",
+            "Manually vendored ABI for setting up Safes to use ComposableCoW",
+        )
         .npm(
             "IUniswapLikePair",
             "@uniswap/v2-periphery@1.1.0-beta.0/build/IUniswapV2Pair.json",
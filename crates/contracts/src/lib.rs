@@ -77,10 +77,15 @@ include_contracts! {
     CowAmmUniswapV2PriceOracle;
     CoWSwapEthFlow;
     CoWSwapOnchainOrders;
+    ComposableCoW;
     CowProtocolToken;
+    CurveCryptoSwapPool;
+    CurveMetaRegistry;
+    CurveStableSwapPool;
     ERC1271SignatureValidator;
     ERC20;
     ERC20Mintable;
+    ExtensibleFallbackHandler;
     ERC3156FlashLoanSolverWrapper;
     FlashLoanRouter;
     GPv2AllowListAuthentication;
@@ -108,6 +113,8 @@ include_contracts! {
     UniswapV2Router02;
     UniswapV3Pool;
     UniswapV3SwapRouter;
+    UniswapV4PoolManager;
+    UniswapV4UniversalRouter;
     WETH9;
 }
 
@@ -211,6 +218,10 @@ mod tests {
         for network in &[MAINNET, GOERLI, GNOSIS, SEPOLIA] {
             assert_has_deployment_address!(CowProtocolToken for *network);
         }
+        for network in &[MAINNET, GNOSIS, SEPOLIA, ARBITRUM_ONE] {
+            assert_has_deployment_address!(ComposableCoW for *network);
+            assert_has_deployment_address!(ExtensibleFallbackHandler for *network);
+        }
         for network in &[MAINNET, GOERLI, GNOSIS, ARBITRUM_ONE] {
             assert_has_deployment_address!(SushiSwapRouter for *network);
             assert_has_deployment_address!(UniswapV2Factory for *network);
@@ -220,6 +231,10 @@ mod tests {
             assert_has_deployment_address!(UniswapV3SwapRouter for *network);
             assert_has_deployment_address!(IUniswapV3Factory for *network);
         }
+        for network in &[MAINNET, ARBITRUM_ONE] {
+            assert_has_deployment_address!(UniswapV4PoolManager for *network);
+            assert_has_deployment_address!(UniswapV4UniversalRouter for *network);
+        }
         for network in &[MAINNET, GOERLI, ARBITRUM_ONE] {
             assert_has_deployment_address!(BalancerV2WeightedPool2TokensFactory for *network);
             assert_has_deployment_address!(BalancerV2LiquidityBootstrappingPoolFactory for *network);
@@ -245,6 +260,9 @@ mod tests {
             assert_has_deployment_address!(SwaprRouter for *network);
         }
 
+        // only mainnet
+        assert_has_deployment_address!(CurveMetaRegistry for MAINNET);
+
         // only gnosis
         assert_has_deployment_address!(BaoswapRouter for GNOSIS);
         assert_has_deployment_address!(HoneyswapRouter for GNOSIS);
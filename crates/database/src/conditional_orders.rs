@@ -0,0 +1,207 @@
+use {
+    crate::{Address, OrderUid, byte_array::ByteArray},
+    sqlx::{
+        PgConnection,
+        types::chrono::{DateTime, Utc},
+    },
+};
+
+pub type ConditionalOrderId = ByteArray<32>;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ConditionalOrder {
+    pub id: ConditionalOrderId,
+    pub owner: Address,
+    pub handler: Address,
+    pub salt: ByteArray<32>,
+    pub static_input: Vec<u8>,
+    pub creation_timestamp: DateTime<Utc>,
+    pub active: bool,
+}
+
+/// Inserts a conditional order. Returns `false` if the owner already has a
+/// conditional order with the same id.
+pub async fn insert(ex: &mut PgConnection, order: &ConditionalOrder) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO conditional_orders (id, owner, handler, salt, static_input, creation_timestamp, active)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+ON CONFLICT DO NOTHING
+    "#;
+    let result = sqlx::query(QUERY)
+        .bind(order.id)
+        .bind(order.owner)
+        .bind(order.handler)
+        .bind(order.salt)
+        .bind(&order.static_input)
+        .bind(order.creation_timestamp)
+        .bind(order.active)
+        .execute(ex)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch(
+    ex: &mut PgConnection,
+    owner: &Address,
+    id: &ConditionalOrderId,
+) -> Result<Option<ConditionalOrder>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT * FROM conditional_orders
+WHERE owner = $1 AND id = $2
+    "#;
+    sqlx::query_as(QUERY)
+        .bind(owner)
+        .bind(id)
+        .fetch_optional(ex)
+        .await
+}
+
+/// Returns all conditional orders that still need to be polled.
+pub async fn active(ex: &mut PgConnection) -> Result<Vec<ConditionalOrder>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT * FROM conditional_orders
+WHERE active
+    "#;
+    sqlx::query_as(QUERY).fetch_all(ex).await
+}
+
+/// Stops polling the conditional order.
+pub async fn deactivate(
+    ex: &mut PgConnection,
+    owner: &Address,
+    id: &ConditionalOrderId,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE conditional_orders
+SET active = false
+WHERE owner = $1 AND id = $2
+    "#;
+    sqlx::query(QUERY).bind(owner).bind(id).execute(ex).await?;
+    Ok(())
+}
+
+/// Records that an order was placed for the conditional order.
+pub async fn insert_part(
+    ex: &mut PgConnection,
+    owner: &Address,
+    id: &ConditionalOrderId,
+    order_uid: &OrderUid,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO conditional_order_parts (order_uid, owner, conditional_order)
+VALUES ($1, $2, $3)
+ON CONFLICT DO NOTHING
+    "#;
+    sqlx::query(QUERY)
+        .bind(order_uid)
+        .bind(owner)
+        .bind(id)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Returns the orders placed for the conditional order sorted by their
+/// creation time.
+pub async fn parts(
+    ex: &mut PgConnection,
+    owner: &Address,
+    id: &ConditionalOrderId,
+) -> Result<Vec<OrderUid>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT p.order_uid
+FROM conditional_order_parts p
+LEFT OUTER JOIN orders o ON o.uid = p.order_uid
+WHERE p.owner = $1 AND p.conditional_order = $2
+ORDER BY o.creation_timestamp, p.order_uid
+    "#;
+    sqlx::query_scalar(QUERY)
+        .bind(owner)
+        .bind(id)
+        .fetch_all(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_conditional_orders_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = ConditionalOrder {
+            id: ByteArray([1; 32]),
+            owner: ByteArray([2; 20]),
+            handler: ByteArray([3; 20]),
+            salt: ByteArray([4; 32]),
+            static_input: vec![5, 6],
+            creation_timestamp: DateTime::from_timestamp_millis(1_000).unwrap(),
+            active: true,
+        };
+        assert!(insert(&mut db, &order).await.unwrap());
+        assert!(!insert(&mut db, &order).await.unwrap());
+        assert_eq!(
+            fetch(&mut db, &order.owner, &order.id).await.unwrap(),
+            Some(order.clone())
+        );
+        assert_eq!(active(&mut db).await.unwrap(), vec![order.clone()]);
+
+        // Another owner can authorize a conditional order with the same
+        // parameters.
+        let other = ConditionalOrder {
+            owner: ByteArray([9; 20]),
+            ..order.clone()
+        };
+        assert!(insert(&mut db, &other).await.unwrap());
+
+        deactivate(&mut db, &order.owner, &order.id).await.unwrap();
+        assert_eq!(active(&mut db).await.unwrap(), vec![other.clone()]);
+        assert!(
+            !fetch(&mut db, &order.owner, &order.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .active
+        );
+        assert!(
+            fetch(&mut db, &other.owner, &other.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .active
+        );
+        assert_eq!(
+            fetch(&mut db, &order.owner, &ByteArray([0; 32]))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_conditional_order_parts() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        let id = ByteArray([1; 32]);
+        assert!(parts(&mut db, &owner, &id).await.unwrap().is_empty());
+
+        let part = ByteArray([2; 56]);
+        insert_part(&mut db, &owner, &id, &part).await.unwrap();
+        insert_part(&mut db, &owner, &id, &part).await.unwrap();
+        insert_part(&mut db, &owner, &ByteArray([3; 32]), &ByteArray([3; 56]))
+            .await
+            .unwrap();
+        insert_part(&mut db, &ByteArray([4; 20]), &id, &ByteArray([4; 56]))
+            .await
+            .unwrap();
+        assert_eq!(parts(&mut db, &owner, &id).await.unwrap(), vec![part]);
+    }
+}
@@ -1,5 +1,6 @@
 use {
     crate::{OrderUid, auction::AuctionId},
+    bigdecimal::BigDecimal,
     sqlx::{PgConnection, QueryBuilder},
     std::collections::HashMap,
 };
@@ -16,6 +17,9 @@ pub struct FeePolicy {
     pub volume_factor: Option<f64>,
     pub price_improvement_factor: Option<f64>,
     pub price_improvement_max_volume_factor: Option<f64>,
+    pub tiered_surplus_thresholds: Option<Vec<BigDecimal>>,
+    pub tiered_surplus_factors: Option<Vec<f64>>,
+    pub tiered_surplus_max_volume_factor: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq, sqlx::Type)]
@@ -24,6 +28,7 @@ pub enum FeePolicyKind {
     Surplus,
     Volume,
     PriceImprovement,
+    TieredSurplus,
 }
 
 pub async fn insert_batch(
@@ -38,7 +43,8 @@ pub async fn insert_batch(
     let mut query_builder = QueryBuilder::new(
         "INSERT INTO fee_policies (auction_id, order_uid, kind, surplus_factor, \
          surplus_max_volume_factor, volume_factor, price_improvement_factor, \
-         price_improvement_max_volume_factor)",
+         price_improvement_max_volume_factor, tiered_surplus_thresholds, tiered_surplus_factors, \
+         tiered_surplus_max_volume_factor)",
     );
 
     query_builder.push_values(fee_policies, |mut b, fee_policy| {
@@ -49,7 +55,10 @@ pub async fn insert_batch(
             .push_bind(fee_policy.surplus_max_volume_factor)
             .push_bind(fee_policy.volume_factor)
             .push_bind(fee_policy.price_improvement_factor)
-            .push_bind(fee_policy.price_improvement_max_volume_factor);
+            .push_bind(fee_policy.price_improvement_max_volume_factor)
+            .push_bind(fee_policy.tiered_surplus_thresholds)
+            .push_bind(fee_policy.tiered_surplus_factors)
+            .push_bind(fee_policy.tiered_surplus_max_volume_factor);
     });
 
     query_builder.build().execute(ex).await.map(|_| ())
@@ -124,6 +133,9 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
         };
         // surplus fee policy with caps
         let fee_policy_2 = FeePolicy {
@@ -135,6 +147,9 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
         };
         // volume based fee policy
         let fee_policy_3 = FeePolicy {
@@ -146,6 +161,9 @@ mod tests {
             volume_factor: Some(0.06),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
         };
         // price improvement fee policy
         let fee_policy_4 = FeePolicy {
@@ -157,6 +175,23 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: Some(0.1),
             price_improvement_max_volume_factor: Some(0.99999),
+            tiered_surplus_thresholds: None,
+            tiered_surplus_factors: None,
+            tiered_surplus_max_volume_factor: None,
+        };
+        // tiered surplus fee policy
+        let fee_policy_5 = FeePolicy {
+            auction_id: auction_id_b,
+            order_uid: order_uid_b,
+            kind: FeePolicyKind::TieredSurplus,
+            surplus_factor: None,
+            surplus_max_volume_factor: None,
+            volume_factor: None,
+            price_improvement_factor: None,
+            price_improvement_max_volume_factor: None,
+            tiered_surplus_thresholds: Some(vec![0.into(), 10_000_000_000_000_000_u64.into()]),
+            tiered_surplus_factors: Some(vec![0.5, 0.1]),
+            tiered_surplus_max_volume_factor: Some(0.01),
         };
 
         let fee_policies = vec![
@@ -164,6 +199,7 @@ mod tests {
             fee_policy_2.clone(),
             fee_policy_3.clone(),
             fee_policy_4.clone(),
+            fee_policy_5.clone(),
         ];
         insert_batch(&mut db, fee_policies.clone()).await.unwrap();
 
@@ -179,7 +215,7 @@ mod tests {
 
         expected.insert(
             (auction_id_b, order_uid_b),
-            vec![fee_policy_2, fee_policy_3],
+            vec![fee_policy_2, fee_policy_3, fee_policy_5],
         );
         let output = fetch_all(
             &mut db,
@@ -4,6 +4,7 @@ pub mod auction_orders;
 pub mod auction_participants;
 pub mod auction_prices;
 pub mod byte_array;
+pub mod conditional_orders;
 pub mod ethflow_orders;
 pub mod events;
 pub mod fee_policies;
@@ -16,6 +17,7 @@ pub mod order_execution;
 pub mod order_history;
 pub mod orders;
 pub mod quotes;
+pub mod reference_scores;
 pub mod settlement_executions;
 pub mod settlement_observations;
 pub mod settlement_scores;
@@ -55,6 +57,8 @@ pub const TABLES: &[&str] = &[
     "auction_orders",
     "auctions",
     "competition_auctions",
+    "conditional_order_parts",
+    "conditional_orders",
     "ethflow_orders",
     "ethflow_refunds",
     "fee_policies",
@@ -71,6 +75,7 @@ pub const TABLES: &[&str] = &[
     "proposed_jit_orders",
     "proposed_solutions",
     "quotes",
+    "reference_scores",
     "settlement_executions",
     "settlement_observations",
     "settlement_scores",
@@ -20,10 +20,29 @@ pub struct TradesQueryRow {
     pub auction_id: Option<AuctionId>,
 }
 
+/// Filters for [`trades`]. Unset fields do not filter.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TradesFilter {
+    pub owner: Option<Address>,
+    pub order_uid: Option<OrderUid>,
+    pub sell_token: Option<Address>,
+    pub buy_token: Option<Address>,
+    pub tx_hash: Option<TransactionHash>,
+    /// First block (inclusive) to return trades for.
+    pub from_block: Option<i64>,
+    /// Last block (inclusive) to return trades for.
+    pub to_block: Option<i64>,
+    /// Only return trades that come after the trade at this position. Used to
+    /// continue paginating from the last trade of the previous page.
+    pub after: Option<EventIndex>,
+    /// Maximum number of trades to return.
+    pub limit: Option<i64>,
+}
+
+/// Returns the matching trades ordered by block number and log index.
 pub fn trades<'a>(
     ex: &'a mut PgConnection,
-    owner_filter: Option<&'a Address>,
-    order_uid_filter: Option<&'a OrderUid>,
+    filter: &TradesFilter,
 ) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
     const COMMON_QUERY: &str = r#"
 SELECT
@@ -47,28 +66,57 @@ LEFT OUTER JOIN LATERAL (
     LIMIT 1
 ) AS settlement ON true"#;
 
+    // The filters, ordering and limit are applied to every branch of the union
+    // so that each one only reads the trades of the requested page from the
+    // indices instead of all trades of the owner.
+    const FILTER: &str = const_format::concatcp!(
+        " AND ($3 IS NULL OR o.sell_token = $3)",
+        " AND ($4 IS NULL OR o.buy_token = $4)",
+        " AND ($5 IS NULL OR settlement.tx_hash = $5)",
+        " AND ($6 IS NULL OR t.block_number >= $6)",
+        " AND ($7 IS NULL OR t.block_number <= $7)",
+        " AND ($8 IS NULL OR (t.block_number, t.log_index) > ($8, $9))",
+        " ORDER BY t.block_number, t.log_index",
+        " LIMIT $10",
+    );
+
     const QUERY: &str = const_format::concatcp!(
+        "(",
         COMMON_QUERY,
         " JOIN orders o ON o.uid = t.order_uid",
         " WHERE ($1 IS NULL OR o.owner = $1)",
         " AND ($2 IS NULL OR o.uid = $2)",
-        " UNION ",
+        FILTER,
+        ") UNION (",
         COMMON_QUERY,
         " JOIN orders o ON o.uid = t.order_uid",
         " LEFT OUTER JOIN onchain_placed_orders onchain_o",
         " ON onchain_o.uid = t.order_uid",
         " WHERE onchain_o.sender = $1",
         " AND ($2 IS NULL OR o.uid = $2)",
-        " UNION ",
+        FILTER,
+        ") UNION (",
         COMMON_QUERY,
         " JOIN jit_orders o ON o.uid = t.order_uid",
         " WHERE ($1 IS NULL OR o.owner = $1)",
         " AND ($2 IS NULL OR o.uid = $2)",
+        FILTER,
+        ")",
+        " ORDER BY block_number, log_index",
+        " LIMIT $10",
     );
 
     sqlx::query_as(QUERY)
-        .bind(owner_filter)
-        .bind(order_uid_filter)
+        .bind(filter.owner)
+        .bind(filter.order_uid)
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.tx_hash)
+        .bind(filter.from_block)
+        .bind(filter.to_block)
+        .bind(filter.after.map(|after| after.block_number))
+        .bind(filter.after.map(|after| after.log_index))
+        .bind(filter.limit)
         .fetch(ex)
 }
 
@@ -131,6 +179,37 @@ FROM (
     Ok(block_number)
 }
 
+/// The amounts an order got executed with before a block.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ExecutedAmounts {
+    pub sum_sell: BigDecimal,
+    pub sum_buy: BigDecimal,
+    pub sum_fee: BigDecimal,
+    pub executed_fee: BigDecimal,
+}
+
+/// Sums up the trades (and executed fees) of an order that happened before the
+/// given block. This allows reconstructing the state of an order at a past
+/// block.
+pub async fn executed_amounts_before_block(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+    block_number: i64,
+) -> Result<ExecutedAmounts, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT
+    (SELECT COALESCE(SUM(t.sell_amount), 0) FROM trades t WHERE t.order_uid = $1 AND t.block_number < $2) AS sum_sell,
+    (SELECT COALESCE(SUM(t.buy_amount), 0) FROM trades t WHERE t.order_uid = $1 AND t.block_number < $2) AS sum_buy,
+    (SELECT COALESCE(SUM(t.fee_amount), 0) FROM trades t WHERE t.order_uid = $1 AND t.block_number < $2) AS sum_fee,
+    COALESCE((SELECT SUM(executed_fee) FROM order_execution oe WHERE oe.order_uid = $1 AND oe.block_number < $2), 0) AS executed_fee
+"#;
+    sqlx::query_as(QUERY)
+        .bind(order_uid)
+        .bind(block_number)
+        .fetch_one(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -209,11 +288,20 @@ mod tests {
         order_uid_filter: Option<&OrderUid>,
         expected: &[TradesQueryRow],
     ) {
-        let mut filtered = trades(db, owner_filter, order_uid_filter)
-            .try_collect::<Vec<_>>()
-            .await
-            .unwrap();
-        filtered.sort_by_key(|t| (t.block_number, t.log_index));
+        let filter = TradesFilter {
+            owner: owner_filter.copied(),
+            order_uid: order_uid_filter.copied(),
+            ..Default::default()
+        };
+        assert_filtered_trades(db, &filter, expected).await;
+    }
+
+    async fn assert_filtered_trades(
+        db: &mut PgConnection,
+        filter: &TradesFilter,
+        expected: &[TradesQueryRow],
+    ) {
+        let filtered = trades(db, filter).try_collect::<Vec<_>>().await.unwrap();
         assert_eq!(filtered, expected);
     }
 
@@ -282,7 +370,11 @@ mod tests {
         }
 
         let now = std::time::Instant::now();
-        trades(&mut db, Some(&ByteArray([2u8; 20])), None)
+        let filter = TradesFilter {
+            owner: Some(ByteArray([2u8; 20])),
+            ..Default::default()
+        };
+        trades(&mut db, &filter)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -369,6 +461,85 @@ mod tests {
         assert_trades(&mut db, None, Some(&order_ids[2]), &[]).await;
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_trades_pagination_and_filters() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(1, 3).await;
+        let mut expected = Vec::new();
+        for (i, (block_number, log_index)) in [(1, 0), (1, 1), (2, 0)].into_iter().enumerate() {
+            let order = Order {
+                uid: order_ids[i],
+                owner: owners[0],
+                sell_token: ByteArray([i as u8; 20]),
+                ..Default::default()
+            };
+            crate::orders::insert_order(&mut db, &order).await.unwrap();
+            let event_index = EventIndex {
+                block_number,
+                log_index,
+            };
+            let trade = add_trade(&mut db, owners[0], order_ids[i], event_index, None, None).await;
+            expected.push(TradesQueryRow {
+                sell_token: order.sell_token,
+                ..trade
+            });
+        }
+
+        let owner = TradesFilter {
+            owner: Some(owners[0]),
+            ..Default::default()
+        };
+        assert_filtered_trades(&mut db, &owner, &expected).await;
+
+        // Paginating through all trades.
+        let first_page = TradesFilter {
+            limit: Some(2),
+            ..owner
+        };
+        assert_filtered_trades(&mut db, &first_page, &expected[..2]).await;
+        let second_page = TradesFilter {
+            after: Some(EventIndex {
+                block_number: 1,
+                log_index: 1,
+            }),
+            ..first_page
+        };
+        assert_filtered_trades(&mut db, &second_page, &expected[2..]).await;
+
+        // Filtering by block range.
+        let from_block = TradesFilter {
+            from_block: Some(2),
+            ..owner
+        };
+        assert_filtered_trades(&mut db, &from_block, &expected[2..]).await;
+        let to_block = TradesFilter {
+            to_block: Some(1),
+            ..owner
+        };
+        assert_filtered_trades(&mut db, &to_block, &expected[..2]).await;
+
+        // Filtering by token and transaction.
+        let sell_token = TradesFilter {
+            sell_token: Some(ByteArray([1; 20])),
+            ..owner
+        };
+        assert_filtered_trades(&mut db, &sell_token, &expected[1..2]).await;
+        let buy_token = TradesFilter {
+            buy_token: Some(ByteArray([1; 20])),
+            ..owner
+        };
+        assert_filtered_trades(&mut db, &buy_token, &[]).await;
+        let tx_hash = TradesFilter {
+            tx_hash: Some(ByteArray([1; 32])),
+            ..owner
+        };
+        assert_filtered_trades(&mut db, &tx_hash, &[]).await;
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_trade_without_matching_order() {
@@ -631,4 +802,56 @@ mod tests {
             Some(123)
         );
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_executed_amounts_before_block() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order_uid = ByteArray([1; 56]);
+        for (block_number, amount) in [(1, 10), (2, 20), (3, 40)] {
+            crate::events::append(
+                &mut db,
+                &[(
+                    EventIndex {
+                        block_number,
+                        log_index: 0,
+                    },
+                    Event::Trade(Trade {
+                        order_uid,
+                        sell_amount_including_fee: amount.into(),
+                        buy_amount: (2 * amount).into(),
+                        fee_amount: 1.into(),
+                    }),
+                )],
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            executed_amounts_before_block(&mut db, &order_uid, 3)
+                .await
+                .unwrap(),
+            ExecutedAmounts {
+                sum_sell: 30.into(),
+                sum_buy: 60.into(),
+                sum_fee: 2.into(),
+                executed_fee: 0.into(),
+            }
+        );
+        assert_eq!(
+            executed_amounts_before_block(&mut db, &order_uid, 1)
+                .await
+                .unwrap(),
+            ExecutedAmounts {
+                sum_sell: 0.into(),
+                sum_buy: 0.into(),
+                sum_fee: 0.into(),
+                executed_fee: 0.into(),
+            }
+        );
+    }
 }
@@ -0,0 +1,4 @@
+#[tokio::main]
+async fn main() {
+    driver::replay(std::env::args()).await;
+}
@@ -5,21 +5,34 @@ use {
             eth,
             liquidity::{self, balancer},
         },
-        infra::{self, blockchain::Ethereum},
+        infra::{
+            self,
+            blockchain::{Ethereum, contracts::deployment_information},
+        },
     },
     anyhow::{Context, Result},
+    chain::Chain,
     contracts::{
         BalancerV2ComposableStablePoolFactory,
+        BalancerV2ComposableStablePoolFactoryV3,
+        BalancerV2ComposableStablePoolFactoryV4,
+        BalancerV2ComposableStablePoolFactoryV5,
+        BalancerV2ComposableStablePoolFactoryV6,
         BalancerV2LiquidityBootstrappingPoolFactory,
+        BalancerV2NoProtocolFeeLiquidityBootstrappingPoolFactory,
         BalancerV2StablePoolFactoryV2,
         BalancerV2Vault,
+        BalancerV2WeightedPool2TokensFactory,
         BalancerV2WeightedPoolFactory,
         BalancerV2WeightedPoolFactoryV3,
+        BalancerV2WeightedPoolFactoryV4,
         GPv2Settlement,
     },
+    ethcontract::common::DeploymentInformation,
     ethrpc::block_stream::{BlockRetrieving, CurrentBlockWatcher},
     shared::{
         http_solver::model::TokenAmount,
+        maintenance::ServiceMaintenance,
         sources::balancer_v2::{
             BalancerFactoryKind,
             BalancerPoolFetcher,
@@ -113,9 +126,13 @@ async fn init_liquidity(
                 .map(|&factory| {
                     (
                         BalancerFactoryKind::Weighted,
-                        BalancerV2WeightedPoolFactory::at(&web3, factory.into())
-                            .raw_instance()
-                            .clone(),
+                        BalancerV2WeightedPoolFactory::with_deployment_info(
+                            &web3,
+                            factory.into(),
+                            factory_deployment_information(eth.chain(), factory),
+                        )
+                        .raw_instance()
+                        .clone(),
                     )
                 })
                 .collect::<Vec<_>>(),
@@ -125,9 +142,13 @@ async fn init_liquidity(
                 .map(|&factory| {
                     (
                         BalancerFactoryKind::WeightedV3,
-                        BalancerV2WeightedPoolFactoryV3::at(&web3, factory.into())
-                            .raw_instance()
-                            .clone(),
+                        BalancerV2WeightedPoolFactoryV3::with_deployment_info(
+                            &web3,
+                            factory.into(),
+                            factory_deployment_information(eth.chain(), factory),
+                        )
+                        .raw_instance()
+                        .clone(),
                     )
                 })
                 .collect::<Vec<_>>(),
@@ -137,9 +158,13 @@ async fn init_liquidity(
                 .map(|&factory| {
                     (
                         BalancerFactoryKind::StableV2,
-                        BalancerV2StablePoolFactoryV2::at(&web3, factory.into())
-                            .raw_instance()
-                            .clone(),
+                        BalancerV2StablePoolFactoryV2::with_deployment_info(
+                            &web3,
+                            factory.into(),
+                            factory_deployment_information(eth.chain(), factory),
+                        )
+                        .raw_instance()
+                        .clone(),
                     )
                 })
                 .collect::<Vec<_>>(),
@@ -149,9 +174,13 @@ async fn init_liquidity(
                 .map(|&factory| {
                     (
                         BalancerFactoryKind::LiquidityBootstrapping,
-                        BalancerV2LiquidityBootstrappingPoolFactory::at(&web3, factory.into())
-                            .raw_instance()
-                            .clone(),
+                        BalancerV2LiquidityBootstrappingPoolFactory::with_deployment_info(
+                            &web3,
+                            factory.into(),
+                            factory_deployment_information(eth.chain(), factory),
+                        )
+                        .raw_instance()
+                        .clone(),
                     )
                 })
                 .collect::<Vec<_>>(),
@@ -161,9 +190,13 @@ async fn init_liquidity(
                 .map(|&factory| {
                     (
                         BalancerFactoryKind::ComposableStable,
-                        BalancerV2ComposableStablePoolFactory::at(&web3, factory.into())
-                            .raw_instance()
-                            .clone(),
+                        BalancerV2ComposableStablePoolFactory::with_deployment_info(
+                            &web3,
+                            factory.into(),
+                            factory_deployment_information(eth.chain(), factory),
+                        )
+                        .raw_instance()
+                        .clone(),
                     )
                 })
                 .collect::<Vec<_>>(),
@@ -178,11 +211,12 @@ async fn init_liquidity(
 
     let balancer_pool_fetcher = Arc::new(
         BalancerPoolFetcher::new(
-            &config.graph_url,
+            config.graph_url.as_ref(),
             block_retriever.clone(),
             token_info_fetcher.clone(),
             boundary::liquidity::cache_config(),
             block_stream.clone(),
+            eth.reorgs(),
             boundary::liquidity::http_client(),
             web3.clone(),
             &contracts,
@@ -192,6 +226,10 @@ async fn init_liquidity(
         .context("failed to create balancer pool fetcher")?,
     );
 
+    let update_task = ServiceMaintenance::new(vec![balancer_pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
     Ok(BalancerV2Liquidity::new(
         web3,
         balancer_pool_fetcher,
@@ -199,3 +237,27 @@ async fn init_liquidity(
         contracts.vault,
     ))
 }
+
+/// Returns the deployment information of a known Balancer V2 pool factory, so
+/// that on-chain pool discovery can start indexing from its deployment block.
+fn factory_deployment_information(
+    chain: Chain,
+    factory: eth::ContractAddress,
+) -> Option<DeploymentInformation> {
+    [
+        BalancerV2WeightedPoolFactory::raw_contract(),
+        BalancerV2WeightedPool2TokensFactory::raw_contract(),
+        BalancerV2WeightedPoolFactoryV3::raw_contract(),
+        BalancerV2WeightedPoolFactoryV4::raw_contract(),
+        BalancerV2StablePoolFactoryV2::raw_contract(),
+        BalancerV2LiquidityBootstrappingPoolFactory::raw_contract(),
+        BalancerV2NoProtocolFeeLiquidityBootstrappingPoolFactory::raw_contract(),
+        BalancerV2ComposableStablePoolFactory::raw_contract(),
+        BalancerV2ComposableStablePoolFactoryV3::raw_contract(),
+        BalancerV2ComposableStablePoolFactoryV4::raw_contract(),
+        BalancerV2ComposableStablePoolFactoryV5::raw_contract(),
+        BalancerV2ComposableStablePoolFactoryV6::raw_contract(),
+    ]
+    .into_iter()
+    .find_map(|contract| deployment_information(contract, chain, factory))
+}
@@ -0,0 +1,150 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{self, curve},
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    contracts::{CurveCryptoSwapPool, CurveStableSwapPool},
+    ethrpc::block_stream::CurrentBlockWatcher,
+    shared::{
+        baseline_solver::BaselineSolvable,
+        sources::curve::{PoolKind, pool_fetching::CurvePoolFetcher},
+    },
+    solver::{
+        interactions::{CurveExchangeInteraction, CurvePool},
+        liquidity::{CurvePoolOrder, curve::CurveLiquidity},
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+pub fn to_domain(id: liquidity::Id, pool: CurvePoolOrder) -> Result<liquidity::Liquidity> {
+    let gas = pool.pool.gas_cost();
+    let pool = pool.pool;
+    anyhow::ensure!(
+        pool.tokens.len() == pool.balances.len(),
+        "mismatching Curve pool token and balance count",
+    );
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::U256::from(gas).into(),
+        kind: liquidity::Kind::Curve(curve::Pool {
+            address: pool.address.into(),
+            reserves: curve::Reserves::try_new(
+                pool.tokens
+                    .iter()
+                    .zip(&pool.balances)
+                    .map(|(token, balance)| eth::Asset {
+                        token: (*token).into(),
+                        amount: (*balance).into(),
+                    })
+                    .collect(),
+            )?,
+            invariant: match pool.kind {
+                PoolKind::Stable(parameters) => curve::Invariant::StableSwap(curve::StableSwap {
+                    amplification: parameters.amplification,
+                    fee: parameters.fee,
+                    rates: parameters.rates,
+                }),
+                PoolKind::Crypto(parameters) => curve::Invariant::CryptoSwap(curve::CryptoSwap {
+                    a: parameters.a,
+                    gamma: parameters.gamma,
+                    d: parameters.d,
+                    price_scale: parameters.price_scale,
+                    mid_fee: parameters.mid_fee,
+                    out_fee: parameters.out_fee,
+                    fee_gamma: parameters.fee_gamma,
+                    precisions: parameters.precisions,
+                }),
+            },
+        }),
+    })
+}
+
+pub fn to_interaction(
+    pool: &curve::Pool,
+    i: usize,
+    j: usize,
+    input: &eth::Asset,
+    min_output: &eth::Asset,
+    // Curve pools always send the output tokens to the caller, so the
+    // receiver is implicitly the settlement contract.
+    _receiver: &eth::Address,
+) -> eth::Interaction {
+    let web3 = ethrpc::dummy::web3();
+    let contract = match pool.invariant {
+        curve::Invariant::StableSwap(_) => {
+            CurvePool::Stable(CurveStableSwapPool::at(&web3, pool.address.into()))
+        }
+        curve::Invariant::CryptoSwap(_) => {
+            CurvePool::Crypto(CurveCryptoSwapPool::at(&web3, pool.address.into()))
+        }
+    };
+
+    let interaction = CurveExchangeInteraction {
+        pool: contract,
+        i,
+        j,
+        amount_in: input.amount.into(),
+        min_amount_out: min_output.amount.into(),
+    };
+
+    let (target, value, call_data) = shared::interaction::Interaction::encode(&interaction);
+
+    eth::Interaction {
+        target: target.into(),
+        value: value.into(),
+        call_data: call_data.0.into(),
+    }
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_stream: CurrentBlockWatcher,
+    config: &infra::liquidity::config::Curve,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("curve".into()));
+    let config = Arc::new(config.clone());
+    let init = move || {
+        let eth = eth.clone();
+        let block_stream = block_stream.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, &block_stream, &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "curve",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_stream: &CurrentBlockWatcher,
+    config: &infra::liquidity::config::Curve,
+) -> Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let pool_fetcher = Arc::new(
+        CurvePoolFetcher::new(
+            &web3,
+            config.registry.into(),
+            boundary::liquidity::cache_config(),
+            block_stream.clone(),
+            eth.reorgs(),
+        )
+        .await
+        .context("failed to create Curve pool fetcher")?,
+    );
+
+    Ok(CurveLiquidity::new(
+        web3,
+        pool_fetcher,
+        eth.contracts().settlement().address(),
+    ))
+}
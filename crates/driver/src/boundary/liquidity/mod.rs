@@ -5,7 +5,6 @@ use {
         infra::{self, blockchain::Ethereum},
     },
     anyhow::Result,
-    ethrpc::block_stream::CurrentBlockWatcher,
     futures::future,
     model::TokenPair,
     shared::{
@@ -27,6 +26,7 @@ use {
 };
 
 pub mod balancer;
+pub mod curve;
 pub mod swapr;
 pub mod uniswap;
 pub mod zeroex;
@@ -52,7 +52,6 @@ fn http_client() -> reqwest::Client {
 }
 
 pub struct Fetcher {
-    blocks: CurrentBlockWatcher,
     inner: LiquidityCollector,
     swapr_routers: HashSet<eth::ContractAddress>,
 }
@@ -62,6 +61,7 @@ impl Fetcher {
     pub async fn try_new(eth: &Ethereum, config: &infra::liquidity::Config) -> Result<Self> {
         let blocks = current_block::Arguments {
             block_stream_poll_interval: BLOCK_POLL_INTERVAL,
+            block_stream_ws_url: None,
         };
 
         let block_stream = eth.current_block();
@@ -92,12 +92,24 @@ impl Fetcher {
             })
             .collect();
 
+        let curve: Vec<_> = config
+            .curve
+            .iter()
+            .map(|config| curve::collector(eth, block_stream.clone(), config))
+            .collect();
+
         let uni_v3: Vec<_> = config
             .uniswap_v3
             .iter()
             .map(|config| uniswap::v3::collector(eth, block_retriever.clone(), config))
             .collect();
 
+        let uni_v4: Vec<_> = config
+            .uniswap_v4
+            .iter()
+            .map(|config| uniswap::v4::collector(eth, block_retriever.clone(), config))
+            .collect();
+
         let zeroex: Vec<_> = future::try_join_all(
             config
                 .zeroex
@@ -119,9 +131,8 @@ impl Fetcher {
         );
 
         Ok(Self {
-            blocks: block_stream.clone(),
             inner: LiquidityCollector {
-                liquidity_sources: [uni_v2, swapr, bal_v2, uni_v3, zeroex]
+                liquidity_sources: [uni_v2, swapr, bal_v2, curve, uni_v3, uni_v4, zeroex]
                     .into_iter()
                     .flatten()
                     .collect(),
@@ -147,10 +158,7 @@ impl Fetcher {
 
         let block = match block {
             infra::liquidity::AtBlock::Recent => recent_block_cache::Block::Recent,
-            infra::liquidity::AtBlock::Latest => {
-                let block_number = self.blocks.borrow().number;
-                recent_block_cache::Block::Number(block_number)
-            }
+            infra::liquidity::AtBlock::Number(block) => recent_block_cache::Block::Number(block.0),
         };
         let liquidity = self.inner.get_liquidity(pairs, block).await?;
 
@@ -171,6 +179,8 @@ impl Fetcher {
                     Liquidity::BalancerStable(pool) => balancer::v2::stable::to_domain(id, pool),
                     Liquidity::LimitOrder(pool) => zeroex::to_domain(id, pool),
                     Liquidity::Concentrated(pool) => uniswap::v3::to_domain(id, pool),
+                    Liquidity::Curve(pool) => curve::to_domain(id, pool),
+                    Liquidity::UniswapV4(pool) => uniswap::v4::to_domain(id, pool),
                 }
                 // Ignore "bad" liquidity - this allows the driver to continue
                 // solving with the other good stuff.
@@ -184,7 +194,6 @@ impl Fetcher {
 impl std::fmt::Debug for Fetcher {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Fetcher")
-            .field("blocks", &self.blocks)
             .field("inner", &"LiquidityCollector")
             .finish()
     }
@@ -152,6 +152,7 @@ where
             boundary::liquidity::cache_config(),
             Arc::new(pool_fetcher),
             blocks.clone(),
+            eth.reorgs(),
         )?)
     };
 
@@ -8,10 +8,13 @@ use {
                 uniswap::v3::{Fee, Liquidity, LiquidityNet, Pool, SqrtPrice, Tick},
             },
         },
-        infra::{self, blockchain::Ethereum},
+        infra::{
+            self,
+            blockchain::{Ethereum, contracts::deployment_information},
+        },
     },
     anyhow::Context,
-    contracts::{GPv2Settlement, UniswapV3SwapRouter},
+    contracts::{GPv2Settlement, IUniswapV3Factory, UniswapV3SwapRouter},
     ethrpc::block_stream::BlockRetrieving,
     shared::{
         http_solver::model::TokenAmount,
@@ -129,17 +132,30 @@ async fn init_liquidity(
     let web3 = boundary::web3(eth);
     let router = UniswapV3SwapRouter::at(&web3, config.router.0);
 
-    let pool_fetcher = Arc::new(
-        UniswapV3PoolFetcher::new(
-            &config.graph_url,
-            web3.clone(),
-            boundary::liquidity::http_client(),
-            block_retriever,
-            config.max_pools_to_initialize,
-        )
-        .await
-        .context("failed to initialise UniswapV3 liquidity")?,
-    );
+    let pool_fetcher = match &config.graph_url {
+        Some(graph_url) => {
+            UniswapV3PoolFetcher::new(
+                graph_url,
+                web3.clone(),
+                boundary::liquidity::http_client(),
+                block_retriever,
+                config.max_pools_to_initialize,
+            )
+            .await
+        }
+        None => {
+            // The factory is taken from the router so that Uniswap V3 forks
+            // work without further configuration.
+            let factory = eth::ContractAddress(router.factory().call().await?);
+            let factory = IUniswapV3Factory::with_deployment_info(
+                &web3,
+                factory.0,
+                deployment_information(IUniswapV3Factory::raw_contract(), eth.chain(), factory),
+            );
+            UniswapV3PoolFetcher::new_on_chain(factory, web3.clone(), block_retriever).await
+        }
+    };
+    let pool_fetcher = Arc::new(pool_fetcher.context("failed to initialise UniswapV3 liquidity")?);
 
     let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
         .run_maintenance_on_new_block(eth.current_block().clone());
@@ -0,0 +1,179 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{
+                self,
+                uniswap::{
+                    v3::{Fee, Liquidity, LiquidityNet, SqrtPrice, Tick},
+                    v4::{Id, Key, Pool},
+                },
+            },
+        },
+        infra::{
+            self,
+            blockchain::{Ethereum, contracts::deployment_information},
+        },
+    },
+    anyhow::Context,
+    contracts::{UniswapV4PoolManager, UniswapV4UniversalRouter},
+    ethrpc::block_stream::BlockRetrieving,
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::uniswap_v4::pool_fetching::{PoolKey, UniswapV4PoolFetcher},
+    },
+    solver::{
+        liquidity::{
+            UniswapV4PoolOrder,
+            uniswap_v4::{SettlementHandler, UniswapV4Liquidity},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::{collections::BTreeMap, sync::Arc},
+};
+
+pub fn to_domain(id: liquidity::Id, pool: UniswapV4PoolOrder) -> Result<liquidity::Liquidity> {
+    let handler = pool
+        .settlement_handling
+        .as_any()
+        .downcast_ref::<SettlementHandler>()
+        .expect("downcast uniswap v4 settlement handler");
+
+    let key = pool.pool.key;
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::Gas(pool.pool.gas_stats.mean_gas),
+        kind: liquidity::Kind::UniswapV4(Pool {
+            router: handler.router.address().into(),
+            id: Id(pool.pool.id),
+            key: Key {
+                currency0: key.currency0.into(),
+                currency1: key.currency1.into(),
+                fee: key.fee,
+                tick_spacing: key.tick_spacing,
+                hooks: key.hooks.into(),
+            },
+            tokens: liquidity::TokenPair::try_new(
+                pool.pool.tokens.0.into(),
+                pool.pool.tokens.1.into(),
+            )?,
+            sqrt_price: SqrtPrice(pool.pool.state.sqrt_price),
+            liquidity: Liquidity(pool.pool.state.liquidity.as_u128()),
+            tick: Tick(pool.pool.state.tick.try_into()?),
+            liquidity_net: pool
+                .pool
+                .state
+                .liquidity_net
+                .iter()
+                .map(|(key, value)| -> Result<_> {
+                    Ok((Tick(key.try_into()?), LiquidityNet(value.try_into()?)))
+                })
+                .collect::<Result<BTreeMap<_, _>>>()?,
+            fee: Fee(pool.pool.state.fee),
+            hooks_affect_swaps: key.hooks_affect_swaps(),
+        }),
+    })
+}
+
+pub fn to_interactions(
+    pool: &liquidity::uniswap::v4::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+) -> Result<Vec<eth::Interaction>> {
+    let web3 = ethrpc::dummy::web3();
+
+    // The handler expects the pool's tokens in the order of the pool key, with
+    // the native token replaced by its wrapped counterpart.
+    let currency1 = eth::TokenAddress::from(pool.key.currency1.0);
+    let currency0 = if pool.key.currency0.0.is_zero() {
+        let (a, b) = pool.tokens.get();
+        if a == currency1 { b } else { a }
+    } else {
+        eth::TokenAddress::from(pool.key.currency0.0)
+    };
+
+    let handler = SettlementHandler::new(
+        UniswapV4UniversalRouter::at(&web3, pool.router.0),
+        web3,
+        PoolKey {
+            currency0: pool.key.currency0.0,
+            currency1: pool.key.currency1.0,
+            fee: pool.key.fee,
+            tick_spacing: pool.key.tick_spacing,
+            hooks: pool.key.hooks.0,
+        },
+        (currency0.0.0, currency1.0.0),
+    );
+    let (transfer, swap) = handler.swap(
+        TokenAmount::new(input.0.token.into(), input.0.amount),
+        TokenAmount::new(output.0.token.into(), output.0.amount),
+    )?;
+
+    Ok([transfer.encode(), swap.encode()]
+        .into_iter()
+        .map(|encoded| eth::Interaction {
+            target: eth::Address(encoded.0),
+            value: eth::Ether(encoded.1),
+            call_data: crate::util::Bytes(encoded.2.0),
+        })
+        .collect())
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::UniswapV4,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("uniswapV4".into()));
+    let config = Arc::new(*config);
+    let init = move || {
+        let eth = eth.clone();
+        let block_retriever = block_retriever.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, block_retriever.clone(), &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "uniswap-v4",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::UniswapV4,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let router = UniswapV4UniversalRouter::at(&web3, config.router.0);
+    let manager = UniswapV4PoolManager::with_deployment_info(
+        &web3,
+        config.pool_manager.0,
+        deployment_information(
+            UniswapV4PoolManager::raw_contract(),
+            eth.chain(),
+            config.pool_manager,
+        ),
+    );
+
+    let pool_fetcher = Arc::new(
+        UniswapV4PoolFetcher::new(
+            manager,
+            eth.contracts().weth().address(),
+            web3.clone(),
+            block_retriever,
+        )
+        .await
+        .context("failed to initialise UniswapV4 liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(UniswapV4Liquidity::new(router, web3, pool_fetcher))
+}
@@ -93,19 +93,23 @@ impl Competition {
         competition
     }
 
-    /// Solve an auction as part of this competition.
-    pub async fn solve(&self, auction: Auction) -> Result<Option<Solved>, Error> {
+    /// Solve an auction as part of this competition. Returns the
+    /// best-scoring solutions sorted descending by score.
+    pub async fn solve(&self, auction: Auction) -> Result<Vec<Solved>, Error> {
         let auction = &self
             .bad_tokens
             .filter_unsupported_orders_in_auction(auction)
             .await;
 
+        // Pin the block so that the exact liquidity the solver gets to see can
+        // be reproduced from the archived auction.
+        let block = eth::BlockNo(self.eth.current_block().borrow().number);
         let liquidity = match self.solver.liquidity() {
             solver::Liquidity::Fetch => {
                 self.liquidity
                     .fetch(
                         &auction.liquidity_pairs(),
-                        infra::liquidity::AtBlock::Latest,
+                        infra::liquidity::AtBlock::Number(block),
                     )
                     .await
             }
@@ -115,7 +119,7 @@ impl Competition {
         // Fetch the solutions from the solver.
         let solutions = self
             .solver
-            .solve(auction, &liquidity)
+            .solve(auction, &liquidity, Some(block))
             .await
             .tap_err(|err| {
                 if err.is_timeout() {
@@ -249,53 +253,69 @@ impl Competition {
             observe::score(settlement, score);
         }
 
-        // Pick the best-scoring settlement.
-        let (mut score, settlement) = scores
+        // Pick the best-scoring settlements.
+        let max_solutions = self.solver.max_solutions_per_auction().get();
+        let (mut solved, settlements): (Vec<_>, Vec<_>) = scores
             .into_iter()
-            .max_by_key(|(score, _)| score.to_owned())
+            .sorted_by_key(|(score, _)| Reverse(score.to_owned()))
+            .take(max_solutions)
             .map(|(score, settlement)| {
                 (
-                    Solved {
+                    Some(Solved {
                         id: settlement.solution().clone(),
                         score,
                         trades: settlement.orders(),
                         prices: settlement.prices(),
                         gas: Some(settlement.gas.estimate),
-                    },
+                    }),
                     settlement,
                 )
             })
             .unzip();
 
-        let Some(settlement) = settlement else {
+        if settlements.is_empty() {
             // Don't wait for the deadline because we can't produce a solution anyway.
-            return Ok(score);
-        };
-        let solution_id = settlement.solution().get();
+            return Ok(Vec::new());
+        }
 
         {
             let mut lock = self.settlements.lock().unwrap();
-            lock.push_front(settlement.clone());
+            // Insert in reverse order to keep the best settlement at the front.
+            for settlement in settlements.iter().rev() {
+                lock.push_front(settlement.clone());
+            }
 
-            /// Number of solutions that may be cached at most.
-            const MAX_SOLUTION_STORAGE: usize = 5;
-            lock.truncate(MAX_SOLUTION_STORAGE);
+            /// Number of auctions for which solutions may be cached at most.
+            const MAX_AUCTION_STORAGE: usize = 5;
+            lock.truncate(MAX_AUCTION_STORAGE * max_solutions);
         }
 
-        // Re-simulate the solution on every new block until the deadline ends to make
-        // sure we actually submit a working solution close to when the winner
-        // gets picked by the procotol.
+        // Re-simulate the solutions on every new block until the deadline ends to
+        // make sure we actually submit working solutions close to when the winners
+        // get picked by the procotol.
         if let Ok(remaining) = auction.deadline().driver().remaining() {
-            let score_ref = &mut score;
+            let solved_ref = &mut solved;
+            let settlements = &settlements;
             let simulate_on_new_blocks = async move {
                 let mut stream =
                     ethrpc::block_stream::into_stream(self.eth.current_block().clone());
                 while let Some(block) = stream.next().await {
-                    if let Err(infra::simulator::Error::Revert(err)) =
-                        self.simulate_settlement(&settlement).await
-                    {
+                    let simulations = solved_ref
+                        .iter()
+                        .zip(settlements)
+                        .enumerate()
+                        .filter(|(_, (solved, _))| solved.is_some())
+                        .map(|(i, (_, settlement))| async move {
+                            (i, self.simulate_settlement(settlement).await)
+                        });
+                    for (i, result) in futures::future::join_all(simulations).await {
+                        let Err(infra::simulator::Error::Revert(err)) = result else {
+                            continue;
+                        };
+                        let settlement = &settlements[i];
+                        let solution_id = settlement.solution().get();
                         observe::winner_voided(block, &err);
-                        *score_ref = None;
+                        solved_ref[i] = None;
                         self.settlements
                             .lock()
                             .unwrap()
@@ -307,6 +327,8 @@ impl Competition {
                             &infra::simulator::Error::Revert(err),
                             true,
                         );
+                    }
+                    if solved_ref.iter().all(Option::is_none) {
                         return;
                     }
                 }
@@ -314,7 +336,7 @@ impl Competition {
             let _ = tokio::time::timeout(remaining, simulate_on_new_blocks).await;
         }
 
-        Ok(score)
+        Ok(solved.into_iter().flatten().collect())
     }
 
     pub async fn reveal(
@@ -1,4 +1,4 @@
-use crate::domain::competition::order;
+use crate::domain::{competition::order, eth};
 
 #[derive(Clone, Debug)]
 pub enum FeePolicy {
@@ -44,4 +44,23 @@ pub enum FeePolicy {
         /// fee.
         factor: f64,
     },
+    /// Like [`FeePolicy::Surplus`] but with a progressive factor: every tier
+    /// charges its own factor on the part of the surplus above its threshold
+    /// and below the threshold of the next tier.
+    TieredSurplus {
+        /// Tiers sorted by threshold where the first threshold is always 0.
+        /// Thresholds are denominated in the surplus token and refer to a
+        /// full execution of the order.
+        tiers: Vec<SurplusTier>,
+        /// Cap protocol fee with a percentage of the order's volume.
+        max_volume_factor: f64,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SurplusTier {
+    /// Surplus from which on the tier's factor applies.
+    pub threshold: eth::U256,
+    /// Factor of the surplus within the tier the protocol charges as a fee.
+    pub factor: f64,
 }
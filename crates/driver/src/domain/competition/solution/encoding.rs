@@ -280,16 +280,18 @@ pub fn tx(
             continue;
         }
 
-        interactions.push(match interaction {
-            competition::solution::Interaction::Custom(interaction) => eth::Interaction {
-                value: interaction.value,
-                target: interaction.target.into(),
-                call_data: interaction.call_data.clone(),
-            },
-            competition::solution::Interaction::Liquidity(liquidity) => {
-                liquidity_interaction(liquidity, &slippage, contracts.settlement())?
+        match interaction {
+            competition::solution::Interaction::Custom(interaction) => {
+                interactions.push(eth::Interaction {
+                    value: interaction.value,
+                    target: interaction.target.into(),
+                    call_data: interaction.call_data.clone(),
+                })
             }
-        })
+            competition::solution::Interaction::Liquidity(liquidity) => interactions.extend(
+                liquidity_interaction(liquidity, &slippage, contracts.settlement())?,
+            ),
+        }
     }
 
     // Encode WETH unwrap
@@ -336,11 +338,14 @@ pub fn tx(
     })
 }
 
+/// Encodes the interactions for executing a liquidity interaction. Most
+/// liquidity is executed with a single interaction, but some require
+/// additional setup (such as transferring the input tokens first).
 pub fn liquidity_interaction(
     liquidity: &Liquidity,
     slippage: &slippage::Parameters,
     settlement: &contracts::GPv2Settlement,
-) -> Result<eth::Interaction, Error> {
+) -> Result<Vec<eth::Interaction>, Error> {
     let (input, output) = slippage.apply_to(&slippage::Interaction {
         input: liquidity.input,
         output: liquidity.output,
@@ -362,8 +367,36 @@ pub fn liquidity_interaction(
         liquidity::Kind::Swapr(pool) => pool
             .swap(&input, &output, &settlement.address().into())
             .ok(),
+        liquidity::Kind::Curve(pool) => {
+            // Curve pools only support exact input swaps. So instead of
+            // increasing the input amount, scale down the minimum output amount
+            // by the same slippage factor.
+            let min_output = output
+                .0
+                .amount
+                .0
+                .checked_mul(liquidity.input.amount.0)
+                .and_then(|amount| amount.checked_div(input.0.amount.0));
+            min_output.and_then(|amount| {
+                pool.swap(
+                    &liquidity.input,
+                    &eth::Asset {
+                        token: output.0.token,
+                        amount: amount.into(),
+                    },
+                    &settlement.address().into(),
+                )
+                .ok()
+            })
+        }
+        liquidity::Kind::UniswapV4(pool) => {
+            return pool
+                .swap(&input, &output)
+                .map_err(|_| Error::InvalidInteractionExecution(liquidity.clone()));
+        }
         liquidity::Kind::ZeroEx(limit_order) => limit_order.to_interaction(&input).ok(),
     }
+    .map(|interaction| vec![interaction])
     .ok_or(Error::InvalidInteractionExecution(liquidity.clone()))
 }
 
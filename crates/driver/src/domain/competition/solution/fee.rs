@@ -125,6 +125,17 @@ impl Fulfillment {
                 )?;
                 self.calculate_fee(price_limits, prices, *factor, *max_volume_factor)
             }
+            FeePolicy::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => {
+                let fee_from_surplus = self.fee_from_tiered_surplus(prices, tiers)?;
+                let fee_from_volume = self.fee_from_volume(prices, *max_volume_factor)?;
+                // take the smaller of the two
+                let protocol_fee = std::cmp::min(fee_from_surplus, fee_from_volume);
+                tracing::debug!(uid=?self.order().uid, ?fee_from_surplus, ?fee_from_volume, ?protocol_fee, executed=?self.executed(), surplus_fee=?self.surplus_fee(), "calculated protocol fee");
+                Ok(protocol_fee)
+            }
             FeePolicy::Volume { factor } => {
                 let fee_from_volume = self.fee_from_volume(prices, *factor)?;
                 tracing::debug!(uid=?self.order().uid, ?fee_from_volume, executed=?self.executed(), surplus_fee=?self.surplus_fee(), "calculated protocol fee");
@@ -168,6 +179,35 @@ impl Fulfillment {
             .map_err(Into::into)
     }
 
+    /// Computes the progressive surplus fee in the surplus token by applying
+    /// each tier's factor to the part of the surplus over the limit price that
+    /// falls into the tier.
+    ///
+    /// Tier thresholds refer to the fully executed order so they get scaled
+    /// down to the executed amount for partially fillable orders.
+    fn fee_from_tiered_surplus(
+        &self,
+        prices: ClearingPrices,
+        tiers: &[order::fees::SurplusTier],
+    ) -> Result<eth::TokenAmount, Error> {
+        let surplus = self.surplus_over_reference_price(
+            self.order().sell.amount.0,
+            self.order().buy.amount.0,
+            prices,
+        )?;
+        let (executed, full) = match self.order().side {
+            Side::Buy => (self.executed().0, self.order().buy.amount.0),
+            Side::Sell => (
+                self.executed()
+                    .0
+                    .checked_add(self.surplus_fee().unwrap_or_default().0)
+                    .ok_or(Math::Overflow)?,
+                self.order().sell.amount.0,
+            ),
+        };
+        Ok(fee_from_tiers(surplus, tiers, executed, full)?)
+    }
+
     /// Computes the volume based fee in surplus token
     ///
     /// The volume is defined as a full sell amount (including fees) for buy
@@ -285,6 +325,43 @@ pub fn adjust_quote_to_order_limits(order: Order, quote: Quote) -> Result<PriceL
     }
 }
 
+/// Protocol fee as a progressive cut of `surplus`: every tier takes its factor
+/// of the part of the surplus between its threshold and the next one.
+///
+/// Thresholds get scaled by `executed / full`, see
+/// `Fulfillment::fee_from_tiered_surplus`.
+pub fn fee_from_tiers(
+    surplus: eth::TokenAmount,
+    tiers: &[order::fees::SurplusTier],
+    executed: eth::U256,
+    full: eth::U256,
+) -> Result<eth::TokenAmount, Math> {
+    let scale = |threshold: eth::U256| -> Result<eth::U256, Math> {
+        threshold
+            .checked_mul(executed)
+            .ok_or(Math::Overflow)?
+            .checked_div(full)
+            .ok_or(Math::DivisionByZero)
+    };
+
+    let mut fee = eth::U256::zero();
+    for (i, tier) in tiers.iter().enumerate() {
+        let lower = scale(tier.threshold)?;
+        if surplus.0 <= lower {
+            break;
+        }
+        let upper = match tiers.get(i + 1) {
+            Some(next) => std::cmp::min(scale(next.threshold)?, surplus.0),
+            None => surplus.0,
+        };
+        let tier_fee = eth::TokenAmount(upper - lower)
+            .apply_factor(tier.factor)
+            .ok_or(Math::Overflow)?;
+        fee = fee.checked_add(tier_fee.0).ok_or(Math::Overflow)?;
+    }
+    Ok(fee.into())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("orders with non solver determined gas cost fees are not supported")]
@@ -42,7 +42,11 @@ impl Interaction {
                     liquidity::Kind::BalancerV2Stable(pool) => pool.vault.into(),
                     liquidity::Kind::BalancerV2Weighted(pool) => pool.vault.into(),
                     liquidity::Kind::Swapr(pool) => pool.base.router.into(),
+                    liquidity::Kind::Curve(pool) => pool.address.into(),
                     liquidity::Kind::ZeroEx(pool) => pool.zeroex.address().into(),
+                    // The input tokens are transferred to the router instead
+                    // of being pulled by it, so no approval is needed.
+                    liquidity::Kind::UniswapV4(_) => return Vec::new(),
                 };
                 // As a gas optimization, we always approve the max amount possible. This
                 // minimizes the number of approvals necessary, and therefore
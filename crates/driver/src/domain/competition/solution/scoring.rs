@@ -281,6 +281,16 @@ impl Trade {
                     self.volume_fee(*max_volume_factor)?.amount,
                 )
             }
+            FeePolicy::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => {
+                let surplus = self.surplus_over_limit_price()?;
+                std::cmp::min(
+                    self.tiered_surplus_fee(surplus, tiers)?.amount,
+                    self.volume_fee(*max_volume_factor)?.amount,
+                )
+            }
             FeePolicy::Volume { factor } => self.volume_fee(*factor)?.amount,
         };
         Ok(eth::Asset {
@@ -352,6 +362,86 @@ impl Trade {
         })
     }
 
+    /// Protocol fee as a progressive cut of surplus, denominated in SURPLUS
+    /// token
+    fn tiered_surplus_fee(
+        &self,
+        surplus: eth::Asset,
+        tiers: &[order::fees::SurplusTier],
+    ) -> Result<eth::Asset, Error> {
+        // Like for `surplus_fee` the protocol fee has to be derived from the
+        // surplus after fee. The fee of each tier is linear in the surplus within
+        // that tier, so every tier covers a range of surplus after fee:
+        //    [threshold - fee(threshold), next_threshold - fee(next_threshold))
+        // Within that range the fee is derived like for `surplus_fee`:
+        //    fee = fee(threshold) + (surplus_after_fee - (threshold -
+        // fee(threshold))) * factor / (1 - factor)
+        //
+        // Thresholds refer to the fully executed order so they get scaled down to
+        // the executed amount to treat partial fills consistently.
+        let full = match self.side {
+            Side::Buy => self.buy.amount.0,
+            Side::Sell => self.sell.amount.0,
+        };
+        let scale = |threshold: eth::U256| {
+            threshold
+                .checked_mul(self.executed.0)
+                .ok_or(Math::Overflow)?
+                .checked_div(full)
+                .ok_or(Math::DivisionByZero)
+        };
+        let tier_fee = |amount: eth::U256, factor: f64| {
+            eth::TokenAmount(amount)
+                .apply_factor(factor)
+                .ok_or(Math::Overflow)
+        };
+
+        let surplus_after_fee = surplus.amount.0;
+        let mut fee_at_threshold = eth::U256::zero();
+        for (i, tier) in tiers.iter().enumerate() {
+            let threshold = scale(tier.threshold)?;
+            let fee_at_next_threshold = match tiers.get(i + 1) {
+                Some(next) => {
+                    let next_threshold = scale(next.threshold)?;
+                    let fee = fee_at_threshold
+                        .checked_add(
+                            tier_fee(next_threshold.saturating_sub(threshold), tier.factor)?.0,
+                        )
+                        .ok_or(Math::Overflow)?;
+                    Some((next_threshold, fee))
+                }
+                None => None,
+            };
+            let within_tier = fee_at_next_threshold.is_none_or(|(next_threshold, fee)| {
+                surplus_after_fee < next_threshold.saturating_sub(fee)
+            });
+            if within_tier {
+                let tier_start = threshold.saturating_sub(fee_at_threshold);
+                let fee = fee_at_threshold
+                    .checked_add(
+                        tier_fee(
+                            surplus_after_fee.saturating_sub(tier_start),
+                            tier.factor / (1.0 - tier.factor),
+                        )?
+                        .0,
+                    )
+                    .ok_or(Math::Overflow)?;
+                return Ok(eth::Asset {
+                    token: surplus.token,
+                    amount: fee.into(),
+                });
+            }
+            if let Some((_, fee)) = fee_at_next_threshold {
+                fee_at_threshold = fee;
+            }
+        }
+
+        Ok(eth::Asset {
+            token: surplus.token,
+            amount: Default::default(),
+        })
+    }
+
     /// Protocol fee as a cut of the trade volume, denominated in SURPLUS token
     fn volume_fee(&self, factor: f64) -> Result<eth::Asset, Error> {
         // Volume fee is specified as a `factor` from raw volume (before fee). Since
@@ -433,3 +523,87 @@ pub enum Error {
     #[error("scoring: failed to calculate custom price for the applied fee policy {0:?}")]
     Scoring(#[source] error::Scoring),
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::domain::competition::order::fees::SurplusTier};
+
+    /// The tiered surplus fee is derived from the surplus before fee in the
+    /// driver and inverted from the surplus after fee when scoring, so both
+    /// have to agree on the fee.
+    #[test]
+    fn tiered_surplus_fee_inverts_fee_from_tiers() {
+        let tiers = [
+            SurplusTier {
+                threshold: 0.into(),
+                factor: 0.5,
+            },
+            SurplusTier {
+                threshold: eth::U256::exp10(16),
+                factor: 0.1,
+            },
+            SurplusTier {
+                threshold: eth::U256::exp10(17),
+                factor: 0.02,
+            },
+        ];
+        let sell = eth::Asset {
+            amount: eth::U256::exp10(18).into(),
+            token: eth::H160([1; 20]).into(),
+        };
+        let buy = eth::Asset {
+            amount: eth::U256::exp10(18).into(),
+            token: eth::H160([2; 20]).into(),
+        };
+
+        // fully and partially executed sell orders
+        for executed in [eth::U256::exp10(18), eth::U256::exp10(18) / 3] {
+            let trade = Trade::new(
+                sell,
+                buy,
+                Side::Sell,
+                order::TargetAmount(executed),
+                CustomClearingPrices {
+                    sell: buy.amount.0,
+                    buy: sell.amount.0,
+                },
+                vec![],
+            );
+            // surplus before fee below, at and above each threshold
+            for surplus in [
+                eth::U256::one(),
+                eth::U256::exp10(15) * 5,
+                eth::U256::exp10(16),
+                eth::U256::exp10(16) + 1,
+                eth::U256::exp10(16) * 2,
+                eth::U256::exp10(17),
+                eth::U256::exp10(17) + 7,
+                eth::U256::exp10(18),
+                eth::U256::exp10(21),
+            ] {
+                let fee = fee::fee_from_tiers(surplus.into(), &tiers, executed, sell.amount.0)
+                    .unwrap()
+                    .0;
+                let inverted = trade
+                    .tiered_surplus_fee(
+                        eth::Asset {
+                            amount: (surplus - fee).into(),
+                            token: buy.token,
+                        },
+                        &tiers,
+                    )
+                    .unwrap()
+                    .amount
+                    .0;
+
+                // `apply_factor` works with 18 decimals of the factor, so
+                // the two computations can differ by rounding errors only
+                let tolerance = fee / eth::U256::exp10(15) + 2;
+                assert!(
+                    fee.max(inverted) - fee.min(inverted) <= tolerance,
+                    "surplus {surplus}, executed {executed}: fee {fee} != {inverted}"
+                );
+            }
+        }
+    }
+}
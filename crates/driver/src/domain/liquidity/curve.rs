@@ -0,0 +1,130 @@
+use {
+    crate::{
+        boundary,
+        domain::{eth, liquidity},
+    },
+    itertools::Itertools,
+};
+
+/// Liquidity data tied to a Curve pool.
+///
+/// Curve pools come in two flavours: StableSwap pools [^1] (including meta
+/// pools, where one of the coins is the LP token of another pool) designed
+/// for assets that trade close to a peg, and CryptoSwap pools [^2] for volatile
+/// asset pairs.
+///
+/// [^1]: <https://classic.curve.fi/files/stableswap-paper.pdf>
+/// [^2]: <https://classic.curve.fi/files/crypto-pools-paper.pdf>
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub address: eth::ContractAddress,
+    pub reserves: Reserves,
+    pub invariant: Invariant,
+}
+
+impl Pool {
+    /// Encodes a pool swap as an interaction. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens do not belong to the pool.
+    ///
+    /// Note that unlike other AMMs, Curve pools only support swapping exact
+    /// input amounts, so the interaction sells the full `input` amount and
+    /// requires at least `min_output` in return.
+    pub fn swap(
+        &self,
+        input: &eth::Asset,
+        min_output: &eth::Asset,
+        receiver: &eth::Address,
+    ) -> Result<eth::Interaction, liquidity::InvalidSwap> {
+        let i = self
+            .reserves
+            .index(&input.token)
+            .ok_or(liquidity::InvalidSwap)?;
+        let j = self
+            .reserves
+            .index(&min_output.token)
+            .ok_or(liquidity::InvalidSwap)?;
+        if i == j {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::curve::to_interaction(
+            self, i, j, input, min_output, receiver,
+        ))
+    }
+}
+
+/// Curve pool reserves.
+///
+/// This is an ordered collection of the pool coins and their balances. The
+/// order matches the coin indices used by the pool contract.
+#[derive(Clone, Debug)]
+pub struct Reserves(Vec<eth::Asset>);
+
+impl Reserves {
+    /// Creates new Curve pool reserves, returns `Err` if the specified token
+    /// reserves are invalid, specifically, if there are fewer than two or
+    /// duplicate tokens.
+    pub fn try_new(reserves: Vec<eth::Asset>) -> Result<Self, InvalidReserves> {
+        if reserves.len() < 2 || !reserves.iter().map(|r| r.token).all_unique() {
+            return Err(InvalidReserves);
+        }
+
+        Ok(Self(reserves))
+    }
+
+    /// Returns the pool index of the specified token.
+    pub fn index(&self, token: &eth::TokenAddress) -> Option<usize> {
+        self.0.iter().position(|r| r.token == *token)
+    }
+
+    /// Returns an iterator over the reserve tokens.
+    pub fn tokens(&self) -> impl Iterator<Item = eth::TokenAddress> + '_ {
+        self.iter().map(|r| r.token)
+    }
+
+    /// Returns an iterator over the reserve assets.
+    pub fn iter(&self) -> impl Iterator<Item = eth::Asset> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid Curve token reserves")]
+pub struct InvalidReserves;
+
+/// The invariant used by the pool along with its parameters.
+#[derive(Clone, Debug)]
+pub enum Invariant {
+    StableSwap(StableSwap),
+    CryptoSwap(CryptoSwap),
+}
+
+/// StableSwap pool parameters.
+#[derive(Clone, Debug)]
+pub struct StableSwap {
+    /// The amplification coefficient, multiplied by the `A_PRECISION` of 100.
+    pub amplification: eth::U256,
+    /// The swap fee with 10 decimals of precision.
+    pub fee: eth::U256,
+    /// The rates used to normalize each coin's balance to 18 decimals, scaled
+    /// by `1e18`.
+    pub rates: Vec<eth::U256>,
+}
+
+/// CryptoSwap pool parameters.
+#[derive(Clone, Debug)]
+pub struct CryptoSwap {
+    /// The amplification coefficient as reported by the pool.
+    pub a: eth::U256,
+    pub gamma: eth::U256,
+    /// The pool invariant.
+    pub d: eth::U256,
+    /// The price of the second coin denominated in the first coin.
+    pub price_scale: eth::U256,
+    pub mid_fee: eth::U256,
+    pub out_fee: eth::U256,
+    pub fee_gamma: eth::U256,
+    /// Factors normalizing each coin's balance to 18 decimals.
+    pub precisions: [eth::U256; 2],
+}
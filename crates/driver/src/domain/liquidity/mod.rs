@@ -8,6 +8,7 @@ use {
 };
 
 pub mod balancer;
+pub mod curve;
 pub mod swapr;
 pub mod uniswap;
 pub mod zeroex;
@@ -47,9 +48,11 @@ pub struct ExactOutput(pub eth::Asset);
 pub enum Kind {
     UniswapV2(uniswap::v2::Pool),
     UniswapV3(uniswap::v3::Pool),
+    UniswapV4(uniswap::v4::Pool),
     BalancerV2Stable(balancer::v2::stable::Pool),
     BalancerV2Weighted(balancer::v2::weighted::Pool),
     Swapr(swapr::Pool),
+    Curve(curve::Pool),
     ZeroEx(zeroex::LimitOrder),
 }
 
@@ -58,9 +61,11 @@ impl From<&Kind> for &'static str {
         match *val {
             Kind::UniswapV2(_) => "UniswapV2",
             Kind::UniswapV3(_) => "UniswapV3",
+            Kind::UniswapV4(_) => "UniswapV4",
             Kind::BalancerV2Stable(_) => "BalancerV2Stable",
             Kind::BalancerV2Weighted(_) => "BalancerV2Weighted",
             Kind::Swapr(_) => "Swapr",
+            Kind::Curve(_) => "Curve",
             Kind::ZeroEx(_) => "ZeroExLimitOrder",
         }
     }
@@ -0,0 +1,85 @@
+use {
+    crate::{
+        boundary,
+        domain::{
+            eth,
+            liquidity::{
+                self,
+                InvalidSwap,
+                uniswap::v3::{Fee, Liquidity, LiquidityNet, SqrtPrice, Tick},
+            },
+        },
+    },
+    derive_more::Debug,
+    std::collections::BTreeMap,
+};
+
+/// A Uniswap V4 concentrated liquidity pool.
+///
+/// Unlike previous versions, all Uniswap V4 pools are managed by a single pool
+/// manager contract. Pools may be attached to hooks, contracts that run
+/// custom logic around pool operations and can change the outcome of
+/// swaps [^1].
+///
+/// [^1]: <https://docs.uniswap.org/contracts/v4/concepts/hooks>
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub router: eth::ContractAddress,
+    pub id: Id,
+    pub key: Key,
+    /// The pool's tokens, where the native token is represented by its
+    /// wrapped counterpart.
+    pub tokens: liquidity::TokenPair,
+    pub sqrt_price: SqrtPrice,
+    pub liquidity: Liquidity,
+    pub tick: Tick,
+    #[debug(ignore)]
+    pub liquidity_net: BTreeMap<Tick, LiquidityNet>,
+    pub fee: Fee,
+    /// Whether the pool's hooks can change the outcome of swaps, in which case
+    /// swap amounts can't be computed from the pool state alone.
+    pub hooks_affect_swaps: bool,
+}
+
+/// The identifier of a pool within the pool manager.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Id(pub eth::H256);
+
+/// The parameters identifying a pool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Key {
+    /// The lower currency of the pool, where the zero address is the native
+    /// token.
+    pub currency0: eth::Address,
+    pub currency1: eth::Address,
+    /// The LP fee in hundredths of a basis point, or a flag signalling that
+    /// the fee is set dynamically by the pool's hooks.
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: eth::ContractAddress,
+}
+
+impl Pool {
+    /// Encodes a pool swap as interactions. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens don't correspond to the pool's token pair.
+    ///
+    /// The swap is executed through the Universal Router, which requires the
+    /// input tokens to be transferred to it first. The output tokens are sent
+    /// to the caller of the router, i.e. the settlement contract.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+    ) -> Result<Vec<eth::Interaction>, InvalidSwap> {
+        let tokens_match = (input.0.token == self.tokens.0 && output.0.token == self.tokens.1)
+            || (input.0.token == self.tokens.1 && output.0.token == self.tokens.0);
+
+        if !tokens_match {
+            return Err(InvalidSwap);
+        }
+
+        boundary::liquidity::uniswap::v4::to_interactions(self, input, output)
+            .map_err(|_| InvalidSwap)
+    }
+}
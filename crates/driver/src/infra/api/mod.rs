@@ -74,6 +74,7 @@ impl Api {
             let name = solver.name().clone();
             let router = axum::Router::new();
             let router = routes::info(router);
+            let router = routes::block(router);
             let router = routes::quote(router);
             let router = routes::solve(router);
             let router = routes::reveal(router);
@@ -0,0 +1,19 @@
+use {crate::infra::api::State, primitive_types::H256, serde::Serialize};
+
+pub(in crate::infra::api) fn block(app: axum::Router<State>) -> axum::Router<State> {
+    app.route("/block", axum::routing::get(route))
+}
+
+async fn route(state: axum::extract::State<State>) -> axum::Json<Block> {
+    let block = *state.eth().current_block().borrow();
+    axum::Json(Block {
+        number: block.number,
+        hash: block.hash,
+    })
+}
+
+#[derive(Serialize)]
+struct Block {
+    number: u64,
+    hash: H256,
+}
@@ -1,3 +1,4 @@
+mod block;
 mod healthz;
 mod info;
 mod metrics;
@@ -8,6 +9,7 @@ mod settle;
 mod solve;
 
 pub(super) use {
+    block::block,
     healthz::healthz,
     info::info,
     metrics::metrics,
@@ -132,6 +132,19 @@ impl SolveRequest {
                                 max_volume_factor,
                                 quote: quote.into_domain(order.sell_token, order.buy_token),
                             },
+                            FeePolicy::TieredSurplus {
+                                tiers,
+                                max_volume_factor,
+                            } => competition::order::FeePolicy::TieredSurplus {
+                                tiers: tiers
+                                    .into_iter()
+                                    .map(|tier| competition::order::fees::SurplusTier {
+                                        threshold: tier.threshold,
+                                        factor: tier.factor,
+                                    })
+                                    .collect(),
+                                max_volume_factor,
+                            },
                             FeePolicy::Volume { factor } => {
                                 competition::order::FeePolicy::Volume { factor }
                             }
@@ -324,9 +337,23 @@ enum FeePolicy {
         quote: Quote,
     },
     #[serde(rename_all = "camelCase")]
+    TieredSurplus {
+        tiers: Vec<SurplusTier>,
+        max_volume_factor: f64,
+    },
+    #[serde(rename_all = "camelCase")]
     Volume { factor: f64 },
 }
 
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SurplusTier {
+    #[serde_as(as = "serialize::U256")]
+    threshold: eth::U256,
+    factor: f64,
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -2,7 +2,7 @@ use {
     crate::{boundary, domain::eth, infra::blockchain::Ethereum},
     chain::Chain,
     contracts::FlashLoanRouter,
-    ethcontract::dyns::DynWeb3,
+    ethcontract::{common::DeploymentInformation, dyns::DynWeb3},
     ethrpc::block_stream::CurrentBlockWatcher,
     thiserror::Error,
     url::Url,
@@ -188,6 +188,21 @@ pub fn deployment_address(
     )
 }
 
+/// Returns the deployment information of a contract for the specified
+/// network, or `None` if the contract is not known to be deployed at the
+/// specified address on that network.
+pub fn deployment_information(
+    contract: &ethcontract::Contract,
+    chain: Chain,
+    address: eth::ContractAddress,
+) -> Option<DeploymentInformation> {
+    let network = contract.networks.get(&chain.id().to_string())?;
+    if network.address != address.0 {
+        return None;
+    }
+    network.deployment_information
+}
+
 /// A trait for initializing contract instances with dynamic addresses.
 pub trait ContractAt {
     fn at(eth: &Ethereum, address: eth::ContractAddress) -> Self;
@@ -3,7 +3,7 @@ use {
     crate::{boundary, domain::eth},
     chain::Chain,
     ethcontract::{dyns::DynWeb3, errors::ExecutionError},
-    ethrpc::block_stream::CurrentBlockWatcher,
+    ethrpc::block_stream::{CurrentBlockWatcher, Reorgs},
     std::{fmt, sync::Arc},
     thiserror::Error,
     url::Url,
@@ -21,12 +21,14 @@ pub struct Rpc {
     web3: DynWeb3,
     chain: Chain,
     url: Url,
+    ws_url: Option<Url>,
 }
 
 impl Rpc {
     /// Instantiate an RPC client to an Ethereum (or Ethereum-compatible) node
-    /// at the specifed URL.
-    pub async fn try_new(url: &url::Url) -> Result<Self, RpcError> {
+    /// at the specifed URL. If a WebSocket URL is given, it is used to
+    /// subscribe to new blocks.
+    pub async fn try_new(url: &url::Url, ws_url: Option<&url::Url>) -> Result<Self, RpcError> {
         let web3 = boundary::buffered_web3_client(url);
         let chain = Chain::try_from(web3.eth().chain_id().await?)?;
 
@@ -34,6 +36,7 @@ impl Rpc {
             web3,
             chain,
             url: url.clone(),
+            ws_url: ws_url.cloned(),
         })
     }
 
@@ -68,6 +71,7 @@ struct Inner {
     contracts: Contracts,
     gas: Arc<GasPriceEstimator>,
     current_block: CurrentBlockWatcher,
+    reorgs: Reorgs,
 }
 
 impl Ethereum {
@@ -83,10 +87,16 @@ impl Ethereum {
         gas: Arc<GasPriceEstimator>,
         archive_node_url: Option<&Url>,
     ) -> Self {
-        let Rpc { web3, chain, url } = rpc;
+        let Rpc {
+            web3,
+            chain,
+            url,
+            ws_url,
+        } = rpc;
 
-        let current_block_stream =
-            ethrpc::block_stream::current_block_stream(url, std::time::Duration::from_millis(500))
+        let poll_interval = std::time::Duration::from_millis(500);
+        let (current_block_stream, reorgs) =
+            ethrpc::block_stream::reorg_aware_block_stream(url, ws_url, poll_interval)
                 .await
                 .expect("couldn't initialize current block stream");
 
@@ -103,6 +113,7 @@ impl Ethereum {
         Self {
             inner: Arc::new(Inner {
                 current_block: current_block_stream,
+                reorgs,
                 chain,
                 contracts,
                 gas,
@@ -146,6 +157,12 @@ impl Ethereum {
         &self.inner.current_block
     }
 
+    /// Returns a handle to get notified about reorgs detected by the current
+    /// block stream.
+    pub fn reorgs(&self) -> &Reorgs {
+        &self.inner.reorgs
+    }
+
     /// Create access list used by a transaction.
     pub async fn create_access_list<T>(&self, tx: T) -> Result<eth::AccessList, Error>
     where
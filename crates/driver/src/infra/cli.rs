@@ -21,6 +21,11 @@ pub struct Args {
     #[clap(long, env)]
     pub ethrpc: Url,
 
+    /// The node WebSocket endpoint. If set, the driver subscribes to new
+    /// blocks instead of polling the RPC API for them.
+    #[clap(long, env)]
+    pub ethrpc_ws: Option<Url>,
+
     /// Path to the driver configuration file. This file should be in TOML
     /// format. For an example see
     /// https://github.com/cowprotocol/services/blob/main/crates/driver/example.toml.
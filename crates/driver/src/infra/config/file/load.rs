@@ -136,6 +136,7 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         .metrics_strategy_token_freeze_time,
                 },
                 settle_queue_size: solver_config.settle_queue_size,
+                max_solutions_per_auction: solver_config.max_solutions_per_auction,
                 flashloans_enabled: config.flashloans_enabled,
                 flashloan_default_lender: eth::Address(config.flashloans_default_lender),
             }
@@ -222,7 +223,7 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         max_pools_to_initialize,
                         ..match preset {
                             file::UniswapV3Preset::UniswapV3 => {
-                                liquidity::config::UniswapV3::uniswap_v3(&graph_url, chain)
+                                liquidity::config::UniswapV3::uniswap_v3(graph_url.as_ref(), chain)
                             }
                         }
                         .expect("no Uniswap V3 preset for current network")
@@ -238,6 +239,27 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     },
                 })
                 .collect(),
+            uniswap_v4: config
+                .liquidity
+                .uniswap_v4
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::UniswapV4Config::Preset { preset } => match preset {
+                        file::UniswapV4Preset::UniswapV4 => {
+                            liquidity::config::UniswapV4::uniswap_v4(chain)
+                        }
+                    }
+                    .expect("no Uniswap V4 preset for current network"),
+                    file::UniswapV4Config::Manual {
+                        pool_manager,
+                        router,
+                    } => liquidity::config::UniswapV4 {
+                        pool_manager: pool_manager.into(),
+                        router: router.into(),
+                    },
+                })
+                .collect(),
             balancer_v2: config
                 .liquidity
                 .balancer_v2
@@ -252,7 +274,10 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         pool_deny_list: pool_deny_list.clone(),
                         ..match preset {
                             file::BalancerV2Preset::BalancerV2 => {
-                                liquidity::config::BalancerV2::balancer_v2(&graph_url, chain)
+                                liquidity::config::BalancerV2::balancer_v2(
+                                    graph_url.as_ref(),
+                                    chain,
+                                )
                             }
                         }
                         .expect("no Balancer V2 preset for current network")
@@ -290,6 +315,21 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     },
                 })
                 .collect(),
+            curve: config
+                .liquidity
+                .curve
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::CurveConfig::Preset { preset } => match preset {
+                        file::CurvePreset::Curve => liquidity::config::Curve::curve(chain),
+                    }
+                    .expect("no Curve preset for current network"),
+                    file::CurveConfig::Manual { registry } => liquidity::config::Curve {
+                        registry: registry.into(),
+                    },
+                })
+                .collect(),
             zeroex: config
                 .liquidity
                 .zeroex
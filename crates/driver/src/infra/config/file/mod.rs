@@ -5,7 +5,7 @@ use {
     serde::{Deserialize, Deserializer, Serialize},
     serde_with::serde_as,
     solver::solver::Arn,
-    std::{collections::HashMap, str::FromStr, time::Duration},
+    std::{collections::HashMap, num::NonZeroUsize, str::FromStr, time::Duration},
 };
 
 mod load;
@@ -293,6 +293,11 @@ struct SolverConfig {
     /// before the driver starts dropping new `/solve` requests.
     #[serde(default = "default_settle_queue_size")]
     settle_queue_size: usize,
+
+    /// The maximum number of best-scoring solutions returned per auction.
+    /// Every returned solution stays revealable and settleable by its ID.
+    #[serde(default = "default_max_solutions_per_auction")]
+    max_solutions_per_auction: NonZeroUsize,
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -309,8 +314,12 @@ pub struct S3 {
     /// Name of the AWS S3 bucket in which the auctions will be stored
     pub bucket: String,
 
-    /// Prepended to the auction id to form the final instance filename on AWS
-    /// S3 bucket. Something like "staging/mainnet/"
+    /// Prepended to the keys of the archived objects on AWS S3 bucket.
+    /// Something like "staging/mainnet/". The auction sent to the solver
+    /// engine is stored at `{prefix}{auction_id}/{block}/auction.json` and the
+    /// solutions it proposed at `{prefix}{auction_id}/{block}/solutions.json`,
+    /// where `block` is the block the liquidity was fetched at. Auctions used
+    /// to be stored at `{prefix}{auction_id}.json`.
     pub prefix: String,
 }
 
@@ -450,10 +459,18 @@ struct LiquidityConfig {
     #[serde(default)]
     uniswap_v3: Vec<UniswapV3Config>,
 
+    /// Liquidity provided by a Uniswap V4 pool manager.
+    #[serde(default)]
+    uniswap_v4: Vec<UniswapV4Config>,
+
     /// Liquidity provided by a Balancer V2 compatible contract.
     #[serde(default)]
     balancer_v2: Vec<BalancerV2Config>,
 
+    /// Liquidity provided by Curve pools.
+    #[serde(default)]
+    curve: Vec<CurveConfig>,
+
     /// Liquidity provided by 0x API.
     #[serde(default)]
     zeroex: Option<ZeroExConfig>,
@@ -531,7 +548,9 @@ enum UniswapV3Config {
         #[serde(default = "uniswap_v3::default_max_pools_to_initialize")]
         max_pools_to_initialize: usize,
 
-        graph_url: Url,
+        /// The URL used to connect to uniswap v3 subgraph client. When not
+        /// set, pools are discovered by indexing the factory events instead.
+        graph_url: Option<Url>,
     },
 
     #[serde(rename_all = "kebab-case")]
@@ -543,8 +562,9 @@ enum UniswapV3Config {
         #[serde(default = "uniswap_v3::default_max_pools_to_initialize")]
         max_pools_to_initialize: usize,
 
-        /// The URL used to connect to uniswap v3 subgraph client.
-        graph_url: Url,
+        /// The URL used to connect to uniswap v3 subgraph client. When not
+        /// set, pools are discovered by indexing the factory events instead.
+        graph_url: Option<Url>,
     },
 }
 
@@ -560,6 +580,28 @@ mod uniswap_v3 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum UniswapV4Config {
+    #[serde(rename_all = "kebab-case")]
+    Preset { preset: UniswapV4Preset },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the Uniswap V4 pool manager.
+        pool_manager: eth::H160,
+
+        /// The address of the Universal Router used for executing swaps.
+        router: eth::H160,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum UniswapV4Preset {
+    UniswapV4,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
 enum BalancerV2Config {
@@ -571,8 +613,9 @@ enum BalancerV2Config {
         #[serde(default)]
         pool_deny_list: Vec<eth::H256>,
 
-        /// The URL used to connect to balancer v2 subgraph client.
-        graph_url: Url,
+        /// The URL used to connect to balancer v2 subgraph client. When not
+        /// set, pools are discovered by indexing the factory events instead.
+        graph_url: Option<Url>,
     },
 
     #[serde(rename_all = "kebab-case")]
@@ -607,8 +650,9 @@ enum BalancerV2Config {
         #[serde(default)]
         pool_deny_list: Vec<eth::H256>,
 
-        /// The URL used to connect to balancer v2 subgraph client.
-        graph_url: Url,
+        /// The URL used to connect to balancer v2 subgraph client. When not
+        /// set, pools are discovered by indexing the factory events instead.
+        graph_url: Option<Url>,
     },
 }
 
@@ -618,6 +662,25 @@ enum BalancerV2Preset {
     BalancerV2,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum CurveConfig {
+    #[serde(rename_all = "kebab-case")]
+    Preset { preset: CurvePreset },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the Curve meta registry contract.
+        registry: eth::H160,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum CurvePreset {
+    Curve,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct ZeroExConfig {
@@ -865,6 +928,11 @@ fn default_settle_queue_size() -> usize {
     2
 }
 
+/// Only return the best solution by default.
+fn default_max_solutions_per_auction() -> NonZeroUsize {
+    NonZeroUsize::MIN
+}
+
 fn default_metrics_bad_token_detector_log_only() -> bool {
     true
 }
@@ -26,10 +26,16 @@ pub struct Config {
     /// for.
     pub uniswap_v3: Vec<UniswapV3>,
 
+    /// The collection of Uniswap V4 pool managers to fetch liquidity for.
+    pub uniswap_v4: Vec<UniswapV4>,
+
     /// The collection of Balancer V2 compatible exchanges to fetch liquidity
     /// for.
     pub balancer_v2: Vec<BalancerV2>,
 
+    /// The collection of Curve meta registries to fetch liquidity for.
+    pub curve: Vec<Curve>,
+
     /// 0x liquidity fetcher.
     pub zeroex: Option<ZeroEx>,
 }
@@ -146,18 +152,43 @@ pub struct UniswapV3 {
     /// How many pools should be initialized during start up.
     pub max_pools_to_initialize: usize,
 
-    /// The URL used to connect to uniswap v3 subgraph client.
-    pub graph_url: Url,
+    /// The URL used to connect to uniswap v3 subgraph client. Pools are
+    /// discovered on-chain from the factory events if it is not set.
+    pub graph_url: Option<Url>,
 }
 
 impl UniswapV3 {
     /// Returns the liquidity configuration for Uniswap V3.
     #[allow(clippy::self_named_constructors)]
-    pub fn uniswap_v3(graph_url: &Url, chain: Chain) -> Option<Self> {
+    pub fn uniswap_v3(graph_url: Option<&Url>, chain: Chain) -> Option<Self> {
         Some(Self {
             router: deployment_address(contracts::UniswapV3SwapRouter::raw_contract(), chain)?,
             max_pools_to_initialize: 100,
-            graph_url: graph_url.clone(),
+            graph_url: graph_url.cloned(),
+        })
+    }
+}
+
+/// Uniswap V4 liquidity fetching options.
+#[derive(Clone, Copy, Debug)]
+pub struct UniswapV4 {
+    /// The address of the pool manager holding all Uniswap V4 pools.
+    pub pool_manager: eth::ContractAddress,
+
+    /// The address of the Universal Router used for executing swaps.
+    pub router: eth::ContractAddress,
+}
+
+impl UniswapV4 {
+    /// Returns the liquidity configuration for Uniswap V4.
+    #[allow(clippy::self_named_constructors)]
+    pub fn uniswap_v4(chain: Chain) -> Option<Self> {
+        Some(Self {
+            pool_manager: deployment_address(
+                contracts::UniswapV4PoolManager::raw_contract(),
+                chain,
+            )?,
+            router: deployment_address(contracts::UniswapV4UniversalRouter::raw_contract(), chain)?,
         })
     }
 }
@@ -190,14 +221,15 @@ pub struct BalancerV2 {
     /// ignored.
     pub pool_deny_list: Vec<eth::H256>,
 
-    /// The base URL used to connect to balancer v2 subgraph client.
-    pub graph_url: Url,
+    /// The base URL used to connect to balancer v2 subgraph client. Pools are
+    /// discovered on-chain from the factory events if it is not set.
+    pub graph_url: Option<Url>,
 }
 
 impl BalancerV2 {
     /// Returns the liquidity configuration for Balancer V2.
     #[allow(clippy::self_named_constructors)]
-    pub fn balancer_v2(graph_url: &Url, chain: Chain) -> Option<Self> {
+    pub fn balancer_v2(graph_url: Option<&Url>, chain: Chain) -> Option<Self> {
         let factory_addresses =
             |contracts: &[&ethcontract::Contract]| -> Vec<eth::ContractAddress> {
                 contracts
@@ -230,7 +262,24 @@ impl BalancerV2 {
                 contracts::BalancerV2ComposableStablePoolFactoryV6::raw_contract(),
             ]),
             pool_deny_list: Vec::new(),
-            graph_url: graph_url.clone(),
+            graph_url: graph_url.cloned(),
+        })
+    }
+}
+
+/// Curve liquidity fetching options.
+#[derive(Clone, Copy, Debug)]
+pub struct Curve {
+    /// The address of the Curve meta registry used for discovering pools.
+    pub registry: eth::ContractAddress,
+}
+
+impl Curve {
+    /// Returns the liquidity configuration for Curve.
+    #[allow(clippy::self_named_constructors)]
+    pub fn curve(chain: Chain) -> Option<Self> {
+        Some(Self {
+            registry: deployment_address(contracts::CurveMetaRegistry::raw_contract(), chain)?,
         })
     }
 }
@@ -1,7 +1,7 @@
 use {
     crate::{
         boundary,
-        domain::liquidity,
+        domain::{eth, liquidity},
         infra::{self, blockchain::Ethereum, observe},
     },
     std::{collections::HashSet, sync::Arc},
@@ -25,8 +25,10 @@ pub enum AtBlock {
     /// computed anyway. At worse, we might provide a slightly sub-optimal
     /// route in some cases, but this is an acceptable trade-off.
     Recent,
-    /// Fetches liquidity liquidity for the latest state of the blockchain.
-    Latest,
+    /// Fetches liquidity for the state of the blockchain at the specified
+    /// block. Auctions are solved with liquidity at the latest block, which
+    /// gets recorded so that the liquidity can be reproduced later on.
+    Number(eth::BlockNo),
 }
 
 impl Fetcher {
@@ -217,16 +217,16 @@ pub fn settled(solver: &solver::Name, result: &Result<competition::Settled, comp
 }
 
 /// Observe the result of solving an auction.
-pub fn solved(solver: &solver::Name, result: &Result<Option<Solved>, competition::Error>) {
+pub fn solved(solver: &solver::Name, result: &Result<Vec<Solved>, competition::Error>) {
     match result {
-        Ok(Some(solved)) => {
+        Ok(solved) if !solved.is_empty() => {
             tracing::info!(?solved, "solved auction");
             metrics::get()
                 .solutions
                 .with_label_values(&[solver.as_str(), "Success"])
                 .inc();
         }
-        Ok(None) => {
+        Ok(_) => {
             tracing::debug!("no solution found");
             metrics::get()
                 .solutions
@@ -1,6 +1,6 @@
 use {
     crate::{
-        domain::competition::auction::Id,
+        domain::{competition::auction::Id, eth},
         infra::{config::file, solver::Config},
     },
     serde::Serialize,
@@ -14,8 +14,12 @@ pub struct S3 {
     /// Name of the AWS S3 bucket in which the auctions will be stored
     pub bucket: String,
 
-    /// Prepended to the auction id to form the final instance filename on AWS
-    /// S3 bucket. Something like "staging/mainnet/"
+    /// Prepended to the keys of the archived objects on AWS S3 bucket.
+    /// Something like "staging/mainnet/". The auction sent to the solver
+    /// engine is stored at `{prefix}{auction_id}/{block}/auction.json` and the
+    /// solutions it proposed at `{prefix}{auction_id}/{block}/solutions.json`,
+    /// where `block` is the block the liquidity was fetched at. Auctions used
+    /// to be stored at `{prefix}{auction_id}.json`.
     pub prefix: String,
 }
 
@@ -53,27 +57,39 @@ impl Persistence {
         }
     }
 
-    /// Saves the given auction with liquidity with fire and forget mentality
-    /// (non-blocking operation)
-    pub fn archive_auction(&self, auction_id: Id, body: impl Serialize) {
+    /// Saves the given auction with liquidity, as it was sent to the solver
+    /// engine, with fire and forget mentality (non-blocking operation). The
+    /// auction is stored together with the block at which the liquidity was
+    /// fetched so that it can be replayed later on.
+    pub fn archive_auction(&self, auction_id: Id, block: eth::BlockNo, body: impl Serialize) {
+        self.archive(key(auction_id, block, "auction"), body);
+    }
+
+    /// Saves the solutions the solver engine proposed for an archived auction
+    /// with fire and forget mentality (non-blocking operation).
+    pub fn archive_solutions(&self, auction_id: Id, block: eth::BlockNo, body: impl Serialize) {
+        self.archive(key(auction_id, block, "solutions"), body);
+    }
+
+    fn archive(&self, key: String, body: impl Serialize) {
         let Some(uploader) = self.s3.clone() else {
             return;
         };
         let body = match to_value(body) {
             Ok(body) => body,
             Err(err) => {
-                tracing::error!(?err, "failed to parse the auction with liquidity to JSON");
+                tracing::error!(?err, key, "failed to serialize archive to JSON");
                 return;
             }
         };
         tokio::spawn(
             async move {
-                match uploader.upload(auction_id.to_string(), body).await {
+                match uploader.upload(key, body).await {
                     Ok(key) => {
-                        tracing::debug!(?key, "uploaded auction with liquidity to s3");
+                        tracing::debug!(?key, "uploaded archive to s3");
                     }
                     Err(err) => {
-                        tracing::warn!(?err, "failed to upload auction to s3");
+                        tracing::warn!(?err, "failed to upload archive to s3");
                     }
                 }
             }
@@ -81,3 +97,9 @@ impl Persistence {
         );
     }
 }
+
+/// Returns the name of an archived object. All objects of an auction share
+/// the same prefix, for example `123/19000000/auction`.
+fn key(auction_id: Id, block: eth::BlockNo, name: &str) -> String {
+    format!("{auction_id}/{}/{name}", block.0)
+}
@@ -50,9 +50,11 @@ pub fn new(
         .flat_map(|liquidity| match &liquidity.kind {
             liquidity::Kind::UniswapV2(pool) => pool.reserves.iter().map(|r| r.token).collect(),
             liquidity::Kind::UniswapV3(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
+            liquidity::Kind::UniswapV4(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
             liquidity::Kind::BalancerV2Stable(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::BalancerV2Weighted(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::Swapr(pool) => pool.base.reserves.iter().map(|r| r.token).collect(),
+            liquidity::Kind::Curve(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::ZeroEx(limit_order) => {
                 vec![
                     limit_order.order.maker_token.into(),
@@ -222,6 +224,26 @@ pub fn new(
                         },
                     )
                 }
+                liquidity::Kind::UniswapV4(pool) => solvers_dto::auction::Liquidity::UniswapV4(
+                    solvers_dto::auction::UniswapV4Pool {
+                        id: liquidity.id.0.to_string(),
+                        pool_id: pool.id.0,
+                        router: pool.router.into(),
+                        gas_estimate: liquidity.gas.0,
+                        tokens: vec![pool.tokens.get().0.into(), pool.tokens.get().1.into()],
+                        sqrt_price: pool.sqrt_price.0,
+                        liquidity: pool.liquidity.0,
+                        tick: pool.tick.0,
+                        liquidity_net: pool
+                            .liquidity_net
+                            .iter()
+                            .map(|(key, value)| (key.0, value.0))
+                            .collect(),
+                        fee: rational_to_big_decimal(&pool.fee.0),
+                        hooks: pool.key.hooks.into(),
+                        hooks_affect_swaps: pool.hooks_affect_swaps,
+                    },
+                ),
                 liquidity::Kind::BalancerV2Stable(pool) => {
                     solvers_dto::auction::Liquidity::Stable(solvers_dto::auction::StablePool {
                         id: liquidity.id.0.to_string(),
@@ -303,6 +325,46 @@ pub fn new(
                         fee: bigdecimal::BigDecimal::new(pool.fee.bps().into(), 4),
                     },
                 ),
+                liquidity::Kind::Curve(pool) => {
+                    solvers_dto::auction::Liquidity::Curve(solvers_dto::auction::CurvePool {
+                        id: liquidity.id.0.to_string(),
+                        address: pool.address.into(),
+                        gas_estimate: liquidity.gas.into(),
+                        tokens: pool
+                            .reserves
+                            .iter()
+                            .map(|asset| solvers_dto::auction::CurveReserve {
+                                token: asset.token.into(),
+                                balance: asset.amount.into(),
+                            })
+                            .collect(),
+                        invariant: match &pool.invariant {
+                            liquidity::curve::Invariant::StableSwap(params) => {
+                                solvers_dto::auction::CurveInvariant::StableSwap(
+                                    solvers_dto::auction::CurveStableSwap {
+                                        amplification: params.amplification,
+                                        fee: params.fee,
+                                        rates: params.rates.clone(),
+                                    },
+                                )
+                            }
+                            liquidity::curve::Invariant::CryptoSwap(params) => {
+                                solvers_dto::auction::CurveInvariant::CryptoSwap(
+                                    solvers_dto::auction::CurveCryptoSwap {
+                                        a: params.a,
+                                        gamma: params.gamma,
+                                        d: params.d,
+                                        price_scale: params.price_scale,
+                                        mid_fee: params.mid_fee,
+                                        out_fee: params.out_fee,
+                                        fee_gamma: params.fee_gamma,
+                                        precisions: params.precisions,
+                                    },
+                                )
+                            }
+                        },
+                    })
+                }
                 liquidity::Kind::ZeroEx(limit_order) => {
                     solvers_dto::auction::Liquidity::LimitOrder(
                         solvers_dto::auction::ForeignLimitOrder {
@@ -355,6 +417,19 @@ fn fee_policy_from_domain(value: fees::FeePolicy) -> solvers_dto::auction::FeePo
             },
         },
         order::FeePolicy::Volume { factor } => solvers_dto::auction::FeePolicy::Volume { factor },
+        order::FeePolicy::TieredSurplus {
+            tiers,
+            max_volume_factor,
+        } => solvers_dto::auction::FeePolicy::TieredSurplus {
+            tiers: tiers
+                .into_iter()
+                .map(|tier| solvers_dto::auction::SurplusTier {
+                    threshold: tier.threshold,
+                    factor: tier.factor,
+                })
+                .collect(),
+            max_volume_factor,
+        },
     }
 }
 
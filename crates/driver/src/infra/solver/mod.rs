@@ -22,7 +22,7 @@ use {
     derive_more::{From, Into},
     num::BigRational,
     reqwest::header::HeaderName,
-    std::{collections::HashMap, time::Duration},
+    std::{collections::HashMap, num::NonZeroUsize, time::Duration},
     tap::TapFallible,
     thiserror::Error,
     tracing::Instrument,
@@ -127,6 +127,8 @@ pub struct Config {
     pub bad_token_detection: BadTokenDetection,
     /// Max size of the pending settlements queue.
     pub settle_queue_size: usize,
+    /// Max number of best-scoring solutions returned per auction.
+    pub max_solutions_per_auction: NonZeroUsize,
     /// Whether flashloan hints should be sent to the solver.
     pub flashloans_enabled: bool,
     /// If no lender is specified in flashloan hint, use default one
@@ -217,12 +219,18 @@ impl Solver {
         self.config.settle_queue_size
     }
 
+    pub fn max_solutions_per_auction(&self) -> NonZeroUsize {
+        self.config.max_solutions_per_auction
+    }
+
     /// Make a POST request instructing the solver to solve an auction.
-    /// Allocates at most `timeout` time for the solving.
+    /// Allocates at most `timeout` time for the solving. `block` is the block
+    /// at which the liquidity was fetched, if it is known exactly.
     pub async fn solve(
         &self,
         auction: &Auction,
         liquidity: &[liquidity::Liquidity],
+        block: Option<eth::BlockNo>,
     ) -> Result<Vec<Solution>, Error> {
         // Fetch the solutions from the solver.
         let weth = self.eth.contracts().weth_address();
@@ -237,8 +245,9 @@ impl Solver {
         );
         // Only auctions with IDs are real auctions (/quote requests don't have an ID,
         // and it makes no sense to store them)
-        if let Some(id) = auction.id() {
-            self.persistence.archive_auction(id, &auction_dto);
+        let archive = auction.id().zip(block);
+        if let Some((id, block)) = archive {
+            self.persistence.archive_auction(id, block, &auction_dto);
         };
         let body = serde_json::to_string(&auction_dto).unwrap();
         let url = shared::url::join(&self.config.endpoint, "solve");
@@ -259,6 +268,9 @@ impl Solver {
         let res = res?;
         let res: solvers_dto::solution::Solutions = serde_json::from_str(&res)
             .tap_err(|err| tracing::warn!(res, ?err, "failed to parse solver response"))?;
+        if let Some((id, block)) = archive {
+            self.persistence.archive_solutions(id, block, &res);
+        }
         let solutions = dto::Solutions::from(res).into_domain(
             auction,
             liquidity,
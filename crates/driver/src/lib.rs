@@ -6,10 +6,14 @@
 pub mod boundary;
 pub mod domain;
 pub mod infra;
+mod replay;
 mod run;
 pub mod util;
 
 #[cfg(test)]
 mod tests;
 
-pub use self::run::{run, start};
+pub use self::{
+    replay::replay,
+    run::{run, start},
+};
@@ -0,0 +1,267 @@
+//! Replays an auction archived by the driver against a solver engine. The
+//! archive contains the auction exactly as it was sent to the solver engine,
+//! including the liquidity fetched at the archived block, so the solver engine
+//! gets to see the same state as during the competition.
+
+use {
+    crate::util,
+    anyhow::{Context, Result},
+    clap::Parser,
+    flate2::read::GzDecoder,
+    primitive_types::{H160, U256},
+    reqwest::Url,
+    solvers_dto::{
+        auction::Auction,
+        solution::{Solution, Solutions, Trade},
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        io::Read,
+        path::{Path, PathBuf},
+        time::Duration,
+    },
+};
+
+#[derive(Debug, clap::Parser)]
+struct Args {
+    /// The log filter.
+    #[clap(long, env, default_value = "warn,driver=info")]
+    log: String,
+
+    /// Path to the archived auction. Files can be either plain or gzip
+    /// compressed JSON, as stored on S3.
+    #[clap(long)]
+    auction: PathBuf,
+
+    /// Path to the archived solutions of the auction. When set, the solutions
+    /// of the replay are compared to them.
+    #[clap(long)]
+    solutions: Option<PathBuf>,
+
+    /// The solver engine endpoint to replay the auction against.
+    #[clap(long)]
+    solver: Url,
+
+    /// The time the solver engine gets for solving the auction.
+    #[clap(long, default_value = "15s", value_parser = humantime::parse_duration)]
+    timeout: Duration,
+
+    /// The maximum size of the solver engine response.
+    #[clap(long, default_value = "10000000")]
+    response_size_limit_max_bytes: usize,
+}
+
+/// Entry-point of the replay command.
+pub async fn replay(args: impl Iterator<Item = String>) {
+    observe::panic_hook::install();
+    let args = Args::parse_from(args);
+    crate::infra::observe::init(&args.log);
+
+    if let Err(err) = run(&args).await {
+        tracing::error!(?err, "failed to replay auction");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: &Args) -> Result<()> {
+    let mut auction: Auction = read(&args.auction)?;
+    // The archived deadline has long passed, so give the solver engine a fresh
+    // one.
+    auction.deadline = chrono::Utc::now() + chrono::Duration::from_std(args.timeout)?;
+
+    let url = shared::url::join(&args.solver, "solve");
+    let req = reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(&auction)?)
+        .timeout(args.timeout);
+    let res = util::http::send(args.response_size_limit_max_bytes, req)
+        .await
+        .context("solver engine request failed")?;
+    let replayed: Solutions =
+        serde_json::from_str(&res).context("failed to parse solver engine response")?;
+
+    match &args.solutions {
+        Some(path) => {
+            let archived: Solutions = read(path)?;
+            let diff = diff(&archived, &replayed);
+            if diff.is_empty() {
+                println!("replayed solutions match the archived ones");
+            }
+            for line in diff {
+                println!("{line}");
+            }
+        }
+        None => println!("{}", serde_json::to_string_pretty(&replayed)?),
+    }
+    Ok(())
+}
+
+/// Reads an archived JSON object, decompressing it if needed.
+fn read<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+    let bytes = std::fs::read(path).with_context(|| format!("failed to read {path:?}"))?;
+    let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+        let mut decoded = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decoded)
+            .with_context(|| format!("failed to decompress {path:?}"))?;
+        decoded
+    } else {
+        bytes
+    };
+    serde_json::from_slice(&bytes).with_context(|| format!("failed to parse {path:?}"))
+}
+
+/// The parts of a solution that are relevant when comparing outcomes.
+#[derive(Debug)]
+struct Outcome {
+    prices: BTreeMap<H160, U256>,
+    /// Executed amounts by traded order.
+    trades: BTreeMap<String, U256>,
+    interactions: usize,
+    gas: Option<u64>,
+}
+
+impl From<&Solution> for Outcome {
+    fn from(solution: &Solution) -> Self {
+        Self {
+            prices: solution.prices.iter().map(|(k, v)| (*k, *v)).collect(),
+            trades: solution
+                .trades
+                .iter()
+                .map(|trade| match trade {
+                    Trade::Fulfillment(trade) => (
+                        format!("0x{}", hex::encode(trade.order)),
+                        trade.executed_amount,
+                    ),
+                    Trade::Jit(trade) => (
+                        format!(
+                            "jit {:?} -> {:?}",
+                            trade.order.sell_token, trade.order.buy_token
+                        ),
+                        trade.executed_amount,
+                    ),
+                })
+                .collect(),
+            interactions: solution.pre_interactions.len()
+                + solution.interactions.len()
+                + solution.post_interactions.len(),
+            gas: solution.gas,
+        }
+    }
+}
+
+/// Compares the archived with the replayed solutions. Solutions are matched
+/// by their position in the response. Returns one line per difference.
+fn diff(archived: &Solutions, replayed: &Solutions) -> Vec<String> {
+    let mut lines = Vec::new();
+    let count = archived.solutions.len().max(replayed.solutions.len());
+    for i in 0..count {
+        let (archived, replayed) = match (archived.solutions.get(i), replayed.solutions.get(i)) {
+            (Some(archived), Some(replayed)) => (Outcome::from(archived), Outcome::from(replayed)),
+            (Some(_), None) => {
+                lines.push(format!("solution {i}: missing in replay"));
+                continue;
+            }
+            (None, Some(_)) => {
+                lines.push(format!("solution {i}: new in replay"));
+                continue;
+            }
+            (None, None) => unreachable!(),
+        };
+
+        diff_maps(
+            &mut lines,
+            &format!("solution {i}: price"),
+            &archived.prices,
+            &replayed.prices,
+        );
+        diff_maps(
+            &mut lines,
+            &format!("solution {i}: trade"),
+            &archived.trades,
+            &replayed.trades,
+        );
+        if archived.interactions != replayed.interactions {
+            lines.push(format!(
+                "solution {i}: interactions {} -> {}",
+                archived.interactions, replayed.interactions
+            ));
+        }
+        if archived.gas != replayed.gas {
+            lines.push(format!(
+                "solution {i}: gas {:?} -> {:?}",
+                archived.gas, replayed.gas
+            ));
+        }
+    }
+    lines
+}
+
+fn diff_maps<K: Ord + std::fmt::Debug>(
+    lines: &mut Vec<String>,
+    prefix: &str,
+    archived: &BTreeMap<K, U256>,
+    replayed: &BTreeMap<K, U256>,
+) {
+    let keys = archived
+        .keys()
+        .chain(replayed.keys())
+        .collect::<BTreeSet<_>>();
+    for key in keys {
+        match (archived.get(key), replayed.get(key)) {
+            (Some(a), Some(b)) if a == b => (),
+            (a, b) => lines.push(format!(
+                "{prefix} {key:?}: {} -> {}",
+                a.map_or("none".to_string(), ToString::to_string),
+                b.map_or("none".to_string(), ToString::to_string),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solvers_dto::solution::{Fulfillment, Solution},
+    };
+
+    fn solution(price: u64, executed: u64) -> Solution {
+        Solution {
+            id: 0,
+            prices: [(H160([1; 20]), price.into())].into_iter().collect(),
+            trades: vec![Trade::Fulfillment(Fulfillment {
+                order: [2; 56],
+                executed_amount: executed.into(),
+                fee: None,
+            })],
+            pre_interactions: Vec::new(),
+            interactions: Vec::new(),
+            post_interactions: Vec::new(),
+            gas: None,
+            flashloans: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn diffs_solutions() {
+        let archived = Solutions {
+            solutions: vec![solution(10, 100), solution(10, 100)],
+        };
+        let replayed = Solutions {
+            solutions: vec![solution(10, 90)],
+        };
+
+        assert_eq!(
+            diff(&archived, &replayed),
+            vec![
+                format!("solution 0: trade \"0x{}\": 100 -> 90", "02".repeat(56)),
+                "solution 1: missing in replay".to_string(),
+            ]
+        );
+        assert!(diff(&archived, &archived).is_empty());
+    }
+}
@@ -139,7 +139,7 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
 }
 
 async fn ethrpc(args: &cli::Args) -> blockchain::Rpc {
-    blockchain::Rpc::try_new(&args.ethrpc)
+    blockchain::Rpc::try_new(&args.ethrpc, args.ethrpc_ws.as_ref())
         .await
         .expect("connect ethereum RPC")
 }
@@ -1,6 +1,6 @@
 use crate::tests::{
     setup,
-    setup::{ab_order, ab_pool, ab_solution},
+    setup::{ab_order, ab_pool, ab_solution, test_solver},
 };
 
 /// Test that the best-scoring solution is picked when the /solve endpoint
@@ -38,3 +38,30 @@ async fn invalid() {
     let id = test.solve().await.ok().orders(&[order]).id();
     test.reveal(id).await.ok().calldata();
 }
+
+/// Test that the best-scoring solutions are returned in descending order and
+/// that each of them can be revealed when the solver is configured to return
+/// more than one solution per auction.
+#[tokio::test]
+#[ignore]
+async fn ranked() {
+    let order = ab_order();
+    let test = setup()
+        .solvers(vec![test_solver().max_solutions_per_auction(2)])
+        .pool(ab_pool())
+        .order(order.clone())
+        .solution(ab_solution().reduce_score())
+        .solution(ab_solution())
+        .solution(ab_solution().increase_gas(1_000))
+        .done()
+        .await;
+
+    let solve = test.solve().await.ok();
+    let ids = solve.ids();
+    let scores = solve.scores();
+    assert_eq!(ids.len(), 2);
+    assert!(scores[0] > scores[1]);
+    for id in ids {
+        test.reveal(id).await.ok().calldata();
+    }
+}
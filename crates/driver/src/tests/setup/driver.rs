@@ -318,6 +318,7 @@ async fn create_config_file(
                http-time-buffer = "{}ms"
                fee-handler = {}
                merge-solutions = {}
+               max-solutions-per-auction = {}
                "#,
             solver.name,
             addr,
@@ -332,6 +333,7 @@ async fn create_config_file(
             solver.timeouts.http_delay.num_milliseconds(),
             serde_json::to_string(&solver.fee_handler).unwrap(),
             solver.merge_solutions,
+            solver.max_solutions_per_auction,
         )
         .unwrap();
     }
@@ -357,6 +357,8 @@ pub struct Solver {
     /// Whether or not solver is allowed to combine multiple solutions into a
     /// new one.
     merge_solutions: bool,
+    /// How many of the best-scoring solutions are returned per auction.
+    max_solutions_per_auction: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -384,6 +386,7 @@ pub fn test_solver() -> Solver {
         },
         fee_handler: FeeHandler::default(),
         merge_solutions: false,
+        max_solutions_per_auction: 1,
     }
 }
 
@@ -422,6 +425,11 @@ impl Solver {
         self.merge_solutions = true;
         self
     }
+
+    pub fn max_solutions_per_auction(mut self, max_solutions_per_auction: usize) -> Self {
+        self.max_solutions_per_auction = max_solutions_per_auction;
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1255,6 +1263,25 @@ impl SolveOk<'_> {
         serde_json::from_str::<Body>(&self.body).unwrap().solutions
     }
 
+    /// Extracts the ids of all solutions in the order they were returned.
+    pub fn ids(&self) -> Vec<u64> {
+        self.solutions()
+            .iter()
+            .map(|solution| solution.get("solutionId").unwrap().as_u64().unwrap())
+            .collect()
+    }
+
+    /// Extracts the scores of all solutions in the order they were returned.
+    pub fn scores(&self) -> Vec<eth::U256> {
+        self.solutions()
+            .iter()
+            .map(|solution| {
+                let score = solution.get("score").unwrap().as_str().unwrap();
+                eth::U256::from_dec_str(score).unwrap()
+            })
+            .collect()
+    }
+
     /// Extracts the solution id from the response. Since response can contain
     /// multiple solutions, it takes the id from the first solution.
     pub fn id(&self) -> u64 {
@@ -33,6 +33,12 @@ pub enum FeePolicyKind {
     /// fee where price improvement is a difference between the executed price
     /// and the best quote.
     PriceImprovement { factor: f64, max_volume_factor: f64 },
+    /// Progressive cut of the order's surplus where each tier's factor applies
+    /// to the surplus above its threshold (denominated in native token).
+    TieredSurplus {
+        tiers: Vec<(u128, f64)>,
+        max_volume_factor: f64,
+    },
 }
 
 impl std::fmt::Display for ProtocolFee {
@@ -58,6 +64,21 @@ impl std::fmt::Display for ProtocolFee {
                 "priceImprovement:{}:{}:{}",
                 factor, max_volume_factor, order_class_str
             ),
+            FeePolicyKind::TieredSurplus {
+                tiers,
+                max_volume_factor,
+            } => {
+                let tiers_str = tiers
+                    .iter()
+                    .map(|(threshold, factor)| format!("{}={}", threshold, factor))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                write!(
+                    f,
+                    "tieredSurplus:{}:{}:{}",
+                    tiers_str, max_volume_factor, order_class_str
+                )
+            }
         }
     }
 }
@@ -12,6 +12,7 @@ use {
     clap::Parser,
     ethcontract::{H160, H256},
     model::{
+        conditional_order::{ConditionalOrder, ConditionalOrderCreation},
         order::{Order, OrderCreation, OrderUid},
         quote::{OrderQuoteRequest, OrderQuoteResponse},
         solver_competition::SolverCompetitionAPI,
@@ -32,6 +33,7 @@ pub const AUCTION_ENDPOINT: &str = "/api/v1/auction";
 pub const TRADES_ENDPOINT: &str = "/api/v1/trades";
 pub const VERSION_ENDPOINT: &str = "/api/v1/version";
 pub const SOLVER_COMPETITION_ENDPOINT: &str = "/api/v1/solver_competition";
+pub const CONDITIONAL_ORDERS_ENDPOINT: &str = "/api/v1/conditional_orders";
 const LOCAL_DB_URL: &str = "postgresql://";
 
 fn order_status_endpoint(uid: &OrderUid) -> String {
@@ -449,6 +451,54 @@ impl<'a> Services<'a> {
         }
     }
 
+    /// Create a [`ConditionalOrder`]. If the response status code is not
+    /// `201`, return the status and the body.
+    pub async fn create_conditional_order(
+        &self,
+        order: &ConditionalOrderCreation,
+    ) -> Result<H256, (StatusCode, String)> {
+        let response = self
+            .http
+            .post(format!("{API_HOST}{CONDITIONAL_ORDERS_ENDPOINT}"))
+            .json(order)
+            .send()
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.text().await.unwrap();
+
+        match status {
+            StatusCode::CREATED => Ok(serde_json::from_str(&body).unwrap()),
+            code => Err((code, body)),
+        }
+    }
+
+    /// Retrieve a [`ConditionalOrder`]. If the response status is not `200`,
+    /// return the status and the body.
+    pub async fn get_conditional_order(
+        &self,
+        owner: &H160,
+        id: &H256,
+    ) -> Result<ConditionalOrder, (StatusCode, String)> {
+        let response = self
+            .http
+            .get(format!(
+                "{API_HOST}{CONDITIONAL_ORDERS_ENDPOINT}/{owner:?}/{id:?}"
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.text().await.unwrap();
+
+        match status {
+            StatusCode::OK => Ok(serde_json::from_str(&body).unwrap()),
+            code => Err((code, body)),
+        }
+    }
+
     /// Submit an [`model::quote::OrderQuote`].
     /// If the response status is not `200`, return the status and the body.
     pub async fn submit_quote(
@@ -0,0 +1,198 @@
+use {
+    app_data::{AppDataHash, hash_full_app_data},
+    chrono::Utc,
+    contracts::{ComposableCoW, ERC20, ExtensibleFallbackHandler, GnosisSafe},
+    e2e::{
+        nodes::local_node::TestNodeApi,
+        setup::{
+            OnchainComponents,
+            Services,
+            TIMEOUT,
+            run_forked_test_with_block_number,
+            safe::Safe,
+            to_wei,
+            wait_for_condition,
+        },
+        tx,
+        tx_value,
+    },
+    ethcontract::{Bytes, H160, H256, web3::ethabi::Token},
+    ethrpc::Web3,
+    hex_literal::hex,
+    model::{
+        conditional_order::{
+            ConditionalOrderCreation,
+            ConditionalOrderParams,
+            ConditionalOrderStatus,
+        },
+        order::OrderStatus,
+    },
+    reqwest::StatusCode,
+};
+
+#[tokio::test]
+#[ignore]
+async fn forked_node_mainnet_conditional_order_lifecycle() {
+    run_forked_test_with_block_number(
+        forked_mainnet_conditional_order_lifecycle,
+        std::env::var("FORK_URL_MAINNET")
+            .expect("FORK_URL_MAINNET must be set to run forked tests"),
+        FORK_BLOCK_MAINNET,
+    )
+    .await;
+}
+
+/// The block number from which we will fetch state for the forked tests.
+const FORK_BLOCK_MAINNET: u64 = 18477910;
+
+/// The TWAP conditional order handler as per
+/// <https://github.com/cowprotocol/composable-cow/blob/main/networks.json>.
+const TWAP_MAINNET: H160 = H160(hex!("6cF1e9cA41f7611dEf408122793c358a3d11E5a5"));
+
+/// DAI token address on mainnet.
+const DAI_MAINNET: H160 = H160(hex!("6B175474E89094C44Da98b954EedeAC495271d0F"));
+
+async fn forked_mainnet_conditional_order_lifecycle(web3: Web3) {
+    let mut onchain = OnchainComponents::deployed(web3.clone()).await;
+    let [solver] = onchain.make_solvers_forked(to_wei(1)).await;
+    let [trader] = onchain.make_accounts(to_wei(10)).await;
+    let composable_cow = ComposableCoW::deployed(&web3).await.unwrap();
+    let fallback_handler = ExtensibleFallbackHandler::deployed(&web3).await.unwrap();
+    let weth = &onchain.contracts().weth;
+    let dai = ERC20::at(&web3, DAI_MAINNET);
+
+    // TWAP parts are only valid from `t0` on while the API rejects orders
+    // that already expired, so the chain needs to agree with the wall clock.
+    let t0 = Utc::now();
+    web3.api::<TestNodeApi<_>>()
+        .set_next_block_timestamp(&t0)
+        .await
+        .unwrap();
+    onchain.mint_block().await;
+
+    tracing::info!("Setting up Safe as ComposableCoW owner.");
+    let safe = Safe::deploy(trader.clone(), &web3).await;
+    safe.exec_call(
+        GnosisSafe::at(&web3, safe.address()).set_fallback_handler(fallback_handler.address()),
+    )
+    .await;
+    safe.exec_call(
+        ExtensibleFallbackHandler::at(&web3, safe.address()).set_domain_verifier(
+            Bytes(onchain.contracts().domain_separator.0),
+            composable_cow.address(),
+        ),
+    )
+    .await;
+    tx_value!(trader.account(), to_wei(2), weth.deposit());
+    tx!(trader.account(), weth.transfer(safe.address(), to_wei(2)));
+    safe.exec_call(weth.approve(onchain.contracts().allowance, to_wei(2)))
+        .await;
+
+    let services = Services::new(&onchain).await;
+    services.start_protocol(solver).await;
+
+    // Child orders only commit to the app data hash so its pre-image needs to
+    // be known to the API.
+    let app_data = "{}";
+    let app_data_hash = AppDataHash(hash_full_app_data(app_data.as_bytes()));
+    services
+        .put_app_data(Some(app_data_hash), app_data)
+        .await
+        .unwrap();
+
+    // Sell 1 WETH for DAI every hour, twice.
+    let static_input = ethcontract::web3::ethabi::encode(&[Token::Tuple(vec![
+        Token::Address(weth.address()),
+        Token::Address(dai.address()),
+        Token::Address(safe.address()),
+        Token::Uint(to_wei(1)),
+        Token::Uint(to_wei(1)),
+        Token::Uint(t0.timestamp().into()),
+        Token::Uint(2.into()),
+        Token::Uint(3600.into()),
+        Token::Uint(0.into()),
+        Token::FixedBytes(app_data_hash.0.to_vec()),
+    ])]);
+    let params = ConditionalOrderParams {
+        handler: TWAP_MAINNET,
+        salt: H256([1; 32]),
+        static_input,
+    };
+    let order = ConditionalOrderCreation {
+        owner: safe.address(),
+        params: params.clone(),
+    };
+
+    tracing::info!("Rejecting conditional orders not authorized on-chain.");
+    let result = services.create_conditional_order(&order).await;
+    assert!(matches!(result, Err((StatusCode::BAD_REQUEST, _))));
+
+    tracing::info!("Creating conditional order.");
+    let contract_params = (
+        params.handler,
+        Bytes(params.salt.0),
+        Bytes(params.static_input.clone()),
+    );
+    safe.exec_call(composable_cow.create(contract_params, false))
+        .await;
+    let id = services.create_conditional_order(&order).await.unwrap();
+    assert_eq!(id, params.hash());
+    let result = services.create_conditional_order(&order).await;
+    assert!(matches!(result, Err((StatusCode::BAD_REQUEST, _))));
+
+    let created = services
+        .get_conditional_order(&safe.address(), &id)
+        .await
+        .unwrap();
+    assert_eq!(created.owner, safe.address());
+    assert_eq!(created.params, params);
+    assert_eq!(created.status, ConditionalOrderStatus::Active);
+
+    tracing::info!("Waiting for the first part to be placed.");
+    let placed = || async {
+        onchain.mint_block().await;
+        !services
+            .get_conditional_order(&safe.address(), &id)
+            .await
+            .unwrap()
+            .parts
+            .is_empty()
+    };
+    wait_for_condition(TIMEOUT, placed).await.unwrap();
+    let parts = services
+        .get_conditional_order(&safe.address(), &id)
+        .await
+        .unwrap()
+        .parts;
+    assert_eq!(parts.len(), 1);
+
+    tracing::info!("Waiting for the first part to be settled.");
+    let settled = || async {
+        onchain.mint_block().await;
+        services.get_order(&parts[0]).await.unwrap().metadata.status == OrderStatus::Fulfilled
+    };
+    wait_for_condition(TIMEOUT, settled).await.unwrap();
+    assert_eq!(
+        weth.balance_of(safe.address()).call().await.unwrap(),
+        to_wei(1)
+    );
+    assert!(dai.balance_of(safe.address()).call().await.unwrap() >= to_wei(1));
+
+    tracing::info!("Removing conditional order on-chain.");
+    safe.exec_call(composable_cow.remove(Bytes(id.0))).await;
+    let removed = || async {
+        onchain.mint_block().await;
+        services
+            .get_conditional_order(&safe.address(), &id)
+            .await
+            .unwrap()
+            .status
+            == ConditionalOrderStatus::Inactive
+    };
+    wait_for_condition(TIMEOUT, removed).await.unwrap();
+
+    let result = services
+        .get_conditional_order(&safe.address(), &H256::zero())
+        .await;
+    assert!(matches!(result, Err((StatusCode::NOT_FOUND, _))));
+}
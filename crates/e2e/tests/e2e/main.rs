@@ -8,6 +8,7 @@ mod app_data;
 mod app_data_signer;
 mod banned_users;
 mod buffers;
+mod conditional_orders;
 mod cow_amm;
 mod database;
 mod eth_integration;
@@ -141,6 +141,7 @@ async fn test_bypass_verification_for_rfq_quotes(web3: Web3) {
         onchain.contracts().gp_settlement.address(),
         onchain.contracts().weth.address(),
         BigDecimal::zero(),
+        false,
     )
     .await
     .unwrap();
@@ -199,6 +200,8 @@ async fn test_bypass_verification_for_rfq_quotes(web3: Web3) {
             pre_interactions: vec![],
             jit_orders: vec![],
         },
+        spread_bps: None,
+        simulation_trace: None,
     };
 
     // `tx_origin: 0x0000` is currently used to bypass quote verification due to an
@@ -1,4 +1,5 @@
 use {
+    self::reorg::RecentChain,
     crate::{Web3, Web3Transport, http::HttpTransport, instrumented::instrument_with_label},
     anyhow::{Context as _, Result, anyhow, ensure},
     futures::StreamExt,
@@ -6,6 +7,7 @@ use {
     std::{
         fmt::Debug,
         num::NonZeroU64,
+        ops::ControlFlow,
         time::{Duration, Instant},
     },
     tokio::sync::watch,
@@ -16,10 +18,14 @@ use {
         BatchTransport,
         Transport,
         helpers,
-        types::{Block, BlockId, BlockNumber, U64},
+        types::{Block, BlockHeader, BlockId, BlockNumber, U64},
     },
 };
 
+mod reorg;
+
+pub use self::reorg::{MAX_REORG_DEPTH, Reorg, ReorgReceiver, Reorgs};
+
 pub type BlockNumberHash = (u64, H256);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -85,6 +91,22 @@ impl PartialEq<Self> for BlockInfo {
     }
 }
 
+impl TryFrom<BlockHeader> for BlockInfo {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BlockHeader) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            number: value.number.context("block missing number")?.as_u64(),
+            hash: value.hash.context("block missing hash")?,
+            parent_hash: value.parent_hash,
+            timestamp: value.timestamp.as_u64(),
+            gas_limit: value.gas_limit,
+            gas_price: value.base_fee_per_gas.context("no gas price")?,
+            observed_at: Instant::now(),
+        })
+    }
+}
+
 impl TryFrom<Block<H256>> for BlockInfo {
     type Error = anyhow::Error;
 
@@ -118,6 +140,54 @@ pub async fn current_block_stream(
     url: Url,
     poll_interval: Duration,
 ) -> Result<CurrentBlockWatcher> {
+    let (current_block, _) = reorg_aware_block_stream(url, None, poll_interval).await?;
+    Ok(current_block)
+}
+
+/// Minimum time to wait before trying to re-establish a failed `newHeads`
+/// subscription.
+const MIN_RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Maximum time to wait before trying to re-establish a failed `newHeads`
+/// subscription.
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How long a `newHeads` subscription may stay silent before it is considered
+/// dead. Some nodes stop pushing updates without closing the connection.
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Creates a cloneable stream that yields the current block whenever it
+/// changes, like [`current_block_stream`], but gets notified about new blocks
+/// by a `newHeads` subscription over WebSocket instead of polling.
+///
+/// Whenever the subscription can't be established or breaks, the node is
+/// polled over HTTP every `poll_interval` until the next reconnection attempt.
+/// Reconnection attempts are spaced out with an exponential backoff.
+pub async fn current_block_ws_stream(
+    ws_url: Url,
+    http_url: Url,
+    poll_interval: Duration,
+) -> Result<CurrentBlockWatcher> {
+    let (current_block, _) =
+        reorg_aware_block_stream(http_url, Some(ws_url), poll_interval).await?;
+    Ok(current_block)
+}
+
+/// Creates a cloneable stream that yields the current block whenever it
+/// changes together with a handle to get notified about reorgs.
+///
+/// The block stream polls the node over HTTP, or subscribes to `newHeads` if a
+/// WebSocket URL is given, see [`current_block_stream`] and
+/// [`current_block_ws_stream`]. In both cases the parent hashes of the
+/// observed blocks are tracked to detect when blocks that were already
+/// observed got replaced. Note that the block stream itself only yields blocks
+/// with increasing numbers, so reorgs that don't increase the block number are
+/// only visible through the reorg notifications.
+pub async fn reorg_aware_block_stream(
+    url: Url,
+    ws_url: Option<Url>,
+    poll_interval: Duration,
+) -> Result<(CurrentBlockWatcher, Reorgs)> {
     // Build new Web3 specifically for the current block stream to avoid batching
     // requests together on chains with a very high block frequency.
     let web3 = Web3::new(Web3Transport::new(HttpTransport::new(
@@ -130,10 +200,55 @@ pub async fn current_block_stream(
     tracing::debug!(number=%first_block.number, hash=?first_block.hash, "polled block");
 
     let (sender, receiver) = watch::channel(first_block);
-    let update_future = async move {
-        let mut previous_block = first_block;
-        loop {
-            tokio::time::sleep(poll_interval).await;
+    let reorgs = Reorgs::new();
+    let updater = Updater::new(sender, reorgs.clone(), web3.clone(), first_block);
+    match ws_url {
+        Some(ws_url) => tokio::task::spawn(
+            subscription_loop(ws_url, web3, updater, poll_interval)
+                .instrument(tracing::info_span!("current_block_ws_stream")),
+        ),
+        None => tokio::task::spawn(
+            polling_loop(web3, updater, poll_interval)
+                .instrument(tracing::info_span!("current_block_stream")),
+        ),
+    };
+    Ok((receiver, reorgs))
+}
+
+async fn polling_loop(web3: Web3, mut updater: Updater, poll_interval: Duration) {
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        let block = match web3.current_block().await {
+            Ok(block) => block,
+            Err(err) => {
+                tracing::warn!("failed to get current block: {:?}", err);
+                continue;
+            }
+        };
+        if updater.update(block).await.is_break() {
+            tracing::debug!("exiting polling loop");
+            break;
+        }
+    }
+}
+
+async fn subscription_loop(ws_url: Url, web3: Web3, mut updater: Updater, poll_interval: Duration) {
+    let mut backoff = MIN_RECONNECT_BACKOFF;
+    'reconnect: loop {
+        match subscribe_new_heads(&ws_url, &mut updater).await {
+            Ok(ControlFlow::Break(())) => break,
+            // The subscription was working, so reconnect soon.
+            Ok(ControlFlow::Continue(())) => backoff = MIN_RECONNECT_BACKOFF,
+            Err(err) => tracing::warn!(?err, ?backoff, "newHeads subscription failed"),
+        }
+
+        // Fall back to polling until it's time to reconnect.
+        let reconnect_at = Instant::now() + backoff;
+        while Instant::now() < reconnect_at {
+            tokio::time::sleep(
+                poll_interval.min(reconnect_at.saturating_duration_since(Instant::now())),
+            )
+            .await;
             let block = match web3.current_block().await {
                 Ok(block) => block,
                 Err(err) => {
@@ -141,34 +256,126 @@ pub async fn current_block_stream(
                     continue;
                 }
             };
+            if updater.update(block).await.is_break() {
+                break 'reconnect;
+            }
+        }
+        backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+    }
+    tracing::debug!("exiting subscription loop");
+}
 
-            // If the block is exactly the same, ignore it.
-            if previous_block.hash == block.hash {
+/// Subscribes to `newHeads` and forwards the received blocks to the updater.
+///
+/// Returns an error if the subscription could not be established, `Continue`
+/// if an established subscription ended and `Break` if there are no receivers
+/// for the updates anymore.
+async fn subscribe_new_heads(url: &Url, updater: &mut Updater) -> Result<ControlFlow<()>> {
+    let transport = web3::transports::WebSocket::new(url.as_str())
+        .await
+        .context("failed to connect")?;
+    let mut heads = web3::Web3::new(transport)
+        .eth_subscribe()
+        .subscribe_new_heads()
+        .await
+        .context("failed to subscribe")?;
+    tracing::debug!("subscribed to newHeads");
+
+    loop {
+        let header = match tokio::time::timeout(SUBSCRIPTION_TIMEOUT, heads.next()).await {
+            Ok(Some(Ok(header))) => header,
+            Ok(Some(Err(err))) => {
+                tracing::warn!(?err, "newHeads subscription error");
+                return Ok(ControlFlow::Continue(()));
+            }
+            Ok(None) => {
+                tracing::warn!("newHeads subscription closed");
+                return Ok(ControlFlow::Continue(()));
+            }
+            Err(_) => {
+                tracing::warn!("newHeads subscription timed out");
+                return Ok(ControlFlow::Continue(()));
+            }
+        };
+        let block = match BlockInfo::try_from(header) {
+            Ok(block) => block,
+            Err(err) => {
+                tracing::warn!(?err, "received invalid block header");
                 continue;
             }
+        };
+        if updater.update(block).await.is_break() {
+            return Ok(ControlFlow::Break(()));
+        }
+    }
+}
 
-            // The new block is different but might still have the same number.
+/// Pushes newly observed blocks into the current block stream and notifies
+/// about reorgs.
+struct Updater {
+    sender: watch::Sender<BlockInfo>,
+    reorgs: Reorgs,
+    chain: RecentChain,
+    retriever: Web3,
+    previous_block: BlockInfo,
+}
 
-            tracing::debug!(number=%block.number, hash=?block.hash, "polled block");
-            update_block_metrics(previous_block.number, block.number);
+impl Updater {
+    fn new(
+        sender: watch::Sender<BlockInfo>,
+        reorgs: Reorgs,
+        retriever: Web3,
+        first_block: BlockInfo,
+    ) -> Self {
+        Self {
+            sender,
+            reorgs,
+            chain: RecentChain::new(&first_block),
+            retriever,
+            previous_block: first_block,
+        }
+    }
 
-            // Only update the stream if the number has increased.
-            if block.number <= previous_block.number {
-                continue;
-            }
+    /// Updates the stream with the observed block if it is newer than the
+    /// current one. Returns `Break` if there are no receivers anymore.
+    async fn update(&mut self, block: BlockInfo) -> ControlFlow<()> {
+        // If the block is exactly the same, ignore it.
+        if self.chain.head().1 == block.hash {
+            return ControlFlow::Continue(());
+        }
 
-            tracing::info!(number=%block.number, hash=?block.hash, "noticed a new block");
-            if sender.send(block).is_err() {
-                tracing::debug!("exiting polling loop");
-                break;
+        // The new block is different but might still have the same number.
+
+        tracing::debug!(number=%block.number, hash=?block.hash, "observed block");
+        update_block_metrics(self.previous_block.number, block.number);
+
+        match self.chain.update(&block, &self.retriever).await {
+            Ok(Some(reorg)) => {
+                tracing::info!(
+                    common_ancestor=?reorg.common_ancestor,
+                    number=%block.number,
+                    hash=?block.hash,
+                    "detected reorg"
+                );
+                self.reorgs.notify(reorg);
             }
+            Ok(None) => (),
+            Err(err) => tracing::warn!(?err, "failed to check block for reorg"),
+        }
 
-            previous_block = block;
+        // Only update the stream if the number has increased.
+        if block.number <= self.previous_block.number {
+            return ControlFlow::Continue(());
         }
-    };
 
-    tokio::task::spawn(update_future.instrument(tracing::info_span!("current_block_stream")));
-    Ok(receiver)
+        tracing::info!(number=%block.number, hash=?block.hash, "noticed a new block");
+        if self.sender.send(block).is_err() {
+            return ControlFlow::Break(());
+        }
+
+        self.previous_block = block;
+        ControlFlow::Continue(())
+    }
 }
 
 /// Returns a stream that is synchronized to the passed in stream by only yields
@@ -399,6 +606,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn mainnet_ws() {
+        observe::tracing::initialize_reentrant("ethrpc=debug");
+        let ws_node = std::env::var("NODE_WS_URL").unwrap().parse().unwrap();
+        let node = std::env::var("NODE_URL").unwrap().parse().unwrap();
+        let receiver = current_block_ws_stream(ws_node, node, Duration::from_secs(1))
+            .await
+            .unwrap();
+        let mut stream = into_stream(receiver);
+        for _ in 0..3 {
+            let block = stream.next().await.unwrap();
+            println!("new block number {}", block.number);
+        }
+    }
+
     #[tokio::test]
     #[ignore]
     async fn current_blocks_test() {
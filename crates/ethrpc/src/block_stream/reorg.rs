@@ -0,0 +1,347 @@
+//! Reorg detection for the current block stream.
+
+use {
+    super::{BlockInfo, BlockNumberHash, BlockRetrieving, RangeInclusive},
+    anyhow::Result,
+    std::collections::VecDeque,
+    tokio::sync::broadcast,
+};
+
+/// How many of the most recent blocks are tracked to detect reorgs. Reorgs
+/// deeper than this are reported without a common ancestor.
+pub const MAX_REORG_DEPTH: u64 = 64;
+
+/// How many reorg notifications a subscriber can lag behind before it starts
+/// missing them.
+const CHANNEL_CAPACITY: usize = 16;
+
+/// A chain reorganization observed by a block stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reorg {
+    /// The most recent block that is part of both the previous and the new
+    /// canonical chain. Everything a consumer derived from blocks after it
+    /// has to be considered invalid.
+    ///
+    /// `None` if none of the tracked blocks are canonical anymore, i.e. the
+    /// reorg is deeper than [`MAX_REORG_DEPTH`]. Consumers then have to
+    /// resync their state from scratch.
+    pub common_ancestor: Option<BlockNumberHash>,
+    /// The head of the new canonical chain.
+    pub new_head: BlockInfo,
+}
+
+pub type ReorgReceiver = broadcast::Receiver<Reorg>;
+
+/// Handle to subscribe to the reorgs detected by a block stream.
+#[derive(Clone, Debug)]
+pub struct Reorgs(broadcast::Sender<Reorg>);
+
+impl Reorgs {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(broadcast::channel(CHANNEL_CAPACITY).0)
+    }
+
+    /// Returns a receiver for all reorgs that are detected from now on.
+    pub fn subscribe(&self) -> ReorgReceiver {
+        self.0.subscribe()
+    }
+
+    /// Notifies all current subscribers about a reorg.
+    pub fn notify(&self, reorg: Reorg) {
+        // Sending only fails if there are no subscribers, which is fine.
+        let _ = self.0.send(reorg);
+    }
+}
+
+/// The most recent blocks of the canonical chain as observed by a block
+/// stream.
+#[derive(Debug)]
+pub(super) struct RecentChain {
+    /// Consecutive blocks sorted by number. Never empty.
+    blocks: VecDeque<BlockNumberHash>,
+}
+
+impl RecentChain {
+    pub fn new(head: &BlockInfo) -> Self {
+        Self {
+            blocks: [(head.number, head.hash)].into(),
+        }
+    }
+
+    pub fn head(&self) -> BlockNumberHash {
+        *self.blocks.back().expect("chain is never empty")
+    }
+
+    fn contains(&self, block: BlockNumberHash) -> bool {
+        self.blocks.iter().rev().any(|known| *known == block)
+    }
+
+    /// Updates the chain with a newly observed block and returns the reorg
+    /// if the block does not build on top of the current head.
+    ///
+    /// Blocks that are already part of the chain or older than all tracked
+    /// blocks are ignored, as those are usually returned by nodes that are
+    /// lagging behind.
+    pub async fn update(
+        &mut self,
+        block: &BlockInfo,
+        retriever: &dyn BlockRetrieving,
+    ) -> Result<Option<Reorg>> {
+        let head = self.head();
+        let oldest = *self.blocks.front().expect("chain is never empty");
+        if self.contains((block.number, block.hash)) || block.number < oldest.0 {
+            return Ok(None);
+        }
+        if block.number == head.0 + 1 && block.parent_hash == head.1 {
+            self.push((block.number, block.hash));
+            return Ok(None);
+        }
+        if block.number > head.0 + MAX_REORG_DEPTH {
+            // Too many blocks were missed to tell whether a reorg happened
+            // in between, so simply start over from the new block.
+            tracing::debug!(
+                ?head,
+                new = block.number,
+                "block stream skipped many blocks"
+            );
+            self.blocks = [(block.number, block.hash)].into();
+            return Ok(None);
+        }
+
+        let parent = (block.number.saturating_sub(1), block.parent_hash);
+        if parent.0 >= head.0 {
+            // Most likely the stream only skipped some blocks, so first check
+            // whether the new block still builds on top of the head before
+            // fetching all tracked blocks.
+            let newer = canonical_blocks(head.0, parent, retriever).await?;
+            if newer.first() == Some(&head) {
+                for canonical in newer.into_iter().skip(1) {
+                    self.push(canonical);
+                }
+                self.push((block.number, block.hash));
+                return Ok(None);
+            }
+        }
+
+        // Compare the tracked blocks with what the node considers canonical
+        // now to find where the chains diverged.
+        let canonical = canonical_blocks(oldest.0, parent, retriever).await?;
+        let common_ancestor = canonical
+            .iter()
+            .rev()
+            .find(|canonical| self.contains(**canonical))
+            .copied();
+        match common_ancestor {
+            Some(common_ancestor) => self.blocks.retain(|known| known.0 <= common_ancestor.0),
+            None => self.blocks.clear(),
+        }
+        for canonical in canonical
+            .into_iter()
+            .filter(|canonical| common_ancestor.is_none_or(|ancestor| canonical.0 > ancestor.0))
+        {
+            self.push(canonical);
+        }
+        self.push((block.number, block.hash));
+
+        Ok((common_ancestor != Some(head)).then_some(Reorg {
+            common_ancestor,
+            new_head: *block,
+        }))
+    }
+
+    fn push(&mut self, block: BlockNumberHash) {
+        self.blocks.push_back(block);
+        while self.blocks.len() as u64 > MAX_REORG_DEPTH {
+            self.blocks.pop_front();
+        }
+    }
+}
+
+/// Returns the canonical blocks from `start` up to and including the parent of
+/// a new block. The parent hash is authoritative for the new block even if the
+/// node's view of the chain changed in the meantime.
+async fn canonical_blocks(
+    start: u64,
+    parent: BlockNumberHash,
+    retriever: &dyn BlockRetrieving,
+) -> Result<Vec<BlockNumberHash>> {
+    let mut blocks = if start < parent.0 {
+        retriever
+            .blocks(RangeInclusive::try_new(start, parent.0 - 1)?)
+            .await?
+    } else {
+        Vec::new()
+    };
+    blocks.push(parent);
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        primitive_types::H256,
+        std::sync::atomic::{AtomicU64, Ordering},
+    };
+
+    /// A chain where the hash of each block is derived from its number and
+    /// the fork it belongs to.
+    #[derive(Debug, Default)]
+    struct Chain {
+        fork_at: u64,
+        fork: u8,
+        /// How many blocks were fetched from the chain.
+        fetched: AtomicU64,
+    }
+
+    fn hash(number: u64, fork: u8) -> H256 {
+        let mut hash = H256::from_low_u64_be(number);
+        hash.0[0] = fork;
+        hash
+    }
+
+    impl Chain {
+        fn new(fork_at: u64, fork: u8) -> Self {
+            Self {
+                fork_at,
+                fork,
+                ..Default::default()
+            }
+        }
+
+        fn block(&self, number: u64) -> BlockNumberHash {
+            let fork = if number > self.fork_at { self.fork } else { 0 };
+            (number, hash(number, fork))
+        }
+
+        fn info(&self, number: u64) -> BlockInfo {
+            BlockInfo {
+                number,
+                hash: self.block(number).1,
+                parent_hash: self.block(number - 1).1,
+                ..Default::default()
+            }
+        }
+
+        fn fetched(&self) -> u64 {
+            self.fetched.swap(0, Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BlockRetrieving for Chain {
+        async fn current_block(&self) -> Result<BlockInfo> {
+            unimplemented!()
+        }
+
+        async fn block(&self, number: u64) -> Result<BlockNumberHash> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(self.block(number))
+        }
+
+        async fn blocks(&self, range: RangeInclusive<u64>) -> Result<Vec<BlockNumberHash>> {
+            let (start, end) = range.into_inner();
+            self.fetched.fetch_add(end - start + 1, Ordering::SeqCst);
+            Ok((start..=end).map(|number| self.block(number)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn follows_chain_without_reorgs() {
+        let chain = Chain::new(u64::MAX, 0);
+        let mut recent = RecentChain::new(&chain.info(10));
+
+        assert_eq!(recent.update(&chain.info(11), &chain).await.unwrap(), None);
+        // Skipped blocks are filled in.
+        assert_eq!(recent.update(&chain.info(14), &chain).await.unwrap(), None);
+        // Lagging nodes are ignored.
+        assert_eq!(recent.update(&chain.info(12), &chain).await.unwrap(), None);
+        assert_eq!(
+            recent.blocks,
+            (10..=14)
+                .map(|number| chain.block(number))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn only_fetches_skipped_blocks() {
+        let chain = Chain::new(u64::MAX, 0);
+        let mut recent = RecentChain::new(&chain.info(10));
+        for number in 11..=70 {
+            recent.update(&chain.info(number), &chain).await.unwrap();
+        }
+        assert_eq!(chain.fetched(), 0);
+
+        // Only the head and the skipped blocks 71 and 72 get fetched, the
+        // parent of the new block is known from its header.
+        assert_eq!(recent.update(&chain.info(74), &chain).await.unwrap(), None);
+        assert_eq!(chain.fetched(), 3);
+        assert_eq!(recent.head(), chain.block(74));
+    }
+
+    #[tokio::test]
+    async fn detects_reorg_at_same_height() {
+        let old = Chain::new(u64::MAX, 0);
+        let mut recent = RecentChain::new(&old.info(10));
+        recent.update(&old.info(11), &old).await.unwrap();
+        recent.update(&old.info(12), &old).await.unwrap();
+
+        let new = Chain::new(11, 1);
+        assert_eq!(
+            recent.update(&new.info(12), &new).await.unwrap(),
+            Some(Reorg {
+                common_ancestor: Some(old.block(11)),
+                new_head: new.info(12),
+            })
+        );
+        assert_eq!(recent.head(), new.block(12));
+    }
+
+    #[tokio::test]
+    async fn detects_reorg_with_new_blocks() {
+        let old = Chain::new(u64::MAX, 0);
+        let mut recent = RecentChain::new(&old.info(10));
+        for number in 11..=15 {
+            recent.update(&old.info(number), &old).await.unwrap();
+        }
+
+        let new = Chain::new(12, 1);
+        assert_eq!(
+            recent.update(&new.info(17), &new).await.unwrap(),
+            Some(Reorg {
+                common_ancestor: Some(old.block(12)),
+                new_head: new.info(17),
+            })
+        );
+        assert_eq!(
+            recent.blocks,
+            (10..=17)
+                .map(|number| new.block(number))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn reorg_deeper_than_tracked_blocks() {
+        let old = Chain::new(u64::MAX, 0);
+        let mut recent = RecentChain::new(&old.info(10));
+        recent.update(&old.info(11), &old).await.unwrap();
+
+        let new = Chain::new(5, 1);
+        assert_eq!(
+            recent.update(&new.info(12), &new).await.unwrap(),
+            Some(Reorg {
+                common_ancestor: None,
+                new_head: new.info(12),
+            })
+        );
+        assert_eq!(
+            recent.blocks,
+            (10..=12)
+                .map(|number| new.block(number))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! Module containing Ethereum RPC extension methods.
 
 use {
-    serde::{Deserialize, Serialize},
+    serde::{Deserialize, Deserializer, Serialize, de},
     std::collections::HashMap,
     web3::{
         self,
@@ -119,6 +119,78 @@ impl<T: Transport> Debug<T> {
                 .execute("debug_traceTransaction", vec![hash, tracing_options]),
         )
     }
+
+    /// Returns the call tree of a call executed with the given state
+    /// overrides using the callTracer.
+    pub fn call_frames(
+        &self,
+        call: CallRequest,
+        block: BlockId,
+        overrides: StateOverrides,
+    ) -> CallFuture<CallFrame, T::Out> {
+        let call = helpers::serialize(&call);
+        let block = helpers::serialize(&block);
+        let tracing_options = serde_json::json!({
+            "tracer": "callTracer",
+            "stateOverrides": overrides,
+        });
+        CallFuture::new(
+            self.transport()
+                .execute("debug_traceCall", vec![call, block, tracing_options]),
+        )
+    }
+
+    /// Returns the opcode level trace of a call using the default struct
+    /// logger, including the memory at every step.
+    pub fn call(&self, call: CallRequest, block: BlockId) -> CallFuture<StructLogs, T::Out> {
+        let call = helpers::serialize(&call);
+        let block = helpers::serialize(&block);
+        let tracing_options = serde_json::json!({
+            "enableMemory": true,
+            "disableStorage": true,
+            "enableReturnData": false,
+        });
+        CallFuture::new(
+            self.transport()
+                .execute("debug_traceCall", vec![call, block, tracing_options]),
+        )
+    }
+}
+
+/// Opcode level trace of the default struct logger.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogs {
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// A single step of a [`StructLogs`] trace.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct StructLog {
+    /// The name of the executed opcode.
+    pub op: String,
+    /// The call depth, starting at 1.
+    pub depth: usize,
+    /// The stack before executing the opcode, with the top of the stack last.
+    #[serde(default)]
+    pub stack: Vec<U256>,
+    /// The memory before executing the opcode.
+    #[serde(default, deserialize_with = "deserialize_memory")]
+    pub memory: Vec<u8>,
+}
+
+/// Memory is traced as a list of hex encoded 32-byte words.
+fn deserialize_memory<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let words = Option::<Vec<String>>::deserialize(deserializer)?.unwrap_or_default();
+    let mut memory = Vec::with_capacity(words.len() * 32);
+    for word in words {
+        let word = hex::decode(word.trim_start_matches("0x")).map_err(de::Error::custom)?;
+        memory.extend(word);
+    }
+    Ok(memory)
 }
 
 /// Taken from alloy::rpc::types::trace::geth::CallFrame
@@ -129,6 +201,12 @@ pub struct CallFrame {
     pub to: Option<primitive_types::H160>,
     /// Calldata input.
     pub input: Bytes,
+    /// Gas used by the call.
+    #[serde(default, rename = "gasUsed")]
+    pub gas_used: U256,
+    /// The error the call failed with.
+    #[serde(default)]
+    pub error: Option<String>,
     /// Recorded child calls.
     #[serde(default)]
     pub calls: Vec<CallFrame>,
@@ -180,4 +258,93 @@ mod tests {
 
         assert_eq!(output.0, (0..32).collect::<Vec<_>>());
     }
+
+    #[test]
+    fn deserialize_struct_logs() {
+        let trace: StructLogs = serde_json::from_value(serde_json::json!({
+            "gas": 24000,
+            "failed": false,
+            "returnValue": "",
+            "structLogs": [
+                {
+                    "pc": 0,
+                    "op": "PUSH1",
+                    "gas": 100000,
+                    "gasCost": 3,
+                    "depth": 1,
+                    "stack": [],
+                },
+                {
+                    "pc": 2,
+                    "op": "KECCAK256",
+                    "gas": 99997,
+                    "gasCost": 42,
+                    "depth": 1,
+                    "stack": ["0x20", "0x0"],
+                    "memory": [
+                        "000000000000000000000000000000000000000000000000000000000000002a",
+                    ],
+                },
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            trace.struct_logs,
+            vec![
+                StructLog {
+                    op: "PUSH1".to_string(),
+                    depth: 1,
+                    stack: vec![],
+                    memory: vec![],
+                },
+                StructLog {
+                    op: "KECCAK256".to_string(),
+                    depth: 1,
+                    stack: vec![0x20.into(), 0.into()],
+                    memory: [[0; 31].as_slice(), &[0x2a]].concat(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_call_frame() {
+        let trace: CallFrame = serde_json::from_value(serde_json::json!({
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "gas": "0x10000",
+            "gasUsed": "0x5208",
+            "input": "0x01",
+            "type": "CALL",
+            "calls": [
+                {
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "to": "0x0000000000000000000000000000000000000003",
+                    "gasUsed": "0x64",
+                    "input": "0x02",
+                    "error": "execution reverted",
+                    "type": "CALL",
+                },
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            trace,
+            CallFrame {
+                to: Some(H160::from_low_u64_be(2)),
+                input: Bytes(vec![1]),
+                gas_used: 21_000.into(),
+                error: None,
+                calls: vec![CallFrame {
+                    to: Some(H160::from_low_u64_be(3)),
+                    input: Bytes(vec![2]),
+                    gas_used: 100.into(),
+                    error: Some("execution reverted".to_string()),
+                    calls: vec![],
+                }],
+            }
+        );
+    }
 }
@@ -0,0 +1,141 @@
+//! Contains the types for conditional orders as defined by ComposableCoW
+//! (<https://github.com/cowprotocol/composable-cow>). A conditional order
+//! does not get signed itself but is authorized on-chain by its owner and
+//! produces the actual orders to trade (its parts) when polled.
+
+use {
+    crate::order::OrderUid,
+    chrono::{DateTime, Utc},
+    primitive_types::{H160, H256},
+    serde::{Deserialize, Serialize},
+    std::fmt::{self, Debug, Formatter},
+    web3::{
+        ethabi::{Token, encode},
+        signing,
+    },
+};
+
+/// The parameters identifying a conditional order.
+#[derive(Eq, PartialEq, Clone, Hash, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalOrderParams {
+    /// The contract implementing the logic of the conditional order (e.g.
+    /// TWAP).
+    pub handler: H160,
+    pub salt: H256,
+    /// The handler specific data of the conditional order.
+    #[serde(with = "bytes_hex")]
+    pub static_input: Vec<u8>,
+}
+
+impl ConditionalOrderParams {
+    /// The hash under which ComposableCoW tracks the conditional order.
+    pub fn hash(&self) -> H256 {
+        let encoded = encode(&[Token::Tuple(vec![
+            Token::Address(self.handler),
+            Token::FixedBytes(self.salt.0.to_vec()),
+            Token::Bytes(self.static_input.clone()),
+        ])]);
+        H256(signing::keccak256(&encoded))
+    }
+}
+
+impl Debug for ConditionalOrderParams {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ConditionalOrderParams")
+            .field("handler", &self.handler)
+            .field("salt", &self.salt)
+            .field(
+                "static_input",
+                &format_args!("0x{}", hex::encode(&self.static_input)),
+            )
+            .finish()
+    }
+}
+
+/// A conditional order as submitted to the API.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalOrderCreation {
+    /// The account that authorized the conditional order with ComposableCoW.
+    pub owner: H160,
+    pub params: ConditionalOrderParams,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConditionalOrderStatus {
+    /// The conditional order gets polled for new parts.
+    #[default]
+    Active,
+    /// The conditional order got removed on-chain by its owner or signaled
+    /// that it will never trade again, so it will not produce any more parts.
+    Inactive,
+}
+
+/// A conditional order as returned by the API.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalOrder {
+    /// The hash of the conditional order's parameters.
+    pub id: H256,
+    pub owner: H160,
+    pub params: ConditionalOrderParams,
+    pub creation_date: DateTime<Utc>,
+    pub status: ConditionalOrderStatus,
+    /// The orders that got placed for this conditional order so far.
+    pub parts: Vec<OrderUid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn params_serialization() {
+        let params = ConditionalOrderParams {
+            handler: H160([1; 20]),
+            salt: H256([2; 32]),
+            static_input: vec![3, 4],
+        };
+        let value = json!({
+            "handler": "0x0101010101010101010101010101010101010101",
+            "salt": "0x0202020202020202020202020202020202020202020202020202020202020202",
+            "staticInput": "0x0304",
+        });
+
+        assert_eq!(serde_json::to_value(&params).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<ConditionalOrderParams>(value).unwrap(),
+            params
+        );
+    }
+
+    #[test]
+    fn params_hash_depends_on_all_fields() {
+        let params = ConditionalOrderParams::default();
+        let hashes = [
+            params.hash(),
+            ConditionalOrderParams {
+                handler: H160([1; 20]),
+                ..params.clone()
+            }
+            .hash(),
+            ConditionalOrderParams {
+                salt: H256([1; 32]),
+                ..params.clone()
+            }
+            .hash(),
+            ConditionalOrderParams {
+                static_input: vec![1],
+                ..params
+            }
+            .hash(),
+        ];
+        for (i, a) in hashes.iter().enumerate() {
+            for b in &hashes[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
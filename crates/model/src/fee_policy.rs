@@ -20,6 +20,21 @@ pub enum FeePolicy {
         max_volume_factor: f64,
         quote: Quote,
     },
+    #[serde(rename_all = "camelCase")]
+    TieredSurplus {
+        tiers: Vec<SurplusTier>,
+        max_volume_factor: f64,
+    },
+}
+
+#[serde_as]
+#[derive(PartialEq, Clone, Debug, Serialize)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct SurplusTier {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub threshold: U256,
+    pub factor: f64,
 }
 
 #[serde_as]
@@ -1,6 +1,7 @@
 //! Contains models that are shared between the orderbook and the solver.
 
 pub mod auction;
+pub mod conditional_order;
 pub mod fee_policy;
 pub mod interaction;
 pub mod order;
@@ -293,6 +293,10 @@ pub struct OrderQuoteResponse {
     pub expiration: DateTime<Utc>,
     pub id: Option<QuoteId>,
     pub verified: bool,
+    /// Slippage tolerance (in basis points) we suggest to use for orders
+    /// created for this quote.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_slippage_bps: Option<u32>,
 }
 
 #[cfg(test)]
@@ -54,6 +54,12 @@ pub struct SolverSettlement {
     pub orders: Vec<Order>,
     #[serde(default)]
     pub is_winner: bool,
+    /// The reference score of the solver if the solution is a winner, i.e.
+    /// the total score of the winners that would have been selected had the
+    /// solver not participated in the auction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    pub reference_score: Option<U256>,
 }
 
 #[serde_as]
@@ -168,6 +174,7 @@ mod tests {
                         }
                     ],
                     "isWinner": true,
+                    "referenceScore": "0",
                 },
             ],
         });
@@ -210,6 +217,7 @@ mod tests {
                         },
                     ],
                     is_winner: true,
+                    reference_score: Some(0.into()),
                 }],
             },
         };
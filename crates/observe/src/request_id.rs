@@ -36,23 +36,32 @@ pub fn info_span(request_id: String) -> Span {
     tracing::info_span!(SPAN_NAME, id = request_id)
 }
 
+/// The address of the peer a request was received from. Gets inserted into
+/// the request extensions by [`make_service_with_request_tracing`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RemoteAddr(pub std::net::SocketAddr);
+
 /// Takes a `tower::Service` and embeds it in a `make_service` function that
 /// spawns one of these services per incoming request.
 /// But crucially before spawning that service task local storage will be
 /// initialized with some request id.
 /// Either that gets taken from the requests `X-REQUEST-ID` header of if that's
 /// missing a globally unique request number will be generated.
+/// The address of the connected peer gets stored as a [`RemoteAddr`] request
+/// extension.
 #[macro_export]
 macro_rules! make_service_with_request_tracing {
     ($service:expr_2021) => {{
         {
             let internal_request_id = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
-            hyper::service::make_service_fn(move |_| {
+            hyper::service::make_service_fn(move |conn: &hyper::server::conn::AddrStream| {
+                let remote_addr = observe::request_id::RemoteAddr(conn.remote_addr());
                 let warp_svc = $service.clone();
                 let internal_request_id = internal_request_id.clone();
                 async move {
                     let svc =
-                        hyper::service::service_fn(move |req: hyper::Request<hyper::Body>| {
+                        hyper::service::service_fn(move |mut req: hyper::Request<hyper::Body>| {
+                            req.extensions_mut().insert(remote_addr);
                             let mut warp_svc = warp_svc.clone();
                             let id = if let Some(header) = req.headers().get("X-Request-ID") {
                                 String::from_utf8_lossy(header.as_bytes()).to_string()
@@ -1,5 +1,14 @@
 use {
-    crate::{app_data, database::Postgres, orderbook::Orderbook, quoter::QuoteHandler},
+    self::rate_limit::RateLimiter,
+    crate::{
+        app_data,
+        conditional_orders::ConditionalOrders,
+        database::Postgres,
+        order_simulation::OrderSimulator,
+        order_updates::OrderUpdates,
+        orderbook::Orderbook,
+        quoter::QuoteHandler,
+    },
     anyhow::Result,
     serde::{Serialize, de::DeserializeOwned},
     shared::price_estimation::{PriceEstimationError, native::NativePriceEstimating},
@@ -18,35 +27,55 @@ mod cancel_order;
 mod cancel_orders;
 mod get_app_data;
 mod get_auction;
+mod get_conditional_order;
 mod get_native_price;
 mod get_order_by_uid;
 mod get_order_status;
+mod get_order_updates;
 mod get_orders_by_tx;
 mod get_solver_competition;
 mod get_token_metadata;
 mod get_total_surplus;
 mod get_trades;
 mod get_user_orders;
+mod post_conditional_order;
 mod post_order;
+mod post_order_simulation;
 mod post_quote;
+mod post_quote_batch;
 mod put_app_data;
+pub mod rate_limit;
 mod version;
 
 pub fn handle_all_routes(
     database: Postgres,
     orderbook: Arc<Orderbook>,
     quotes: Arc<QuoteHandler>,
+    order_simulator: Arc<OrderSimulator>,
     app_data: Arc<app_data::Registry>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_updates: OrderUpdates,
+    conditional_orders: Option<Arc<ConditionalOrders>>,
+    rate_limiter: Arc<RateLimiter>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
     // It is not used to form the actual server response.
 
-    let routes = vec![
+    let mut routes = vec![
         (
             "v1/create_order",
-            box_filter(post_order::post_order(orderbook.clone())),
+            box_filter(post_order::post_order(
+                orderbook.clone(),
+                rate_limiter.clone(),
+            )),
+        ),
+        (
+            "v1/simulate_order",
+            box_filter(post_order_simulation::post_order_simulation(
+                order_simulator,
+                rate_limiter.clone(),
+            )),
         ),
         (
             "v1/get_order",
@@ -56,6 +85,10 @@ pub fn handle_all_routes(
             "v1/get_order_status",
             box_filter(get_order_status::get_status(orderbook.clone())),
         ),
+        (
+            "v1/get_order_updates",
+            get_order_updates::get_order_updates(order_updates).boxed(),
+        ),
         (
             "v1/get_trades",
             box_filter(get_trades::get_trades(database.clone())),
@@ -76,7 +109,14 @@ pub fn handle_all_routes(
             "v1/get_orders_by_tx",
             box_filter(get_orders_by_tx::get_orders_by_tx(orderbook.clone())),
         ),
-        ("v1/post_quote", box_filter(post_quote::post_quote(quotes))),
+        (
+            "v1/post_quote",
+            box_filter(post_quote::post_quote(quotes.clone(), rate_limiter.clone())),
+        ),
+        (
+            "v1/post_quote_batch",
+            box_filter(post_quote_batch::post_quote_batch(quotes, rate_limiter)),
+        ),
         (
             "v1/auction",
             box_filter(get_auction::get_auction(orderbook.clone())),
@@ -113,6 +153,22 @@ pub fn handle_all_routes(
             box_filter(get_token_metadata::get_token_metadata(database)),
         ),
     ];
+    if let Some(conditional_orders) = conditional_orders {
+        routes.extend([
+            (
+                "v1/create_conditional_order",
+                box_filter(post_conditional_order::post_conditional_order(
+                    conditional_orders.clone(),
+                )),
+            ),
+            (
+                "v1/get_conditional_order",
+                box_filter(get_conditional_order::get_conditional_order(
+                    conditional_orders,
+                )),
+            ),
+        ]);
+    }
 
     finalize_router(routes, "orderbook::api::request_summary")
 }
@@ -159,6 +215,7 @@ impl ApiMetrics {
         StatusCode::UNAUTHORIZED,
         StatusCode::FORBIDDEN,
         StatusCode::NOT_FOUND,
+        StatusCode::TOO_MANY_REQUESTS,
         StatusCode::INTERNAL_SERVER_ERROR,
         StatusCode::SERVICE_UNAVAILABLE,
     ];
@@ -254,7 +311,14 @@ pub async fn response_body(response: warp::hyper::Response<warp::hyper::Body>) -
     result
 }
 
-const MAX_JSON_BODY_PAYLOAD: u64 = 1024 * 16;
+/// Returns the JSON body an endpoint would respond with for an error. Used to
+/// embed errors in successful responses.
+pub async fn error_body(err: impl IntoWarpReply) -> serde_json::Value {
+    let body = response_body(err.into_warp_reply().into_response()).await;
+    serde_json::from_slice(&body).unwrap_or_default()
+}
+
+pub(crate) const MAX_JSON_BODY_PAYLOAD: u64 = 1024 * 16;
 
 pub fn extract_payload<T: DeserializeOwned + Send>()
 -> impl Filter<Extract = (T,), Error = Rejection> + Clone {
@@ -322,7 +386,13 @@ pub fn finalize_router(
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS", "PUT", "PATCH"])
-        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"]);
+        .allow_headers(vec![
+            "Origin",
+            "Content-Type",
+            "X-Auth-Token",
+            "X-AppId",
+            rate_limit::API_KEY_HEADER,
+        ]);
 
     warp::path!("api" / ..)
         .and(instrumented)
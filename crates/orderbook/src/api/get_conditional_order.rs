@@ -0,0 +1,32 @@
+use {
+    crate::{api::ApiReply, conditional_orders::ConditionalOrders},
+    primitive_types::{H160, H256},
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, hyper::StatusCode, reply},
+};
+
+fn request() -> impl Filter<Extract = (H160, H256), Error = Rejection> + Clone {
+    warp::path!("v1" / "conditional_orders" / H160 / H256).and(warp::get())
+}
+
+pub fn get_conditional_order(
+    conditional_orders: Arc<ConditionalOrders>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |owner: H160, id: H256| {
+        let conditional_orders = conditional_orders.clone();
+        async move {
+            let reply = match conditional_orders.get(&owner, &id).await {
+                Ok(Some(order)) => reply::with_status(reply::json(&order), StatusCode::OK),
+                Ok(None) => reply::with_status(
+                    super::error("NotFound", "Conditional order was not found"),
+                    StatusCode::NOT_FOUND,
+                ),
+                Err(err) => {
+                    tracing::error!(?err, "get_conditional_order");
+                    crate::api::internal_error_reply()
+                }
+            };
+            Result::<_, Infallible>::Ok(reply)
+        }
+    })
+}
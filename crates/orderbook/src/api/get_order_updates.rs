@@ -0,0 +1,167 @@
+use {
+    crate::{api::error, dto::order::Update, order_updates::OrderUpdates},
+    futures::StreamExt,
+    model::order::OrderUid,
+    primitive_types::H160,
+    serde::Deserialize,
+    std::convert::Infallible,
+    tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+    warp::{Filter, Rejection, Reply, hyper::StatusCode, reply::with_status, sse::Event},
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    pub order_uid: Option<OrderUid>,
+    pub owner: Option<H160>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum UpdateFilter {
+    OrderUid(OrderUid),
+    Owner(H160),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum UpdateFilterError {
+    InvalidFilter(String),
+}
+
+impl Query {
+    fn validate(&self) -> Result<UpdateFilter, UpdateFilterError> {
+        match (self.order_uid, self.owner) {
+            (Some(uid), None) => Ok(UpdateFilter::OrderUid(uid)),
+            (None, Some(owner)) => Ok(UpdateFilter::Owner(owner)),
+            _ => Err(UpdateFilterError::InvalidFilter(
+                "Must specify exactly one of owner or orderUid.".to_owned(),
+            )),
+        }
+    }
+}
+
+impl UpdateFilter {
+    fn matches(&self, update: &Update) -> bool {
+        match self {
+            Self::OrderUid(uid) => update.uid() == uid,
+            Self::Owner(owner) => update.uid().parts().1 == *owner,
+        }
+    }
+}
+
+fn get_order_updates_request()
+-> impl Filter<Extract = (Result<UpdateFilter, UpdateFilterError>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "updates")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+        .map(|query: Query| query.validate())
+}
+
+/// Streams updates of the matching orders as server-sent events until the
+/// client disconnects.
+fn event_stream(
+    updates: &OrderUpdates,
+    filter: UpdateFilter,
+) -> impl futures::Stream<Item = Result<Event, Infallible>> + Send + 'static {
+    BroadcastStream::new(updates.subscribe()).filter_map(move |update| async move {
+        match update {
+            Ok(update) if filter.matches(&update) => {
+                match Event::default().event("update").json_data(&*update) {
+                    Ok(event) => Some(Ok(event)),
+                    Err(err) => {
+                        tracing::error!(?err, "failed to serialize order update");
+                        None
+                    }
+                }
+            }
+            Ok(_) => None,
+            // Let the client know that it missed updates so that it can fetch
+            // the current state of its orders again.
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => Some(Ok(Event::default()
+                .event("lagged")
+                .data(skipped.to_string()))),
+        }
+    })
+}
+
+pub fn get_order_updates(
+    updates: OrderUpdates,
+) -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    get_order_updates_request().map(move |request_result| -> Box<dyn Reply> {
+        match request_result {
+            Ok(filter) => Box::new(warp::sse::reply(
+                warp::sse::keep_alive().stream(event_stream(&updates, filter)),
+            )),
+            Err(UpdateFilterError::InvalidFilter(msg)) => Box::new(with_status(
+                error("InvalidUpdateFilter", msg),
+                StatusCode::BAD_REQUEST,
+            )),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        hex_literal::hex,
+        warp::test::{RequestBuilder, request},
+    };
+
+    #[tokio::test]
+    async fn get_order_updates_request_ok() {
+        let update_filter = |request: RequestBuilder| async move {
+            let filter = get_order_updates_request();
+            request.method("GET").filter(&filter).await
+        };
+
+        let owner = H160::from_slice(&hex!("0000000000000000000000000000000000000001"));
+        let path = format!("/v1/orders/updates?owner=0x{owner:x}");
+        let result = update_filter(request().path(path.as_str()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result, UpdateFilter::Owner(owner));
+
+        let uid = OrderUid([1u8; 56]);
+        let path = format!("/v1/orders/updates?orderUid={uid}");
+        let result = update_filter(request().path(path.as_str()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result, UpdateFilter::OrderUid(uid));
+    }
+
+    #[tokio::test]
+    async fn get_order_updates_request_err() {
+        let update_filter = |request: RequestBuilder| async move {
+            let filter = get_order_updates_request();
+            request.method("GET").filter(&filter).await
+        };
+
+        let owner = H160::from_slice(&hex!("0000000000000000000000000000000000000001"));
+        let uid = OrderUid([1u8; 56]);
+        let path = format!("/v1/orders/updates?owner=0x{owner:x}&orderUid={uid}");
+        let result = update_filter(request().path(path.as_str())).await.unwrap();
+        assert!(result.is_err());
+
+        let result = update_filter(request().path("/v1/orders/updates"))
+            .await
+            .unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn filters_updates() {
+        let uid = OrderUid::from_parts(Default::default(), H160([2; 20]), 0);
+        let update = Update::Invalidation {
+            uid,
+            block_number: 1,
+            log_index: 0,
+        };
+
+        assert!(UpdateFilter::OrderUid(uid).matches(&update));
+        assert!(!UpdateFilter::OrderUid(OrderUid([1; 56])).matches(&update));
+        assert!(UpdateFilter::Owner(H160([2; 20])).matches(&update));
+        assert!(!UpdateFilter::Owner(H160([3; 20])).matches(&update));
+    }
+}
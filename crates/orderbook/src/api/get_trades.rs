@@ -3,12 +3,12 @@ use {
         api::{ApiReply, error},
         database::{
             Postgres,
-            trades::{TradeFilter, TradeRetrieving},
+            trades::{TradeCursor, TradeFilter, TradeRetrieving},
         },
     },
     anyhow::{Context, Result},
     model::order::OrderUid,
-    primitive_types::H160,
+    primitive_types::{H160, H256},
     serde::Deserialize,
     std::convert::Infallible,
     warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
@@ -19,29 +19,71 @@ use {
 struct Query {
     pub order_uid: Option<OrderUid>,
     pub owner: Option<H160>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub tx_hash: Option<H256>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    /// Position of the last trade of the previous page formatted as
+    /// `<blockNumber>:<logIndex>`.
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
 }
 
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+
 #[derive(Debug, Eq, PartialEq)]
 enum TradeFilterError {
     InvalidFilter(String),
 }
 
 impl Query {
-    fn trade_filter(&self) -> TradeFilter {
-        TradeFilter {
+    fn validate(&self) -> Result<TradeFilter, TradeFilterError> {
+        let invalid = |msg: &str| Err(TradeFilterError::InvalidFilter(msg.to_owned()));
+        if self.order_uid.is_some() == self.owner.is_some() {
+            return invalid("Must specify exactly one of owner or orderUid.");
+        }
+        if self
+            .from_block
+            .zip(self.to_block)
+            .is_some_and(|(from_block, to_block)| from_block > to_block)
+        {
+            return invalid("fromBlock must not be greater than toBlock.");
+        }
+        if self
+            .limit
+            .is_some_and(|limit| !(MIN_LIMIT..=MAX_LIMIT).contains(&limit))
+        {
+            return Err(TradeFilterError::InvalidFilter(format!(
+                "The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."
+            )));
+        }
+        let after = match self.cursor.as_deref().map(parse_cursor) {
+            Some(Some(cursor)) => Some(cursor),
+            Some(None) => return invalid("cursor must be formatted as <blockNumber>:<logIndex>."),
+            None => None,
+        };
+        Ok(TradeFilter {
             order_uid: self.order_uid,
             owner: self.owner,
-        }
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            tx_hash: self.tx_hash,
+            from_block: self.from_block,
+            to_block: self.to_block,
+            after,
+            limit: self.limit,
+        })
     }
+}
 
-    fn validate(&self) -> Result<TradeFilter, TradeFilterError> {
-        match (self.order_uid.as_ref(), self.owner.as_ref()) {
-            (Some(_), None) | (None, Some(_)) => Ok(self.trade_filter()),
-            _ => Err(TradeFilterError::InvalidFilter(
-                "Must specify exactly one of owner or orderUid.".to_owned(),
-            )),
-        }
-    }
+fn parse_cursor(cursor: &str) -> Option<TradeCursor> {
+    let (block_number, log_index) = cursor.split_once(':')?;
+    Some(TradeCursor {
+        block_number: block_number.parse().ok()?,
+        log_index: log_index.parse().ok()?,
+    })
 }
 
 fn get_trades_request()
@@ -128,5 +170,52 @@ mod tests {
         let path = "/v1/trades";
         let result = trade_filter(request().path(path)).await.unwrap();
         assert!(result.is_err());
+
+        for query in [
+            "fromBlock=2&toBlock=1",
+            "limit=0",
+            "limit=1001",
+            "cursor=1",
+            "cursor=a:1",
+        ] {
+            let path = format!("/v1/trades?owner=0x{owner:x}&{query}");
+            let result = trade_filter(request().path(path.as_str())).await.unwrap();
+            assert!(result.is_err(), "{query}");
+        }
+    }
+
+    #[tokio::test]
+    async fn get_trades_request_pagination_and_filters() {
+        let owner = H160::from_low_u64_be(1);
+        let token = H160::from_low_u64_be(2);
+        let tx_hash = H256::from_low_u64_be(3);
+        let path = format!(
+            "/v1/trades?owner=0x{owner:x}&sellToken=0x{token:x}&buyToken=0x{token:x}&\
+             txHash=0x{tx_hash:x}&fromBlock=1&toBlock=2&cursor=1:5&limit=10"
+        );
+        let result = request()
+            .path(path.as_str())
+            .method("GET")
+            .filter(&get_trades_request())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            TradeFilter {
+                owner: Some(owner),
+                order_uid: None,
+                sell_token: Some(token),
+                buy_token: Some(token),
+                tx_hash: Some(tx_hash),
+                from_block: Some(1),
+                to_block: Some(2),
+                after: Some(TradeCursor {
+                    block_number: 1,
+                    log_index: 5,
+                }),
+                limit: Some(10),
+            }
+        );
     }
 }
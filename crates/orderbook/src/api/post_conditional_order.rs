@@ -0,0 +1,103 @@
+use {
+    crate::{
+        api::{ApiReply, IntoWarpReply, error, extract_payload},
+        conditional_orders::{ConditionalOrders, CreationError},
+    },
+    anyhow::Result,
+    model::conditional_order::ConditionalOrderCreation,
+    primitive_types::H256,
+    std::{convert::Infallible, sync::Arc},
+    warp::{
+        Filter,
+        Rejection,
+        hyper::StatusCode,
+        reply::{json, with_status},
+    },
+};
+
+fn request() -> impl Filter<Extract = (ConditionalOrderCreation,), Error = Rejection> + Clone {
+    warp::path!("v1" / "conditional_orders")
+        .and(warp::post())
+        .and(extract_payload())
+}
+
+impl IntoWarpReply for CreationError {
+    fn into_warp_reply(self) -> ApiReply {
+        match self {
+            Self::NotAuthorized => with_status(
+                error(
+                    "ConditionalOrderNotAuthorized",
+                    "the conditional order is not authorized by its owner in ComposableCoW",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::Duplicate => with_status(
+                error(
+                    "DuplicatedConditionalOrder",
+                    "conditional order already exists",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::Other(err) => {
+                tracing::error!(?err, "post_conditional_order");
+                crate::api::internal_error_reply()
+            }
+        }
+    }
+}
+
+fn response(result: Result<H256, CreationError>) -> ApiReply {
+    match result {
+        Ok(id) => with_status(json(&id), StatusCode::CREATED),
+        Err(err) => err.into_warp_reply(),
+    }
+}
+
+pub fn post_conditional_order(
+    conditional_orders: Arc<ConditionalOrders>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |order: ConditionalOrderCreation| {
+        let conditional_orders = conditional_orders.clone();
+        async move {
+            let result = conditional_orders.create(&order).await;
+            match &result {
+                Ok(id) => tracing::debug!(?id, "conditional order created"),
+                Err(err) => tracing::debug!(?order, ?err, "error creating conditional order"),
+            }
+            Result::<_, Infallible>::Ok(response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::api::response_body,
+        serde_json::json,
+        warp::{Reply, test::request},
+    };
+
+    #[tokio::test]
+    async fn request_ok() {
+        let order = ConditionalOrderCreation::default();
+        let result = request()
+            .path("/v1/conditional_orders")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&order)
+            .filter(&super::request())
+            .await
+            .unwrap();
+        assert_eq!(result, order);
+    }
+
+    #[tokio::test]
+    async fn response_not_authorized() {
+        let response = response(Err(CreationError::NotAuthorized)).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = response_body(response).await;
+        let body: serde_json::Value = serde_json::from_slice(body.as_slice()).unwrap();
+        assert_eq!(body["errorType"], json!("ConditionalOrderNotAuthorized"));
+    }
+}
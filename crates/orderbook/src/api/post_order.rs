@@ -1,6 +1,12 @@
 use {
     crate::{
-        api::{ApiReply, IntoWarpReply, error, extract_payload},
+        api::{
+            ApiReply,
+            IntoWarpReply,
+            error,
+            extract_payload,
+            rate_limit::{self, RateLimiter, Route},
+        },
         orderbook::{AddOrderError, OrderReplacementError, Orderbook},
     },
     anyhow::Result,
@@ -19,8 +25,9 @@ use {
     warp::{
         Filter,
         Rejection,
+        Reply,
         hyper::StatusCode,
-        reply::{self, with_status},
+        reply::{self, Response, with_status},
     },
 };
 
@@ -116,7 +123,7 @@ impl IntoWarpReply for AppDataValidationErrorWrapper {
     }
 }
 
-pub struct ValidationErrorWrapper(ValidationError);
+pub struct ValidationErrorWrapper(pub ValidationError);
 impl IntoWarpReply for ValidationErrorWrapper {
     fn into_warp_reply(self) -> ApiReply {
         match self.0 {
@@ -304,21 +311,32 @@ pub fn create_order_response(
 
 pub fn post_order(
     orderbook: Arc<Orderbook>,
-) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
-    create_order_request().and_then(move |order: OrderCreation| {
-        let orderbook = orderbook.clone();
-        async move {
-            let result = orderbook.add_order(order.clone()).await;
-            match &result {
-                Ok((order_uid, quote_id)) => {
-                    tracing::debug!(%order_uid, ?quote_id, "order created")
+    rate_limiter: Arc<RateLimiter>,
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
+    create_order_request()
+        .and(rate_limiter.clone().client())
+        .and_then(move |order: OrderCreation, client| {
+            let orderbook = orderbook.clone();
+            let rate_limiter = rate_limiter.clone();
+            async move {
+                if let Err(response) = rate_limiter.check(
+                    Route::Order,
+                    &client,
+                    [rate_limit::app_code(&order.app_data)],
+                ) {
+                    return Result::<_, Infallible>::Ok(response);
+                }
+                let result = orderbook.add_order(order.clone()).await;
+                match &result {
+                    Ok((order_uid, quote_id)) => {
+                        tracing::debug!(%order_uid, ?quote_id, "order created")
+                    }
+                    Err(err) => tracing::debug!(?order, ?err, "error creating order"),
                 }
-                Err(err) => tracing::debug!(?order, ?err, "error creating order"),
-            }
 
-            Result::<_, Infallible>::Ok(create_order_response(result))
-        }
-    })
+                Ok(create_order_response(result).into_response())
+            }
+        })
 }
 
 #[cfg(test)]
@@ -328,7 +346,7 @@ mod tests {
         crate::api::response_body,
         model::order::{OrderCreation, OrderUid},
         serde_json::json,
-        warp::{Reply, test::request},
+        warp::test::request,
     };
 
     #[tokio::test]
@@ -351,7 +369,7 @@ mod tests {
         assert_eq!(response.status(), StatusCode::CREATED);
         let body = response_body(response).await;
         let body: serde_json::Value = serde_json::from_slice(body.as_slice()).unwrap();
-        let expected= json!(
+        let expected = json!(
             "0x0101010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101010101"
         );
         assert_eq!(body, expected);
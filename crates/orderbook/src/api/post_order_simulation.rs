@@ -0,0 +1,312 @@
+use {
+    super::{post_order::ValidationErrorWrapper, post_quote::CalculateQuoteErrorWrapper},
+    crate::{
+        api::{
+            self,
+            IntoWarpReply,
+            rate_limit::{self, RateLimiter, Route},
+        },
+        order_simulation::{HookSimulation, OrderSimulation, OrderSimulator},
+    },
+    ::app_data::Hook,
+    model::order::{OrderCreation, OrderUid},
+    number::serialization::HexOrDecimalU256,
+    primitive_types::{H160, U256},
+    serde::Serialize,
+    serde_with::serde_as,
+    shared::interaction::InteractionExecution,
+    std::{convert::Infallible, sync::Arc},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        hyper::StatusCode,
+        reply::{Response, with_status},
+    },
+};
+
+fn post_order_simulation_request()
+-> impl Filter<Extract = (OrderCreation,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "simulate")
+        .and(warp::post())
+        .and(api::extract_payload())
+}
+
+pub fn post_order_simulation(
+    simulator: Arc<OrderSimulator>,
+    rate_limiter: Arc<RateLimiter>,
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
+    post_order_simulation_request()
+        .and(rate_limiter.clone().client())
+        .and_then(move |order: OrderCreation, client| {
+            let simulator = simulator.clone();
+            let rate_limiter = rate_limiter.clone();
+            async move {
+                if let Err(response) = rate_limiter.check(
+                    Route::Quote,
+                    &client,
+                    [rate_limit::app_code(&order.app_data)],
+                ) {
+                    return Result::<_, Infallible>::Ok(response);
+                }
+                let reply = match simulator.simulate(order).await {
+                    Ok(simulation) => with_status(
+                        warp::reply::json(&SimulationReport::new(simulation).await),
+                        StatusCode::OK,
+                    ),
+                    Err(err) => {
+                        tracing::debug!(?err, "order can't be simulated");
+                        ValidationErrorWrapper(err).into_warp_reply()
+                    }
+                };
+                Ok(reply.into_response())
+            }
+        })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulationReport {
+    owner: H160,
+    order_uid: OrderUid,
+    /// The error the order would currently get rejected with when placing it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation_error: Option<serde_json::Value>,
+    /// `None` if the transfer could not be simulated.
+    balance: Option<BalanceReport>,
+    hooks: HooksReport,
+    /// `None` if the order could not be settled.
+    trade: Option<TradeReport>,
+    /// The error the order's settlement simulation failed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trade_error: Option<serde_json::Value>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BalanceReport {
+    #[serde_as(as = "HexOrDecimalU256")]
+    balance: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    allowance: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    effective_balance: U256,
+    can_transfer: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct HooksReport {
+    pre: Vec<HookReport>,
+    post: Vec<HookReport>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HookReport {
+    #[serde(flatten)]
+    hook: Hook,
+    /// How the hook got executed when simulating the sell token transfer.
+    /// Omitted for post-hooks since they are not part of that simulation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transfer: Option<Option<ExecutionReport>>,
+    /// How the hook got executed when simulating the settlement. `None` if
+    /// the hook was not executed or the simulation could not be traced.
+    settlement: Option<ExecutionReport>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionReport {
+    success: bool,
+    gas_used: u64,
+}
+
+impl HookReport {
+    fn new(hook: HookSimulation, pre: bool) -> Self {
+        let report = |execution: Option<InteractionExecution>| {
+            execution.map(|execution| ExecutionReport {
+                success: execution.success,
+                gas_used: execution.gas_used,
+            })
+        };
+        Self {
+            hook: hook.hook,
+            transfer: pre.then(|| report(hook.transfer)),
+            settlement: report(hook.settlement),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TradeReport {
+    verified: bool,
+    /// Gas used by the settlement of the order, including its hooks. Taken
+    /// from the settlement simulation if the trade was verified, otherwise
+    /// estimated by the solver.
+    gas_used: u64,
+    #[serde_as(as = "HexOrDecimalU256")]
+    executed_sell_amount: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    executed_buy_amount: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    fee_amount: U256,
+}
+
+impl SimulationReport {
+    async fn new(simulation: OrderSimulation) -> Self {
+        let validation_error = match simulation.validation {
+            Ok(()) => None,
+            Err(err) => Some(api::error_body(ValidationErrorWrapper(err)).await),
+        };
+        let balance = match simulation.transfer {
+            Ok(transfer) => Some(BalanceReport {
+                balance: transfer.token_balance,
+                allowance: transfer.allowance,
+                effective_balance: transfer.effective_balance,
+                can_transfer: transfer.can_transfer,
+            }),
+            Err(err) => {
+                tracing::warn!(?err, "failed to simulate sell token transfer");
+                None
+            }
+        };
+        let (trade, trade_error) = match simulation.trade {
+            Ok(quote) => (
+                Some(TradeReport {
+                    verified: quote.data.verified,
+                    gas_used: quote.data.fee_parameters.gas_amount as u64,
+                    executed_sell_amount: quote.sell_amount,
+                    executed_buy_amount: quote.buy_amount,
+                    fee_amount: quote.fee_amount,
+                }),
+                None,
+            ),
+            Err(err) => (
+                None,
+                Some(api::error_body(CalculateQuoteErrorWrapper(err)).await),
+            ),
+        };
+
+        Self {
+            owner: simulation.owner,
+            order_uid: simulation.uid,
+            validation_error,
+            balance,
+            hooks: HooksReport {
+                pre: simulation
+                    .pre_hooks
+                    .into_iter()
+                    .map(|hook| HookReport::new(hook, true))
+                    .collect(),
+                post: simulation
+                    .post_hooks
+                    .into_iter()
+                    .map(|hook| HookReport::new(hook, false))
+                    .collect(),
+            },
+            trade,
+            trade_error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        anyhow::anyhow,
+        serde_json::json,
+        shared::order_validation::ValidationError,
+        warp::test::request,
+    };
+
+    #[tokio::test]
+    async fn post_order_simulation_request_ok() {
+        let filter = post_order_simulation_request();
+        let order = OrderCreation::default();
+        let request = request()
+            .path("/v1/orders/simulate")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&order);
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, order);
+    }
+
+    #[tokio::test]
+    async fn serializes_simulation_report() {
+        let report = SimulationReport::new(OrderSimulation {
+            owner: H160([0x01; 20]),
+            uid: OrderUid([0x02; 56]),
+            validation: Err(ValidationError::InsufficientBalance),
+            transfer: Ok(Default::default()),
+            pre_hooks: vec![HookSimulation {
+                hook: Hook {
+                    target: H160([0x03; 20]),
+                    call_data: vec![0x04],
+                    gas_limit: 100_000,
+                },
+                transfer: Some(InteractionExecution {
+                    success: true,
+                    gas_used: 21_000,
+                }),
+                settlement: None,
+            }],
+            post_hooks: vec![HookSimulation {
+                hook: Hook {
+                    target: H160([0x05; 20]),
+                    call_data: vec![],
+                    gas_limit: 50_000,
+                },
+                transfer: None,
+                settlement: Some(InteractionExecution {
+                    success: false,
+                    gas_used: 50_000,
+                }),
+            }],
+            trade: Err(anyhow!("boom").into()),
+        })
+        .await;
+
+        let report = serde_json::to_value(report).unwrap();
+        assert_eq!(
+            report["validationError"]["errorType"],
+            json!("InsufficientBalance")
+        );
+        assert_eq!(
+            report["balance"],
+            json!({
+                "balance": "0",
+                "allowance": "0",
+                "effectiveBalance": "0",
+                "canTransfer": false,
+            })
+        );
+        assert_eq!(
+            report["hooks"],
+            json!({
+                "pre": [{
+                    "target": "0x0303030303030303030303030303030303030303",
+                    "callData": "0x04",
+                    "gasLimit": "100000",
+                    "transfer": { "success": true, "gasUsed": 21000 },
+                    "settlement": null,
+                }],
+                "post": [{
+                    "target": "0x0505050505050505050505050505050505050505",
+                    "callData": "0x",
+                    "gasLimit": "50000",
+                    "settlement": { "success": false, "gasUsed": 50000 },
+                }],
+            })
+        );
+        assert_eq!(report["trade"], json!(null));
+        assert_eq!(
+            report["tradeError"]["errorType"],
+            json!("InternalServerError")
+        );
+    }
+}
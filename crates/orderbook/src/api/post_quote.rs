@@ -1,7 +1,15 @@
 use {
     super::post_order::{AppDataValidationErrorWrapper, PartialValidationErrorWrapper},
     crate::{
-        api::{self, ApiReply, IntoWarpReply, convert_json_response, error, rich_error},
+        api::{
+            self,
+            ApiReply,
+            IntoWarpReply,
+            convert_json_response,
+            error,
+            rate_limit::{self, RateLimiter, Route},
+            rich_error,
+        },
         quoter::{OrderQuoteError, QuoteHandler},
     },
     anyhow::Result,
@@ -9,7 +17,7 @@ use {
     reqwest::StatusCode,
     shared::order_quoting::CalculateQuoteError,
     std::{convert::Infallible, sync::Arc},
-    warp::{Filter, Rejection},
+    warp::{Filter, Rejection, Reply, reply::Response},
 };
 
 fn post_quote_request() -> impl Filter<Extract = (OrderQuoteRequest,), Error = Rejection> + Clone {
@@ -20,20 +28,31 @@ fn post_quote_request() -> impl Filter<Extract = (OrderQuoteRequest,), Error = R
 
 pub fn post_quote(
     quotes: Arc<QuoteHandler>,
-) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
-    post_quote_request().and_then(move |request: OrderQuoteRequest| {
-        let quotes = quotes.clone();
-        async move {
-            let result = quotes
-                .calculate_quote(&request)
-                .await
-                .map_err(OrderQuoteErrorWrapper);
-            if let Err(err) = &result {
-                tracing::warn!(?err, ?request, "post_quote error");
+    rate_limiter: Arc<RateLimiter>,
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
+    post_quote_request()
+        .and(rate_limiter.clone().client())
+        .and_then(move |request: OrderQuoteRequest, client| {
+            let quotes = quotes.clone();
+            let rate_limiter = rate_limiter.clone();
+            async move {
+                if let Err(response) = rate_limiter.check(
+                    Route::Quote,
+                    &client,
+                    [rate_limit::app_code(&request.app_data)],
+                ) {
+                    return Result::<_, Infallible>::Ok(response);
+                }
+                let result = quotes
+                    .calculate_quote(&request)
+                    .await
+                    .map_err(OrderQuoteErrorWrapper);
+                if let Err(err) = &result {
+                    tracing::warn!(?err, ?request, "post_quote error");
+                }
+                Ok(convert_json_response(result).into_response())
             }
-            Result::<_, Infallible>::Ok(convert_json_response(result))
-        }
-    })
+        })
 }
 
 #[derive(Debug)]
@@ -50,7 +69,7 @@ impl IntoWarpReply for OrderQuoteErrorWrapper {
     }
 }
 
-pub struct CalculateQuoteErrorWrapper(CalculateQuoteError);
+pub struct CalculateQuoteErrorWrapper(pub CalculateQuoteError);
 impl IntoWarpReply for CalculateQuoteErrorWrapper {
     fn into_warp_reply(self) -> ApiReply {
         match self.0 {
@@ -106,7 +125,7 @@ mod tests {
         reqwest::StatusCode,
         serde_json::json,
         shared::order_quoting::CalculateQuoteError,
-        warp::{Reply, test::request},
+        warp::test::request,
     };
 
     #[test]
@@ -287,6 +306,7 @@ mod tests {
             expiration: Utc.timestamp_millis_opt(0).unwrap(),
             id: Some(0),
             verified: false,
+            suggested_slippage_bps: Some(50),
         };
         let response = convert_json_response::<OrderQuoteResponse, OrderQuoteErrorWrapper>(Ok(
             order_quote_response.clone(),
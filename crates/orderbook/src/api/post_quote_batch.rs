@@ -0,0 +1,164 @@
+use {
+    super::post_quote::OrderQuoteErrorWrapper,
+    crate::{
+        api::{
+            self,
+            ApiReply,
+            IntoWarpReply,
+            MAX_JSON_BODY_PAYLOAD,
+            error,
+            rate_limit::{self, RateLimiter, Route},
+        },
+        quoter::QuoteHandler,
+    },
+    model::quote::{OrderQuoteRequest, OrderQuoteResponse},
+    reqwest::StatusCode,
+    serde::Serialize,
+    std::{convert::Infallible, sync::Arc, time::Duration},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        reply::{Response, with_status},
+    },
+};
+
+/// The maximum number of quote requests in a batch.
+const MAX_BATCH_SIZE: usize = 50;
+
+/// The time all quotes of a batch have to be computed in. Quotes that aren't
+/// ready by then are returned as errors.
+const BATCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn post_quote_batch_request()
+-> impl Filter<Extract = (Vec<OrderQuoteRequest>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "quotes" / "batch")
+        .and(warp::post())
+        .and(api::extract_payload_with_max_size(
+            MAX_JSON_BODY_PAYLOAD * MAX_BATCH_SIZE as u64,
+        ))
+}
+
+pub fn post_quote_batch(
+    quotes: Arc<QuoteHandler>,
+    rate_limiter: Arc<RateLimiter>,
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
+    post_quote_batch_request()
+        .and(rate_limiter.clone().client())
+        .and_then(move |requests: Vec<OrderQuoteRequest>, client| {
+            let quotes = quotes.clone();
+            let rate_limiter = rate_limiter.clone();
+            async move {
+                if requests.len() > MAX_BATCH_SIZE {
+                    return Result::<_, Infallible>::Ok(
+                        with_status(
+                            error(
+                                "TooManyQuoteRequests",
+                                format!("at most {MAX_BATCH_SIZE} quotes can be requested at once"),
+                            ),
+                            StatusCode::BAD_REQUEST,
+                        )
+                        .into_response(),
+                    );
+                }
+                // Every quote of the batch counts against the quote rate limit.
+                if let Err(response) = rate_limiter.check(
+                    Route::Quote,
+                    &client,
+                    requests
+                        .iter()
+                        .map(|request| rate_limit::app_code(&request.app_data)),
+                ) {
+                    return Ok(response);
+                }
+
+                // Quotes of the batch with identical price queries share their
+                // price estimates since the price estimator shares all queries
+                // that are in flight at the same time.
+                let deadline = tokio::time::Instant::now() + BATCH_TIMEOUT;
+                let quotes = &quotes;
+                let results =
+                    futures::future::join_all(requests.iter().map(|request| async move {
+                        match tokio::time::timeout_at(deadline, quotes.calculate_quote(request))
+                            .await
+                        {
+                            Ok(Ok(response)) => BatchQuoteResult::Quote(response),
+                            Ok(Err(err)) => {
+                                tracing::warn!(?err, ?request, "post_quote_batch error");
+                                BatchQuoteResult::error(OrderQuoteErrorWrapper(err)).await
+                            }
+                            Err(_) => BatchQuoteResult::error(Timeout).await,
+                        }
+                    }))
+                    .await;
+                Ok(with_status(warp::reply::json(&results), StatusCode::OK).into_response())
+            }
+        })
+}
+
+/// The result of a single quote request in a batch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum BatchQuoteResult {
+    Quote(OrderQuoteResponse),
+    /// The error that the quote endpoint would respond with.
+    Error(serde_json::Value),
+}
+
+impl BatchQuoteResult {
+    async fn error(err: impl IntoWarpReply) -> Self {
+        Self::Error(api::error_body(err).await)
+    }
+}
+
+struct Timeout;
+
+impl IntoWarpReply for Timeout {
+    fn into_warp_reply(self) -> ApiReply {
+        with_status(
+            error(
+                "Timeout",
+                "The quote could not be computed before the batch deadline.",
+            ),
+            StatusCode::GATEWAY_TIMEOUT,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::H160, serde_json::json, warp::test::request};
+
+    #[tokio::test]
+    async fn post_quote_batch_request_ok() {
+        let filter = post_quote_batch_request();
+        let request_payload = vec![
+            OrderQuoteRequest::default(),
+            OrderQuoteRequest {
+                from: H160([0x01; 20]),
+                ..Default::default()
+            },
+        ];
+        let request = request()
+            .path("/v1/quotes/batch")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&request_payload);
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, request_payload);
+    }
+
+    #[tokio::test]
+    async fn serializes_batch_results() {
+        let error = BatchQuoteResult::error(Timeout).await;
+        assert_eq!(
+            serde_json::to_value(error).unwrap(),
+            json!({
+                "error": {
+                    "errorType": "Timeout",
+                    "description": "The quote could not be computed before the batch deadline.",
+                },
+            }),
+        );
+    }
+}
@@ -0,0 +1,664 @@
+//! Token bucket rate limiting of incoming API requests.
+//!
+//! Every client gets its own bucket per configured route quota. Clients
+//! sending a known API key in the `X-API-Key` header are identified by that
+//! key. All other clients are identified by their IP address. Requests whose
+//! app data contains an `appCode` are additionally charged to a bucket for
+//! that app code within the IP address' bucket, so a single app can't use up
+//! the quota of all apps sharing an IP address. App codes are chosen by the
+//! clients, so they never replace the IP address' bucket.
+
+use {
+    anyhow::{Context, Result, ensure},
+    model::order::OrderCreationAppData,
+    observe::request_id::RemoteAddr,
+    serde::Deserialize,
+    std::{
+        collections::HashMap,
+        convert::Infallible,
+        fmt::{self, Display, Formatter},
+        net::IpAddr,
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    warp::{
+        Filter,
+        Reply,
+        http::HeaderMap,
+        hyper::StatusCode,
+        reply::{Response, with_header, with_status},
+    },
+};
+
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// How often buckets that are full again get dropped.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The quotas requests can get charged to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Route {
+    /// Quotes, order simulations and batches of quotes (one token per quote).
+    Quote,
+    /// Order placements.
+    Order,
+}
+
+impl Route {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Quote => "quote",
+            Self::Order => "order",
+        }
+    }
+}
+
+impl FromStr for Route {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "quote" => Ok(Self::Quote),
+            "order" => Ok(Self::Order),
+            _ => anyhow::bail!("unknown route {s:?}, expected `quote` or `order`"),
+        }
+    }
+}
+
+/// A quota for requests to a route. Parsed from
+/// `<route>=<requests>/<period>`, for example `quote=100/1m`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteQuota {
+    route: Route,
+    /// The number of requests a client can make in a burst.
+    requests: u32,
+    /// The time it takes to replenish all `requests`.
+    period: Duration,
+}
+
+impl FromStr for RouteQuota {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (route, quota) = s.split_once('=').context("missing quota")?;
+        let route = route.parse()?;
+        let (requests, period) = quota.split_once('/').context("missing period")?;
+        let requests: u32 = requests.parse().context("parsing requests")?;
+        ensure!(requests > 0, "requests must be positive");
+        let period = humantime::parse_duration(period).context("parsing period")?;
+        ensure!(!period.is_zero(), "period must be positive");
+        Ok(Self {
+            route,
+            requests,
+            period,
+        })
+    }
+}
+
+impl Display for RouteQuota {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}={}/{}",
+            self.route.label(),
+            self.requests,
+            humantime::format_duration(self.period)
+        )
+    }
+}
+
+/// An API key clients can identify themselves with. Parsed from
+/// `<name>:<key>`. Only the name shows up in logs and metrics.
+#[derive(Clone, Eq, PartialEq)]
+pub struct ApiKey {
+    name: String,
+    key: String,
+}
+
+impl ApiKey {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl FromStr for ApiKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, key) = s.split_once(':').context("missing API key name")?;
+        ensure!(!name.is_empty(), "missing API key name");
+        ensure!(!key.is_empty(), "missing API key");
+        Ok(Self {
+            name: name.to_string(),
+            key: key.to_string(),
+        })
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKey")
+            .field("name", &self.name)
+            .field("key", &"SECRET")
+            .finish()
+    }
+}
+
+/// Who sent a request.
+#[derive(Clone, Debug, Default)]
+pub struct Client {
+    /// The name of the client's API key.
+    api_key: Option<String>,
+    ip: Option<IpAddr>,
+}
+
+impl Client {
+    /// The label used in metrics. IP addresses are not known in advance so
+    /// they get aggregated to not blow up the metrics cardinality.
+    fn metric_label(&self) -> &str {
+        self.api_key.as_deref().unwrap_or("ip")
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Owner {
+    ApiKey(String),
+    Ip(IpAddr),
+    AppCode(IpAddr, String),
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    /// The time it takes to replenish all tokens.
+    period: Duration,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(capacity: f64, period: Duration, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            capacity,
+            period,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated);
+        let refilled = self.capacity * elapsed.as_secs_f64() / self.period.as_secs_f64();
+        self.tokens = (self.tokens + refilled).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Returns how long it takes until `cost` tokens can be taken from the
+    /// bucket. Requests costing more than the capacity can be made once the
+    /// bucket is full and leave it in debt.
+    fn wait_time(&self, cost: f64) -> Duration {
+        let needed = cost.min(self.capacity);
+        if self.tokens >= needed {
+            Duration::ZERO
+        } else {
+            self.period.mul_f64((needed - self.tokens) / self.capacity)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<(Route, Owner), Bucket>,
+    last_cleanup: Instant,
+}
+
+/// Limits how many requests every client can make to the configured routes.
+pub struct RateLimiter {
+    quotas: Vec<RouteQuota>,
+    /// Names of the known API keys by key.
+    api_keys: HashMap<String, String>,
+    /// Proxies whose `X-Forwarded-For` header can be trusted.
+    trusted_proxies: Vec<IpAddr>,
+    /// The share of an IP address' quota a single app code can use. Every app
+    /// code can make at least one request per period.
+    app_code_share: f64,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        quotas: Vec<RouteQuota>,
+        api_keys: Vec<ApiKey>,
+        trusted_proxies: Vec<IpAddr>,
+        app_code_share: f64,
+    ) -> Self {
+        assert!(
+            (0. ..=1.).contains(&app_code_share),
+            "app code share must be in [0, 1]"
+        );
+        let metrics = Metrics::get();
+        for quota in &quotas {
+            let route = quota.route.label();
+            for key in &api_keys {
+                metrics.reset(route, &key.name);
+            }
+            metrics.reset(route, "ip");
+        }
+
+        Self {
+            quotas,
+            api_keys: api_keys
+                .into_iter()
+                .map(|ApiKey { name, key }| (key, name))
+                .collect(),
+            trusted_proxies,
+            app_code_share,
+            buckets: Mutex::new(Buckets {
+                buckets: Default::default(),
+                last_cleanup: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns a filter extracting who sent the request.
+    pub fn client(self: Arc<Self>) -> impl Filter<Extract = (Client,), Error = Infallible> + Clone {
+        warp::header::headers_cloned()
+            .and(warp::ext::optional::<RemoteAddr>())
+            .map(move |headers: HeaderMap, remote: Option<RemoteAddr>| {
+                self.identify(&headers, remote.map(|remote| remote.0.ip()))
+            })
+    }
+
+    /// Charges one token per item of `app_codes` to the client's quota for
+    /// the route. Returns a `429 Too Many Requests` response if the client
+    /// exceeded its quota.
+    pub fn check(
+        &self,
+        route: Route,
+        client: &Client,
+        app_codes: impl IntoIterator<Item = Option<String>>,
+    ) -> Result<(), Response> {
+        self.acquire(route, client, app_codes, Instant::now())
+            .map_err(too_many_requests)
+    }
+
+    fn identify(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Client {
+        let api_key = headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|key| self.api_keys.get(key.trim()))
+            .cloned();
+        Client {
+            api_key,
+            ip: peer.map(|peer| self.client_ip(headers, peer)),
+        }
+    }
+
+    /// Proxies append the address they received a request from to the
+    /// `X-Forwarded-For` header. Walking the header backwards as long as the
+    /// addresses belong to trusted proxies yields the client's address. Any
+    /// address before that was chosen by the client and can't be trusted.
+    fn client_ip(&self, headers: &HeaderMap, peer: IpAddr) -> IpAddr {
+        let forwarded = headers
+            .get_all("X-Forwarded-For")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut ip = peer;
+        for address in forwarded.rsplit(',') {
+            if !self.trusted_proxies.contains(&ip) {
+                break;
+            }
+            match address.trim().parse() {
+                Ok(address) => ip = address,
+                Err(_) => break,
+            }
+        }
+        ip
+    }
+
+    fn acquire(
+        &self,
+        route: Route,
+        client: &Client,
+        app_codes: impl IntoIterator<Item = Option<String>>,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let Some(quota) = self.quotas.iter().find(|quota| quota.route == route) else {
+            return Ok(());
+        };
+        let capacity = f64::from(quota.requests);
+
+        // The buckets to charge along with their capacity and cost.
+        let mut charges = Vec::new();
+        let mut cost = 0.;
+        match (&client.api_key, client.ip) {
+            (Some(name), _) => {
+                cost = app_codes.into_iter().count() as f64;
+                charges.push((Owner::ApiKey(name.clone()), capacity, cost));
+            }
+            (None, Some(ip)) => {
+                let mut app_code_costs = HashMap::<String, f64>::new();
+                for app_code in app_codes {
+                    cost += 1.;
+                    if let Some(app_code) = app_code {
+                        *app_code_costs.entry(app_code).or_default() += 1.;
+                    }
+                }
+                charges.push((Owner::Ip(ip), capacity, cost));
+                let app_code_capacity = (capacity * self.app_code_share).max(1.);
+                charges.extend(app_code_costs.into_iter().map(|(app_code, cost)| {
+                    (Owner::AppCode(ip, app_code), app_code_capacity, cost)
+                }));
+            }
+            // Requests that can't be attributed to anyone can't be limited.
+            (None, None) => return Ok(()),
+        }
+
+        let retry_after = {
+            let mut buckets = self.buckets.lock().unwrap();
+            if now.saturating_duration_since(buckets.last_cleanup) >= CLEANUP_INTERVAL {
+                buckets.buckets.retain(|_, bucket| {
+                    bucket.refill(now);
+                    bucket.tokens < bucket.capacity
+                });
+                buckets.last_cleanup = now;
+            }
+
+            // Only take tokens if all buckets have enough of them.
+            let retry_after = charges
+                .iter()
+                .map(|(owner, capacity, cost)| {
+                    let bucket = buckets
+                        .buckets
+                        .entry((route, owner.clone()))
+                        .or_insert_with(|| Bucket::full(*capacity, quota.period, now));
+                    bucket.refill(now);
+                    bucket.wait_time(*cost)
+                })
+                .max()
+                .unwrap_or_default();
+            if retry_after.is_zero() {
+                for (owner, _, cost) in &charges {
+                    if let Some(bucket) = buckets.buckets.get_mut(&(route, owner.clone())) {
+                        bucket.tokens -= cost;
+                    }
+                }
+            }
+            retry_after
+        };
+
+        let metrics = Metrics::get();
+        let labels = [route.label(), client.metric_label()];
+        if retry_after.is_zero() {
+            metrics
+                .requests_allowed
+                .with_label_values(&labels)
+                .inc_by(cost as u64);
+            Ok(())
+        } else {
+            tracing::debug!(?client, ?route, cost, ?retry_after, "rate limited request");
+            metrics
+                .requests_limited
+                .with_label_values(&labels)
+                .inc_by(cost as u64);
+            Err(retry_after)
+        }
+    }
+}
+
+/// Returns the `appCode` of the app data. Requests only specifying the app
+/// data hash don't have an app code since looking up the document would
+/// defeat the purpose of rate limiting.
+pub fn app_code(app_data: &OrderCreationAppData) -> Option<String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Document {
+        app_code: Option<String>,
+    }
+
+    match app_data {
+        OrderCreationAppData::Full { full } | OrderCreationAppData::Both { full, .. } => {
+            serde_json::from_str::<Document>(full).ok()?.app_code
+        }
+        OrderCreationAppData::Hash { .. } => None,
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    // `Retry-After` only supports whole seconds.
+    let retry_after = retry_after.as_secs_f64().ceil().max(1.);
+    with_header(
+        with_status(
+            super::error(
+                "TooManyRequests",
+                "Too many requests, please slow down and retry later.",
+            ),
+            StatusCode::TOO_MANY_REQUESTS,
+        ),
+        "Retry-After",
+        format!("{retry_after}"),
+    )
+    .into_response()
+}
+
+#[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
+#[metric(subsystem = "api_rate_limit")]
+struct Metrics {
+    /// Number of requests that were within the client's quota.
+    #[metric(labels("route", "client"))]
+    requests_allowed: prometheus::IntCounterVec,
+
+    /// Number of requests that got rejected because the client exceeded its
+    /// quota.
+    #[metric(labels("route", "client"))]
+    requests_limited: prometheus::IntCounterVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Self::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+
+    fn reset(&self, route: &str, client: &str) {
+        self.requests_allowed
+            .with_label_values(&[route, client])
+            .reset();
+        self.requests_limited
+            .with_label_values(&[route, client])
+            .reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::net::SocketAddr, warp::test::request};
+
+    fn limiter(quotas: &[&str]) -> RateLimiter {
+        RateLimiter::new(
+            quotas.iter().map(|quota| quota.parse().unwrap()).collect(),
+            vec!["partner:secret".parse().unwrap()],
+            vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+            0.5,
+        )
+    }
+
+    fn ip(ip: &str) -> Client {
+        Client {
+            api_key: None,
+            ip: Some(ip.parse().unwrap()),
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    warp::http::HeaderName::from_static(name),
+                    warp::http::HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_route_quotas() {
+        assert_eq!(
+            "quote=100/1m".parse::<RouteQuota>().unwrap(),
+            RouteQuota {
+                route: Route::Quote,
+                requests: 100,
+                period: Duration::from_secs(60),
+            }
+        );
+        for invalid in [
+            "quote",
+            "quote=100",
+            "=100/1m",
+            "v1/quote=100/1m",
+            "quote=0/1m",
+            "quote=100/0s",
+        ] {
+            assert!(invalid.parse::<RouteQuota>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn identifies_clients() {
+        let limiter = limiter(&[]);
+        let identify = |headers: &HeaderMap, peer: &str| {
+            let client = limiter.identify(headers, Some(peer.parse().unwrap()));
+            (client.api_key, client.ip.unwrap().to_string())
+        };
+
+        assert_eq!(
+            identify(&headers(&[("x-api-key", "secret")]), "1.1.1.1"),
+            (Some("partner".to_string()), "1.1.1.1".to_string())
+        );
+        assert_eq!(
+            identify(&headers(&[("x-api-key", "unknown")]), "1.1.1.1"),
+            (None, "1.1.1.1".to_string())
+        );
+        // Forwarded addresses are ignored unless the peer is a trusted proxy.
+        assert_eq!(
+            identify(&headers(&[("x-forwarded-for", "2.2.2.2")]), "1.1.1.1"),
+            (None, "1.1.1.1".to_string())
+        );
+        // Addresses prepended by the client are ignored.
+        assert_eq!(
+            identify(
+                &headers(&[("x-forwarded-for", "3.3.3.3, 2.2.2.2, 10.0.0.2")]),
+                "10.0.0.1"
+            ),
+            (None, "2.2.2.2".to_string())
+        );
+        assert_eq!(limiter.identify(&headers(&[]), None).ip, None);
+    }
+
+    #[test]
+    fn extracts_app_code() {
+        assert_eq!(
+            app_code(&OrderCreationAppData::Full {
+                full: r#"{"appCode":"CoW Swap","metadata":{}}"#.to_string(),
+            }),
+            Some("CoW Swap".to_string())
+        );
+        assert_eq!(
+            app_code(&OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            }),
+            None
+        );
+        assert_eq!(app_code(&OrderCreationAppData::default()), None);
+    }
+
+    #[test]
+    fn limits_requests_per_client() {
+        let limiter = limiter(&["quote=2/1s"]);
+        let alice = ip("1.1.1.1");
+        let bob = ip("2.2.2.2");
+        let check = |client: &Client, now| limiter.acquire(Route::Quote, client, [None], now);
+        let now = Instant::now();
+
+        assert_eq!(check(&alice, now), Ok(()));
+        assert_eq!(check(&alice, now), Ok(()));
+        assert_eq!(check(&alice, now), Err(Duration::from_millis(500)));
+        // Other clients and routes are not affected.
+        assert_eq!(check(&bob, now), Ok(()));
+        assert_eq!(limiter.acquire(Route::Order, &alice, [None], now), Ok(()));
+        // Tokens get replenished over time.
+        assert_eq!(check(&alice, now + Duration::from_millis(500)), Ok(()));
+        assert!(check(&alice, now + Duration::from_millis(500)).is_err());
+    }
+
+    #[test]
+    fn app_codes_subdivide_ip_quota() {
+        let limiter = limiter(&["quote=4/4s"]);
+        let client = ip("1.1.1.1");
+        let check = |app_code: &str| {
+            limiter.acquire(
+                Route::Quote,
+                &client,
+                [Some(app_code.to_string())],
+                Instant::now(),
+            )
+        };
+
+        // Every app code can use half of the IP address' quota.
+        assert_eq!(check("a"), Ok(()));
+        assert_eq!(check("a"), Ok(()));
+        assert!(check("a").is_err());
+        // Switching app codes doesn't give the IP address more requests.
+        assert_eq!(check("b"), Ok(()));
+        assert_eq!(check("b"), Ok(()));
+        assert!(check("c").is_err());
+    }
+
+    #[test]
+    fn charges_one_token_per_item() {
+        let limiter = limiter(&["quote=10/10s"]);
+        let client = ip("1.1.1.1");
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.acquire(Route::Quote, &client, vec![None; 8], now),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.acquire(Route::Quote, &client, vec![None; 3], now),
+            Err(Duration::from_secs(1))
+        );
+        // Batches larger than the quota are accepted once the bucket is full
+        // and put it into debt.
+        let client = ip("2.2.2.2");
+        assert_eq!(
+            limiter.acquire(Route::Quote, &client, vec![None; 15], now),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.acquire(Route::Quote, &client, [None], now),
+            Err(Duration::from_secs(6))
+        );
+    }
+
+    #[tokio::test]
+    async fn identifies_clients_by_remote_address() {
+        let filter = Arc::new(limiter(&[])).client();
+        let client = request()
+            .extension(RemoteAddr(SocketAddr::from(([1, 1, 1, 1], 1234))))
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(client.ip, Some("1.1.1.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn responds_with_retry_after() {
+        let response = too_many_requests(Duration::from_millis(59_500));
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "60");
+    }
+}
@@ -1,4 +1,5 @@
 use {
+    crate::api::rate_limit::{ApiKey, RouteQuota},
     primitive_types::H160,
     reqwest::Url,
     shared::{
@@ -7,7 +8,11 @@ use {
         http_client,
         price_estimation::{self, NativePriceEstimators},
     },
-    std::{net::SocketAddr, num::NonZeroUsize, time::Duration},
+    std::{
+        net::{IpAddr, SocketAddr},
+        num::NonZeroUsize,
+        time::Duration,
+    },
 };
 
 #[derive(clap::Parser)]
@@ -130,6 +135,13 @@ pub struct Arguments {
     #[clap(long, env)]
     pub hooks_contract_address: Option<H160>,
 
+    /// Override the address of the `ComposableCoW` contract whose conditional
+    /// orders get polled and placed by the orderbook. If not specified, the
+    /// default contract deployment for the current network will be used.
+    /// Conditional orders are not supported on networks without a deployment.
+    #[clap(long, env)]
+    pub composable_cow_address: Option<H160>,
+
     /// Set the maximum size in bytes of order app data.
     #[clap(long, env, default_value = "8192")]
     pub app_data_size_limit: usize,
@@ -142,6 +154,65 @@ pub struct Arguments {
     /// whether an order is actively being bid on.
     #[clap(long, env, default_value = "5")]
     pub active_order_competition_threshold: u32,
+
+    /// If set, quotes include a suggested slippage tolerance. To gauge the
+    /// price impact of a trade its price also gets estimated for this many
+    /// times the quoted amount, which increases the load on the price
+    /// estimators.
+    #[clap(long, env, value_parser = clap::value_parser!(u32).range(2..))]
+    pub quote_slippage_probe_factor: Option<u32>,
+
+    /// How long to wait for the price estimate of the larger amount when
+    /// suggesting a slippage tolerance. Slower estimates are ignored so that
+    /// they don't delay the quote.
+    #[clap(
+        long,
+        env,
+        default_value = "1s",
+        value_parser = humantime::parse_duration,
+    )]
+    pub quote_slippage_probe_timeout: Duration,
+
+    /// The smallest slippage tolerance in basis points that gets suggested
+    /// for quotes.
+    #[clap(long, env, default_value = "10")]
+    pub quote_min_suggested_slippage_bps: u32,
+
+    /// The largest slippage tolerance in basis points that gets suggested for
+    /// quotes.
+    #[clap(long, env, default_value = "500")]
+    pub quote_max_suggested_slippage_bps: u32,
+
+    /// Quotas of API requests in the format `<route>=<requests>/<period>`,
+    /// e.g. `quote=100/1m,order=10/1m`. Every client can make up to
+    /// `requests` requests in a burst which get replenished over `period`.
+    /// The `quote` quota applies to quotes (every quote of a batch counts) and
+    /// order simulations, the `order` quota to order placements. Clients
+    /// exceeding their quota get a `429` response.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_rate_limits: Vec<RouteQuota>,
+
+    /// API keys in the format `<name>:<key>`. Clients sending a known key in
+    /// the `X-API-Key` header get rate limited by their key instead of their
+    /// IP address.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_keys: Vec<ApiKey>,
+
+    /// Addresses of reverse proxies in front of the API. The client IP address
+    /// used for rate limiting only gets taken from the `X-Forwarded-For`
+    /// header of requests coming from these proxies.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_trusted_proxies: Vec<IpAddr>,
+
+    /// The share of an IP address' rate limit a single app code (taken from
+    /// the request's app data) can use up.
+    #[clap(
+        long,
+        env,
+        default_value = "0.5",
+        value_parser = shared::arguments::parse_percentage_factor
+    )]
+    pub api_rate_limit_app_code_share: f64,
 }
 
 impl std::fmt::Display for Arguments {
@@ -169,10 +240,19 @@ impl std::fmt::Display for Arguments {
             ipfs_gateway,
             ipfs_pinata_auth,
             hooks_contract_address,
+            composable_cow_address,
             app_data_size_limit,
             db_url,
             max_gas_per_order,
             active_order_competition_threshold,
+            quote_slippage_probe_factor,
+            quote_slippage_probe_timeout,
+            quote_min_suggested_slippage_bps,
+            quote_max_suggested_slippage_bps,
+            api_rate_limits,
+            api_keys,
+            api_trusted_proxies,
+            api_rate_limit_app_code_share,
         } = self;
 
         write!(f, "{}", shared)?;
@@ -231,6 +311,11 @@ impl std::fmt::Display for Arguments {
             "hooks_contract_address",
             &hooks_contract_address.map(|a| format!("{a:?}")),
         )?;
+        display_option(
+            f,
+            "composable_cow_address",
+            &composable_cow_address.map(|a| format!("{a:?}")),
+        )?;
         writeln!(f, "app_data_size_limit: {}", app_data_size_limit)?;
         writeln!(f, "max_gas_per_order: {}", max_gas_per_order)?;
         writeln!(
@@ -238,6 +323,45 @@ impl std::fmt::Display for Arguments {
             "active_order_competition_threshold: {}",
             active_order_competition_threshold
         )?;
+        display_option(
+            f,
+            "quote_slippage_probe_factor",
+            quote_slippage_probe_factor,
+        )?;
+        writeln!(
+            f,
+            "quote_slippage_probe_timeout: {:?}",
+            quote_slippage_probe_timeout
+        )?;
+        writeln!(
+            f,
+            "quote_min_suggested_slippage_bps: {}",
+            quote_min_suggested_slippage_bps
+        )?;
+        writeln!(
+            f,
+            "quote_max_suggested_slippage_bps: {}",
+            quote_max_suggested_slippage_bps
+        )?;
+        writeln!(
+            f,
+            "api_rate_limits: {:?}",
+            api_rate_limits
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        )?;
+        writeln!(
+            f,
+            "api_keys: {:?}",
+            api_keys.iter().map(|key| key.name()).collect::<Vec<_>>()
+        )?;
+        writeln!(f, "api_trusted_proxies: {:?}", api_trusted_proxies)?;
+        writeln!(
+            f,
+            "api_rate_limit_app_code_share: {}",
+            api_rate_limit_app_code_share
+        )?;
 
         Ok(())
     }
@@ -0,0 +1,558 @@
+//! Native support for ComposableCoW conditional orders
+//! (<https://github.com/cowprotocol/composable-cow>).
+//!
+//! Conditional orders are polled by simulating `getTradeableOrderWithSignature`
+//! whenever they are due. Whenever a conditional order produces an order that
+//! is not known yet, it gets placed like any other EIP-1271 order so that it
+//! becomes part of the next auctions. Conditional orders that don't want to
+//! trade revert with custom errors telling when they want to be polled again.
+
+use {
+    crate::{
+        database::Postgres,
+        orderbook::{AddOrderError, Orderbook},
+    },
+    anyhow::{Context, Result},
+    app_data::AppDataHash,
+    chrono::Utc,
+    contracts::ComposableCoW,
+    ethcontract::{
+        BlockId,
+        Bytes,
+        tokens::Tokenize,
+        web3::{
+            self,
+            ethabi::{self, ParamType, Token},
+            types::CallRequest,
+        },
+    },
+    ethrpc::block_stream::{BlockInfo, CurrentBlockWatcher, into_stream},
+    futures::{StreamExt, stream},
+    model::{
+        DomainSeparator,
+        conditional_order::{ConditionalOrder, ConditionalOrderCreation},
+        order::{
+            BuyTokenDestination,
+            OrderCreation,
+            OrderCreationAppData,
+            OrderData,
+            OrderKind,
+            SellTokenSource,
+        },
+        signature::Signature,
+    },
+    primitive_types::{H160, H256, U256},
+    shared::code_simulation::SimulationError,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+    thiserror::Error,
+    tracing::Instrument,
+};
+
+#[derive(Debug, Error)]
+pub enum CreationError {
+    #[error("the conditional order is not authorized by its owner")]
+    NotAuthorized,
+    #[error("duplicated conditional order")]
+    Duplicate,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// `GPv2Order.Data` as returned by ComposableCoW.
+type ContractOrder = (
+    H160,
+    H160,
+    H160,
+    U256,
+    U256,
+    u32,
+    Bytes<[u8; 32]>,
+    U256,
+    Bytes<[u8; 32]>,
+    bool,
+    Bytes<[u8; 32]>,
+    Bytes<[u8; 32]>,
+);
+
+/// How many conditional orders get polled concurrently.
+const MAX_CONCURRENT_POLLS: usize = 10;
+
+/// When a conditional order wants to be polled again.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum NextPoll {
+    NextBlock,
+    /// Once the chain reached the block number.
+    AtBlock(u64),
+    /// Once the chain reached the timestamp.
+    AtEpoch(u64),
+    /// The conditional order will not produce any more orders.
+    Never,
+}
+
+impl NextPoll {
+    fn is_due(&self, block: &BlockInfo) -> bool {
+        match self {
+            Self::NextBlock => true,
+            Self::AtBlock(number) => block.number >= *number,
+            Self::AtEpoch(timestamp) => block.timestamp >= *timestamp,
+            Self::Never => false,
+        }
+    }
+
+    /// Whether the conditional order can be deactivated because it will not
+    /// produce any more orders.
+    fn is_final(&self) -> bool {
+        matches!(self, Self::Never)
+    }
+}
+
+/// What a conditional order wants to do at a block.
+#[derive(Debug)]
+enum Poll {
+    Trade(OrderCreation),
+    Wait(NextPoll),
+}
+
+pub struct ConditionalOrders {
+    database: Postgres,
+    orderbook: Arc<Orderbook>,
+    composable_cow: ComposableCoW,
+    domain_separator: DomainSeparator,
+    /// When the conditional orders that are not polled every block are due
+    /// again, keyed by owner and id.
+    schedule: Mutex<HashMap<(H160, H256), NextPoll>>,
+}
+
+impl ConditionalOrders {
+    pub fn new(
+        database: Postgres,
+        orderbook: Arc<Orderbook>,
+        composable_cow: ComposableCoW,
+        domain_separator: DomainSeparator,
+    ) -> Self {
+        Self {
+            database,
+            orderbook,
+            composable_cow,
+            domain_separator,
+            schedule: Default::default(),
+        }
+    }
+
+    /// Starts polling the conditional order. Returns its id.
+    pub async fn create(&self, order: &ConditionalOrderCreation) -> Result<H256, CreationError> {
+        let id = order.params.hash();
+        if !self.is_authorized(order.owner, id, None).await? {
+            return Err(CreationError::NotAuthorized);
+        }
+        if !self
+            .database
+            .insert_conditional_order(order, Utc::now())
+            .await?
+        {
+            return Err(CreationError::Duplicate);
+        }
+        Ok(id)
+    }
+
+    pub async fn get(&self, owner: &H160, id: &H256) -> Result<Option<ConditionalOrder>> {
+        self.database.conditional_order(owner, id).await
+    }
+
+    /// Spawns a background task polling the active conditional orders that
+    /// are due on every new block.
+    pub fn spawn_polling(self: Arc<Self>, current_block: CurrentBlockWatcher) {
+        tokio::task::spawn(
+            async move {
+                let mut blocks = into_stream(current_block);
+                while let Some(block) = blocks.next().await {
+                    if let Err(err) = self.poll_all(&block).await {
+                        tracing::warn!(?err, block = block.number, "failed to poll");
+                    }
+                }
+            }
+            .instrument(tracing::info_span!("conditional_orders")),
+        );
+    }
+
+    async fn poll_all(&self, block: &BlockInfo) -> Result<()> {
+        let orders = self
+            .database
+            .active_conditional_orders()
+            .await?
+            .into_iter()
+            .map(|order| ((order.owner, order.params.hash()), order))
+            .collect::<HashMap<_, _>>();
+        let due = {
+            let mut schedule = self.schedule.lock().unwrap();
+            schedule.retain(|key, _| orders.contains_key(key));
+            orders
+                .into_iter()
+                .filter(|(key, _)| schedule.get(key).is_none_or(|next| next.is_due(block)))
+                .collect::<Vec<_>>()
+        };
+
+        stream::iter(due)
+            .map(|(key, order)| async move {
+                match self.poll(&order, block.number).await {
+                    Ok(NextPoll::NextBlock) => {
+                        self.schedule.lock().unwrap().remove(&key);
+                    }
+                    Ok(next) => {
+                        tracing::trace!(?order, ?next, "scheduled conditional order");
+                        self.schedule.lock().unwrap().insert(key, next);
+                    }
+                    Err(err) => tracing::warn!(?err, ?order, "failed to poll conditional order"),
+                }
+            })
+            .buffer_unordered(MAX_CONCURRENT_POLLS)
+            .collect::<()>()
+            .await;
+        Ok(())
+    }
+
+    /// Polls the conditional order and places the order it produces. Returns
+    /// when the conditional order wants to be polled again.
+    async fn poll(&self, order: &ConditionalOrderCreation, block: u64) -> Result<NextPoll> {
+        let id = order.params.hash();
+        if !self.is_authorized(order.owner, id, Some(block)).await? {
+            tracing::debug!(?id, "conditional order got removed");
+            self.database
+                .deactivate_conditional_order(&order.owner, &id)
+                .await?;
+            return Ok(NextPoll::Never);
+        }
+
+        let creation = match self.tradeable_order(order, block).await? {
+            Poll::Trade(creation) => creation,
+            Poll::Wait(next) if next.is_final() => {
+                tracing::debug!(?id, "conditional order will never trade again");
+                self.database
+                    .deactivate_conditional_order(&order.owner, &id)
+                    .await?;
+                return Ok(next);
+            }
+            Poll::Wait(next) => return Ok(next),
+        };
+        let uid = creation.data().uid(&self.domain_separator, &order.owner);
+        if self.orderbook.get_order(&uid).await?.is_some() {
+            return Ok(NextPoll::NextBlock);
+        }
+
+        match self.orderbook.add_order(creation).await {
+            Ok(_) => tracing::debug!(?id, ?uid, "placed order for conditional order"),
+            Err(AddOrderError::DuplicatedOrder) => (),
+            Err(err) => {
+                tracing::debug!(?id, ?uid, ?err, "conditional order produced invalid order");
+                return Ok(NextPoll::NextBlock);
+            }
+        }
+        self.database
+            .insert_conditional_order_part(&order.owner, &id, &uid)
+            .await?;
+        Ok(NextPoll::NextBlock)
+    }
+
+    async fn is_authorized(&self, owner: H160, id: H256, block: Option<u64>) -> Result<bool> {
+        let mut call = self.composable_cow.single_orders(owner, Bytes(id.0));
+        if let Some(block) = block {
+            call = call.block(BlockId::Number(block.into()));
+        }
+        call.call().await.context("singleOrders")
+    }
+
+    /// Returns the order the conditional order wants to trade at the given
+    /// block or when it wants to be polled again.
+    async fn tradeable_order(&self, order: &ConditionalOrderCreation, block: u64) -> Result<Poll> {
+        let params = (
+            order.params.handler,
+            Bytes(order.params.salt.0),
+            Bytes(order.params.static_input.clone()),
+        );
+        let tx = self
+            .composable_cow
+            .get_tradeable_order_with_signature(order.owner, params, Default::default(), vec![])
+            .tx;
+        let call = CallRequest {
+            to: Some(self.composable_cow.address()),
+            data: tx.data,
+            ..Default::default()
+        };
+        let output = match self
+            .composable_cow
+            .raw_instance()
+            .web3()
+            .eth()
+            .call(call, Some(BlockId::Number(block.into())))
+            .await
+        {
+            Ok(output) => output,
+            Err(err) => {
+                let next = revert_data(&err).as_deref().and_then(decode_poll_error);
+                return match SimulationError::from(err) {
+                    // Conditional orders signal that they don't want to trade
+                    // (yet) by reverting.
+                    SimulationError::Revert(reason) => {
+                        tracing::trace!(?reason, ?next, "conditional order not tradeable");
+                        Ok(Poll::Wait(next.unwrap_or(NextPoll::NextBlock)))
+                    }
+                    SimulationError::Other(err) => Err(err),
+                };
+            }
+        };
+
+        let function = ComposableCoW::raw_contract()
+            .interface
+            .abi
+            .function("getTradeableOrderWithSignature")
+            .unwrap();
+        let tokens = function.decode_output(&output.0).context("decode")?;
+        let (order_data, signature): (ContractOrder, Bytes<Vec<u8>>) =
+            Tokenize::from_token(Token::Tuple(tokens))?;
+        Ok(Poll::Trade(order_creation(
+            order.owner,
+            order_data,
+            signature.0,
+        )?))
+    }
+}
+
+/// Returns the data a call reverted with if the node included it in the
+/// error.
+fn revert_data(err: &web3::Error) -> Option<Vec<u8>> {
+    let web3::Error::Rpc(err) = err else {
+        return None;
+    };
+    let data = err.data.as_ref()?.as_str()?;
+    hex::decode(data.strip_prefix("0x")?).ok()
+}
+
+/// Decodes the custom errors conditional orders revert with to signal when
+/// they want to be polled again
+/// (<https://github.com/cowprotocol/composable-cow/blob/main/src/interfaces/IConditionalOrder.sol>).
+///
+/// `OrderNotValid` is raised for conditions that clear up on their own, like a
+/// TWAP outside of the span of its current part or a stop loss whose strike
+/// price isn't reached yet, so only `PollNever` stops the polling for good.
+fn decode_poll_error(data: &[u8]) -> Option<NextPoll> {
+    let (selector, data) = data.split_first_chunk::<4>()?;
+    let decode = |name: &str, params: &[ParamType]| {
+        (*selector == ethabi::short_signature(name, params))
+            .then(|| ethabi::decode(params, data).ok())
+            .flatten()
+    };
+    // Values that don't fit are so far in the future that they are never due.
+    let uint = |tokens: Vec<Token>| {
+        let value = tokens.into_iter().next()?.into_uint()?;
+        Some(u64::try_from(value).unwrap_or(u64::MAX))
+    };
+
+    let reason = [ParamType::String];
+    let at_with_reason = [ParamType::Uint(256), ParamType::String];
+    if decode("PollTryNextBlock", &reason).is_some() {
+        Some(NextPoll::NextBlock)
+    } else if let Some(tokens) = decode("PollTryAtBlock", &at_with_reason) {
+        uint(tokens).map(NextPoll::AtBlock)
+    } else if let Some(tokens) = decode("PollTryAtEpoch", &at_with_reason) {
+        uint(tokens).map(NextPoll::AtEpoch)
+    } else if decode("OrderNotValid", &reason).is_some() {
+        Some(NextPoll::NextBlock)
+    } else if decode("PollNever", &reason).is_some() {
+        Some(NextPoll::Never)
+    } else {
+        None
+    }
+}
+
+fn order_creation(owner: H160, order: ContractOrder, signature: Vec<u8>) -> Result<OrderCreation> {
+    let data = OrderData {
+        sell_token: order.0,
+        buy_token: order.1,
+        receiver: (!order.2.is_zero()).then_some(order.2),
+        sell_amount: order.3,
+        buy_amount: order.4,
+        valid_to: order.5,
+        app_data: AppDataHash(order.6.0),
+        fee_amount: order.7,
+        kind: OrderKind::from_contract_bytes(order.8.0)?,
+        partially_fillable: order.9,
+        sell_token_balance: SellTokenSource::from_contract_bytes(order.10.0)?,
+        buy_token_balance: BuyTokenDestination::from_contract_bytes(order.11.0)?,
+    };
+    Ok(OrderCreation {
+        sell_token: data.sell_token,
+        buy_token: data.buy_token,
+        receiver: data.receiver,
+        sell_amount: data.sell_amount,
+        buy_amount: data.buy_amount,
+        valid_to: data.valid_to,
+        fee_amount: data.fee_amount,
+        kind: data.kind,
+        partially_fillable: data.partially_fillable,
+        sell_token_balance: data.sell_token_balance,
+        buy_token_balance: data.buy_token_balance,
+        from: Some(owner),
+        signature: Signature::Eip1271(signature),
+        quote_id: None,
+        app_data: OrderCreationAppData::Hash {
+            hash: data.app_data,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_contract_order() {
+        let owner = H160([1; 20]);
+        let order = (
+            H160([2; 20]),
+            H160([3; 20]),
+            H160::zero(),
+            4.into(),
+            5.into(),
+            6,
+            Bytes([7; 32]),
+            0.into(),
+            Bytes(OrderKind::SELL),
+            false,
+            Bytes(SellTokenSource::ERC20),
+            Bytes(BuyTokenDestination::ERC20),
+        );
+
+        let creation = order_creation(owner, order, vec![8]).unwrap();
+        assert_eq!(
+            creation,
+            OrderCreation {
+                sell_token: H160([2; 20]),
+                buy_token: H160([3; 20]),
+                receiver: None,
+                sell_amount: 4.into(),
+                buy_amount: 5.into(),
+                valid_to: 6,
+                fee_amount: 0.into(),
+                kind: OrderKind::Sell,
+                partially_fillable: false,
+                sell_token_balance: SellTokenSource::Erc20,
+                buy_token_balance: BuyTokenDestination::Erc20,
+                from: Some(owner),
+                signature: Signature::Eip1271(vec![8]),
+                quote_id: None,
+                app_data: OrderCreationAppData::Hash {
+                    hash: AppDataHash([7; 32]),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_order_kind() {
+        let order = (
+            H160::zero(),
+            H160::zero(),
+            H160::zero(),
+            0.into(),
+            0.into(),
+            0,
+            Bytes([0; 32]),
+            0.into(),
+            Bytes([0; 32]),
+            false,
+            Bytes(SellTokenSource::ERC20),
+            Bytes(BuyTokenDestination::ERC20),
+        );
+        assert!(order_creation(H160::zero(), order, vec![]).is_err());
+    }
+
+    /// Encodes the custom error a conditional order reverts with.
+    fn error(name: &str, tokens: &[Token]) -> Vec<u8> {
+        let params = tokens
+            .iter()
+            .map(|token| match token {
+                Token::Uint(_) => ParamType::Uint(256),
+                _ => ParamType::String,
+            })
+            .collect::<Vec<_>>();
+        [
+            ethabi::short_signature(name, &params).as_slice(),
+            &ethabi::encode(tokens),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decodes_poll_errors() {
+        let reason = Token::String("reason".to_string());
+
+        assert_eq!(
+            decode_poll_error(&error("PollTryNextBlock", &[reason.clone()])),
+            Some(NextPoll::NextBlock)
+        );
+        assert_eq!(
+            decode_poll_error(&error(
+                "PollTryAtBlock",
+                &[Token::Uint(42.into()), reason.clone()]
+            )),
+            Some(NextPoll::AtBlock(42))
+        );
+        assert_eq!(
+            decode_poll_error(&error(
+                "PollTryAtEpoch",
+                &[Token::Uint(U256::MAX), reason.clone()]
+            )),
+            Some(NextPoll::AtEpoch(u64::MAX))
+        );
+        assert_eq!(
+            decode_poll_error(&error("PollNever", &[reason.clone()])),
+            Some(NextPoll::Never)
+        );
+        assert_eq!(
+            decode_poll_error(&error("OrderNotValid", &[reason.clone()])),
+            Some(NextPoll::NextBlock)
+        );
+        assert_eq!(decode_poll_error(&error("Error", &[reason.clone()])), None);
+        assert_eq!(decode_poll_error(&[]), None);
+    }
+
+    #[test]
+    fn keeps_temporarily_invalid_orders_active() {
+        let block = BlockInfo {
+            number: 10,
+            timestamp: 100,
+            ..Default::default()
+        };
+
+        // TWAPs outside of the span of their current part and stop losses
+        // with stale oracles become valid again without any intervention.
+        for reason in ["not within span", "oracle stale price"] {
+            let next = decode_poll_error(&error(
+                "OrderNotValid",
+                &[Token::String(reason.to_string())],
+            ))
+            .unwrap();
+            assert!(!next.is_final());
+            assert!(next.is_due(&block));
+        }
+    }
+
+    #[test]
+    fn next_poll_is_due() {
+        let block = BlockInfo {
+            number: 10,
+            timestamp: 100,
+            ..Default::default()
+        };
+
+        assert!(NextPoll::NextBlock.is_due(&block));
+        assert!(NextPoll::AtBlock(10).is_due(&block));
+        assert!(!NextPoll::AtBlock(11).is_due(&block));
+        assert!(NextPoll::AtEpoch(100).is_due(&block));
+        assert!(!NextPoll::AtEpoch(101).is_due(&block));
+        assert!(!NextPoll::Never.is_due(&block));
+    }
+}
@@ -0,0 +1,138 @@
+use {
+    anyhow::{Context, Result},
+    chrono::{DateTime, Utc},
+    database::{byte_array::ByteArray, conditional_orders as db},
+    model::{
+        conditional_order::{
+            ConditionalOrder,
+            ConditionalOrderCreation,
+            ConditionalOrderParams,
+            ConditionalOrderStatus,
+        },
+        order::OrderUid,
+    },
+    primitive_types::{H160, H256},
+};
+
+impl super::Postgres {
+    /// Inserts a new conditional order. Returns `false` if the owner already
+    /// created the conditional order.
+    pub async fn insert_conditional_order(
+        &self,
+        order: &ConditionalOrderCreation,
+        creation_timestamp: DateTime<Utc>,
+    ) -> Result<bool> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_conditional_order"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let order = db::ConditionalOrder {
+            id: ByteArray(order.params.hash().0),
+            owner: ByteArray(order.owner.0),
+            handler: ByteArray(order.params.handler.0),
+            salt: ByteArray(order.params.salt.0),
+            static_input: order.params.static_input.clone(),
+            creation_timestamp,
+            active: true,
+        };
+        Ok(db::insert(&mut ex, &order).await?)
+    }
+
+    pub async fn conditional_order(
+        &self,
+        owner: &H160,
+        id: &H256,
+    ) -> Result<Option<ConditionalOrder>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["conditional_order"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let (owner, id) = (ByteArray(owner.0), ByteArray(id.0));
+        let Some(order) = db::fetch(&mut ex, &owner, &id).await? else {
+            return Ok(None);
+        };
+        let parts = db::parts(&mut ex, &owner, &id).await?;
+        Ok(Some(ConditionalOrder {
+            parts: parts.into_iter().map(|uid| OrderUid(uid.0)).collect(),
+            ..conditional_order_from(order)
+        }))
+    }
+
+    pub async fn active_conditional_orders(&self) -> Result<Vec<ConditionalOrderCreation>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["active_conditional_orders"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let orders = db::active(&mut ex).await?;
+        Ok(orders
+            .into_iter()
+            .map(|order| {
+                let order = conditional_order_from(order);
+                ConditionalOrderCreation {
+                    owner: order.owner,
+                    params: order.params,
+                }
+            })
+            .collect())
+    }
+
+    pub async fn deactivate_conditional_order(&self, owner: &H160, id: &H256) -> Result<()> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["deactivate_conditional_order"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        db::deactivate(&mut ex, &ByteArray(owner.0), &ByteArray(id.0))
+            .await
+            .context("deactivate")
+    }
+
+    pub async fn insert_conditional_order_part(
+        &self,
+        owner: &H160,
+        id: &H256,
+        uid: &OrderUid,
+    ) -> Result<()> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_conditional_order_part"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        db::insert_part(
+            &mut ex,
+            &ByteArray(owner.0),
+            &ByteArray(id.0),
+            &ByteArray(uid.0),
+        )
+        .await
+        .context("insert_part")
+    }
+}
+
+/// Converts the stored conditional order without its parts.
+fn conditional_order_from(order: db::ConditionalOrder) -> ConditionalOrder {
+    ConditionalOrder {
+        id: H256(order.id.0),
+        owner: H160(order.owner.0),
+        params: ConditionalOrderParams {
+            handler: H160(order.handler.0),
+            salt: H256(order.salt.0),
+            static_input: order.static_input,
+        },
+        creation_date: order.creation_timestamp,
+        status: if order.active {
+            ConditionalOrderStatus::Active
+        } else {
+            ConditionalOrderStatus::Inactive
+        },
+        parts: Vec::new(),
+    }
+}
@@ -5,7 +5,7 @@ use {
         num_traits::{CheckedDiv, CheckedMul},
     },
     database::{OrderUid, auction::AuctionId},
-    model::fee_policy::{ExecutedProtocolFee, FeePolicy, Quote},
+    model::fee_policy::{ExecutedProtocolFee, FeePolicy, Quote, SurplusTier},
     num::BigRational,
     number::conversions::{big_decimal_to_u256, big_rational_to_u256},
     std::collections::HashMap,
@@ -107,6 +107,34 @@ fn fee_policy_from(
                 .volume_factor
                 .context("missing volume factor")?,
         },
+        database::fee_policies::FeePolicyKind::TieredSurplus => {
+            let thresholds = db_fee_policy
+                .tiered_surplus_thresholds
+                .context("missing tiered surplus thresholds")?;
+            let factors = db_fee_policy
+                .tiered_surplus_factors
+                .context("missing tiered surplus factors")?;
+            anyhow::ensure!(
+                thresholds.len() == factors.len(),
+                "mismatching number of tiered surplus thresholds and factors"
+            );
+            FeePolicy::TieredSurplus {
+                tiers: thresholds
+                    .iter()
+                    .zip(factors)
+                    .map(|(threshold, factor)| {
+                        Ok(SurplusTier {
+                            threshold: big_decimal_to_u256(threshold)
+                                .context("invalid tiered surplus threshold")?,
+                            factor,
+                        })
+                    })
+                    .collect::<anyhow::Result<_>>()?,
+                max_volume_factor: db_fee_policy
+                    .tiered_surplus_max_volume_factor
+                    .context("missing tiered surplus max volume factor")?,
+            }
+        }
         database::fee_policies::FeePolicyKind::PriceImprovement => {
             let quote = quote.context(format!(
                 "missing price improvement quote for order '{:?}'",
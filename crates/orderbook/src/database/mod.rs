@@ -1,6 +1,7 @@
 pub mod app_data;
 pub mod auction_prices;
 pub mod auctions;
+pub mod conditional_orders;
 mod fee_policies;
 pub mod orders;
 pub mod quotes;
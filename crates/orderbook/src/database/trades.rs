@@ -1,7 +1,11 @@
 use {
     crate::database::Postgres,
     anyhow::{Context, Result},
-    database::{byte_array::ByteArray, trades::TradesQueryRow},
+    database::{
+        byte_array::ByteArray,
+        events::EventIndex,
+        trades::{TradesFilter, TradesQueryRow},
+    },
     ethcontract::H160,
     futures::stream::TryStreamExt,
     model::{fee_policy::ExecutedProtocolFee, order::OrderUid, trade::Trade},
@@ -20,6 +24,50 @@ pub trait TradeRetrieving: Send + Sync {
 pub struct TradeFilter {
     pub owner: Option<H160>,
     pub order_uid: Option<OrderUid>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub tx_hash: Option<H256>,
+    /// First block (inclusive) to return trades for.
+    pub from_block: Option<u64>,
+    /// Last block (inclusive) to return trades for.
+    pub to_block: Option<u64>,
+    /// Only return trades after this position.
+    pub after: Option<TradeCursor>,
+    /// Maximum number of trades to return.
+    pub limit: Option<u64>,
+}
+
+/// Position of a trade on chain. Trades are returned in this order which
+/// makes it usable as a cursor to paginate through them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TradeCursor {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+impl TradeFilter {
+    fn to_db(&self) -> Result<TradesFilter> {
+        let to_i64 = |value: u64| i64::try_from(value).context("value does not fit into i64");
+        Ok(TradesFilter {
+            owner: self.owner.map(|owner| ByteArray(owner.0)),
+            order_uid: self.order_uid.map(|uid| ByteArray(uid.0)),
+            sell_token: self.sell_token.map(|token| ByteArray(token.0)),
+            buy_token: self.buy_token.map(|token| ByteArray(token.0)),
+            tx_hash: self.tx_hash.map(|hash| ByteArray(hash.0)),
+            from_block: self.from_block.map(to_i64).transpose()?,
+            to_block: self.to_block.map(to_i64).transpose()?,
+            after: self
+                .after
+                .map(|after| -> Result<_> {
+                    Ok(EventIndex {
+                        block_number: to_i64(after.block_number)?,
+                        log_index: to_i64(after.log_index)?,
+                    })
+                })
+                .transpose()?,
+            limit: self.limit.map(to_i64).transpose()?,
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -30,15 +78,12 @@ impl TradeRetrieving for Postgres {
             .with_label_values(&["trades"])
             .start_timer();
 
+        let filter = filter.to_db()?;
         let mut ex = self.pool.acquire().await?;
-        let trades = database::trades::trades(
-            &mut ex,
-            filter.owner.map(|owner| ByteArray(owner.0)).as_ref(),
-            filter.order_uid.map(|uid| ByteArray(uid.0)).as_ref(),
-        )
-        .map_err(anyhow::Error::from)
-        .try_collect::<Vec<TradesQueryRow>>()
-        .await?;
+        let trades = database::trades::trades(&mut ex, &filter)
+            .map_err(anyhow::Error::from)
+            .try_collect::<Vec<TradesQueryRow>>()
+            .await?;
         timer.stop_and_record();
 
         let auction_order_uids = trades
@@ -1,5 +1,6 @@
 use {
     app_data::AppDataHash,
+    chrono::{DateTime, Utc},
     model::{
         interaction::InteractionData,
         order::{BuyTokenDestination, OrderClass, OrderKind, OrderUid, SellTokenSource},
@@ -70,6 +71,20 @@ pub enum FeePolicy {
         max_volume_factor: f64,
         quote: Quote,
     },
+    #[serde(rename_all = "camelCase")]
+    TieredSurplus {
+        tiers: Vec<SurplusTier>,
+        max_volume_factor: f64,
+    },
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurplusTier {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub threshold: U256,
+    pub factor: f64,
 }
 
 #[serde_as]
@@ -119,3 +134,67 @@ pub enum Status {
     /// The user cancelled the order. It will no longer show up in any auctions.
     Cancelled,
 }
+
+/// A change in the life cycle of an order as pushed to API clients.
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Update {
+    /// A new event in the life cycle of the order got registered.
+    #[serde(rename_all = "camelCase")]
+    Event {
+        uid: OrderUid,
+        label: EventLabel,
+        timestamp: DateTime<Utc>,
+    },
+    /// The order got (partially) filled on-chain.
+    #[serde(rename_all = "camelCase")]
+    Trade {
+        uid: OrderUid,
+        block_number: u64,
+        log_index: u64,
+        #[serde_as(as = "HexOrDecimalU256")]
+        sell_amount: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        buy_amount: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        fee_amount: U256,
+    },
+    /// The order got invalidated on-chain.
+    #[serde(rename_all = "camelCase")]
+    Invalidation {
+        uid: OrderUid,
+        block_number: u64,
+        log_index: u64,
+    },
+}
+
+impl Update {
+    /// The order this update belongs to.
+    pub fn uid(&self) -> &OrderUid {
+        match self {
+            Self::Event { uid, .. } | Self::Trade { uid, .. } | Self::Invalidation { uid, .. } => {
+                uid
+            }
+        }
+    }
+}
+
+/// The life cycle events of an order that get pushed to API clients.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum EventLabel {
+    /// Order was added to the orderbook.
+    Created,
+    /// Order was included in an auction and got sent to the solvers.
+    Ready,
+    /// Order was included in a valid solution.
+    Considered,
+    /// Order was included in the winning solution which is being submitted
+    /// on-chain.
+    Executing,
+    /// Order was settled on-chain.
+    Traded,
+    /// Order was cancelled by the user.
+    Cancelled,
+}
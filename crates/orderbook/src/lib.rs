@@ -1,10 +1,13 @@
 pub mod api;
 pub mod app_data;
 pub mod arguments;
+pub mod conditional_orders;
 pub mod database;
 pub mod dto;
 mod ipfs;
 mod ipfs_app_data;
+mod order_simulation;
+pub mod order_updates;
 pub mod orderbook;
 mod quoter;
 pub mod run;
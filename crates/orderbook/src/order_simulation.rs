@@ -0,0 +1,419 @@
+use {
+    crate::app_data,
+    ::app_data::Hook,
+    anyhow::Result,
+    ethrpc::extensions::CallFrame,
+    model::{
+        DomainSeparator,
+        order::{OrderCreation, OrderCreationAppData, OrderData, OrderKind, OrderUid},
+        quote::{OrderQuoteSide, SellAmount},
+    },
+    number::nonzero::U256 as NonZeroU256,
+    primitive_types::H160,
+    shared::{
+        account_balances::{self, BalanceFetching, TransferSimulation},
+        interaction::InteractionExecution,
+        order_quoting::{CalculateQuoteError, OrderQuoting, Quote, QuoteParameters},
+        order_validation::{
+            OrderValidating,
+            ValidationError,
+            convert_signing_scheme_into_quote_signing_scheme,
+        },
+        price_estimation::Verification,
+        trade_finding,
+    },
+    std::sync::Arc,
+};
+
+/// Simulates orders without placing them to explain why they would (not) get
+/// accepted and settled.
+pub struct OrderSimulator {
+    domain_separator: DomainSeparator,
+    settlement_contract: H160,
+    order_validator: Arc<dyn OrderValidating>,
+    quoter: Arc<dyn OrderQuoting>,
+    balance_fetcher: Arc<dyn BalanceFetching>,
+    app_data: Arc<app_data::Registry>,
+    /// Whether the simulations get traced to report how every hook got
+    /// executed. Requires the node to support `debug_traceCall`.
+    trace_hooks: bool,
+}
+
+/// The outcome of simulating an order.
+#[derive(Debug)]
+pub struct OrderSimulation {
+    pub owner: H160,
+    pub uid: OrderUid,
+    /// Why the order would be rejected when it got placed.
+    pub validation: Result<(), ValidationError>,
+    /// The sell token balance and allowance of the owner after executing the
+    /// pre-hooks.
+    pub transfer: Result<TransferSimulation>,
+    pub pre_hooks: Vec<HookSimulation>,
+    pub post_hooks: Vec<HookSimulation>,
+    /// The verified quote for the order's amounts. It gets computed by
+    /// simulating a settlement of the order including all of its hooks.
+    pub trade: Result<Quote, CalculateQuoteError>,
+}
+
+/// How a hook got executed in the simulations of an order.
+#[derive(Debug, PartialEq)]
+pub struct HookSimulation {
+    pub hook: Hook,
+    /// How the hook got executed when simulating the sell token transfer.
+    /// Only pre-hooks are part of that simulation.
+    pub transfer: Option<InteractionExecution>,
+    /// How the hook got executed when simulating the settlement of the order.
+    pub settlement: Option<InteractionExecution>,
+}
+
+impl OrderSimulator {
+    pub fn new(
+        domain_separator: DomainSeparator,
+        settlement_contract: H160,
+        order_validator: Arc<dyn OrderValidating>,
+        quoter: Arc<dyn OrderQuoting>,
+        balance_fetcher: Arc<dyn BalanceFetching>,
+        app_data: Arc<app_data::Registry>,
+        trace_hooks: bool,
+    ) -> Self {
+        Self {
+            domain_separator,
+            settlement_contract,
+            order_validator,
+            quoter,
+            balance_fetcher,
+            app_data,
+            trace_hooks,
+        }
+    }
+
+    /// Runs the full order validation, a simulation of the sell token transfer
+    /// and a simulated settlement of the order.
+    ///
+    /// Returns an error if the order's app data or owner can't be determined
+    /// since nothing can be simulated without them. Unsigned orders can be
+    /// simulated by using the `presign` scheme and specifying `from`.
+    pub async fn simulate(&self, order: OrderCreation) -> Result<OrderSimulation, ValidationError> {
+        tracing::debug!(?order, "simulating order");
+
+        let full_app_data_override = match order.app_data {
+            OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await.unwrap_or(None),
+            _ => None,
+        };
+        let app_data = self
+            .order_validator
+            .validate_app_data(&order.app_data, &full_app_data_override)?;
+        let owner = order.verify_owner(&self.domain_separator, app_data.inner.protocol.signer)?;
+        let data = OrderData {
+            app_data: app_data.inner.hash,
+            ..order.data()
+        };
+        let uid = data.uid(&self.domain_separator, &owner);
+
+        let validation = async {
+            self.order_validator
+                .validate_and_construct_order(
+                    order.clone(),
+                    &self.domain_separator,
+                    self.settlement_contract,
+                    full_app_data_override.clone(),
+                )
+                .await
+                .map(|_| ())
+        };
+        let transfer_query = account_balances::Query {
+            owner,
+            token: data.sell_token,
+            source: data.sell_token_balance,
+            interactions: app_data.interactions.pre.clone(),
+        };
+        let transfer = self
+            .balance_fetcher
+            .simulate_transfer(&transfer_query, data.sell_amount);
+        let transfer_trace = async {
+            if !self.trace_hooks || transfer_query.interactions.is_empty() {
+                return None;
+            }
+            self.balance_fetcher
+                .trace_transfer(&transfer_query, data.sell_amount)
+                .await
+                .inspect_err(|err| tracing::debug!(?err, "failed to trace sell token transfer"))
+                .ok()
+        };
+        let trade = async {
+            let side = match data.kind {
+                OrderKind::Sell => OrderQuoteSide::Sell {
+                    sell_amount: SellAmount::AfterFee {
+                        value: NonZeroU256::try_from(data.sell_amount)
+                            .map_err(|_| ValidationError::ZeroAmount)?,
+                    },
+                },
+                OrderKind::Buy => OrderQuoteSide::Buy {
+                    buy_amount_after_fee: NonZeroU256::try_from(data.buy_amount)
+                        .map_err(|_| ValidationError::ZeroAmount)?,
+                },
+            };
+            let parameters = QuoteParameters {
+                sell_token: data.sell_token,
+                buy_token: data.buy_token,
+                side,
+                verification: Verification {
+                    from: owner,
+                    receiver: data.receiver.unwrap_or(owner),
+                    sell_token_source: data.sell_token_balance,
+                    buy_token_destination: data.buy_token_balance,
+                    pre_interactions: trade_finding::map_interactions(&app_data.interactions.pre),
+                    post_interactions: trade_finding::map_interactions(&app_data.interactions.post),
+                },
+                // The gas needed to verify EIP-1271 signatures is not known
+                // without validating the signature first so it is ignored here.
+                signing_scheme: convert_signing_scheme_into_quote_signing_scheme(
+                    order.signature.scheme(),
+                    true,
+                    0,
+                )
+                .map_err(|_| ValidationError::InvalidSignature)?,
+                additional_gas: app_data.inner.protocol.hooks.gas_limit(),
+            };
+            Ok::<_, ValidationError>(self.quoter.calculate_quote(parameters).await)
+        };
+
+        let (validation, transfer, transfer_trace, trade) =
+            futures::join!(validation, transfer, transfer_trace, trade);
+        let trade = trade?;
+
+        let hooks = &app_data.inner.protocol.hooks;
+        let settlement_trace = trade
+            .as_ref()
+            .ok()
+            .and_then(|quote| quote.simulation_trace.as_ref());
+        let pre_transfer = executions(transfer_trace.as_ref(), &hooks.pre);
+        // Pre- and post-hooks are matched in one go since the same hook could
+        // be used as a pre- and a post-hook.
+        let mut pre_settlement = executions(settlement_trace, hooks.pre.iter().chain(&hooks.post));
+        let post_settlement = pre_settlement.split_off(hooks.pre.len());
+        let pre_hooks = hooks
+            .pre
+            .iter()
+            .zip(pre_transfer.into_iter().zip(pre_settlement))
+            .map(|(hook, (transfer, settlement))| HookSimulation {
+                hook: hook.clone(),
+                transfer,
+                settlement,
+            })
+            .collect();
+        let post_hooks = hooks
+            .post
+            .iter()
+            .zip(post_settlement)
+            .map(|(hook, settlement)| HookSimulation {
+                hook: hook.clone(),
+                transfer: None,
+                settlement,
+            })
+            .collect();
+
+        Ok(OrderSimulation {
+            owner,
+            uid,
+            validation,
+            transfer,
+            pre_hooks,
+            post_hooks,
+            trade,
+        })
+    }
+}
+
+/// Looks up how the hooks got executed in a simulation's call trace. Hooks get
+/// called individually by the hooks trampoline so they are matched by their
+/// own target and call data.
+fn executions<'a>(
+    trace: Option<&CallFrame>,
+    hooks: impl IntoIterator<Item = &'a Hook>,
+) -> Vec<Option<InteractionExecution>> {
+    let hooks = hooks
+        .into_iter()
+        .map(|hook| (hook.target, hook.call_data.as_slice()));
+    match trace {
+        Some(trace) => InteractionExecution::find_all(trace, hooks),
+        None => hooks.map(|_| None).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ::app_data::{AppDataHash, Hooks, ProtocolAppData, ValidatedAppData},
+        model::{interaction::InteractionData, order::Interactions, signature::Signature},
+        primitive_types::U256,
+        shared::{
+            account_balances::MockBalanceFetching,
+            order_quoting::MockOrderQuoting,
+            order_validation::{MockOrderValidating, OrderAppData},
+        },
+    };
+
+    #[tokio::test]
+    async fn simulates_order_with_hooks() {
+        let owner = H160([0x01; 20]);
+        let trampoline = H160([0x02; 20]);
+        let pre_hook = Hook {
+            target: H160([0x03; 20]),
+            call_data: vec![0x03],
+            gas_limit: 100_000,
+        };
+        let post_hook = Hook {
+            target: H160([0x04; 20]),
+            call_data: vec![0x04],
+            gas_limit: 50_000,
+        };
+        // Hooks get executed by the hooks trampoline.
+        let call = |target: H160, call_data: &[u8], gas_used: u64, calls| CallFrame {
+            to: Some(target),
+            input: call_data.to_vec().into(),
+            gas_used: gas_used.into(),
+            error: None,
+            calls,
+        };
+        let hook_call =
+            |hook: &Hook, gas_used: u64| call(hook.target, &hook.call_data, gas_used, vec![]);
+        let trampoline_call = |calls| call(trampoline, &[0xff], 0, calls);
+
+        let mut order_validator = MockOrderValidating::new();
+        let app_data = OrderAppData {
+            inner: ValidatedAppData {
+                hash: AppDataHash([0x05; 32]),
+                document: Default::default(),
+                protocol: ProtocolAppData {
+                    hooks: Hooks {
+                        pre: vec![pre_hook.clone()],
+                        post: vec![post_hook.clone()],
+                    },
+                    ..Default::default()
+                },
+            },
+            interactions: Interactions {
+                pre: vec![InteractionData {
+                    target: trampoline,
+                    value: U256::zero(),
+                    call_data: vec![0xff],
+                }],
+                post: vec![InteractionData {
+                    target: trampoline,
+                    value: U256::zero(),
+                    call_data: vec![0xff],
+                }],
+            },
+        };
+        order_validator
+            .expect_validate_app_data()
+            .return_once(move |_, _| Ok(app_data));
+        order_validator
+            .expect_validate_and_construct_order()
+            .returning(|_, _, _, _| Err(ValidationError::InsufficientBalance));
+
+        let mut balance_fetcher = MockBalanceFetching::new();
+        balance_fetcher
+            .expect_simulate_transfer()
+            .returning(|_, _| {
+                Ok(TransferSimulation {
+                    can_transfer: true,
+                    ..Default::default()
+                })
+            });
+        let transfer_trace = call(
+            H160([0x06; 20]),
+            &[],
+            100_000,
+            vec![trampoline_call(vec![hook_call(&pre_hook, 21_000)])],
+        );
+        balance_fetcher
+            .expect_trace_transfer()
+            .returning(move |_, _| Ok(transfer_trace.clone()));
+
+        let mut quoter = MockOrderQuoting::new();
+        let settlement_trace = call(
+            H160([0x07; 20]),
+            &[],
+            200_000,
+            vec![
+                trampoline_call(vec![CallFrame {
+                    error: Some("execution reverted".to_string()),
+                    ..hook_call(&pre_hook, 30_000)
+                }]),
+                trampoline_call(vec![hook_call(&post_hook, 40_000)]),
+            ],
+        );
+        quoter.expect_calculate_quote().returning(move |_| {
+            Ok(Quote {
+                simulation_trace: Some(settlement_trace.clone()),
+                ..Default::default()
+            })
+        });
+
+        let database = crate::database::Postgres::try_new("postgresql://").unwrap();
+        let simulator = OrderSimulator::new(
+            Default::default(),
+            H160([0x08; 20]),
+            Arc::new(order_validator),
+            Arc::new(quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(app_data::Registry::new(
+                ::app_data::Validator::new(8192),
+                database,
+                None,
+            )),
+            true,
+        );
+        let simulation = simulator
+            .simulate(OrderCreation {
+                sell_amount: 1.into(),
+                buy_amount: 1.into(),
+                kind: OrderKind::Sell,
+                from: Some(owner),
+                signature: Signature::PreSign,
+                app_data: OrderCreationAppData::Full {
+                    full: Default::default(),
+                },
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(simulation.owner, owner);
+        assert!(matches!(
+            simulation.validation,
+            Err(ValidationError::InsufficientBalance)
+        ));
+        assert!(simulation.transfer.unwrap().can_transfer);
+        assert!(simulation.trade.is_ok());
+        assert_eq!(
+            simulation.pre_hooks,
+            vec![HookSimulation {
+                hook: pre_hook,
+                transfer: Some(InteractionExecution {
+                    success: true,
+                    gas_used: 21_000,
+                }),
+                settlement: Some(InteractionExecution {
+                    success: false,
+                    gas_used: 30_000,
+                }),
+            }]
+        );
+        assert_eq!(
+            simulation.post_hooks,
+            vec![HookSimulation {
+                hook: post_hook,
+                transfer: None,
+                settlement: Some(InteractionExecution {
+                    success: true,
+                    gas_used: 40_000,
+                }),
+            }]
+        );
+    }
+}
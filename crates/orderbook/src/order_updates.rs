@@ -0,0 +1,131 @@
+//! Forwards changes in the life cycle of orders from the database to API
+//! clients as they get written.
+//!
+//! The database publishes the changes on a notification channel (see
+//! `database/sql/V083__notify_order_updates.sql`) so that this also picks up
+//! changes written by other services like the autopilot.
+
+use {
+    crate::dto::order::Update,
+    anyhow::{Context, Result},
+    sqlx::{PgPool, postgres::PgListener},
+    std::{sync::Arc, time::Duration},
+    tokio::sync::broadcast,
+    tracing::Instrument,
+};
+
+/// The notification channel the database publishes order updates on.
+const CHANNEL: &str = "order_updates";
+
+/// How many updates a subscriber can lag behind before it starts missing them.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// How long to wait before listening again after the listener failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Broadcasts order updates to all subscribed API clients.
+#[derive(Clone)]
+pub struct OrderUpdates(broadcast::Sender<Arc<Update>>);
+
+impl OrderUpdates {
+    /// Spawns a background task listening for order updates on the database.
+    pub fn new(pool: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        tokio::task::spawn(
+            forward_updates(pool, sender.clone()).instrument(tracing::info_span!("order_updates")),
+        );
+        Self(sender)
+    }
+
+    /// Returns a receiver for all order updates from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Update>> {
+        self.0.subscribe()
+    }
+}
+
+async fn forward_updates(pool: PgPool, sender: broadcast::Sender<Arc<Update>>) {
+    loop {
+        if let Err(err) = listen(&pool, &sender).await {
+            tracing::warn!(?err, "failed to listen for order updates");
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn listen(pool: &PgPool, sender: &broadcast::Sender<Arc<Update>>) -> Result<()> {
+    let mut listener = PgListener::connect_with(pool)
+        .await
+        .context("connect listener")?;
+    listener.listen(CHANNEL).await.context("listen")?;
+    loop {
+        // Unlike `recv()` this lets us know when the connection got lost and
+        // had to be re-established, in which case updates might be missing.
+        let Some(notification) = listener.try_recv().await.context("receive")? else {
+            tracing::warn!("lost database connection; order updates might be missing");
+            continue;
+        };
+        match serde_json::from_str::<Update>(notification.payload()) {
+            Ok(update) => {
+                // Sending only fails if there are no subscribers, which is fine.
+                let _ = sender.send(Arc::new(update));
+            }
+            Err(err) => tracing::warn!(
+                ?err,
+                payload = notification.payload(),
+                "invalid order update"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::dto::order::EventLabel, model::order::OrderUid, primitive_types::U256};
+
+    #[test]
+    fn parses_database_notifications() {
+        // Payloads as built by the triggers in the database.
+        let uid = format!("0x{}", "01".repeat(56));
+        let event = serde_json::from_str::<Update>(&format!(
+            r#"{{"type": "event", "uid": "{uid}", "label": "traded", "timestamp": "2024-05-01T12:00:00.123456+00:00"}}"#
+        ))
+        .unwrap();
+        assert_eq!(
+            event,
+            Update::Event {
+                uid: OrderUid([1; 56]),
+                label: EventLabel::Traded,
+                timestamp: "2024-05-01T12:00:00.123456Z".parse().unwrap(),
+            }
+        );
+
+        let trade = serde_json::from_str::<Update>(&format!(
+            r#"{{"type": "trade", "uid": "{uid}", "blockNumber": 1, "logIndex": 2, "sellAmount": "3", "buyAmount": "4", "feeAmount": "0"}}"#
+        ))
+        .unwrap();
+        assert_eq!(
+            trade,
+            Update::Trade {
+                uid: OrderUid([1; 56]),
+                block_number: 1,
+                log_index: 2,
+                sell_amount: U256::from(3),
+                buy_amount: U256::from(4),
+                fee_amount: U256::zero(),
+            }
+        );
+
+        let invalidation = serde_json::from_str::<Update>(&format!(
+            r#"{{"type": "invalidation", "uid": "{uid}", "blockNumber": 1, "logIndex": 2}}"#
+        ))
+        .unwrap();
+        assert_eq!(
+            invalidation,
+            Update::Invalidation {
+                uid: OrderUid([1; 56]),
+                block_number: 1,
+                log_index: 2,
+            }
+        );
+    }
+}
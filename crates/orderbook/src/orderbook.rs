@@ -528,8 +528,8 @@ impl Orderbook {
         let trades = self
             .database
             .trades(&TradeFilter {
-                owner: None,
                 order_uid: Some(*uid),
+                ..Default::default()
             })
             .await?;
 
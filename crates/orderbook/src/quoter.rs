@@ -128,6 +128,7 @@ impl QuoteHandler {
             expiration: quote.data.expiration,
             id: quote.id,
             verified: quote.data.verified,
+            suggested_slippage_bps: quote.suggested_slippage_bps,
         };
 
         tracing::debug!(?response, "finished computing quote");
@@ -1,10 +1,13 @@
 use {
     crate::{
-        api,
+        api::{self, rate_limit::RateLimiter},
         arguments::Arguments,
+        conditional_orders::ConditionalOrders,
         database::Postgres,
         ipfs::Ipfs,
         ipfs_app_data::IpfsAppData,
+        order_simulation::OrderSimulator,
+        order_updates::OrderUpdates,
         orderbook::Orderbook,
         quoter::QuoteHandler,
     },
@@ -12,7 +15,14 @@ use {
     app_data::Validator,
     chain::Chain,
     clap::Parser,
-    contracts::{BalancerV2Vault, GPv2Settlement, HooksTrampoline, IUniswapV3Factory, WETH9},
+    contracts::{
+        BalancerV2Vault,
+        ComposableCoW,
+        GPv2Settlement,
+        HooksTrampoline,
+        IUniswapV3Factory,
+        WETH9,
+    },
     ethcontract::errors::DeployError,
     futures::{FutureExt, StreamExt},
     model::{DomainSeparator, order::BUY_ETH_ADDRESS},
@@ -38,6 +48,7 @@ use {
             QuoteVerificationMode,
             factory::{self, PriceEstimatorFactory},
             native::NativePriceEstimating,
+            slippage::SlippageEstimator,
         },
         signature_validator,
         sources::{self, BaselineSource, uniswap_v2::UniV2BaselineSourceParameters},
@@ -319,9 +330,19 @@ pub async fn run(args: Arguments) {
         max_limit: args.max_limit_order_validity_period,
     };
 
+    let slippage_estimator = args.quote_slippage_probe_factor.map(|factor| {
+        Arc::new(SlippageEstimator::new(
+            native_price_estimator.clone(),
+            factor,
+            args.quote_slippage_probe_timeout,
+            args.quote_min_suggested_slippage_bps,
+            args.quote_max_suggested_slippage_bps,
+        ))
+    });
+
     let create_quoter = |price_estimator: Arc<dyn PriceEstimating>,
                          verification: QuoteVerificationMode| {
-        Arc::new(OrderQuoter::new(
+        let quoter = OrderQuoter::new(
             price_estimator,
             native_price_estimator.clone(),
             gas_price_estimator.clone(),
@@ -342,7 +363,11 @@ pub async fn run(args: Arguments) {
             },
             balance_fetcher.clone(),
             verification,
-        ))
+        );
+        Arc::new(match &slippage_estimator {
+            Some(estimator) => quoter.with_slippage_estimator(estimator.clone()),
+            None => quoter,
+        })
     };
     let optimal_quoter = create_quoter(price_estimator, args.price_estimation.quote_verification);
     // Fast quoting is able to return early and if none of the produced quotes are
@@ -364,7 +389,7 @@ pub async fn run(args: Arguments) {
         bad_token_detector.clone(),
         hooks_contract,
         optimal_quoter.clone(),
-        balance_fetcher,
+        balance_fetcher.clone(),
         signature_validator,
         Arc::new(postgres.clone()),
         args.max_limit_orders_per_user,
@@ -398,22 +423,65 @@ pub async fn run(args: Arguments) {
     ));
 
     check_database_connection(orderbook.as_ref()).await;
+    let order_simulator = Arc::new(OrderSimulator::new(
+        domain_separator,
+        settlement_contract.address(),
+        order_validator.clone(),
+        optimal_quoter.clone(),
+        balance_fetcher,
+        app_data.clone(),
+        args.price_estimation.quote_trace_hooks,
+    ));
     let quotes = Arc::new(
         QuoteHandler::new(order_validator, optimal_quoter, app_data.clone())
             .with_fast_quoter(fast_quoter),
     );
 
+    let composable_cow = match args.composable_cow_address {
+        Some(address) => Some(ComposableCoW::at(&web3, address)),
+        None => match ComposableCoW::deployed(&web3).await {
+            Ok(contract) => Some(contract),
+            Err(DeployError::NotFound(_)) => {
+                tracing::warn!("ComposableCoW is not deployed on this network");
+                None
+            }
+            Err(err) => panic!("failed to get ComposableCoW contract: {err}"),
+        },
+    };
+    let conditional_orders = composable_cow.map(|composable_cow| {
+        let conditional_orders = Arc::new(ConditionalOrders::new(
+            postgres.clone(),
+            orderbook.clone(),
+            composable_cow,
+            domain_separator,
+        ));
+        conditional_orders
+            .clone()
+            .spawn_polling(current_block_stream.clone());
+        conditional_orders
+    });
+
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
+    let order_updates = OrderUpdates::new(postgres.pool.clone());
     let serve_api = serve_api(
         postgres,
         orderbook.clone(),
         quotes,
+        order_simulator,
         app_data,
         args.bind_address,
         async {
             let _ = shutdown_receiver.await;
         },
         native_price_estimator,
+        order_updates,
+        conditional_orders,
+        Arc::new(RateLimiter::new(
+            args.api_rate_limits,
+            args.api_keys,
+            args.api_trusted_proxies,
+            args.api_rate_limit_app_code_share,
+        )),
     );
 
     let mut metrics_address = args.bind_address;
@@ -475,17 +543,25 @@ fn serve_api(
     database: Postgres,
     orderbook: Arc<Orderbook>,
     quotes: Arc<QuoteHandler>,
+    order_simulator: Arc<OrderSimulator>,
     app_data: Arc<crate::app_data::Registry>,
     address: SocketAddr,
     shutdown_receiver: impl Future<Output = ()> + Send + 'static,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_updates: OrderUpdates,
+    conditional_orders: Option<Arc<ConditionalOrders>>,
+    rate_limiter: Arc<RateLimiter>,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
         orderbook,
         quotes,
+        order_simulator,
         app_data,
         native_price_estimator,
+        order_updates,
+        conditional_orders,
+        rate_limiter,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
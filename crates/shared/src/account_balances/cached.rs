@@ -1,7 +1,15 @@
 use {
-    crate::account_balances::{BalanceFetching, Query, TransferSimulationError},
+    crate::account_balances::{
+        BalanceFetching,
+        Query,
+        TransferSimulation,
+        TransferSimulationError,
+    },
     anyhow::Result,
-    ethrpc::block_stream::{CurrentBlockWatcher, into_stream},
+    ethrpc::{
+        block_stream::{CurrentBlockWatcher, into_stream},
+        extensions::CallFrame,
+    },
     futures::StreamExt,
     itertools::Itertools,
     primitive_types::U256,
@@ -194,6 +202,14 @@ impl BalanceFetching for Balances {
         // profit from caching.
         self.inner.can_transfer(query, amount).await
     }
+
+    async fn simulate_transfer(&self, query: &Query, amount: U256) -> Result<TransferSimulation> {
+        self.inner.simulate_transfer(query, amount).await
+    }
+
+    async fn trace_transfer(&self, query: &Query, amount: U256) -> Result<CallFrame> {
+        self.inner.trace_transfer(query, amount).await
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use {
     anyhow::Result,
-    ethrpc::{Web3, block_stream::CurrentBlockWatcher},
+    ethrpc::{Web3, block_stream::CurrentBlockWatcher, extensions::CallFrame},
     model::{
         interaction::InteractionData,
         order::{Order, SellTokenSource},
@@ -31,6 +31,15 @@ impl Query {
     }
 }
 
+/// The state observed while simulating a transfer of the sell token.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransferSimulation {
+    pub token_balance: U256,
+    pub allowance: U256,
+    pub effective_balance: U256,
+    pub can_transfer: bool,
+}
+
 #[derive(Debug)]
 pub enum TransferSimulationError {
     InsufficientAllowance,
@@ -63,6 +72,17 @@ pub trait BalanceFetching: Send + Sync {
         query: &Query,
         amount: U256,
     ) -> Result<(), TransferSimulationError>;
+
+    // Simulates transferring `amount` into the settlement contract like
+    // `can_transfer` does but reports the balance and allowance that were
+    // observed instead of only whether the transfer succeeded.
+    async fn simulate_transfer(&self, query: &Query, amount: U256) -> Result<TransferSimulation>;
+
+    // Returns the call trace of the transfer simulation, for example to see
+    // how the pre-interactions of the query got executed. Also works when a
+    // pre-interaction reverts and the transfer simulation fails because of
+    // that. Requires the node to support `debug_traceCall`.
+    async fn trace_transfer(&self, query: &Query, amount: U256) -> Result<CallFrame>;
 }
 
 /// Contracts required for balance simulation.
@@ -3,11 +3,14 @@
 //! from a node in a single round-trip, while accounting for pre-interactions.
 
 use {
-    super::{BalanceFetching, Query, TransferSimulationError},
-    anyhow::Result,
+    super::{BalanceFetching, Query, TransferSimulation, TransferSimulationError},
+    anyhow::{Context, Result},
     contracts::{BalancerV2Vault, erc20::Contract},
-    ethcontract::{Bytes, H160, U256},
-    ethrpc::Web3,
+    ethcontract::{Bytes, H160, U256, dyns::DynMethodBuilder, web3::types::BlockNumber},
+    ethrpc::{
+        Web3,
+        extensions::{CallFrame, DebugNamespace},
+    },
     futures::future,
     model::order::SellTokenSource,
 };
@@ -42,7 +45,7 @@ impl Balances {
         }
     }
 
-    async fn simulate(&self, query: &Query, amount: Option<U256>) -> Result<Simulation> {
+    async fn simulate(&self, query: &Query, amount: Option<U256>) -> Result<TransferSimulation> {
         // We simulate the balances from the Settlement contract's context. This
         // allows us to check:
         // 1. How the pre-interactions would behave as part of the settlement
@@ -53,22 +56,11 @@ impl Balances {
         let (token_balance, allowance, effective_balance, can_transfer) =
             contracts::storage_accessible::simulate(
                 contracts::bytecode!(contracts::support::Balances),
-                self.balances.methods().balance(
-                    (self.settlement, self.vault_relayer, self.vault),
-                    query.owner,
-                    query.token,
-                    amount.unwrap_or_default(),
-                    Bytes(query.source.as_bytes()),
-                    query
-                        .interactions
-                        .iter()
-                        .map(|i| (i.target, i.value, Bytes(i.call_data.clone())))
-                        .collect(),
-                ),
+                self.balance(query, amount),
             )
             .await?;
 
-        let simulation = Simulation {
+        let simulation = TransferSimulation {
             token_balance,
             allowance,
             effective_balance,
@@ -79,6 +71,25 @@ impl Balances {
         Ok(simulation)
     }
 
+    fn balance(
+        &self,
+        query: &Query,
+        amount: Option<U256>,
+    ) -> DynMethodBuilder<(U256, U256, U256, bool)> {
+        self.balances.methods().balance(
+            (self.settlement, self.vault_relayer, self.vault),
+            query.owner,
+            query.token,
+            amount.unwrap_or_default(),
+            Bytes(query.source.as_bytes()),
+            query
+                .interactions
+                .iter()
+                .map(|i| (i.target, i.value, Bytes(i.call_data.clone())))
+                .collect(),
+        )
+    }
+
     async fn tradable_balance_simulated(&self, query: &Query) -> Result<U256> {
         let simulation = self.simulate(query, None).await?;
         Ok(if simulation.can_transfer {
@@ -132,14 +143,6 @@ impl Balances {
     }
 }
 
-#[derive(Debug)]
-struct Simulation {
-    token_balance: U256,
-    allowance: U256,
-    effective_balance: U256,
-    can_transfer: bool,
-}
-
 #[async_trait::async_trait]
 impl BalanceFetching for Balances {
     async fn get_balances(&self, queries: &[Query]) -> Vec<Result<U256>> {
@@ -178,6 +181,25 @@ impl BalanceFetching for Balances {
 
         Ok(())
     }
+
+    async fn simulate_transfer(&self, query: &Query, amount: U256) -> Result<TransferSimulation> {
+        self.simulate(query, Some(amount)).await
+    }
+
+    async fn trace_transfer(&self, query: &Query, amount: U256) -> Result<CallFrame> {
+        let tx = self.balance(query, Some(amount)).tx;
+        let call = contracts::storage_accessible::call(
+            tx.to.context("missing target")?,
+            contracts::bytecode!(contracts::support::Balances),
+            tx.data.context("missing calldata")?,
+        );
+        let trace = self
+            .web3
+            .debug()
+            .call_frames(call, BlockNumber::Latest.into(), Default::default())
+            .await?;
+        Ok(trace)
+    }
 }
 
 #[cfg(test)]
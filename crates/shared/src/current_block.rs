@@ -1,11 +1,12 @@
 //! Global block stream arguments.
 
 use {
+    crate::arguments::display_option,
     anyhow::Result,
     clap::Parser,
     ethrpc::{
         Web3,
-        block_stream::{BlockRetrieving, CurrentBlockWatcher, current_block_stream},
+        block_stream::{BlockRetrieving, CurrentBlockWatcher, Reorgs, reorg_aware_block_stream},
     },
     std::{
         fmt::{self, Display, Formatter},
@@ -28,6 +29,13 @@ pub struct Arguments {
         value_parser = humantime::parse_duration,
     )]
     pub block_stream_poll_interval: Duration,
+
+    /// WebSocket node endpoint to subscribe to new blocks with. If set, the
+    /// block stream gets notified about new blocks by a `newHeads`
+    /// subscription and only falls back to polling while the subscription is
+    /// unavailable.
+    #[clap(long, env)]
+    pub block_stream_ws_url: Option<Url>,
 }
 
 impl Arguments {
@@ -36,7 +44,19 @@ impl Arguments {
     }
 
     pub async fn stream(&self, rpc: Url) -> Result<CurrentBlockWatcher> {
-        current_block_stream(rpc, self.block_stream_poll_interval).await
+        let (current_block, _) = self.stream_with_reorgs(rpc).await?;
+        Ok(current_block)
+    }
+
+    /// Creates the block stream together with a handle to subscribe to the
+    /// reorgs it detects.
+    pub async fn stream_with_reorgs(&self, rpc: Url) -> Result<(CurrentBlockWatcher, Reorgs)> {
+        reorg_aware_block_stream(
+            rpc,
+            self.block_stream_ws_url.clone(),
+            self.block_stream_poll_interval,
+        )
+        .await
     }
 }
 
@@ -44,6 +64,7 @@ impl Display for Arguments {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let Self {
             block_stream_poll_interval,
+            block_stream_ws_url,
         } = self;
 
         writeln!(
@@ -51,6 +72,7 @@ impl Display for Arguments {
             "block_stream_poll_interval: {:?}",
             block_stream_poll_interval
         )?;
+        display_option(f, "block_stream_ws_url", block_stream_ws_url)?;
 
         Ok(())
     }
@@ -4,14 +4,22 @@ use {
     ethcontract::{
         Event as EthcontractEvent,
         EventMetadata,
+        Instance,
+        common::DeploymentInformation,
         contract::{AllEventsBuilder, ParseLog},
         dyns::DynTransport,
         errors::ExecutionError,
     },
-    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving, RangeInclusive},
+    ethrpc::block_stream::{
+        BlockNumberHash,
+        BlockRetrieving,
+        RangeInclusive,
+        ReorgReceiver,
+        Reorgs,
+    },
     futures::{Stream, StreamExt, TryStreamExt, future},
     std::sync::Arc,
-    tokio::sync::Mutex,
+    tokio::sync::{Mutex, broadcast::error::TryRecvError},
     tracing::Instrument,
 };
 
@@ -50,6 +58,10 @@ where
     contract: C,
     store: S,
     last_handled_blocks: Vec<BlockNumberHash>,
+    reorgs: Option<ReorgReceiver>,
+    /// Set when a reorg notification invalidated some of the handled blocks,
+    /// so the events of the next update have to replace the stored ones.
+    reorged: bool,
 }
 
 /// `EventStoring` is used by `EventHandler` for the purpose of giving the user
@@ -89,6 +101,11 @@ pub trait EventStoring<T>: Send + Sync {
 pub trait EventRetrieving {
     type Event: ParseLog;
     fn get_events(&self) -> AllEventsBuilder<DynTransport, Self::Event>;
+
+    /// The number of blocks to query at once when fetching historic events.
+    fn block_page_size(&self) -> u64 {
+        500
+    }
 }
 
 #[derive(Debug)]
@@ -122,9 +139,19 @@ where
                     None => vec![],
                 }
             },
+            reorgs: None,
+            reorged: false,
         }
     }
 
+    /// Subscribes to the reorgs detected by the block stream. This lets the
+    /// event handler replace events of reorged blocks on the next update even
+    /// if the new chain is not longer than the previous one.
+    pub fn with_reorgs(mut self, reorgs: &Reorgs) -> Self {
+        self.reorgs = Some(reorgs.subscribe());
+        self
+    }
+
     /// Creates a new instance of the event handler that does not index events
     /// appearing in blocks before the specified input date. Note that this
     /// is a different behavior compared to [`Self::new()`]: that function
@@ -282,16 +309,79 @@ where
         })
     }
 
+    /// Drops the handled blocks that got reorged according to the received
+    /// reorg notifications.
+    fn handle_reorgs(&mut self) {
+        let Some(reorgs) = &mut self.reorgs else {
+            return;
+        };
+        let mut common_ancestor: Option<BlockNumberHash> = None;
+        let mut deep_reorg = false;
+        loop {
+            match reorgs.try_recv() {
+                Ok(reorg) => match reorg.common_ancestor {
+                    Some(ancestor) => {
+                        if common_ancestor.is_none_or(|block| ancestor.0 < block.0) {
+                            common_ancestor = Some(ancestor);
+                        }
+                    }
+                    None => deep_reorg = true,
+                },
+                Err(TryRecvError::Lagged(missed)) => {
+                    // The regular reorg detection still applies, so it is
+                    // fine to continue.
+                    tracing::warn!(missed, "missed reorg notifications");
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+
+        if deep_reorg {
+            // None of the recent blocks are known to be canonical anymore.
+            // Only keeping the oldest handled block makes the next update
+            // replace the events of all the blocks after it, or go further
+            // back through the history range if that block got reorged too.
+            tracing::warn!("reorg deeper than the tracked blocks, resyncing events");
+            self.last_handled_blocks.truncate(1);
+            self.reorged = true;
+            return;
+        }
+        let Some(common_ancestor) = common_ancestor else {
+            return;
+        };
+        if self
+            .last_handled_blocks
+            .last()
+            .is_none_or(|block| block.0 <= common_ancestor.0)
+        {
+            // None of the handled blocks were affected.
+            return;
+        }
+        tracing::debug!(?common_ancestor, "handled blocks got reorged");
+        self.last_handled_blocks
+            .retain(|block| block.0 <= common_ancestor.0);
+        if self.last_handled_blocks.is_empty() {
+            self.last_handled_blocks.push(common_ancestor);
+        }
+        self.reorged = true;
+    }
+
     /// Get new events from the contract and insert them into the database.
     pub async fn update_events(&mut self) -> Result<()> {
+        self.handle_reorgs();
         let event_range = self.event_block_range().await?;
 
         if let Some(range) = event_range.history_range {
             self.update_events_from_old_blocks(range).await?;
+            self.reorged = false;
         }
         if let Some(last_block) = event_range.latest_blocks.last() {
-            self.update_events_from_latest_blocks(&event_range.latest_blocks, event_range.is_reorg)
-                .await?;
+            self.update_events_from_latest_blocks(
+                &event_range.latest_blocks,
+                event_range.is_reorg || self.reorged,
+            )
+            .await?;
+            self.reorged = false;
             self.store_mut()
                 .persist_last_indexed_block(last_block.0)
                 .await?;
@@ -450,7 +540,7 @@ where
             .get_events()
             .from_block((*block_range.start()).into())
             .to_block((*block_range.end()).into())
-            .block_page_size(500)
+            .block_page_size(self.contract.block_page_size())
             .query_paginated()
             .await?
             .map_err(Error::from))
@@ -531,6 +621,27 @@ fn split_range(range: RangeInclusive<u64>) -> (Option<RangeInclusive<u64>>, Rang
     }
 }
 
+/// Returns the block from which to start indexing the events of a contract,
+/// which is its deployment block if known. Otherwise indexing starts from the
+/// genesis block.
+pub async fn indexing_start_block(
+    block_retriever: &dyn BlockRetrieving,
+    instance: &Instance<DynTransport>,
+) -> Result<Option<BlockNumberHash>> {
+    match instance.deployment_information() {
+        Some(DeploymentInformation::BlockNumber(block)) => {
+            Ok(Some(block_retriever.block(block).await?))
+        }
+        _ => {
+            tracing::warn!(
+                address = ?instance.address(),
+                "contract deployment block unknown, indexing events from genesis"
+            );
+            Ok(None)
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl<C, S> Maintaining for Mutex<EventHandler<C, S>>
 where
@@ -622,10 +733,10 @@ mod tests {
     use {
         super::*,
         contracts::{GPv2Settlement, gpv2_settlement},
-        ethcontract::{BlockNumber, H256},
+        ethcontract::{BlockNumber, H160, H256},
         ethrpc::{
             Web3,
-            block_stream::block_number_to_block_number_hash,
+            block_stream::{BlockInfo, Reorg, block_number_to_block_number_hash},
             create_env_test_transport,
         },
         std::str::FromStr,
@@ -747,6 +858,90 @@ mod tests {
         assert!(is_reorg);
     }
 
+    fn reorged_event_handler(
+        reorgs: &Reorgs,
+        handled_blocks: std::ops::RangeInclusive<u64>,
+    ) -> EventHandler<GPv2SettlementContract, EventStorage<gpv2_settlement::Event>> {
+        let web3 = Web3::new(DynTransport::new(ethrpc::mock::MockTransport::new()));
+        let contract = GPv2Settlement::at(&web3, H160::zero());
+        let mut event_handler = EventHandler::new(
+            Arc::new(web3),
+            GPv2SettlementContract(contract),
+            EventStorage { events: vec![] },
+            None,
+        )
+        .with_reorgs(reorgs);
+        event_handler.last_handled_blocks = handled_blocks.map(block).collect();
+        event_handler
+    }
+
+    fn block(number: u64) -> BlockNumberHash {
+        (number, H256::from_low_u64_be(number))
+    }
+
+    fn reorg(common_ancestor: Option<u64>) -> Reorg {
+        Reorg {
+            common_ancestor: common_ancestor.map(block),
+            new_head: BlockInfo::default(),
+        }
+    }
+
+    #[test]
+    fn handle_reorgs_drops_reorged_blocks() {
+        let reorgs = Reorgs::new();
+        let mut event_handler = reorged_event_handler(&reorgs, 10..=15);
+
+        reorgs.notify(reorg(Some(13)));
+        reorgs.notify(reorg(Some(12)));
+        event_handler.handle_reorgs();
+
+        assert_eq!(
+            event_handler.last_handled_blocks,
+            (10..=12).map(block).collect::<Vec<_>>()
+        );
+        assert!(event_handler.reorged);
+    }
+
+    #[test]
+    fn handle_reorgs_ignores_unhandled_blocks() {
+        let reorgs = Reorgs::new();
+        let mut event_handler = reorged_event_handler(&reorgs, 10..=15);
+
+        reorgs.notify(reorg(Some(15)));
+        event_handler.handle_reorgs();
+
+        assert_eq!(
+            event_handler.last_handled_blocks,
+            (10..=15).map(block).collect::<Vec<_>>()
+        );
+        assert!(!event_handler.reorged);
+    }
+
+    #[test]
+    fn handle_reorgs_keeps_ancestor_older_than_handled_blocks() {
+        let reorgs = Reorgs::new();
+        let mut event_handler = reorged_event_handler(&reorgs, 10..=15);
+
+        reorgs.notify(reorg(Some(5)));
+        event_handler.handle_reorgs();
+
+        assert_eq!(event_handler.last_handled_blocks, [block(5)]);
+        assert!(event_handler.reorged);
+    }
+
+    #[test]
+    fn handle_reorgs_resyncs_after_deep_reorg() {
+        let reorgs = Reorgs::new();
+        let mut event_handler = reorged_event_handler(&reorgs, 10..=15);
+
+        reorgs.notify(reorg(Some(12)));
+        reorgs.notify(reorg(None));
+        event_handler.handle_reorgs();
+
+        assert_eq!(event_handler.last_handled_blocks, [block(10)]);
+        assert!(event_handler.reorged);
+    }
+
     #[test]
     fn split_range_test_equal() {
         let range = RangeInclusive::try_new(0, 0).unwrap();
@@ -1,5 +1,6 @@
 use {
     ethcontract::Bytes,
+    ethrpc::extensions::CallFrame,
     model::interaction::InteractionData,
     primitive_types::{H160, U256},
 };
@@ -35,3 +36,101 @@ impl Interaction for InteractionData {
         (self.target, self.value, Bytes(self.call_data.clone()))
     }
 }
+
+/// How an interaction behaved when it got executed in a simulation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InteractionExecution {
+    pub success: bool,
+    pub gas_used: u64,
+}
+
+impl InteractionExecution {
+    /// Looks up how the interactions, given as `(target, call_data)`, got
+    /// executed in the call trace of a simulation. Interactions have to be
+    /// called in the given order. An interaction is `None` if it was not
+    /// called, for example because an earlier interaction reverted.
+    pub fn find_all<'a>(
+        trace: &CallFrame,
+        interactions: impl IntoIterator<Item = (H160, &'a [u8])>,
+    ) -> Vec<Option<Self>> {
+        fn flatten<'a>(frame: &'a CallFrame, frames: &mut Vec<&'a CallFrame>) {
+            frames.push(frame);
+            for call in &frame.calls {
+                flatten(call, frames);
+            }
+        }
+        let mut frames = Vec::new();
+        flatten(trace, &mut frames);
+
+        let mut next = 0;
+        interactions
+            .into_iter()
+            .map(|(target, call_data)| {
+                let position = frames[next..]
+                    .iter()
+                    .position(|frame| frame.to == Some(target) && frame.input.0 == call_data)?;
+                let frame = frames[next + position];
+                next += position + 1;
+                Some(Self {
+                    success: frame.error.is_none(),
+                    gas_used: frame.gas_used.low_u64(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, web3::types::Bytes as CallData};
+
+    #[test]
+    fn finds_interaction_executions() {
+        let frame = |target: u64, call_data: &[u8], gas_used: u64, calls| CallFrame {
+            to: Some(H160::from_low_u64_be(target)),
+            input: CallData(call_data.to_vec()),
+            gas_used: gas_used.into(),
+            error: None,
+            calls,
+        };
+
+        let trace = frame(
+            1,
+            &[],
+            100_000,
+            vec![
+                frame(2, &[1], 1_000, vec![]),
+                frame(3, &[], 50_000, vec![frame(2, &[1], 2_000, vec![])]),
+                CallFrame {
+                    error: Some("execution reverted".to_string()),
+                    ..frame(4, &[2], 3_000, vec![])
+                },
+            ],
+        );
+
+        let interactions: [(H160, &[u8]); 4] = [
+            (H160::from_low_u64_be(2), &[1]),
+            (H160::from_low_u64_be(2), &[1]),
+            (H160::from_low_u64_be(4), &[2]),
+            (H160::from_low_u64_be(5), &[]),
+        ];
+        assert_eq!(
+            InteractionExecution::find_all(&trace, interactions),
+            vec![
+                Some(InteractionExecution {
+                    success: true,
+                    gas_used: 1_000,
+                }),
+                Some(InteractionExecution {
+                    success: true,
+                    gas_used: 2_000,
+                }),
+                Some(InteractionExecution {
+                    success: false,
+                    gas_used: 3_000,
+                }),
+                None,
+            ]
+        );
+    }
+}
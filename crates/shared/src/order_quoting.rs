@@ -10,13 +10,19 @@ use {
         db_order_conversions::order_kind_from,
         fee::FeeParameters,
         order_validation::PreOrderData,
-        price_estimation::{Estimate, QuoteVerificationMode, Verification},
+        price_estimation::{
+            Estimate,
+            QuoteVerificationMode,
+            Verification,
+            slippage::SlippageEstimator,
+        },
         trade_finding::external::dto,
     },
     anyhow::{Context, Result},
     chrono::{DateTime, Duration, Utc},
     database::quotes::{Quote as QuoteRow, QuoteKind},
     ethcontract::{H160, U256},
+    ethrpc::extensions::CallFrame,
     futures::TryFutureExt as _,
     gas_estimation::GasPriceEstimating,
     model::{
@@ -91,6 +97,12 @@ pub struct Quote {
     /// The fee amount for any order created for this quote. The fee is
     /// denoted in the sell token.
     pub fee_amount: U256,
+    /// The slippage tolerance (in basis points) we suggest for orders created
+    /// for this quote. Only computed and never persisted.
+    pub suggested_slippage_bps: Option<u32>,
+    /// The call trace of the simulation verifying the quote, for example to
+    /// see how the hooks got executed. Only computed and never persisted.
+    pub simulation_trace: Option<CallFrame>,
 }
 
 impl Quote {
@@ -101,6 +113,8 @@ impl Quote {
             sell_amount: data.quoted_sell_amount,
             buy_amount: data.quoted_buy_amount,
             fee_amount: data.fee_parameters.fee(),
+            suggested_slippage_bps: None,
+            simulation_trace: None,
             data,
         }
     }
@@ -378,6 +392,7 @@ pub struct OrderQuoter {
     validity: Validity,
     balance_fetcher: Arc<dyn BalanceFetching>,
     quote_verification: QuoteVerificationMode,
+    slippage: Option<Arc<SlippageEstimator>>,
 }
 
 impl OrderQuoter {
@@ -399,13 +414,20 @@ impl OrderQuoter {
             validity,
             balance_fetcher,
             quote_verification,
+            slippage: None,
         }
     }
 
-    async fn compute_quote_data(
+    /// Makes the quoter suggest a slippage tolerance for every computed quote.
+    pub fn with_slippage_estimator(mut self, estimator: Arc<SlippageEstimator>) -> Self {
+        self.slippage = Some(estimator);
+        self
+    }
+
+    async fn compute_quote(
         &self,
         parameters: &QuoteParameters,
-    ) -> Result<QuoteData, CalculateQuoteError> {
+    ) -> Result<Quote, CalculateQuoteError> {
         let expiration = match parameters.signing_scheme {
             QuoteSigningScheme::Eip1271 {
                 onchain_order: true,
@@ -418,7 +440,27 @@ impl OrderQuoter {
         };
 
         let trade_query = Arc::new(parameters.to_price_query());
-        let (gas_estimate, trade_estimate, sell_token_price, _) = futures::try_join!(
+        let probe_query = self
+            .slippage
+            .as_ref()
+            .and_then(|slippage| slippage.probe_query(&trade_query))
+            .map(Arc::new);
+        // The probe is only used to gauge the price impact so failing to
+        // estimate it in time should not fail or delay the quote.
+        let probe_estimate = async {
+            let estimate = match (&probe_query, &self.slippage) {
+                (Some(query), Some(slippage)) => tokio::time::timeout(
+                    slippage.probe_timeout(),
+                    self.price_estimator.estimate(query.clone()),
+                )
+                .await
+                .ok()
+                .and_then(Result::ok),
+                _ => None,
+            };
+            Ok::<_, PriceEstimationError>(estimate)
+        };
+        let (gas_estimate, trade_estimate, sell_token_price, _, probe_estimate) = futures::try_join!(
             self.gas_estimator
                 .estimate()
                 .map_err(PriceEstimationError::ProtocolInternal),
@@ -430,6 +472,7 @@ impl OrderQuoter {
             // we make the native buy_token price a requirement here as well.
             self.native_price_estimator
                 .estimate_native_price(parameters.buy_token),
+            probe_estimate,
         )?;
 
         let (quoted_sell_amount, quoted_buy_amount) = match &parameters.side {
@@ -452,6 +495,11 @@ impl OrderQuoter {
         self.verify_quote(&trade_estimate, parameters, quoted_sell_amount)
             .await?;
 
+        let suggested_slippage_bps = self.slippage.as_ref().map(|slippage| {
+            let probe = probe_query.as_deref().zip(probe_estimate.as_ref());
+            slippage.suggest(&trade_query, &trade_estimate, probe)
+        });
+
         let quote_kind = quote_kind_from_signing_scheme(&parameters.signing_scheme);
         let quote = QuoteData {
             sell_token: parameters.sell_token,
@@ -472,7 +520,11 @@ impl OrderQuoter {
             .into(),
         };
 
-        Ok(quote)
+        Ok(Quote {
+            suggested_slippage_bps,
+            simulation_trace: trade_estimate.simulation_trace,
+            ..Quote::new(Default::default(), quote)
+        })
     }
 
     /// Makes sure a quote was verified according to the configured rule.
@@ -538,9 +590,10 @@ impl OrderQuoting for OrderQuoter {
         &self,
         parameters: QuoteParameters,
     ) -> Result<Quote, CalculateQuoteError> {
-        let data = self.compute_quote_data(&parameters).await?;
-        let mut quote =
-            Quote::new(Default::default(), data).with_additional_cost(parameters.additional_cost());
+        let mut quote = self
+            .compute_quote(&parameters)
+            .await?
+            .with_additional_cost(parameters.additional_cost());
 
         // Make sure to scale the sell and buy amounts for quotes for sell
         // amounts before fees.
@@ -726,7 +779,11 @@ mod tests {
         crate::{
             account_balances::MockBalanceFetching,
             gas_price_estimation::FakeGasPriceEstimator,
-            price_estimation::{MockPriceEstimating, native::MockNativePriceEstimating},
+            price_estimation::{
+                MockPriceEstimating,
+                native::MockNativePriceEstimating,
+                native_price_cache::CachingNativePriceEstimator,
+            },
         },
         chrono::Utc,
         ethcontract::H160,
@@ -818,6 +875,8 @@ mod tests {
                         solver: H160([1; 20]),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
@@ -872,6 +931,7 @@ mod tests {
             validity: super::Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         let quote = quoter.calculate_quote(parameters).await.unwrap();
@@ -901,10 +961,86 @@ mod tests {
                 sell_amount: 70.into(),
                 buy_amount: 29.into(),
                 fee_amount: 30.into(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             }
         );
     }
 
+    #[tokio::test]
+    async fn compute_quote_with_suggested_slippage() {
+        let parameters = QuoteParameters {
+            sell_token: H160([1; 20]),
+            buy_token: H160([2; 20]),
+            side: OrderQuoteSide::Sell {
+                sell_amount: SellAmount::BeforeFee {
+                    value: NonZeroU256::try_from(100).unwrap(),
+                },
+            },
+            signing_scheme: QuoteSigningScheme::Eip712,
+            ..Default::default()
+        };
+
+        let mut price_estimator = MockPriceEstimating::new();
+        price_estimator
+            .expect_estimate()
+            .times(2)
+            .returning(|query| {
+                // The probe for the larger amount never finishes, so the
+                // suggestion only accounts for the spread of the estimate.
+                if query.in_amount.get() != 100.into() {
+                    return futures::future::pending().boxed();
+                }
+                async {
+                    Ok(price_estimation::Estimate {
+                        out_amount: 42.into(),
+                        gas: 3,
+                        spread_bps: Some(50),
+                        ..Default::default()
+                    })
+                }
+                .boxed()
+            });
+
+        let mut native_price_estimator = MockNativePriceEstimating::new();
+        native_price_estimator
+            .expect_estimate_native_price()
+            .returning(|_| async { Ok(0.2) }.boxed());
+
+        let mut native_prices = MockNativePriceEstimating::new();
+        native_prices.expect_estimate_native_price().never();
+        let native_prices = CachingNativePriceEstimator::new(
+            Box::new(native_prices),
+            std::time::Duration::from_secs(600),
+            Default::default(),
+            None,
+            Default::default(),
+            1,
+            Default::default(),
+        );
+        let slippage = SlippageEstimator::new(
+            Arc::new(native_prices),
+            10,
+            std::time::Duration::from_millis(10),
+            0,
+            10_000,
+        );
+
+        let quoter = OrderQuoter::new(
+            Arc::new(price_estimator),
+            Arc::new(native_price_estimator),
+            Arc::new(FakeGasPriceEstimator::default()),
+            Arc::new(MockQuoteStoring::new()),
+            Validity::default(),
+            mock_balance_fetcher(),
+            QuoteVerificationMode::Unverified,
+        )
+        .with_slippage_estimator(Arc::new(slippage));
+
+        let quote = quoter.calculate_quote(parameters).await.unwrap();
+        assert_eq!(quote.suggested_slippage_bps, Some(50));
+    }
+
     #[tokio::test]
     async fn compute_sell_after_fee_quote() {
         let now = Utc::now();
@@ -956,6 +1092,8 @@ mod tests {
                         solver: H160([1; 20]),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
@@ -1010,6 +1148,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         let quote = quoter.calculate_quote(parameters).await.unwrap();
@@ -1039,6 +1178,8 @@ mod tests {
                 sell_amount: 100.into(),
                 buy_amount: 42.into(),
                 fee_amount: 60.into(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             }
         );
     }
@@ -1089,6 +1230,8 @@ mod tests {
                         solver: H160([1; 20]),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
@@ -1143,6 +1286,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         let quote = quoter.calculate_quote(parameters).await.unwrap();
@@ -1172,6 +1316,8 @@ mod tests {
                 sell_amount: 100.into(),
                 buy_amount: 42.into(),
                 fee_amount: 30.into(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             }
         );
     }
@@ -1208,6 +1354,8 @@ mod tests {
                     solver: H160([1; 20]),
                     verified: false,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 })
             }
             .boxed()
@@ -1240,6 +1388,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert!(matches!(
@@ -1280,6 +1429,8 @@ mod tests {
                     solver: H160([1; 20]),
                     verified: false,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 })
             }
             .boxed()
@@ -1312,6 +1463,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert!(matches!(
@@ -1369,6 +1521,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert_eq!(
@@ -1398,6 +1551,8 @@ mod tests {
                 // market price.
                 buy_amount: 35.into(),
                 fee_amount: 30.into(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             }
         );
     }
@@ -1451,6 +1606,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert_eq!(
@@ -1477,6 +1633,8 @@ mod tests {
                 sell_amount: 100.into(),
                 buy_amount: 42.into(),
                 fee_amount: 30.into(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             }
         );
     }
@@ -1535,6 +1693,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert_eq!(
@@ -1561,6 +1720,8 @@ mod tests {
                 sell_amount: 100.into(),
                 buy_amount: 42.into(),
                 fee_amount: 30.into(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             }
         );
     }
@@ -1607,6 +1768,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert!(matches!(
@@ -1637,6 +1799,7 @@ mod tests {
             validity: Validity::default(),
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
+            slippage: None,
         };
 
         assert!(matches!(
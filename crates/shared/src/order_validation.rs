@@ -1462,6 +1462,8 @@ mod tests {
                 sell_amount: U256::from(1),
                 buy_amount: U256::from(1),
                 fee_amount: Default::default(),
+                suggested_slippage_bps: None,
+                simulation_trace: None,
             })
         });
         bad_token_detector
@@ -11,7 +11,7 @@ use {
     anyhow::Context,
     futures::future::{BoxFuture, FutureExt, TryFutureExt},
     model::order::OrderKind,
-    primitive_types::{H160, U256},
+    primitive_types::{H160, U256, U512},
     std::{cmp::Ordering, sync::Arc},
 };
 
@@ -34,6 +34,13 @@ impl PriceEstimating for CompetitionEstimator<Arc<dyn PriceEstimating>> {
 
             let (context, results) = futures::try_join!(get_context, get_results)?;
 
+            let spread_bps = spread_bps(
+                results
+                    .iter()
+                    .filter(|(_index, r)| gas_is_reasonable(r))
+                    .filter_map(|(_index, r)| r.as_ref().ok())
+                    .map(|estimate| estimate.out_amount),
+            );
             let winner = results
                 .into_iter()
                 .filter(|(_index, r)| r.is_err() || gas_is_reasonable(r))
@@ -49,11 +56,35 @@ impl PriceEstimating for CompetitionEstimator<Arc<dyn PriceEstimating>> {
                 .with_context(|| "all price estimates reported 0 gas cost")
                 .map_err(PriceEstimationError::EstimatorInternal)?;
             self.report_winner(&query, query.kind, winner)
+                .map(|estimate| Estimate {
+                    spread_bps,
+                    ..estimate
+                })
         }
         .boxed()
     }
 }
 
+/// Computes by how many basis points the given `out_amount`s differ relative
+/// to the largest one. Returns `None` if there are not at least 2 amounts to
+/// compare.
+fn spread_bps(out_amounts: impl Iterator<Item = U256>) -> Option<u32> {
+    let (min, max, count) = out_amounts
+        .fold((U256::MAX, U256::zero(), 0), |(min, max, count), amount| {
+            (min.min(amount), max.max(amount), count + 1)
+        });
+    if count < 2 {
+        return None;
+    }
+    if max.is_zero() {
+        return Some(0);
+    }
+    let numerator = (max - min).full_mul(U256::from(10_000));
+    let denominator = U512::from(max);
+    // The spread is at most 100% so the result always fits into a `u32`.
+    Some(((numerator + denominator - U512::one()) / denominator).low_u32())
+}
+
 fn compare_quote_result(
     query: &Query,
     a: &PriceEstimateResult,
@@ -160,6 +191,13 @@ mod tests {
         })
     }
 
+    fn with_spread(estimate: PriceEstimateResult, spread_bps: u32) -> PriceEstimateResult {
+        estimate.map(|estimate| Estimate {
+            spread_bps: Some(spread_bps),
+            ..estimate
+        })
+    }
+
     fn error<T>(err: PriceEstimationError) -> Result<T, PriceEstimationError> {
         Err(err)
     }
@@ -242,7 +280,7 @@ mod tests {
             QuoteVerificationMode::Unverified,
         )
         .await;
-        assert_eq!(best, price(104_000, 1_000));
+        assert_eq!(best, with_spread(price(104_000, 1_000), 371));
 
         let best = best_response(
             bang_for_buck_ranking(),
@@ -256,7 +294,7 @@ mod tests {
             QuoteVerificationMode::Unverified,
         )
         .await;
-        assert_eq!(best, price(96_000, 1_000));
+        assert_eq!(best, with_spread(price(96_000, 1_000), 417));
     }
 
     /// Same test as above but now we also add an estimate that should
@@ -280,7 +318,7 @@ mod tests {
             QuoteVerificationMode::Unverified,
         )
         .await;
-        assert_eq!(best, price(104_000, 1_000));
+        assert_eq!(best, with_spread(price(104_000, 1_000), 371));
 
         let best = best_response(
             bang_for_buck_ranking(),
@@ -297,7 +335,7 @@ mod tests {
             QuoteVerificationMode::Unverified,
         )
         .await;
-        assert_eq!(best, price(96_000, 1_000));
+        assert_eq!(best, with_spread(price(96_000, 1_000), 417));
     }
 
     /// If all estimators returned an error we return the one with the highest
@@ -359,7 +397,7 @@ mod tests {
             QuoteVerificationMode::Prefer,
         )
         .await;
-        assert_eq!(best, worse_verified_quote.clone());
+        assert_eq!(best, with_spread(worse_verified_quote.clone(), 1_000));
 
         let best = best_response(
             PriceRanking::MaxOutAmount,
@@ -371,7 +409,7 @@ mod tests {
             QuoteVerificationMode::EnforceWhenPossible,
         )
         .await;
-        assert_eq!(best, worse_verified_quote.clone());
+        assert_eq!(best, with_spread(worse_verified_quote.clone(), 1_000));
 
         let best = best_response(
             PriceRanking::MaxOutAmount,
@@ -383,6 +421,23 @@ mod tests {
             QuoteVerificationMode::Unverified,
         )
         .await;
-        assert_eq!(best, better_unverified_quote);
+        assert_eq!(best, with_spread(better_unverified_quote, 1_000));
+    }
+
+    #[test]
+    fn computes_spread_between_estimates() {
+        let spread = |amounts: &[u64]| spread_bps(amounts.iter().copied().map(U256::from));
+        assert_eq!(spread(&[]), None);
+        assert_eq!(spread(&[100]), None);
+        assert_eq!(spread(&[0, 0]), Some(0));
+        assert_eq!(spread(&[100, 100]), Some(0));
+        assert_eq!(spread(&[90, 100, 95]), Some(1_000));
+        // Rounds up.
+        assert_eq!(spread(&[99_999, 100_000]), Some(1));
+        assert_eq!(spread(&[0, 100]), Some(10_000));
+        assert_eq!(
+            spread_bps([U256::zero(), U256::MAX].into_iter()),
+            Some(10_000)
+        );
     }
 }
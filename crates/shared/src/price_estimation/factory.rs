@@ -9,6 +9,7 @@ use {
         native::{self, NativePriceEstimator},
         native_price_cache::CachingNativePriceEstimator,
         sanitized::SanitizedPriceEstimator,
+        sharing::SharingPriceEstimator,
         trade_verifier::{TradeVerifier, TradeVerifying},
     },
     crate::{
@@ -114,7 +115,7 @@ impl<'a> PriceEstimatorFactory<'a> {
             None => Arc::new(web3.clone()),
         };
 
-        let balance_overrides = args.balance_overrides.init(simulator.clone());
+        let balance_overrides = args.balance_overrides.init(simulator.clone(), &web3);
 
         let verifier = TradeVerifier::new(
             web3,
@@ -125,6 +126,7 @@ impl<'a> PriceEstimatorFactory<'a> {
             network.settlement,
             network.native_token,
             args.quote_inaccuracy_limit.clone(),
+            args.quote_trace_hooks,
         )
         .await?;
         Ok(Some(Arc::new(verifier)))
@@ -324,7 +326,10 @@ impl<'a> PriceEstimatorFactory<'a> {
             PriceRanking::BestBangForBuck { native, gas },
         )
         .with_verification(self.args.quote_verification);
-        Ok(Arc::new(self.sanitized(Arc::new(competition_estimator))))
+        Ok(Arc::new(SharingPriceEstimator::new(
+            Arc::new(self.sanitized(Arc::new(competition_estimator))),
+            "optimal",
+        )))
     }
 
     pub fn fast_price_estimator(
@@ -335,15 +340,18 @@ impl<'a> PriceEstimatorFactory<'a> {
         gas: Arc<dyn GasPriceEstimating>,
     ) -> Result<Arc<dyn PriceEstimating>> {
         let estimators = self.get_estimators(solvers, |entry| &entry.fast)?;
-        Ok(Arc::new(
-            self.sanitized(Arc::new(
-                CompetitionEstimator::new(
-                    vec![estimators],
-                    PriceRanking::BestBangForBuck { native, gas },
-                )
-                .with_early_return(fast_price_estimation_results_required),
-            )),
-        ))
+        Ok(Arc::new(SharingPriceEstimator::new(
+            Arc::new(
+                self.sanitized(Arc::new(
+                    CompetitionEstimator::new(
+                        vec![estimators],
+                        PriceRanking::BestBangForBuck { native, gas },
+                    )
+                    .with_early_return(fast_price_estimation_results_required),
+                )),
+            ),
+            "fast",
+        )))
     }
 
     pub async fn native_price_estimator(
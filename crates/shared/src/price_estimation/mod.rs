@@ -7,6 +7,7 @@ use {
     anyhow::{Result, ensure},
     bigdecimal::BigDecimal,
     ethcontract::{H160, U256},
+    ethrpc::extensions::CallFrame,
     futures::future::BoxFuture,
     itertools::Itertools,
     model::order::{BuyTokenDestination, OrderKind, SellTokenSource},
@@ -36,6 +37,8 @@ pub mod instrumented;
 pub mod native;
 pub mod native_price_cache;
 pub mod sanitized;
+pub mod sharing;
+pub mod slippage;
 pub mod trade_finder;
 pub mod trade_verifier;
 
@@ -244,6 +247,13 @@ pub struct Arguments {
     )]
     pub quote_timeout: Duration,
 
+    /// Trace the simulations of orders with hooks (the sell token transfer
+    /// and the quote verification) to report how every hook got executed in
+    /// order simulations. This requires the nodes to support
+    /// `debug_traceCall`.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value_t)]
+    pub quote_trace_hooks: bool,
+
     #[clap(flatten)]
     pub balance_overrides: balance_overrides::Arguments,
 
@@ -357,6 +367,7 @@ impl Display for Arguments {
             quote_inaccuracy_limit,
             quote_verification,
             quote_timeout,
+            quote_trace_hooks,
             balance_overrides,
             native_price_approximation_tokens,
         } = self;
@@ -436,6 +447,7 @@ impl Display for Arguments {
         writeln!(f, "quote_inaccuracy_limit: {}", quote_inaccuracy_limit)?;
         writeln!(f, "quote_verification: {:?}", quote_verification)?;
         writeln!(f, "quote_timeout: {:?}", quote_timeout)?;
+        writeln!(f, "quote_trace_hooks: {}", quote_trace_hooks)?;
         write!(f, "{}", balance_overrides)?;
         writeln!(
             f,
@@ -541,6 +553,13 @@ pub struct Estimate {
     pub verified: bool,
     /// Data associated with this estimation.
     pub execution: QuoteExecution,
+    /// By how many basis points the `out_amount`s of competing estimates
+    /// differed. Only set by estimators comparing multiple sources.
+    pub spread_bps: Option<u32>,
+    /// The call trace of the simulation verifying this estimate. Only set if
+    /// tracing is enabled and the [`Verification`] has interactions.
+    #[serde(skip)]
+    pub simulation_trace: Option<CallFrame>,
 }
 
 impl Estimate {
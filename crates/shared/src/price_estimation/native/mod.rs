@@ -130,6 +130,8 @@ mod tests {
                     solver: H160([1; 20]),
                     verified: false,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 })
             }
             .boxed()
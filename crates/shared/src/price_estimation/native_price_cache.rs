@@ -12,7 +12,7 @@ use {
     prometheus::{IntCounter, IntCounterVec, IntGauge},
     rand::Rng,
     std::{
-        collections::{HashMap, hash_map::Entry},
+        collections::{HashMap, VecDeque, hash_map::Entry},
         sync::{Arc, Mutex, MutexGuard, Weak},
         time::{Duration, Instant},
     },
@@ -50,6 +50,9 @@ pub struct CachingNativePriceEstimator(Arc<Inner>);
 
 struct Inner {
     cache: Mutex<HashMap<H160, CachedResult>>,
+    /// The most recently fetched prices of every token (oldest first). Used
+    /// to estimate how volatile a token's price is.
+    history: Mutex<HashMap<H160, VecDeque<f64>>>,
     high_priority: Mutex<IndexSet<H160>>,
     estimator: Box<dyn NativePriceEstimating>,
     max_age: Duration,
@@ -88,6 +91,9 @@ struct CachedResult {
 /// estimator.
 const ACCUMULATIVE_ERRORS_THRESHOLD: u32 = 5;
 
+/// How many of the most recently fetched prices get remembered per token.
+const PRICE_HISTORY_SIZE: usize = 10;
+
 impl CachedResult {
     fn new(
         result: CacheEntry,
@@ -202,6 +208,9 @@ impl Inner {
                     CachedResult::new(result.clone(), now, now, current_accumulative_errors_count),
                 );
             };
+            if let Ok(price) = &result {
+                self.record_price(*token, *price);
+            }
 
             (*token, result)
         });
@@ -210,6 +219,15 @@ impl Inner {
             .boxed()
     }
 
+    fn record_price(&self, token: H160, price: f64) {
+        let mut history = self.history.lock().unwrap();
+        let prices = history.entry(token).or_default();
+        if prices.len() == PRICE_HISTORY_SIZE {
+            prices.pop_front();
+        }
+        prices.push_back(price);
+    }
+
     /// Tokens with highest priority first.
     fn sorted_tokens_to_update(&self, max_age: Duration, now: Instant) -> Vec<H160> {
         let mut outdated: Vec<_> = self
@@ -334,6 +352,7 @@ impl CachingNativePriceEstimator {
         let inner = Arc::new(Inner {
             estimator,
             cache: Default::default(),
+            history: Default::default(),
             high_priority: Default::default(),
             max_age,
             concurrent_requests,
@@ -383,6 +402,21 @@ impl CachingNativePriceEstimator {
         results
     }
 
+    /// Returns the volatility of the token's native price, i.e. the standard
+    /// deviation of the recently fetched prices relative to their mean.
+    /// Returns `None` if not enough prices have been fetched yet.
+    pub fn volatility(&self, token: H160) -> Option<f64> {
+        let history = self.0.history.lock().unwrap();
+        let prices = history.get(&token).filter(|prices| prices.len() >= 2)?;
+        let count = prices.len() as f64;
+        let mean = prices.iter().sum::<f64>() / count;
+        if mean <= 0. {
+            return None;
+        }
+        let variance = prices.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / count;
+        Some(variance.sqrt() / mean)
+    }
+
     pub fn replace_high_priority(&self, tokens: IndexSet<H160>) {
         *self.0.high_priority.lock().unwrap() = tokens;
     }
@@ -913,6 +947,7 @@ mod tests {
                 .into_iter()
                 .collect(),
             ),
+            history: Default::default(),
             high_priority: Default::default(),
             estimator: Box::new(MockNativePriceEstimating::new()),
             max_age: Default::default(),
@@ -932,4 +967,32 @@ mod tests {
         assert_eq!(tokens[0], t1);
         assert_eq!(tokens[1], t0);
     }
+
+    #[tokio::test]
+    async fn computes_volatility_of_recent_prices() {
+        let mut inner = MockNativePriceEstimating::new();
+        inner.expect_estimate_native_price().never();
+
+        let estimator = CachingNativePriceEstimator::new(
+            Box::new(inner),
+            Duration::from_secs(600),
+            Default::default(),
+            None,
+            Default::default(),
+            1,
+            Default::default(),
+        );
+
+        assert_eq!(estimator.volatility(token(0)), None);
+        estimator.0.record_price(token(0), 1.);
+        assert_eq!(estimator.volatility(token(0)), None);
+        estimator.0.record_price(token(0), 3.);
+        assert_eq!(estimator.volatility(token(0)), Some(0.5));
+
+        // Only the most recent prices are taken into account.
+        for _ in 0..PRICE_HISTORY_SIZE {
+            estimator.0.record_price(token(0), 2.);
+        }
+        assert_eq!(estimator.volatility(token(0)), Some(0.));
+    }
 }
@@ -68,6 +68,8 @@ impl PriceEstimating for SanitizedPriceEstimator {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 };
                 tracing::debug!(?query, ?estimation, "generate trivial price estimation");
                 return Ok(estimation);
@@ -81,6 +83,8 @@ impl PriceEstimating for SanitizedPriceEstimator {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 };
                 tracing::debug!(?query, ?estimation, "generate trivial unwrap estimation");
                 return Ok(estimation);
@@ -94,6 +98,8 @@ impl PriceEstimating for SanitizedPriceEstimator {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 };
                 tracing::debug!(?query, ?estimation, "generate trivial wrap estimation");
                 return Ok(estimation);
@@ -189,6 +195,8 @@ mod tests {
                     solver: Default::default(),
                     verified: false,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // `sanitized_estimator` will replace `buy_token` with `native_token` before querying
@@ -211,6 +219,8 @@ mod tests {
                     solver: Default::default(),
                     verified: false,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // Will cause buffer overflow of gas price in `sanitized_estimator`.
@@ -247,6 +257,8 @@ mod tests {
                     solver: Default::default(),
                     verified: false,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // Can be estimated by `sanitized_estimator` because `buy_token` and `sell_token` are
@@ -266,6 +278,8 @@ mod tests {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // Can be estimated by `sanitized_estimator` because both tokens are the native token.
@@ -284,6 +298,8 @@ mod tests {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // Can be estimated by `sanitized_estimator` because it is a native token unwrap.
@@ -303,6 +319,8 @@ mod tests {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // Can be estimated by `sanitized_estimator` because it is a native token wrap.
@@ -322,6 +340,8 @@ mod tests {
                     solver: Default::default(),
                     verified: true,
                     execution: Default::default(),
+                    spread_bps: None,
+                    simulation_trace: None,
                 }),
             ),
             // Will throw `UnsupportedToken` error in `sanitized_estimator`.
@@ -387,6 +407,8 @@ mod tests {
                         solver: Default::default(),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
@@ -403,6 +425,8 @@ mod tests {
                         solver: Default::default(),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
@@ -419,6 +443,8 @@ mod tests {
                         solver: Default::default(),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
@@ -435,6 +461,8 @@ mod tests {
                         solver: Default::default(),
                         verified: false,
                         execution: Default::default(),
+                        spread_bps: None,
+                        simulation_trace: None,
                     })
                 }
                 .boxed()
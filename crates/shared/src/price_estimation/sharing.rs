@@ -0,0 +1,86 @@
+use {
+    crate::{
+        price_estimation::{PriceEstimateResult, PriceEstimating, Query},
+        request_sharing::BoxRequestSharing,
+    },
+    futures::{FutureExt, future::BoxFuture},
+    std::sync::Arc,
+};
+
+/// Shares the estimate of a query with all identical queries that get
+/// requested while it is still in flight. This avoids finding and verifying
+/// the same trade multiple times, for example for the quotes of a batch.
+pub struct SharingPriceEstimator {
+    inner: Arc<dyn PriceEstimating>,
+    sharing: BoxRequestSharing<Arc<Query>, PriceEstimateResult>,
+}
+
+impl SharingPriceEstimator {
+    pub fn new(inner: Arc<dyn PriceEstimating>, name: &str) -> Self {
+        Self {
+            inner,
+            sharing: BoxRequestSharing::labelled(format!("price_estimator_{name}")),
+        }
+    }
+}
+
+impl PriceEstimating for SharingPriceEstimator {
+    fn estimate(&self, query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult> {
+        let inner = self.inner.clone();
+        self.sharing
+            .shared_or_else(query, move |query| {
+                let query = query.clone();
+                async move { inner.estimate(query).await }.boxed()
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::price_estimation::{Estimate, MockPriceEstimating},
+        futures::future,
+        model::order::OrderKind,
+        number::nonzero::U256 as NonZeroU256,
+        primitive_types::H160,
+    };
+
+    #[tokio::test]
+    async fn shares_identical_queries_in_flight() {
+        let query = |sell_token| {
+            Arc::new(Query {
+                sell_token: H160::from_low_u64_be(sell_token),
+                buy_token: H160::from_low_u64_be(3),
+                in_amount: NonZeroU256::try_from(1).unwrap(),
+                kind: OrderKind::Sell,
+                verification: Default::default(),
+                block_dependent: false,
+            })
+        };
+
+        let mut inner = MockPriceEstimating::new();
+        inner.expect_estimate().times(2).returning(|query| {
+            let estimate = Estimate {
+                out_amount: query.sell_token.to_low_u64_be().into(),
+                ..Default::default()
+            };
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                Ok(estimate)
+            }
+            .boxed()
+        });
+        let estimator = SharingPriceEstimator::new(Arc::new(inner), "test");
+
+        let estimates =
+            future::join_all([query(1), query(2), query(1)].map(|query| estimator.estimate(query)))
+                .await;
+        let out_amounts = estimates
+            .into_iter()
+            .map(|estimate| estimate.unwrap().out_amount.as_u64())
+            .collect::<Vec<_>>();
+        assert_eq!(out_amounts, [1, 2, 1]);
+    }
+}
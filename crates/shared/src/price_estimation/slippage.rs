@@ -0,0 +1,211 @@
+use {
+    super::{Estimate, Query, native_price_cache::CachingNativePriceEstimator},
+    number::nonzero::U256 as NonZeroU256,
+    std::{sync::Arc, time::Duration},
+};
+
+const BPS_PER_UNIT: f64 = 10_000.;
+
+/// Suggests a slippage tolerance for quotes. The suggestion accounts for how
+/// much the competing price estimators disagreed, how volatile the native
+/// prices of the traded tokens were recently and how much the price degrades
+/// when trading a larger amount.
+pub struct SlippageEstimator {
+    native_prices: Arc<CachingNativePriceEstimator>,
+    price_impact_probe_factor: u32,
+    probe_timeout: Duration,
+    min_bps: u32,
+    max_bps: u32,
+}
+
+impl SlippageEstimator {
+    /// Creates a new estimator. Suggestions get clamped to
+    /// `[min_bps, max_bps]`.
+    pub fn new(
+        native_prices: Arc<CachingNativePriceEstimator>,
+        price_impact_probe_factor: u32,
+        probe_timeout: Duration,
+        min_bps: u32,
+        max_bps: u32,
+    ) -> Self {
+        assert!(min_bps <= max_bps);
+        Self {
+            native_prices,
+            price_impact_probe_factor,
+            probe_timeout,
+            min_bps,
+            max_bps,
+        }
+    }
+
+    /// Returns a query for `price_impact_probe_factor` times the amount of the
+    /// original query. Comparing the estimates of both queries shows how
+    /// sensitive the price is to the traded amount.
+    pub fn probe_query(&self, query: &Query) -> Option<Query> {
+        if self.price_impact_probe_factor < 2 {
+            return None;
+        }
+        let in_amount = query
+            .in_amount
+            .get()
+            .checked_mul(self.price_impact_probe_factor.into())?;
+        Some(Query {
+            in_amount: NonZeroU256::try_from(in_amount).ok()?,
+            ..query.clone()
+        })
+    }
+
+    /// How long to wait for the estimate of a probe query. Quotes get
+    /// suggested a slippage tolerance without accounting for the price impact
+    /// if the probe takes longer than that.
+    pub fn probe_timeout(&self) -> Duration {
+        self.probe_timeout
+    }
+
+    /// Suggests a slippage tolerance in basis points for an estimate and the
+    /// (optional) estimate of its probe query.
+    pub fn suggest(
+        &self,
+        query: &Query,
+        estimate: &Estimate,
+        probe: Option<(&Query, &Estimate)>,
+    ) -> u32 {
+        let spread = f64::from(estimate.spread_bps.unwrap_or_default()) / BPS_PER_UNIT;
+        let volatility = |token| self.native_prices.volatility(token).unwrap_or_default();
+        // The exchange rate moves with the native prices of both tokens.
+        let volatility = volatility(query.sell_token).hypot(volatility(query.buy_token));
+        let price_impact = probe
+            .map(|(probe_query, probe)| {
+                let price = estimate.price_in_buy_token_f64(query);
+                let probe_price = probe.price_in_buy_token_f64(probe_query);
+                // Assuming the price impact grows linearly with the traded
+                // amount the probe's price is `factor - 1` times worse than
+                // the price impact of the original amount.
+                ((price - probe_price) / price).max(0.)
+                    / f64::from(self.price_impact_probe_factor - 1)
+            })
+            .unwrap_or_default();
+
+        tracing::debug!(
+            spread,
+            volatility,
+            price_impact,
+            "computed slippage components"
+        );
+        // `as` saturates (and maps `NaN` to 0) so this can't overflow.
+        let bps = ((spread + volatility + price_impact) * BPS_PER_UNIT).ceil() as u32;
+        bps.clamp(self.min_bps, self.max_bps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::price_estimation::native::MockNativePriceEstimating,
+        model::order::OrderKind,
+        primitive_types::U256,
+    };
+
+    fn estimator(min_bps: u32, max_bps: u32) -> SlippageEstimator {
+        let mut native = MockNativePriceEstimating::new();
+        native.expect_estimate_native_price().never();
+        let native_prices = CachingNativePriceEstimator::new(
+            Box::new(native),
+            Duration::from_secs(600),
+            Default::default(),
+            None,
+            Default::default(),
+            1,
+            Default::default(),
+        );
+        SlippageEstimator::new(
+            Arc::new(native_prices),
+            10,
+            Duration::from_secs(1),
+            min_bps,
+            max_bps,
+        )
+    }
+
+    fn estimate(out_amount: u64, spread_bps: Option<u32>) -> Estimate {
+        Estimate {
+            out_amount: out_amount.into(),
+            spread_bps,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn builds_probe_query() {
+        let estimator = estimator(0, 10_000);
+        let query = Query {
+            in_amount: NonZeroU256::try_from(100).unwrap(),
+            kind: OrderKind::Sell,
+            ..Default::default()
+        };
+
+        let probe = estimator.probe_query(&query).unwrap();
+        assert_eq!(probe.in_amount.get(), U256::from(1_000));
+        assert_eq!(probe.kind, query.kind);
+
+        let query = Query {
+            in_amount: NonZeroU256::try_from(U256::MAX).unwrap(),
+            ..query
+        };
+        assert_eq!(estimator.probe_query(&query), None);
+    }
+
+    #[tokio::test]
+    async fn combines_spread_and_price_impact() {
+        let estimator = estimator(0, 10_000);
+        let query = Query {
+            in_amount: NonZeroU256::try_from(1_000).unwrap(),
+            kind: OrderKind::Sell,
+            ..Default::default()
+        };
+        let probe_query = estimator.probe_query(&query).unwrap();
+
+        // Without any signals there is nothing to suggest.
+        assert_eq!(estimator.suggest(&query, &estimate(2_000, None), None), 0);
+
+        // 10x the amount results in a 9% worse price, so the original amount
+        // is assumed to have a 1% price impact.
+        let probe = estimate(18_200, None);
+        assert_eq!(
+            estimator.suggest(
+                &query,
+                &estimate(2_000, Some(50)),
+                Some((&probe_query, &probe))
+            ),
+            150
+        );
+
+        // Probes with better prices don't reduce the suggestion.
+        let probe = estimate(30_000, None);
+        assert_eq!(
+            estimator.suggest(
+                &query,
+                &estimate(2_000, Some(50)),
+                Some((&probe_query, &probe))
+            ),
+            50
+        );
+    }
+
+    #[tokio::test]
+    async fn clamps_suggestion() {
+        let estimator = estimator(10, 100);
+        let query = Query {
+            in_amount: NonZeroU256::try_from(1_000).unwrap(),
+            kind: OrderKind::Sell,
+            ..Default::default()
+        };
+
+        assert_eq!(estimator.suggest(&query, &estimate(2_000, None), None), 10);
+        assert_eq!(
+            estimator.suggest(&query, &estimate(2_000, Some(500)), None),
+            100
+        );
+    }
+}
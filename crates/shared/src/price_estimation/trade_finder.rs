@@ -90,6 +90,8 @@ impl Inner {
             solver: quote.solver,
             verified: false,
             execution: quote.execution,
+            spread_bps: None,
+            simulation_trace: None,
         })
     }
 }
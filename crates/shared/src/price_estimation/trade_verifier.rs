@@ -26,7 +26,11 @@ use {
         support::{AnyoneAuthenticator, Solver, Spardose, Trader},
     },
     ethcontract::{Bytes, H160, U256, tokens::Tokenize},
-    ethrpc::{Web3, block_stream::CurrentBlockWatcher, extensions::StateOverride},
+    ethrpc::{
+        Web3,
+        block_stream::CurrentBlockWatcher,
+        extensions::{CallFrame, DebugNamespace, StateOverride},
+    },
     model::{
         DomainSeparator,
         order::{BUY_ETH_ADDRESS, OrderData, OrderKind},
@@ -38,7 +42,10 @@ use {
         nonzero::U256 as NonZeroU256,
     },
     std::{collections::HashMap, sync::Arc},
-    web3::{ethabi::Token, types::CallRequest},
+    web3::{
+        ethabi::Token,
+        types::{BlockNumber, CallRequest},
+    },
 };
 
 #[async_trait::async_trait]
@@ -66,6 +73,9 @@ pub struct TradeVerifier {
     native_token: H160,
     quote_inaccuracy_limit: BigRational,
     domain_separator: DomainSeparator,
+    /// Whether simulations of trades with user interactions get traced, for
+    /// example to report how every hook got executed.
+    trace_interactions: bool,
 }
 
 impl TradeVerifier {
@@ -83,6 +93,7 @@ impl TradeVerifier {
         settlement: H160,
         native_token: H160,
         quote_inaccuracy_limit: BigDecimal,
+        trace_interactions: bool,
     ) -> Result<Self> {
         let settlement_contract = GPv2Settlement::at(&web3, settlement);
         let domain_separator =
@@ -97,6 +108,7 @@ impl TradeVerifier {
             quote_inaccuracy_limit: big_decimal_to_big_rational(&quote_inaccuracy_limit),
             web3,
             domain_separator,
+            trace_interactions,
         })
     }
 
@@ -106,6 +118,7 @@ impl TradeVerifier {
         mut verification: Verification,
         trade: &TradeKind,
         out_amount: &U256,
+        simulation_trace: &mut Option<CallFrame>,
     ) -> Result<Estimate, Error> {
         let start = std::time::Instant::now();
 
@@ -181,10 +194,13 @@ impl TradeVerifier {
             ..Default::default()
         };
 
-        let output = self
-            .simulator
-            .simulate(call, overrides, Some(block.number))
-            .await
+        let simulation =
+            self.simulator
+                .simulate(call.clone(), overrides.clone(), Some(block.number));
+        let trace = self.trace(&verification, call, overrides, block.number);
+        let (output, trace) = futures::join!(simulation, trace);
+        *simulation_trace = trace;
+        let output = output
             .context("failed to simulate quote")
             .map_err(Error::SimulationFailed);
 
@@ -209,6 +225,8 @@ impl TradeVerifier {
                         pre_interactions: map_interactions_data(&trade.pre_interactions()),
                         jit_orders: trade.jit_orders(),
                     },
+                    spread_bps: None,
+                    simulation_trace: None,
                 };
                 tracing::warn!(
                     ?estimate,
@@ -268,6 +286,30 @@ impl TradeVerifier {
         ensure_quote_accuracy(&self.quote_inaccuracy_limit, query, trade, &summary)
     }
 
+    /// Traces the simulation of a trade with user interactions. Returns `None`
+    /// if tracing is disabled, there are no user interactions or the
+    /// simulation could not be traced.
+    async fn trace(
+        &self,
+        verification: &Verification,
+        call: CallRequest,
+        overrides: HashMap<H160, StateOverride>,
+        block: u64,
+    ) -> Option<CallFrame> {
+        if !self.trace_interactions
+            || (verification.pre_interactions.is_empty()
+                && verification.post_interactions.is_empty())
+        {
+            return None;
+        }
+        self.web3
+            .debug()
+            .call_frames(call, BlockNumber::Number(block.into()).into(), overrides)
+            .await
+            .inspect_err(|err| tracing::debug!(?err, "failed to trace quote simulation"))
+            .ok()
+    }
+
     /// Configures all the state overrides that are needed to mock the given
     /// trade.
     async fn prepare_state_overrides(
@@ -410,11 +452,21 @@ impl TradeVerifying for TradeVerifier {
                 &query.kind,
             )
             .context("failed to compute trade out amount")?;
-        match self
-            .verify_inner(query, verification.clone(), &trade, &out_amount)
-            .await
-        {
-            Ok(verified) => Ok(verified),
+        let mut simulation_trace = None;
+        let result = self
+            .verify_inner(
+                query,
+                verification.clone(),
+                &trade,
+                &out_amount,
+                &mut simulation_trace,
+            )
+            .await;
+        match result {
+            Ok(verified) => Ok(Estimate {
+                simulation_trace,
+                ..verified
+            }),
             Err(Error::SimulationFailed(err)) => match trade.gas_estimate() {
                 Some(gas) => {
                     let estimate = Estimate {
@@ -427,6 +479,8 @@ impl TradeVerifying for TradeVerifier {
                             pre_interactions: map_interactions_data(&trade.pre_interactions()),
                             jit_orders: trade.jit_orders(),
                         },
+                        spread_bps: None,
+                        simulation_trace,
                     };
                     tracing::warn!(
                         ?err,
@@ -821,6 +875,8 @@ fn ensure_quote_accuracy(
             pre_interactions: map_interactions_data(&trade.pre_interactions()),
             jit_orders: trade.jit_orders(),
         },
+        spread_bps: None,
+        simulation_trace: None,
     })
 }
 
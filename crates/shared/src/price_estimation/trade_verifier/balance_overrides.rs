@@ -6,7 +6,7 @@ use {
     anyhow::Context as _,
     cached::{Cached, SizedCache},
     ethcontract::{Address, H256, U256},
-    ethrpc::extensions::StateOverride,
+    ethrpc::{Web3, extensions::StateOverride},
     maplit::hashmap,
     std::{
         collections::HashMap,
@@ -28,7 +28,10 @@ pub struct Arguments {
     /// The expected format is a comma separated list of `${ADDR}@${SLOT}`,
     /// where `ADDR` is the token address and `SLOT` is the Solidity storage
     /// slot for the balances mapping. For example for WETH:
-    /// `0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2@3`.
+    /// `0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2@3`. Other storage layouts
+    /// can be configured as `SoladyMapping(${ADDR})`,
+    /// `VyperMapping(${ADDR}@${SLOT})` and
+    /// `SolidityPackedMapping(${ADDR}@${SLOT}+${OFFSET})`.
     #[clap(long, env, default_value_t)]
     pub quote_token_balance_overrides: TokenConfiguration,
 
@@ -43,6 +46,12 @@ pub struct Arguments {
     #[clap(long, env, action = clap::ArgAction::Set, default_value = "60")]
     pub quote_autodetect_token_balance_overrides_probing_depth: u8,
 
+    /// Enable detecting token balance overrides by tracing `balanceOf` calls
+    /// for tokens whose balances aren't found by probing storage slots. This
+    /// requires the simulation node to support `debug_traceCall`.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value_t)]
+    pub quote_autodetect_token_balance_overrides_tracing: bool,
+
     /// Controls for how many tokens we store the result of the automatic
     /// balance override detection before evicting less used entries.
     #[clap(long, env, action = clap::ArgAction::Set, default_value = "1000")]
@@ -51,7 +60,11 @@ pub struct Arguments {
 
 impl Arguments {
     /// Creates a balance overrides instance from the current configuration.
-    pub fn init(&self, simulator: Arc<dyn CodeSimulating>) -> Arc<dyn BalanceOverriding> {
+    pub fn init(
+        &self,
+        simulator: Arc<dyn CodeSimulating>,
+        web3: &Web3,
+    ) -> Arc<dyn BalanceOverriding> {
         Arc::new(BalanceOverrides {
             hardcoded: self.quote_token_balance_overrides.0.clone(),
             detector: self.quote_autodetect_token_balance_overrides.then(|| {
@@ -59,6 +72,8 @@ impl Arguments {
                     Detector::new(
                         simulator,
                         self.quote_autodetect_token_balance_overrides_probing_depth,
+                        self.quote_autodetect_token_balance_overrides_tracing
+                            .then(|| web3.clone()),
                     ),
                     Mutex::new(SizedCache::with_size(
                         self.quote_autodetect_token_balance_overrides_cache_size,
@@ -75,6 +90,7 @@ impl Display for Arguments {
             quote_token_balance_overrides,
             quote_autodetect_token_balance_overrides,
             quote_autodetect_token_balance_overrides_probing_depth,
+            quote_autodetect_token_balance_overrides_tracing,
             quote_autodetect_token_balance_overrides_cache_size,
         } = self;
 
@@ -93,6 +109,11 @@ impl Display for Arguments {
             "quote_autodetect_token_balance_overrides_probing_depth: {:?}",
             quote_autodetect_token_balance_overrides_probing_depth
         )?;
+        writeln!(
+            f,
+            "quote_autodetect_token_balance_overrides_tracing: {:?}",
+            quote_autodetect_token_balance_overrides_tracing
+        )?;
         writeln!(
             f,
             "quote_autodetect_token_balance_overrides_cache_size: {:?}",
@@ -113,6 +134,10 @@ impl Display for TokenConfiguration {
             |f: &mut Formatter, (addr, strategy): (&Address, &Strategy)| match strategy {
                 Strategy::SolidityMapping { slot } => write!(f, "{addr:?}@{slot}"),
                 Strategy::SoladyMapping => write!(f, "SoladyMapping({addr:?})"),
+                Strategy::VyperMapping { slot } => write!(f, "VyperMapping({addr:?}@{slot})"),
+                Strategy::SolidityPackedMapping { slot, offset } => {
+                    write!(f, "SolidityPackedMapping({addr:?}@{slot}+{offset})")
+                }
             };
 
         let mut entries = self.0.iter();
@@ -139,18 +164,58 @@ impl FromStr for TokenConfiguration {
             return Ok(Self::default());
         }
 
+        fn strip_strategy<'a>(part: &'a str, name: &str) -> Option<&'a str> {
+            part.strip_prefix(name)?
+                .strip_prefix('(')?
+                .strip_suffix(')')
+        }
+        // Slots are formatted in decimal, so parse them as such (`U256`'s
+        // `FromStr` implementation expects hex).
+        let parse_slot =
+            |slot: &str| U256::from_dec_str(slot).with_context(|| format!("invalid slot {slot:?}"));
+
         let entries = s
             .split(',')
             .map(|part| -> Result<_, Self::Err> {
-                let (addr, slot) = part
-                    .split_once('@')
-                    .context("expected {addr}@{slot} format")?;
-                Ok((
-                    addr.parse()?,
-                    Strategy::SolidityMapping {
-                        slot: slot.parse()?,
-                    },
-                ))
+                let (addr, strategy) = if let Some(addr) = strip_strategy(part, "SoladyMapping") {
+                    (addr, Strategy::SoladyMapping)
+                } else if let Some(entry) = strip_strategy(part, "VyperMapping") {
+                    let (addr, slot) = entry
+                        .split_once('@')
+                        .context("expected VyperMapping({addr}@{slot}) format")?;
+                    (
+                        addr,
+                        Strategy::VyperMapping {
+                            slot: parse_slot(slot)?,
+                        },
+                    )
+                } else if let Some(entry) = strip_strategy(part, "SolidityPackedMapping") {
+                    let (addr, slot, offset) = entry
+                        .split_once('@')
+                        .and_then(|(addr, rest)| {
+                            let (slot, offset) = rest.split_once('+')?;
+                            Some((addr, slot, offset))
+                        })
+                        .context("expected SolidityPackedMapping({addr}@{slot}+{offset}) format")?;
+                    (
+                        addr,
+                        Strategy::SolidityPackedMapping {
+                            slot: parse_slot(slot)?,
+                            offset: offset.parse()?,
+                        },
+                    )
+                } else {
+                    let (addr, slot) = part
+                        .split_once('@')
+                        .context("expected {addr}@{slot} format")?;
+                    (
+                        addr,
+                        Strategy::SolidityMapping {
+                            slot: parse_slot(slot)?,
+                        },
+                    )
+                };
+                Ok((addr.parse()?, strategy))
             })
             .collect::<Result<_, _>>()?;
         Ok(Self(entries))
@@ -184,14 +249,32 @@ pub enum Strategy {
     /// form `mapping(address holder => uint256 amount)`.
     ///
     /// The strategy is configured with the storage slot [^1] of the mapping.
+    /// This also covers mappings in namespaced storage [^2], such as the ones
+    /// of OpenZeppelin's upgradeable contracts, whose slots are derived from
+    /// the namespace.
     ///
     /// [^1]: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html#mappings-and-dynamic-arrays>
+    /// [^2]: <https://eips.ethereum.org/EIPS/eip-7201>
     SolidityMapping { slot: U256 },
     /// Strategy computing storage slot for balances based on the Solady library
     /// [^1].
     ///
     /// [^1]: <https://github.com/Vectorized/solady/blob/6122858a3aed96ee9493b99f70a245237681a95f/src/tokens/ERC20.sol#L75-L81>
     SoladyMapping,
+    /// Balance override strategy for tokens whose balances are stored in a
+    /// Vyper `HashMap[address, uint256]`. Unlike Solidity, Vyper computes the
+    /// storage slot of a key by hashing the slot of the mapping first [^1].
+    ///
+    /// [^1]: <https://docs.vyperlang.org/en/stable/scoping-and-declarations.html#storage-layout>
+    VyperMapping { slot: U256 },
+    /// Balance override strategy for tokens whose balances are packed together
+    /// with other data in the values of a Solidity mapping, such as the
+    /// balances of tokens storing them as `uint128` next to some flags.
+    ///
+    /// The balance is stored starting at bit `offset` of the value. Overriding
+    /// the balance clears the other data stored for the holder, and amounts
+    /// exceeding the size of the balance field get truncated.
+    SolidityPackedMapping { slot: U256, offset: u8 },
 }
 
 impl Strategy {
@@ -199,7 +282,7 @@ impl Strategy {
     /// holder and amount.
     fn state_override(&self, holder: &Address, amount: &U256) -> (H256, H256) {
         let key = match self {
-            Self::SolidityMapping { slot } => {
+            Self::SolidityMapping { slot } | Self::SolidityPackedMapping { slot, .. } => {
                 let mut buf = [0; 64];
                 buf[12..32].copy_from_slice(holder.as_fixed_bytes());
                 slot.to_big_endian(&mut buf[32..64]);
@@ -211,9 +294,19 @@ impl Strategy {
                 buf[28..32].copy_from_slice(&[0x87, 0xa2, 0x11, 0xa2]);
                 H256(signing::keccak256(&buf))
             }
+            Self::VyperMapping { slot } => {
+                let mut buf = [0; 64];
+                slot.to_big_endian(&mut buf[0..32]);
+                buf[44..64].copy_from_slice(holder.as_fixed_bytes());
+                H256(signing::keccak256(&buf))
+            }
         };
 
         let value = {
+            let amount = match self {
+                Self::SolidityPackedMapping { offset, .. } => *amount << usize::from(*offset),
+                _ => *amount,
+            };
             let mut buf = [0; 32];
             amount.to_big_endian(&mut buf);
             H256(buf)
@@ -350,6 +443,47 @@ mod tests {
         // ```
     }
 
+    #[test]
+    fn token_configuration_round_trip() {
+        let config = TokenConfiguration(hashmap! {
+            addr!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2") => Strategy::SolidityMapping {
+                slot: U256::from(3),
+            },
+            addr!("0000000000c5dc95539589fbd24be07c6c14eca4") => Strategy::SoladyMapping,
+            addr!("D533a949740bb3306d119CC777fa900bA034cd52") => Strategy::VyperMapping {
+                slot: U256::from(14),
+            },
+            addr!("6B175474E89094C44Da98b954EedeAC495271d0F") => Strategy::SolidityPackedMapping {
+                slot: U256::from_dec_str(
+                    "52961213592916848040427766522424519064938043609474785393043815034016837316352",
+                )
+                .unwrap(),
+                offset: 128,
+            },
+        });
+
+        let parsed = config.to_string().parse::<TokenConfiguration>().unwrap();
+        assert_eq!(parsed.0, config.0);
+
+        let parsed = "0x0000000000000000000000000000000000000001@10"
+            .parse::<TokenConfiguration>()
+            .unwrap();
+        assert_eq!(
+            parsed.0,
+            hashmap! {
+                addr!("0000000000000000000000000000000000000001") => Strategy::SolidityMapping {
+                    slot: U256::from(10),
+                },
+            }
+        );
+        assert!("".parse::<TokenConfiguration>().unwrap().0.is_empty());
+        assert!(
+            "VyperMapping(0x0000000000000000000000000000000000000001)"
+                .parse::<TokenConfiguration>()
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn balance_overrides_none_for_unknown_tokens() {
         let balance_overrides = BalanceOverrides::default();
@@ -365,6 +499,58 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn balance_override_computation_vyper_and_packed() {
+        let balance_overrides = BalanceOverrides {
+            hardcoded: hashmap! {
+                addr!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490") => Strategy::VyperMapping {
+                    slot: U256::from(3),
+                },
+                addr!("DEf1CA1fb7FBcDC777520aa7f396b4E015F497aB") => Strategy::SolidityPackedMapping {
+                    slot: U256::from(0),
+                    offset: 128,
+                },
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            balance_overrides
+                .state_override(BalanceOverrideRequest {
+                    token: addr!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490"),
+                    holder: addr!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+                    amount: 0x42_u64.into(),
+                })
+                .await,
+            Some(StateOverride {
+                state_diff: Some(hashmap! {
+                    H256(hex!("a978e450803b26c0afa492f4cfabbc1225f68116353fb514024a35211561287f")) =>
+                        H256(hex!("0000000000000000000000000000000000000000000000000000000000000042")),
+                }),
+                ..Default::default()
+            }),
+        );
+
+        // The packed balance is stored in the same slot as with a plain
+        // Solidity mapping, but shifted by the offset.
+        assert_eq!(
+            balance_overrides
+                .state_override(BalanceOverrideRequest {
+                    token: addr!("DEf1CA1fb7FBcDC777520aa7f396b4E015F497aB"),
+                    holder: addr!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+                    amount: 0x42_u64.into(),
+                })
+                .await,
+            Some(StateOverride {
+                state_diff: Some(hashmap! {
+                    H256(hex!("fca351f4d96129454cfc8ef7930b638ac71fea35eb69ee3b8d959496beb04a33")) =>
+                        H256(hex!("0000000000000000000000000000004200000000000000000000000000000000")),
+                }),
+                ..Default::default()
+            }),
+        );
+    }
+
     #[tokio::test]
     async fn balance_override_computation_solady() {
         let balance_overrides = BalanceOverrides {
@@ -3,7 +3,10 @@ use {
     crate::code_simulation::{CodeSimulating, SimulationError},
     contracts::{ERC20, dummy_contract},
     ethcontract::{Address, H256, U256},
-    ethrpc::extensions::StateOverride,
+    ethrpc::{
+        Web3,
+        extensions::{DebugNamespace, StateOverride, StructLogs},
+    },
     maplit::hashmap,
     std::{
         collections::HashMap,
@@ -11,12 +14,18 @@ use {
         sync::Arc,
     },
     thiserror::Error,
-    web3::{signing::keccak256, types::CallRequest},
+    web3::{
+        signing::keccak256,
+        types::{BlockNumber, CallRequest},
+    },
 };
 
 /// A heuristic balance override detector based on `eth_call` simulations.
 ///
-/// This has the exact same node requirements as trade verification.
+/// This has the exact same node requirements as trade verification. When
+/// configured with a tracing node, tokens whose balances can't be found by
+/// probing are additionally detected by tracing the storage slots read by
+/// `balanceOf`.
 #[derive(Clone)]
 pub struct Detector(Arc<Inner>);
 
@@ -28,6 +37,8 @@ pub struct Inner {
     strategies: Vec<StrategyHelper>,
     /// overrides for all tested strategies
     state_overrides: HashMap<H256, H256>,
+    /// node used for tracing `balanceOf` calls, if enabled
+    tracer: Option<Web3>,
 }
 
 impl std::ops::Deref for Detector {
@@ -40,7 +51,11 @@ impl std::ops::Deref for Detector {
 
 impl Detector {
     /// Creates a new balance override detector.
-    pub fn new(simulator: Arc<dyn CodeSimulating>, probing_depth: u8) -> Self {
+    pub fn new(
+        simulator: Arc<dyn CodeSimulating>,
+        probing_depth: u8,
+        tracer: Option<Web3>,
+    ) -> Self {
         let holder = {
             // On a technical note, Ethereum public addresses are, for the most
             // part, generated by taking the 20 last bytes of a Keccak-256 hash (for
@@ -93,6 +108,7 @@ impl Detector {
             holder,
             strategies,
             state_overrides,
+            tracer,
         }))
     }
 
@@ -100,31 +116,200 @@ impl Detector {
     /// Returns an `Err` if it cannot detect the strategy or an internal
     /// simulation fails.
     pub async fn detect(&self, token: Address) -> Result<Strategy, DetectionError> {
-        let token = dummy_contract!(ERC20, token);
-        let call = CallRequest {
-            to: Some(token.address()),
-            data: token.methods().balance_of(self.holder).m.tx.data,
-            ..Default::default()
+        match self.probe(token).await {
+            Err(DetectionError::NotFound) => match &self.tracer {
+                Some(tracer) => self.trace(tracer, token).await,
+                None => Err(DetectionError::NotFound),
+            },
+            result => result,
+        }
+    }
+
+    /// Detects the strategy by overriding all probed storage slots at once,
+    /// each with a different balance.
+    async fn probe(&self, token: Address) -> Result<Strategy, DetectionError> {
+        let balance = self.balance_of(token, self.state_overrides.clone()).await?;
+
+        self.strategies
+            .iter()
+            .find_map(|helper| (helper.balance == balance).then_some(helper.strategy.clone()))
+            .ok_or(DetectionError::NotFound)
+    }
+
+    /// Detects the strategy by tracing the storage slots that `balanceOf`
+    /// reads, and checking which of them holds the balance.
+    async fn trace(&self, tracer: &Web3, token: Address) -> Result<Strategy, DetectionError> {
+        let trace = tracer
+            .debug()
+            .call(self.balance_of_call(token), BlockNumber::Latest.into())
+            .await
+            .map_err(DetectionError::Tracing)?;
+
+        for strategy in candidates(&trace, token, self.holder) {
+            if let Some(strategy) = self.verify(token, strategy).await? {
+                return Ok(strategy);
+            }
+        }
+        Err(DetectionError::NotFound)
+    }
+
+    /// Verifies that overriding the storage slot of a candidate strategy
+    /// changes the balance of the holder. The slot is first filled with a
+    /// pattern of distinct bytes in order to find where in the slot the
+    /// balance is stored, so that packed balances are detected as well.
+    async fn verify(
+        &self,
+        token: Address,
+        strategy: Strategy,
+    ) -> Result<Option<Strategy>, DetectionError> {
+        let (key, _) = strategy.state_override(&self.holder, &U256::zero());
+        let Some(balance) = self
+            .try_balance_of(token, hashmap! { key => H256(PACKING_PATTERN) })
+            .await?
+        else {
+            return Ok(None);
         };
+
+        // The least significant byte of the balance tells how many bytes of
+        // the slot are below the balance.
+        let strategy = match (strategy, 32_u8.checked_sub(balance.byte(0))) {
+            (strategy, Some(0)) => strategy,
+            (Strategy::SolidityMapping { slot }, Some(bytes @ 1..32)) => {
+                Strategy::SolidityPackedMapping {
+                    slot,
+                    offset: bytes * 8,
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        let (key, value) = strategy.state_override(&self.holder, &VERIFICATION_BALANCE.into());
+        let balance = self
+            .try_balance_of(token, hashmap! { key => value })
+            .await?;
+        Ok((balance == Some(VERIFICATION_BALANCE.into())).then_some(strategy))
+    }
+
+    /// Returns the balance of the holder with the specified storage overrides,
+    /// or `None` if the call reverts.
+    async fn try_balance_of(
+        &self,
+        token: Address,
+        state_diff: HashMap<H256, H256>,
+    ) -> Result<Option<U256>, DetectionError> {
+        match self.balance_of(token, state_diff).await {
+            Ok(balance) => Ok(Some(balance)),
+            Err(DetectionError::Simulation(SimulationError::Revert(_))) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn balance_of(
+        &self,
+        token: Address,
+        state_diff: HashMap<H256, H256>,
+    ) -> Result<U256, DetectionError> {
         let overrides = hashmap! {
-            token.address() => StateOverride {
-                state_diff: Some(self.state_overrides.clone()),
+            token => StateOverride {
+                state_diff: Some(state_diff),
                 ..Default::default()
             },
         };
 
-        let output = self.simulator.simulate(call, overrides, None).await?;
-        let balance = (output.len() == 32)
+        let output = self
+            .simulator
+            .simulate(self.balance_of_call(token), overrides, None)
+            .await?;
+        (output.len() == 32)
             .then(|| U256::from_big_endian(&output))
-            .ok_or(DetectionError::Decode)?;
+            .ok_or(DetectionError::Decode)
+    }
 
-        self.strategies
-            .iter()
-            .find_map(|helper| (helper.balance == balance).then_some(helper.strategy.clone()))
-            .ok_or(DetectionError::NotFound)
+    fn balance_of_call(&self, token: Address) -> CallRequest {
+        let token = dummy_contract!(ERC20, token);
+        CallRequest {
+            to: Some(token.address()),
+            data: token.methods().balance_of(self.holder).m.tx.data,
+            ..Default::default()
+        }
     }
 }
 
+/// Returns the candidate strategies for the storage slots of the token that
+/// were read in the trace, most recently read slots first. Only slots whose
+/// keys are hashes of the holder in one of the known mapping layouts are
+/// considered. Slots are attributed to the contract whose storage they belong
+/// to, so reads by implementations of proxies are included.
+fn candidates(trace: &StructLogs, token: Address, holder: Address) -> Vec<Strategy> {
+    let mut preimages = HashMap::<H256, &[u8]>::new();
+    let mut reads = Vec::<H256>::new();
+    // storage contexts of the calls at every depth
+    let mut contexts = Vec::<Address>::new();
+    let mut hashing = None;
+
+    for step in &trace.struct_logs {
+        // the hash is on top of the stack after executing the hashing step
+        if let (Some(preimage), Some(hash)) = (hashing.take(), step.stack.last()) {
+            preimages.insert(word(*hash), preimage);
+        }
+
+        // Entering a call without a known storage context means that it was
+        // delegated, and it therefore keeps the storage context of its caller.
+        contexts.truncate(step.depth);
+        let caller = contexts.last().copied().unwrap_or(token);
+        contexts.resize(step.depth, caller);
+        let context = contexts.last().copied().unwrap_or(token);
+
+        let stack = |n: usize| step.stack.iter().rev().nth(n).copied();
+        match step.op.as_str() {
+            "KECCAK256" | "SHA3" => {
+                let (Some(offset), Some(size)) = (stack(0), stack(1)) else {
+                    continue;
+                };
+                let (offset, size) = (offset.low_u64() as usize, size.low_u64() as usize);
+                hashing = step.memory.get(offset..offset.saturating_add(size));
+            }
+            "SLOAD" if context == token => reads.extend(stack(0).map(word)),
+            "CALL" | "STATICCALL" => {
+                if let Some(to) = stack(1) {
+                    contexts.push(Address::from(word(to)));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let mut key = [0; 32];
+    key[12..].copy_from_slice(holder.as_bytes());
+    let (solady, _) = Strategy::SoladyMapping.state_override(&holder, &U256::zero());
+    let mut candidates = Vec::new();
+    for slot in reads.iter().rev() {
+        let Some(preimage) = preimages.get(slot) else {
+            continue;
+        };
+        let strategy = match preimage.len() {
+            64 if preimage[..32] == key => Strategy::SolidityMapping {
+                slot: U256::from_big_endian(&preimage[32..]),
+            },
+            64 if preimage[32..] == key => Strategy::VyperMapping {
+                slot: U256::from_big_endian(&preimage[..32]),
+            },
+            32 if *slot == solady => Strategy::SoladyMapping,
+            _ => continue,
+        };
+        if !candidates.contains(&strategy) {
+            candidates.push(strategy);
+        }
+    }
+    candidates
+}
+
+fn word(value: U256) -> H256 {
+    let mut buf = [0; 32];
+    value.to_big_endian(&mut buf);
+    H256(buf)
+}
+
 /// Contains all the information we need to determine which state override
 /// was successful.
 struct StrategyHelper {
@@ -150,6 +335,16 @@ impl StrategyHelper {
     }
 }
 
+/// Storage value with distinct bytes, used for finding where in a storage slot
+/// a balance is stored. The least significant byte is `0x20`.
+const PACKING_PATTERN: [u8; 32] = [
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10,
+    0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20,
+];
+
+/// Balance used for verifying traced strategies.
+const VERIFICATION_BALANCE: u64 = 0x1337_c0de;
+
 // <https://github.com/OpenZeppelin/openzeppelin-contracts-upgradeable/blob/master/contracts/token/ERC20/ERC20Upgradeable.sol#L43-L44>
 const OPEN_ZEPPELIN_ERC20_UPGRADEABLE: &str =
     "52c63247e1f47db19d5ce0460030c497f067ca4cebf71ba98eeadabe20bace00";
@@ -171,11 +366,104 @@ pub enum DetectionError {
     Decode,
     #[error(transparent)]
     Simulation(#[from] SimulationError),
+    #[error("unable to trace balance call")]
+    Tracing(#[source] web3::Error),
 }
 
 #[cfg(test)]
 mod tests {
-    use {super::*, ethrpc::create_env_test_transport, web3::Web3};
+    use {
+        super::*,
+        ethrpc::{create_env_test_transport, extensions::StructLog},
+        web3::Web3,
+    };
+
+    fn step(op: &str, depth: usize, stack: &[U256], memory: &[u8]) -> StructLog {
+        StructLog {
+            op: op.to_string(),
+            depth,
+            stack: stack.to_vec(),
+            memory: memory.to_vec(),
+        }
+    }
+
+    fn uint(word: H256) -> U256 {
+        U256::from_big_endian(word.as_bytes())
+    }
+
+    #[test]
+    fn detects_candidates_from_trace() {
+        let token = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+        let holder = Address::repeat_byte(3);
+        let mut holder_word = [0; 32];
+        holder_word[12..].copy_from_slice(holder.as_bytes());
+        let slot = |slot: u64| {
+            let mut buf = [0; 32];
+            U256::from(slot).to_big_endian(&mut buf);
+            buf
+        };
+        let key = |strategy: Strategy| uint(strategy.state_override(&holder, &U256::zero()).0);
+
+        // A proxy delegating to an implementation that stores balances in a
+        // Solidity mapping, and which calls into another contract that reads
+        // a Vyper mapping.
+        let solidity = key(Strategy::SolidityMapping { slot: 51.into() });
+        let vyper = key(Strategy::VyperMapping { slot: 3.into() });
+        let trace = StructLogs {
+            struct_logs: vec![
+                step("SLOAD", 1, &[0.into()], &[]),
+                step("DELEGATECALL", 1, &[], &[]),
+                step(
+                    "KECCAK256",
+                    2,
+                    &[0x40.into(), 0.into()],
+                    &[holder_word, slot(51)].concat(),
+                ),
+                step("SLOAD", 2, &[solidity], &[]),
+                step(
+                    "STATICCALL",
+                    2,
+                    &[0.into(), uint(H256::from(other)), 0.into()],
+                    &[],
+                ),
+                step(
+                    "KECCAK256",
+                    3,
+                    &[0x40.into(), 0.into()],
+                    &[slot(3), holder_word].concat(),
+                ),
+                step("SLOAD", 3, &[vyper], &[]),
+                step("RETURN", 3, &[], &[]),
+                step("RETURN", 2, &[], &[]),
+                step("RETURN", 1, &[], &[]),
+            ],
+        };
+        assert_eq!(
+            candidates(&trace, token, holder),
+            vec![Strategy::SolidityMapping { slot: 51.into() }]
+        );
+        assert_eq!(
+            candidates(&trace, other, holder),
+            vec![Strategy::VyperMapping { slot: 3.into() }]
+        );
+
+        // Solady computes the slot from the holder and a seed.
+        let solady = key(Strategy::SoladyMapping);
+        let mut preimage = [0; 32];
+        preimage[..20].copy_from_slice(holder.as_bytes());
+        preimage[28..].copy_from_slice(&[0x87, 0xa2, 0x11, 0xa2]);
+        let trace = StructLogs {
+            struct_logs: vec![
+                step("KECCAK256", 1, &[0x20.into(), 0.into()], &preimage),
+                step("SLOAD", 1, &[solady], &[]),
+            ],
+        };
+        assert_eq!(
+            candidates(&trace, token, holder),
+            vec![Strategy::SoladyMapping]
+        );
+    }
 
     /// Tests that we can detect storage slots by probing the first
     /// n slots or by checking hardcoded known slots.
@@ -183,7 +471,7 @@ mod tests {
     #[ignore]
     #[tokio::test]
     async fn detects_storage_slots_mainnet() {
-        let detector = Detector::new(Arc::new(Web3::new(create_env_test_transport())), 60);
+        let detector = Detector::new(Arc::new(Web3::new(create_env_test_transport())), 60, None);
 
         let storage = detector
             .detect(addr!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"))
@@ -215,7 +503,7 @@ mod tests {
     #[ignore]
     #[tokio::test]
     async fn detects_storage_slots_arbitrum() {
-        let detector = Detector::new(Arc::new(Web3::new(create_env_test_transport())), 60);
+        let detector = Detector::new(Arc::new(Web3::new(create_env_test_transport())), 60, None);
 
         // all bridged tokens on arbitrum require a ton of probing
         let storage = detector
@@ -224,4 +512,29 @@ mod tests {
             .unwrap();
         assert_eq!(storage, Strategy::SolidityMapping { slot: 51.into() });
     }
+
+    /// Tests that we can detect storage layouts that can't be probed by
+    /// tracing `balanceOf` calls.
+    /// Set `NODE_URL` environment to a mainnet RPC URL supporting
+    /// `debug_traceCall`.
+    #[ignore]
+    #[tokio::test]
+    async fn detects_storage_slots_with_tracing_mainnet() {
+        let web3 = Web3::new(create_env_test_transport());
+        let detector = Detector::new(Arc::new(web3.clone()), 0, Some(web3));
+
+        // Curve's 3pool LP token is written in Vyper
+        let storage = detector
+            .detect(addr!("6c3F90f043a72FA612cbac8115EE7e52BDe6E490"))
+            .await
+            .unwrap();
+        assert!(matches!(storage, Strategy::VyperMapping { .. }));
+
+        // WETH balances are found at the same slot as with probing
+        let storage = detector
+            .detect(addr!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"))
+            .await
+            .unwrap();
+        assert_eq!(storage, Strategy::SolidityMapping { slot: 3.into() });
+    }
 }
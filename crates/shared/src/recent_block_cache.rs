@@ -29,7 +29,7 @@ use {
     anyhow::{Context, Result},
     cached::{Cached, SizedCache},
     ethcontract::BlockNumber,
-    ethrpc::block_stream::CurrentBlockWatcher,
+    ethrpc::block_stream::{CurrentBlockWatcher, MAX_REORG_DEPTH, Reorg, Reorgs},
     futures::{FutureExt, StreamExt},
     itertools::Itertools,
     prometheus::IntCounterVec,
@@ -41,6 +41,7 @@ use {
         sync::{Arc, Mutex},
         time::Duration,
     },
+    tokio::sync::broadcast::error::RecvError,
     tracing::Instrument,
 };
 
@@ -193,6 +194,36 @@ where
         self.inner.fetch(keys, block).await
     }
 
+    /// Drops cached entries of reorged blocks and refetches the recently used
+    /// entries at the new head whenever the block stream detects a reorg.
+    pub fn subscribe_to_reorgs(&self, reorgs: &Reorgs) {
+        let inner = Arc::downgrade(&self.inner);
+        let mut reorgs = reorgs.subscribe();
+        let label = self.inner.metrics_label;
+        tokio::task::spawn(
+            async move {
+                loop {
+                    let reorg = match reorgs.recv().await {
+                        Ok(reorg) => reorg,
+                        Err(RecvError::Lagged(missed)) => {
+                            tracing::warn!(missed, "missed reorg notifications");
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
+                    let Some(inner) = inner.upgrade() else {
+                        tracing::debug!("cache no longer in use; terminate reorg task");
+                        break;
+                    };
+                    if let Err(err) = inner.handle_reorg(&reorg).await {
+                        tracing::warn!(?err, "failed to update cache after reorg");
+                    }
+                }
+            }
+            .instrument(tracing::info_span!("cache_reorgs", cache = label)),
+        );
+    }
+
     fn spawn_gc_task(
         inner: std::sync::Weak<Inner<K, V, F>>,
         block_stream: CurrentBlockWatcher,
@@ -243,6 +274,21 @@ where
         Ok(())
     }
 
+    async fn handle_reorg(&self, reorg: &Reorg) -> Result<()> {
+        // Without a common ancestor none of the tracked blocks are canonical
+        // anymore, so everything that recent got reorged as well.
+        let newest_to_keep = match reorg.common_ancestor {
+            Some((common_ancestor, _)) => common_ancestor,
+            None => reorg.new_head.number.saturating_sub(MAX_REORG_DEPTH),
+        };
+        tracing::debug!(newest_to_keep, "dropping reorged blocks from cache");
+        self.mutexed
+            .lock()
+            .unwrap()
+            .remove_cached_blocks_newer_than(newest_to_keep);
+        self.update_cache_at_block(reorg.new_head.number).await
+    }
+
     async fn fetch_inner_many(&self, keys: HashSet<K>, block: Block) -> Result<Vec<V>> {
         let fetched =
             futures::future::join_all(keys.iter().map(|key| self.fetch_inner(key.clone(), block)))
@@ -455,6 +501,14 @@ where
         );
     }
 
+    fn remove_cached_blocks_newer_than(&mut self, newest_to_keep: u64) {
+        self.entries
+            .retain(|(block, _), _| *block <= newest_to_keep);
+        self.cached_most_recently_at_block
+            .retain(|_, block| *block <= newest_to_keep);
+        self.last_update_block = cmp::min(self.last_update_block, newest_to_keep);
+    }
+
     fn keys_of_recently_used_entries(&self) -> impl Iterator<Item = K> + '_ {
         self.recently_used.key_order().cloned()
     }
@@ -868,4 +922,60 @@ mod tests {
         assert!(cache.mutexed.lock().unwrap().get(key, Some(8)).is_some());
         assert!(cache.mutexed.lock().unwrap().get(key, None).is_some());
     }
+
+    #[tokio::test]
+    async fn drops_reorged_blocks() {
+        let fetcher = FakeCacheFetcher::default();
+        let values = fetcher.0.clone();
+        let block_stream = mock_single_block(BlockInfo {
+            number: 10,
+            ..Default::default()
+        });
+        let cache = RecentBlockCache::new(
+            CacheConfig {
+                number_of_blocks_to_cache: NonZeroU64::new(5).unwrap(),
+                number_of_entries_to_auto_update: NonZeroUsize::new(2).unwrap(),
+                maximum_recent_block_age: 5,
+                ..Default::default()
+            },
+            fetcher,
+            block_stream,
+            "",
+        )
+        .unwrap()
+        .inner;
+        let key = TestKey(0);
+
+        *values.lock().unwrap() = vec![TestValue::new(0, "old")];
+        for block in 8..=10 {
+            cache
+                .fetch(std::iter::once(key), Block::Number(block))
+                .await
+                .unwrap();
+        }
+
+        // Blocks 9 and 10 got replaced by a new block 10.
+        *values.lock().unwrap() = vec![TestValue::new(0, "new")];
+        cache
+            .handle_reorg(&Reorg {
+                common_ancestor: Some((8, Default::default())),
+                new_head: BlockInfo {
+                    number: 10,
+                    ..Default::default()
+                },
+            })
+            .await
+            .unwrap();
+
+        let mut mutexed = cache.mutexed.lock().unwrap();
+        assert_eq!(mutexed.get(key, Some(9)), None);
+        assert_eq!(
+            mutexed.get(key, Some(10)),
+            Some([TestValue::new(0, "new")].as_slice())
+        );
+        assert_eq!(
+            mutexed.get(key, None),
+            Some([TestValue::new(0, "new")].as_slice())
+        );
+    }
 }
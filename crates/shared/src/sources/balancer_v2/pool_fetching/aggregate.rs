@@ -3,7 +3,11 @@
 
 use {
     super::internal::InternalPoolFetching,
-    crate::{recent_block_cache::Block, sources::balancer_v2::pools::Pool},
+    crate::{
+        maintenance::Maintaining,
+        recent_block_cache::Block,
+        sources::balancer_v2::pools::Pool,
+    },
     anyhow::Result,
     ethcontract::H256,
     futures::future,
@@ -49,3 +53,20 @@ impl InternalPoolFetching for Aggregate {
         .collect())
     }
 }
+
+#[async_trait::async_trait]
+impl Maintaining for Aggregate {
+    async fn run_maintenance(&self) -> Result<()> {
+        future::try_join_all(
+            self.fetchers
+                .iter()
+                .map(|fetcher| fetcher.run_maintenance()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "BalancerPoolFetcher"
+    }
+}
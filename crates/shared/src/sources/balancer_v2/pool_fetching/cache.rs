@@ -6,12 +6,13 @@
 use {
     super::internal::InternalPoolFetching,
     crate::{
+        maintenance::Maintaining,
         recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
         sources::balancer_v2::pools::Pool,
     },
     anyhow::Result,
     ethcontract::H256,
-    ethrpc::block_stream::CurrentBlockWatcher,
+    ethrpc::block_stream::{CurrentBlockWatcher, Reorgs},
     std::{collections::HashSet, sync::Arc},
 };
 
@@ -36,10 +37,12 @@ where
         inner: Inner,
         config: CacheConfig,
         block_stream: CurrentBlockWatcher,
+        reorgs: &Reorgs,
     ) -> Result<Self> {
         let inner = Arc::new(inner);
         let fetcher = CacheFetcher(inner.clone());
         let cache = RecentBlockCache::new(config, fetcher, block_stream, "balancerv2")?;
+        cache.subscribe_to_reorgs(reorgs);
         Ok(Self { inner, cache })
     }
 }
@@ -61,6 +64,20 @@ where
     }
 }
 
+#[async_trait::async_trait]
+impl<Inner> Maintaining for Cache<Inner>
+where
+    Inner: InternalPoolFetching,
+{
+    async fn run_maintenance(&self) -> Result<()> {
+        self.inner.run_maintenance().await
+    }
+
+    fn name(&self) -> &str {
+        "BalancerPoolFetcher"
+    }
+}
+
 impl CacheKey<Pool> for H256 {
     fn first_ord() -> Self {
         H256::zero()
@@ -2,7 +2,11 @@
 //! strategies.
 
 use {
-    crate::{recent_block_cache::Block, sources::balancer_v2::pools::Pool},
+    crate::{
+        maintenance::Maintaining,
+        recent_block_cache::Block,
+        sources::balancer_v2::pools::Pool,
+    },
     anyhow::Result,
     ethcontract::H256,
     model::TokenPair,
@@ -13,8 +17,9 @@ use {
 /// fetching.
 ///
 /// This allows us to compose different inner pool fetching strategies together.
+/// Maintenance keeps the indexed pools up to date with the chain.
 #[async_trait::async_trait]
-pub trait InternalPoolFetching: Send + Sync + 'static {
+pub trait InternalPoolFetching: Maintaining + Send + Sync + 'static {
     /// Retrives all pool IDs that trade the specified pairs.
     async fn pool_ids_for_token_pairs(&self, token_pairs: HashSet<TokenPair>) -> HashSet<H256>;
 
@@ -27,6 +27,8 @@ use {
     },
     crate::{
         ethrpc::{Web3, Web3Transport},
+        event_handling::indexing_start_block,
+        maintenance::Maintaining,
         recent_block_cache::{Block, CacheConfig},
         token_info::TokenInfoFetching,
     },
@@ -48,7 +50,7 @@ use {
         BalancerV2WeightedPoolFactoryV4,
     },
     ethcontract::{BlockId, H160, H256, Instance, dyns::DynInstance},
-    ethrpc::block_stream::{BlockRetrieving, CurrentBlockWatcher},
+    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving, CurrentBlockWatcher, Reorgs},
     model::TokenPair,
     reqwest::{Client, Url},
     std::{
@@ -292,32 +294,47 @@ impl BalancerContracts {
 }
 
 impl BalancerPoolFetcher {
+    /// Creates a new Balancer pool fetcher. Existing pools are initialized
+    /// from the subgraph if a URL is specified, otherwise they are discovered
+    /// by indexing the `PoolCreated` events of each factory from its
+    /// deployment.
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
-        subgraph_url: &Url,
+        subgraph_url: Option<&Url>,
         block_retriever: Arc<dyn BlockRetrieving>,
         token_infos: Arc<dyn TokenInfoFetching>,
         config: CacheConfig,
         block_stream: CurrentBlockWatcher,
+        reorgs: &Reorgs,
         client: Client,
         web3: Web3,
         contracts: &BalancerContracts,
         deny_listed_pool_ids: Vec<H256>,
     ) -> Result<Self> {
-        let pool_initializer = BalancerSubgraphClient::from_subgraph_url(subgraph_url, client)?;
+        let registered_pools = match subgraph_url {
+            Some(subgraph_url) => Some(
+                BalancerSubgraphClient::from_subgraph_url(subgraph_url, client)?
+                    .initialize_pools()
+                    .await?,
+            ),
+            None => None,
+        };
         let web3 = ethrpc::instrumented::instrument_with_label(&web3, "balancerV2".into());
-        let fetcher = Arc::new(Cache::new(
-            create_aggregate_pool_fetcher(
-                web3,
-                pool_initializer,
-                block_retriever,
-                token_infos,
-                contracts,
-            )
-            .await?,
-            config,
-            block_stream,
-        )?);
+        let aggregate = create_aggregate_pool_fetcher(
+            web3,
+            registered_pools,
+            block_retriever,
+            token_infos,
+            contracts,
+        )
+        .await?;
+        if subgraph_url.is_none() {
+            aggregate
+                .run_maintenance()
+                .await
+                .context("failed to index Balancer pools")?;
+        }
+        let fetcher = Arc::new(Cache::new(aggregate, config, block_stream, reorgs)?);
 
         Ok(Self {
             fetcher,
@@ -372,27 +389,59 @@ impl BalancerPoolFetching for BalancerPoolFetcher {
     }
 }
 
-/// Creates an aggregate fetcher for all supported pool factories.
+#[async_trait::async_trait]
+impl Maintaining for BalancerPoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.fetcher.run_maintenance().await
+    }
+
+    fn name(&self) -> &str {
+        "BalancerPoolFetcher"
+    }
+}
+
+/// Creates an aggregate fetcher for all supported pool factories. Without
+/// registered pools from the subgraph, each factory gets indexed from its
+/// deployment block.
 async fn create_aggregate_pool_fetcher(
     web3: Web3,
-    pool_initializer: impl PoolInitializing,
+    registered_pools: Option<RegisteredPools>,
     block_retriever: Arc<dyn BlockRetrieving>,
     token_infos: Arc<dyn TokenInfoFetching>,
     contracts: &BalancerContracts,
 ) -> Result<Aggregate> {
-    let registered_pools = pool_initializer.initialize_pools().await?;
-    let fetched_block_number = registered_pools.fetched_block_number;
-    let fetched_block_hash = web3
-        .eth()
-        .block(BlockId::Number(fetched_block_number.into()))
-        .await?
-        .context("failed to get block by block number")?
-        .hash
-        .context("missing hash from block")?;
-    let mut registered_pools_by_factory = registered_pools.group_by_factory();
+    let fetched_block = match &registered_pools {
+        Some(registered_pools) => {
+            let fetched_block_number = registered_pools.fetched_block_number;
+            let fetched_block_hash = web3
+                .eth()
+                .block(BlockId::Number(fetched_block_number.into()))
+                .await?
+                .context("failed to get block by block number")?
+                .hash
+                .context("missing hash from block")?;
+            Some((fetched_block_number, fetched_block_hash))
+        }
+        None => None,
+    };
+    let mut registered_pools_by_factory = registered_pools
+        .map(RegisteredPools::group_by_factory)
+        .unwrap_or_default();
 
     macro_rules! registry {
         ($factory:ident, $instance:expr_2021) => {{
+            let (registered_pools, start_sync_at_block) = match fetched_block {
+                Some(block) => (
+                    registered_pools_by_factory
+                        .remove(&$instance.address())
+                        .unwrap_or_else(|| RegisteredPools::empty(block.0)),
+                    Some(block),
+                ),
+                None => (
+                    RegisteredPools::empty(0),
+                    indexing_start_block(block_retriever.as_ref(), $instance).await?,
+                ),
+            };
             create_internal_pool_fetcher(
                 contracts.vault.clone(),
                 $factory::with_deployment_info(
@@ -403,10 +452,8 @@ async fn create_aggregate_pool_fetcher(
                 block_retriever.clone(),
                 token_infos.clone(),
                 $instance,
-                registered_pools_by_factory
-                    .remove(&$instance.address())
-                    .unwrap_or_else(|| RegisteredPools::empty(fetched_block_number)),
-                fetched_block_hash,
+                registered_pools,
+                start_sync_at_block,
             )?
         }};
     }
@@ -467,7 +514,7 @@ fn create_internal_pool_fetcher<Factory>(
     token_infos: Arc<dyn TokenInfoFetching>,
     factory_instance: &Instance<Web3Transport>,
     registered_pools: RegisteredPools,
-    fetched_block_hash: H256,
+    start_sync_at_block: Option<BlockNumberHash>,
 ) -> Result<Box<dyn InternalPoolFetching>>
 where
     Factory: FactoryIndexing,
@@ -477,7 +524,6 @@ where
         .iter()
         .map(|pool| Factory::PoolInfo::from_graph_data(pool, registered_pools.fetched_block_number))
         .collect::<Result<_>>()?;
-    let start_sync_at_block = Some((registered_pools.fetched_block_number, fetched_block_hash));
 
     Ok(Box::new(Registry::new(
         block_retriever,
@@ -46,6 +46,12 @@ impl EventRetrieving for BasePoolFactoryContract {
         events.filter = events.filter.topic0(POOL_CREATED_TOPIC.into());
         events
     }
+
+    fn block_page_size(&self) -> u64 {
+        // Pools get created rarely, so we can afford to query large block
+        // ranges at once when indexing a factory from its deployment.
+        5_000
+    }
 }
 
 /// Type alias for the internal event updater type.
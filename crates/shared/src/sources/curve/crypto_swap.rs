@@ -0,0 +1,280 @@
+//! Module emulating the CryptoSwap invariant used by Curve two-coin crypto
+//! pools. The original Vyper code can be found at:
+//! https://github.com/curvefi/curve-crypto-contract/blob/master/contracts/two/CurveCryptoSwap2ETH.vy
+
+use ethcontract::U256;
+
+/// Multiplier applied to the amplification coefficient.
+const A_MULTIPLIER: u64 = 10_000;
+
+/// Denominator for the pool fees.
+const FEE_DENOMINATOR: u64 = 10_000_000_000;
+
+/// Precision used for fixed point math in the pool.
+const PRECISION: u64 = 1_000_000_000_000_000_000;
+
+const N_COINS: u64 = 2;
+
+const MAX_ITERATIONS: usize = 255;
+
+/// Upper bound for the number of times the input amount gets doubled while
+/// searching for the amount in.
+const MAX_SEARCH_DOUBLINGS: usize = 128;
+
+/// CryptoSwap pool parameters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameters {
+    /// The amplification coefficient as returned by `A()`. This already
+    /// includes the `A_MULTIPLIER` and `N ** N` factors.
+    pub a: U256,
+    pub gamma: U256,
+    /// The stored pool invariant.
+    pub d: U256,
+    /// The price of coin 1 denominated in coin 0.
+    pub price_scale: U256,
+    pub mid_fee: U256,
+    pub out_fee: U256,
+    pub fee_gamma: U256,
+    /// Factors converting each coin's balance to 18 decimals, i.e.
+    /// `10 ** (18 - decimals)`.
+    pub precisions: [U256; 2],
+}
+
+impl Parameters {
+    /// Computes the amount of coin `j` received for selling `dx` of coin `i`.
+    ///
+    /// https://github.com/curvefi/curve-crypto-contract/blob/d7d04cd9ae038970e40be850df99de8c1ff7241b/contracts/two/CurveCryptoSwap2ETH.vy#L749-L775
+    pub fn get_dy(&self, balances: &[U256; 2], i: usize, j: usize, dx: U256) -> Option<U256> {
+        if i == j || i > 1 || j > 1 {
+            return None;
+        }
+
+        let mut xp = *balances;
+        xp[i] = xp[i].checked_add(dx)?;
+        let mut xp = [
+            xp[0].checked_mul(self.precisions[0])?,
+            xp[1]
+                .checked_mul(self.price_scale)?
+                .checked_mul(self.precisions[1])?
+                / PRECISION,
+        ];
+
+        let y = newton_y(self.a, self.gamma, &xp, self.d, j)?;
+        let mut dy = xp[j].checked_sub(y)?.checked_sub(1.into())?;
+        xp[j] = y;
+        if j > 0 {
+            dy = dy
+                .checked_mul(PRECISION.into())?
+                .checked_div(self.price_scale)?;
+        }
+        dy /= self.precisions[j];
+        let fee = self.fee(&xp)?.checked_mul(dy)? / FEE_DENOMINATOR;
+        dy.checked_sub(fee)
+    }
+
+    /// Computes the amount of coin `i` that needs to be sold in order to
+    /// receive at least `dy` of coin `j`.
+    ///
+    /// Two-coin crypto pools don't expose an inverse of `get_dy`, so the
+    /// smallest sufficient input amount is searched for instead.
+    pub fn get_dx(&self, balances: &[U256; 2], i: usize, j: usize, dy: U256) -> Option<U256> {
+        let is_sufficient =
+            |dx: U256| matches!(self.get_dy(balances, i, j, dx), Some(out) if out >= dy);
+
+        // Use the reverse swap as an initial guess for the input amount, it is
+        // typically within the swap fees of the actual amount.
+        let mut hi = self
+            .get_dy(balances, j, i, dy)
+            .unwrap_or_default()
+            .max(U256::one());
+        let mut doublings = 0;
+        while !is_sufficient(hi) {
+            if doublings == MAX_SEARCH_DOUBLINGS {
+                return None;
+            }
+            hi = hi.checked_mul(2.into())?;
+            doublings += 1;
+        }
+
+        let mut lo = U256::zero();
+        while hi - lo > U256::one() {
+            let mid = lo + (hi - lo) / 2;
+            if is_sufficient(mid) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        Some(hi)
+    }
+
+    /// https://github.com/curvefi/curve-crypto-contract/blob/d7d04cd9ae038970e40be850df99de8c1ff7241b/contracts/two/CurveCryptoSwap2ETH.vy#L520-L524
+    fn fee(&self, xp: &[U256; 2]) -> Option<U256> {
+        let f = reduction_coefficient(xp, self.fee_gamma)?;
+        let mid = self.mid_fee.checked_mul(f)?;
+        let out = self
+            .out_fee
+            .checked_mul(U256::from(PRECISION).checked_sub(f)?)?;
+        Some(mid.checked_add(out)? / PRECISION)
+    }
+}
+
+/// https://github.com/curvefi/curve-crypto-contract/blob/d7d04cd9ae038970e40be850df99de8c1ff7241b/contracts/two/CurveCryptoSwap2ETH.vy#L219-L235
+fn reduction_coefficient(xp: &[U256; 2], fee_gamma: U256) -> Option<U256> {
+    let sum = xp[0].checked_add(xp[1])?;
+    let k = U256::from(PRECISION)
+        .checked_mul(N_COINS.into())?
+        .checked_mul(xp[0])?
+        .checked_div(sum)?
+        .checked_mul(N_COINS.into())?
+        .checked_mul(xp[1])?
+        .checked_div(sum)?;
+    if fee_gamma.is_zero() {
+        return Some(k);
+    }
+    fee_gamma
+        .checked_mul(PRECISION.into())?
+        .checked_div(fee_gamma.checked_add(PRECISION.into())?.checked_sub(k)?)
+}
+
+/// Computes the balance of coin `i` that keeps the invariant `d` given the
+/// balance of the other coin.
+///
+/// https://github.com/curvefi/curve-crypto-contract/blob/d7d04cd9ae038970e40be850df99de8c1ff7241b/contracts/two/CurveCryptoSwap2ETH.vy#L290-L357
+fn newton_y(ann: U256, gamma: U256, xp: &[U256; 2], d: U256, i: usize) -> Option<U256> {
+    let one = U256::from(PRECISION);
+    let n = U256::from(N_COINS);
+    let x_j = xp[1 - i];
+
+    let mut y = d.checked_mul(d)?.checked_div(x_j.checked_mul(n * n)?)?;
+    let k0_i = one.checked_mul(n)?.checked_mul(x_j)?.checked_div(d)?;
+    let convergence_limit = (x_j / 100_000_000_000_000_u64)
+        .max(d / 100_000_000_000_000_u64)
+        .max(100.into());
+
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        let k0 = k0_i.checked_mul(y)?.checked_mul(n)?.checked_div(d)?;
+        let s = x_j.checked_add(y)?;
+
+        let g1k0 = gamma.checked_add(one)?;
+        let g1k0 = if g1k0 > k0 {
+            g1k0 - k0 + U256::one()
+        } else {
+            k0 - g1k0 + U256::one()
+        };
+
+        // D / (A * N**N) * g1k0**2 / gamma**2
+        let mul1 = one
+            .checked_mul(d)?
+            .checked_div(gamma)?
+            .checked_mul(g1k0)?
+            .checked_div(gamma)?
+            .checked_mul(g1k0)?
+            .checked_mul(A_MULTIPLIER.into())?
+            .checked_div(ann)?;
+        // 2 * K0 / g1k0
+        let mul2 = one.checked_add(
+            one.checked_mul(2.into())?
+                .checked_mul(k0)?
+                .checked_div(g1k0)?,
+        )?;
+
+        let yfprime = one
+            .checked_mul(y)?
+            .checked_add(s.checked_mul(mul2)?)?
+            .checked_add(mul1)?;
+        let dyfprime = d.checked_mul(mul2)?;
+        if yfprime < dyfprime {
+            y = y_prev / 2;
+            continue;
+        }
+        let yfprime = yfprime - dyfprime;
+        let fprime = yfprime.checked_div(y)?;
+
+        let y_minus = mul1.checked_div(fprime)?;
+        let y_plus = yfprime
+            .checked_add(one.checked_mul(d)?)?
+            .checked_div(fprime)?
+            .checked_add(y_minus.checked_mul(one)?.checked_div(k0)?)?;
+        let y_minus = y_minus.checked_add(one.checked_mul(s)?.checked_div(fprime)?)?;
+
+        y = if y_plus < y_minus {
+            y_prev / 2
+        } else {
+            y_plus - y_minus
+        };
+
+        let diff = if y > y_prev { y - y_prev } else { y_prev - y };
+        if diff < convergence_limit.max(y / 100_000_000_000_000_u64) {
+            let frac = y.checked_mul(one)?.checked_div(d)?;
+            let safe = frac >= U256::exp10(16) && frac <= U256::exp10(20);
+            return safe.then_some(y);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A balanced USDC/WETH pool with 20M USDC and 10K WETH.
+    fn usdc_weth() -> (Parameters, [U256; 2]) {
+        let parameters = Parameters {
+            a: 400_000.into(),
+            gamma: 145_000_000_000_000_u64.into(),
+            d: U256::exp10(25) * 4,
+            price_scale: U256::exp10(18) * 2000,
+            mid_fee: 26_000_000.into(),
+            out_fee: 45_000_000.into(),
+            fee_gamma: 230_000_000_000_000_u64.into(),
+            precisions: [U256::exp10(12), U256::one()],
+        };
+        let balances = [U256::exp10(6) * 20_000_000, U256::exp10(18) * 10_000];
+        (parameters, balances)
+    }
+
+    #[test]
+    fn get_dy_matches_contract_math() {
+        let (parameters, balances) = usdc_weth();
+
+        assert_eq!(
+            parameters
+                .get_dy(&balances, 0, 1, U256::exp10(6) * 2000)
+                .unwrap(),
+            U256::from(997_395_167_304_952_024_u64),
+        );
+        assert_eq!(
+            parameters.get_dy(&balances, 1, 0, U256::exp10(18)).unwrap(),
+            U256::from(1_994_790_335_u64),
+        );
+    }
+
+    #[test]
+    fn get_dx_finds_smallest_sufficient_amount() {
+        let (parameters, balances) = usdc_weth();
+
+        for (i, j, dy) in [
+            (0, 1, U256::exp10(18)),
+            (1, 0, U256::exp10(6) * 5000),
+            (0, 1, U256::exp10(20)),
+        ] {
+            let dx = parameters.get_dx(&balances, i, j, dy).unwrap();
+            assert!(parameters.get_dy(&balances, i, j, dx).unwrap() >= dy);
+            assert!(
+                parameters
+                    .get_dy(&balances, i, j, dx - U256::one())
+                    .unwrap()
+                    < dy
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_indices() {
+        let (parameters, balances) = usdc_weth();
+        assert!(parameters.get_dy(&balances, 1, 1, 1.into()).is_none());
+        assert!(parameters.get_dy(&balances, 0, 2, 1.into()).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! Curve liquidity.
+//!
+//! Pools are discovered through the Curve meta registry which indexes plain,
+//! meta and crypto pools across all Curve factories. Swap amounts are computed
+//! locally by emulating the StableSwap and CryptoSwap invariants, so pools
+//! whose on-chain math diverges from these (for example lending pools with
+//! non-trivial token rates) are ignored when fetching.
+
+pub mod crypto_swap;
+pub mod pool_fetching;
+pub mod stable_swap;
+
+use {
+    crate::baseline_solver::BaselineSolvable,
+    ethcontract::{H160, U256},
+};
+
+// Approximate gas used by a single `exchange` call on the respective pool
+// kinds, crypto pools are more expensive because of their Newton iterations.
+const STABLE_SWAP_GAS_COST: usize = 130_000;
+const CRYPTO_SWAP_GAS_COST: usize = 200_000;
+
+/// The state of a Curve pool at a given block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    /// The pool coins in the order the pool indexes them.
+    pub tokens: Vec<H160>,
+    /// The pool balances for each coin in `tokens`.
+    pub balances: Vec<U256>,
+    pub kind: PoolKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolKind {
+    /// A plain or meta pool using the StableSwap invariant.
+    Stable(stable_swap::Parameters),
+    /// A two-coin pool using the CryptoSwap invariant.
+    Crypto(crypto_swap::Parameters),
+}
+
+impl Pool {
+    /// Returns the index of the specified token in the pool.
+    pub fn index(&self, token: H160) -> Option<usize> {
+        self.tokens.iter().position(|t| *t == token)
+    }
+
+    /// Computes the amount of `j` received for selling `dx` of `i`.
+    pub fn get_dy(&self, i: usize, j: usize, dx: U256) -> Option<U256> {
+        match &self.kind {
+            PoolKind::Stable(parameters) => parameters.get_dy(&self.balances, i, j, dx),
+            PoolKind::Crypto(parameters) => {
+                parameters.get_dy(&self.balances.clone().try_into().ok()?, i, j, dx)
+            }
+        }
+    }
+
+    /// Computes the amount of `i` needed to buy `dy` of `j`.
+    pub fn get_dx(&self, i: usize, j: usize, dy: U256) -> Option<U256> {
+        match &self.kind {
+            PoolKind::Stable(parameters) => parameters.get_dx(&self.balances, i, j, dy),
+            PoolKind::Crypto(parameters) => {
+                parameters.get_dx(&self.balances.clone().try_into().ok()?, i, j, dy)
+            }
+        }
+    }
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        self.get_dy(self.index(in_token)?, self.index(out_token)?, in_amount)
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        self.get_dx(self.index(in_token)?, self.index(out_token)?, out_amount)
+    }
+
+    fn gas_cost(&self) -> usize {
+        match self.kind {
+            PoolKind::Stable(_) => STABLE_SWAP_GAS_COST,
+            PoolKind::Crypto(_) => CRYPTO_SWAP_GAS_COST,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baseline_solvable_uses_token_indices() {
+        let pool = Pool {
+            address: H160([0x01; 20]),
+            tokens: vec![H160([0x02; 20]), H160([0x03; 20])],
+            balances: vec![U256::exp10(24), U256::exp10(24)],
+            kind: PoolKind::Stable(stable_swap::Parameters {
+                amplification: U256::from(100 * stable_swap::A_PRECISION),
+                fee: U256::from(4_000_000),
+                rates: vec![U256::exp10(18), U256::exp10(18)],
+            }),
+        };
+
+        let out = pool
+            .get_amount_out(H160([0x03; 20]), (U256::exp10(18), H160([0x02; 20])))
+            .unwrap();
+        assert_eq!(out, pool.get_dy(0, 1, U256::exp10(18)).unwrap());
+
+        let amount_in = pool
+            .get_amount_in(H160([0x02; 20]), (out, H160([0x03; 20])))
+            .unwrap();
+        assert!(pool.get_dy(0, 1, amount_in).unwrap() >= out);
+
+        assert!(
+            pool.get_amount_out(H160([0x04; 20]), (U256::exp10(18), H160([0x02; 20])))
+                .is_none()
+        );
+    }
+}
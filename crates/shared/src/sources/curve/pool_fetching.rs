@@ -0,0 +1,371 @@
+//! Fetching of Curve pools.
+//!
+//! The static information of all pools (coins, decimals, pool kind) is indexed
+//! from the Curve meta registry once on start up. The dynamic pool state is
+//! then read on demand for the pools relevant to the requested token pairs and
+//! cached per block.
+
+use {
+    super::{Pool, PoolKind, crypto_swap, stable_swap},
+    crate::{
+        ethrpc::Web3,
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+    },
+    anyhow::{Context, Result, ensure},
+    contracts::{CurveCryptoSwapPool, CurveMetaRegistry, CurveStableSwapPool},
+    ethcontract::{BlockId, H160, U256},
+    ethrpc::block_stream::{CurrentBlockWatcher, Reorgs},
+    futures::future,
+    model::TokenPair,
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+};
+
+/// The placeholder address Curve pools use for native ETH. Pools trading
+/// native ETH can't be used by the settlement contract and are skipped.
+const NATIVE_ETH: H160 = H160([0xee; 20]);
+
+/// Fraction of the first coin's balance used to verify that the local swap
+/// math matches the pool's own `get_dy`.
+const PROBE_FRACTION: u64 = 1_000;
+
+/// The maximum relative difference (in parts per million) between the local
+/// and the on-chain quote for a pool to be considered supported.
+const PROBE_TOLERANCE_PPM: u64 = 1;
+
+#[async_trait::async_trait]
+pub trait CurvePoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+pub struct CurvePoolFetcher {
+    pools_by_pair: HashMap<TokenPair, Vec<H160>>,
+    cache: RecentBlockCache<H160, Pool, Arc<PoolReader>>,
+}
+
+impl CurvePoolFetcher {
+    /// Indexes all pools of the meta registry and creates a fetcher for
+    /// their state.
+    pub async fn new(
+        web3: &Web3,
+        registry: H160,
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+        reorgs: &Reorgs,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(web3, "curve".into());
+        let registry = CurveMetaRegistry::at(&web3, registry);
+
+        let pool_count = registry
+            .pool_count()
+            .call()
+            .await
+            .context("failed to get Curve pool count")?;
+        let addresses = future::try_join_all(
+            (0..pool_count.as_u64()).map(|i| registry.pool_list(i.into()).call()),
+        )
+        .await
+        .context("failed to list Curve pools")?;
+
+        let infos = future::join_all(
+            addresses
+                .into_iter()
+                .map(|address| read_pool_info(&web3, &registry, address)),
+        )
+        .await;
+        let pools = infos
+            .into_iter()
+            .filter_map(|info| match info {
+                Ok(info) => info,
+                Err(err) => {
+                    tracing::debug!(?err, "failed to index Curve pool");
+                    None
+                }
+            })
+            .map(|info| (info.address, info))
+            .collect::<HashMap<_, _>>();
+        tracing::info!(total = %pool_count, supported = %pools.len(), "indexed Curve pools");
+
+        let mut pools_by_pair = HashMap::<_, Vec<_>>::new();
+        for info in pools.values() {
+            for (i, a) in info.tokens.iter().enumerate() {
+                for b in &info.tokens[i + 1..] {
+                    if let Some(pair) = TokenPair::new(*a, *b) {
+                        pools_by_pair.entry(pair).or_default().push(info.address);
+                    }
+                }
+            }
+        }
+
+        let reader = Arc::new(PoolReader {
+            web3,
+            registry,
+            pools,
+        });
+        let cache = RecentBlockCache::new(config, reader, block_stream, "curve")?;
+        cache.subscribe_to_reorgs(reorgs);
+
+        Ok(Self {
+            pools_by_pair,
+            cache,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CurvePoolFetching for CurvePoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses = token_pairs
+            .iter()
+            .filter_map(|pair| self.pools_by_pair.get(pair))
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+        self.cache.fetch(addresses, at_block).await
+    }
+}
+
+/// Static pool information that does not change between blocks.
+#[derive(Clone, Debug)]
+struct PoolInfo {
+    address: H160,
+    tokens: Vec<H160>,
+    decimals: Vec<U256>,
+    kind: PoolInfoKind,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PoolInfoKind {
+    /// A StableSwap pool. Meta pools additionally reference the base pool
+    /// whose LP token is the pool's last coin.
+    Stable {
+        base_pool: Option<H160>,
+    },
+    Crypto,
+}
+
+async fn read_pool_info(
+    web3: &Web3,
+    registry: &CurveMetaRegistry,
+    address: H160,
+) -> Result<Option<PoolInfo>> {
+    let (n_coins, coins, decimals, is_meta, base_pool, gamma) = futures::join!(
+        registry.get_n_coins(address).call(),
+        registry.get_coins(address).call(),
+        registry.get_decimals(address).call(),
+        registry.is_meta(address).call(),
+        registry.get_base_pool(address).call(),
+        // Only crypto pools have a `gamma` parameter, so we use it to tell
+        // the pool kinds apart.
+        CurveCryptoSwapPool::at(web3, address).gamma().call(),
+    );
+
+    let (n_coins, coins, decimals) = (n_coins?.as_usize(), coins?, decimals?);
+    ensure!(
+        (2..=coins.len()).contains(&n_coins),
+        "unsupported coin count"
+    );
+    let tokens = coins[..n_coins].to_vec();
+    let decimals = decimals[..n_coins].to_vec();
+
+    if tokens.contains(&NATIVE_ETH) {
+        return Ok(None);
+    }
+    let kind = if gamma.is_ok() {
+        // Only two-coin crypto pools are supported.
+        if n_coins != 2 {
+            return Ok(None);
+        }
+        PoolInfoKind::Crypto
+    } else {
+        PoolInfoKind::Stable {
+            base_pool: is_meta?.then_some(base_pool?),
+        }
+    };
+
+    Ok(Some(PoolInfo {
+        address,
+        tokens,
+        decimals,
+        kind,
+    }))
+}
+
+struct PoolReader {
+    web3: Web3,
+    registry: CurveMetaRegistry,
+    pools: HashMap<H160, PoolInfo>,
+}
+
+impl PoolReader {
+    /// Reads the pool state at the specified block. Returns `None` for pools
+    /// that are empty or whose swap math can't be reproduced locally.
+    async fn read_state(&self, info: &PoolInfo, block: BlockId) -> Result<Option<Pool>> {
+        let balances = self
+            .registry
+            .get_balances(info.address)
+            .block(block)
+            .call()
+            .await?[..info.tokens.len()]
+            .to_vec();
+        let probe = balances[0] / PROBE_FRACTION;
+        if probe.is_zero() {
+            return Ok(None);
+        }
+
+        let (kind, expected) = match info.kind {
+            PoolInfoKind::Stable { base_pool } => {
+                let pool = CurveStableSwapPool::at(&self.web3, info.address);
+                let virtual_price = async {
+                    match base_pool {
+                        Some(base_pool) => CurveStableSwapPool::at(&self.web3, base_pool)
+                            .get_virtual_price()
+                            .block(block)
+                            .call()
+                            .await
+                            .map(Some),
+                        None => Ok(None),
+                    }
+                };
+                let (a, fee, virtual_price, expected) = futures::try_join!(
+                    pool.a().block(block).call(),
+                    pool.fee().block(block).call(),
+                    virtual_price,
+                    pool.get_dy(0, 1, probe).block(block).call(),
+                )?;
+
+                let mut rates = info
+                    .decimals
+                    .iter()
+                    .map(|decimals| {
+                        let exponent = 36_usize.checked_sub(decimals.as_usize())?;
+                        Some(U256::exp10(exponent))
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .context("invalid token decimals")?;
+                if let Some(virtual_price) = virtual_price {
+                    *rates.last_mut().expect("at least two coins") = virtual_price;
+                }
+
+                let kind = PoolKind::Stable(stable_swap::Parameters {
+                    amplification: a
+                        .checked_mul(stable_swap::A_PRECISION.into())
+                        .context("amplification overflow")?,
+                    fee,
+                    rates,
+                });
+                (kind, expected)
+            }
+            PoolInfoKind::Crypto => {
+                let pool = CurveCryptoSwapPool::at(&self.web3, info.address);
+                let state = futures::try_join!(
+                    pool.a().block(block).call(),
+                    pool.gamma().block(block).call(),
+                    pool.d().block(block).call(),
+                    pool.price_scale().block(block).call(),
+                    pool.mid_fee().block(block).call(),
+                    pool.out_fee().block(block).call(),
+                    pool.fee_gamma().block(block).call(),
+                    pool.get_dy(0.into(), 1.into(), probe).block(block).call(),
+                )?;
+                let (a, gamma, d, price_scale, mid_fee, out_fee, fee_gamma, expected) = state;
+
+                let precision = |decimals: U256| -> Result<U256> {
+                    let exponent = 18_usize
+                        .checked_sub(decimals.as_usize())
+                        .context("invalid token decimals")?;
+                    Ok(U256::exp10(exponent))
+                };
+                let kind = PoolKind::Crypto(crypto_swap::Parameters {
+                    a,
+                    gamma,
+                    d,
+                    price_scale,
+                    mid_fee,
+                    out_fee,
+                    fee_gamma,
+                    precisions: [precision(info.decimals[0])?, precision(info.decimals[1])?],
+                });
+                (kind, expected)
+            }
+        };
+
+        let pool = Pool {
+            address: info.address,
+            tokens: info.tokens.clone(),
+            balances,
+            kind,
+        };
+        if !matches_quote(pool.get_dy(0, 1, probe), expected) {
+            tracing::debug!(pool = ?info.address, "Curve pool math diverges from on-chain quote");
+            return Ok(None);
+        }
+        Ok(Some(pool))
+    }
+}
+
+/// Checks whether a locally computed quote is within tolerance of the pool's
+/// on-chain quote.
+fn matches_quote(computed: Option<U256>, expected: U256) -> bool {
+    let Some(computed) = computed else {
+        return false;
+    };
+    let diff = if computed > expected {
+        computed - expected
+    } else {
+        expected - computed
+    };
+    diff.saturating_mul(1_000_000.into()) <= expected.saturating_mul(PROBE_TOLERANCE_PPM.into())
+}
+
+impl CacheKey<Pool> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(pool: &Pool) -> Self {
+        pool.address
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Pool> for Arc<PoolReader> {
+    async fn fetch_values(&self, addresses: HashSet<H160>, block: Block) -> Result<Vec<Pool>> {
+        let block = BlockId::Number(block.into());
+        let pools = future::join_all(
+            addresses
+                .iter()
+                .filter_map(|address| self.pools.get(address))
+                .map(|info| self.read_state(info, block)),
+        )
+        .await;
+
+        Ok(pools
+            .into_iter()
+            .filter_map(|pool| match pool {
+                Ok(pool) => pool,
+                Err(err) => {
+                    tracing::debug!(?err, "failed to read Curve pool state");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_tolerance() {
+        let expected = U256::exp10(18);
+        assert!(matches_quote(Some(expected), expected));
+        assert!(matches_quote(Some(expected - U256::exp10(12)), expected));
+        assert!(!matches_quote(Some(expected - U256::exp10(13)), expected));
+        assert!(!matches_quote(Some(expected + U256::exp10(13)), expected));
+        assert!(!matches_quote(None, expected));
+    }
+}
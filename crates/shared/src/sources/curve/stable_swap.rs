@@ -0,0 +1,226 @@
+//! Module emulating the StableSwap invariant used by Curve plain and meta
+//! pools. The original Vyper code can be found at:
+//! https://github.com/curvefi/curve-contract/blob/master/contracts/pool-templates/base/SwapTemplateBase.vy
+
+use ethcontract::U256;
+
+/// Precision used for the amplification coefficient. Pools expose `A()` as
+/// the whole number, but all math internally uses `A * A_PRECISION`.
+pub const A_PRECISION: u64 = 100;
+
+/// Denominator for the pool fee, i.e. a fee of `4_000_000` is 0.04%.
+pub const FEE_DENOMINATOR: u64 = 10_000_000_000;
+
+/// Precision of the token rates used for normalizing balances.
+pub const PRECISION: u64 = 1_000_000_000_000_000_000;
+
+const MAX_ITERATIONS: usize = 255;
+
+/// StableSwap pool parameters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameters {
+    /// The amplification coefficient multiplied by `A_PRECISION`.
+    pub amplification: U256,
+    /// The swap fee denominated in `FEE_DENOMINATOR`.
+    pub fee: U256,
+    /// Rate for each coin converting its balance to 18 decimals normalized
+    /// "virtual" balances. This is `10 ** (36 - decimals)` for regular coins
+    /// and the base pool's virtual price for the LP coin of meta pools.
+    pub rates: Vec<U256>,
+}
+
+impl Parameters {
+    /// Computes the amount of coin `j` received for selling `dx` of coin `i`.
+    ///
+    /// https://github.com/curvefi/curve-contract/blob/b0bbf77f8f93c9c5f4e415bce9cd71f0cdee960e/contracts/pool-templates/base/SwapTemplateBase.vy#L405-L414
+    pub fn get_dy(&self, balances: &[U256], i: usize, j: usize, dx: U256) -> Option<U256> {
+        let xp = self.xp(balances)?;
+        let x = xp
+            .get(i)?
+            .checked_add(dx.checked_mul(*self.rates.get(i)?)? / PRECISION)?;
+        let y = get_y(self.amplification, &xp, i, j, x)?;
+        let dy = xp.get(j)?.checked_sub(y)?.checked_sub(1.into())?;
+        let fee = self.fee.checked_mul(dy)? / FEE_DENOMINATOR;
+        dy.checked_sub(fee)?
+            .checked_mul(PRECISION.into())?
+            .checked_div(*self.rates.get(j)?)
+    }
+
+    /// Computes the amount of coin `i` that needs to be sold in order to
+    /// receive `dy` of coin `j`.
+    ///
+    /// https://github.com/curvefi/stableswap-ng/blob/fd54b9a1a110d0e2e4f962583761d9e236b70967/contracts/main/CurveStableSwapNGViews.vy#L58-L91
+    pub fn get_dx(&self, balances: &[U256], i: usize, j: usize, dy: U256) -> Option<U256> {
+        let xp = self.xp(balances)?;
+        let dy_with_fee = dy
+            .checked_mul(*self.rates.get(j)?)?
+            .checked_div(PRECISION.into())?
+            .checked_add(1.into())?
+            .checked_mul(FEE_DENOMINATOR.into())?
+            .checked_div(U256::from(FEE_DENOMINATOR).checked_sub(self.fee)?)?;
+        let y = xp.get(j)?.checked_sub(dy_with_fee)?;
+        let x = get_y(self.amplification, &xp, j, i, y)?;
+        x.checked_sub(*xp.get(i)?)?
+            .checked_mul(PRECISION.into())?
+            .checked_div(*self.rates.get(i)?)?
+            .checked_add(1.into())
+    }
+
+    fn xp(&self, balances: &[U256]) -> Option<Vec<U256>> {
+        if balances.len() != self.rates.len() {
+            return None;
+        }
+        balances
+            .iter()
+            .zip(&self.rates)
+            .map(|(balance, rate)| Some(balance.checked_mul(*rate)? / PRECISION))
+            .collect()
+    }
+}
+
+/// https://github.com/curvefi/curve-contract/blob/b0bbf77f8f93c9c5f4e415bce9cd71f0cdee960e/contracts/pool-templates/base/SwapTemplateBase.vy#L204-L229
+fn get_d(amplification: U256, xp: &[U256]) -> Option<U256> {
+    let n = U256::from(xp.len());
+    let sum = xp
+        .iter()
+        .try_fold(U256::zero(), |sum, x| sum.checked_add(*x))?;
+    if sum.is_zero() {
+        return Some(sum);
+    }
+
+    let ann = amplification.checked_mul(n)?;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for x in xp {
+            d_p = d_p.checked_mul(d)?.checked_div(x.checked_mul(n)?)?;
+        }
+        let d_prev = d;
+        let numerator = (ann.checked_mul(sum)? / A_PRECISION)
+            .checked_add(d_p.checked_mul(n)?)?
+            .checked_mul(d)?;
+        let denominator = (ann.checked_sub(A_PRECISION.into())?.checked_mul(d)? / A_PRECISION)
+            .checked_add(n.checked_add(1.into())?.checked_mul(d_p)?)?;
+        d = numerator.checked_div(denominator)?;
+        if converged(d, d_prev) {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Computes the new balance of coin `j` given that coin `i` has the new
+/// balance `x` while keeping the invariant constant.
+///
+/// https://github.com/curvefi/curve-contract/blob/b0bbf77f8f93c9c5f4e415bce9cd71f0cdee960e/contracts/pool-templates/base/SwapTemplateBase.vy#L356-L399
+fn get_y(amplification: U256, xp: &[U256], i: usize, j: usize, x: U256) -> Option<U256> {
+    if i == j || i >= xp.len() || j >= xp.len() {
+        return None;
+    }
+
+    let n = U256::from(xp.len());
+    let d = get_d(amplification, xp)?;
+    let ann = amplification.checked_mul(n)?;
+
+    let mut c = d;
+    let mut sum = U256::zero();
+    for (k, balance) in xp.iter().enumerate() {
+        let balance = if k == i {
+            x
+        } else if k == j {
+            continue;
+        } else {
+            *balance
+        };
+        sum = sum.checked_add(balance)?;
+        c = c.checked_mul(d)?.checked_div(balance.checked_mul(n)?)?;
+    }
+    let c = c
+        .checked_mul(d)?
+        .checked_mul(A_PRECISION.into())?
+        .checked_div(ann.checked_mul(n)?)?;
+    let b = sum.checked_add(d.checked_mul(A_PRECISION.into())?.checked_div(ann)?)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(2.into())?.checked_add(b)?.checked_sub(d)?)?;
+        if converged(y, y_prev) {
+            return Some(y);
+        }
+    }
+    None
+}
+
+fn converged(value: U256, previous: U256) -> bool {
+    let diff = if value > previous {
+        value - previous
+    } else {
+        previous - value
+    };
+    diff <= U256::one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_pool() -> (Parameters, Vec<U256>) {
+        // Parameters resembling the DAI/USDC/USDT pool
+        // `0xbEbc44782C7dB0a1A60Cb6fe97d0b483032FF1C7`.
+        let parameters = Parameters {
+            amplification: U256::from(2000 * A_PRECISION),
+            fee: U256::from(1_000_000),
+            rates: vec![U256::exp10(18), U256::exp10(30), U256::exp10(30)],
+        };
+        let balances = vec![
+            U256::from_dec_str("54163628570301312925066549").unwrap(),
+            U256::from(58_349_127_357_372_u64),
+            U256::from(38_137_395_046_418_u64),
+        ];
+        (parameters, balances)
+    }
+
+    #[test]
+    fn swap_close_to_peg() {
+        let (parameters, balances) = three_pool();
+
+        // Selling 1000 DAI for USDC should yield slightly less than 1000 USDC
+        // because of fees and balance skew.
+        let dy = parameters.get_dy(&balances, 0, 1, U256::exp10(21)).unwrap();
+        assert!(dy < U256::from(1_000_000_000_u64));
+        assert!(dy > U256::from(999_000_000_u64));
+    }
+
+    #[test]
+    fn get_dx_is_inverse_of_get_dy() {
+        let (parameters, balances) = three_pool();
+
+        for (i, j, dy) in [
+            (0, 1, U256::from(1_000_000_000_u64)),
+            (1, 0, U256::exp10(21)),
+            (2, 1, U256::from(123_456_789_u64)),
+        ] {
+            let dx = parameters.get_dx(&balances, i, j, dy).unwrap();
+            let received = parameters.get_dy(&balances, i, j, dx).unwrap();
+            assert!(received >= dy, "{received} < {dy}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_indices() {
+        let (parameters, balances) = three_pool();
+        assert!(parameters.get_dy(&balances, 0, 0, 1.into()).is_none());
+        assert!(parameters.get_dy(&balances, 0, 3, 1.into()).is_none());
+        assert!(parameters.get_dx(&balances, 3, 0, 1.into()).is_none());
+    }
+
+    #[test]
+    fn cannot_drain_pool() {
+        let (parameters, balances) = three_pool();
+        assert!(parameters.get_dx(&balances, 0, 1, balances[1]).is_none());
+    }
+}
@@ -4,7 +4,7 @@ use {
         sources::uniswap_v2::pool_fetching::{Pool, PoolFetching},
     },
     anyhow::Result,
-    ethrpc::block_stream::CurrentBlockWatcher,
+    ethrpc::block_stream::{CurrentBlockWatcher, Reorgs},
     model::TokenPair,
     std::{collections::HashSet, sync::Arc},
 };
@@ -29,18 +29,16 @@ impl CacheFetching<TokenPair, Pool> for Arc<dyn PoolFetching> {
 }
 
 impl PoolCache {
-    /// Creates a new pool cache.
+    /// Creates a new pool cache that drops reorged pools.
     pub fn new(
         config: CacheConfig,
         fetcher: Arc<dyn PoolFetching>,
         block_stream: CurrentBlockWatcher,
+        reorgs: &Reorgs,
     ) -> Result<Self> {
-        Ok(Self(RecentBlockCache::new(
-            config,
-            fetcher,
-            block_stream,
-            "uniswapv2",
-        )?))
+        let cache = RecentBlockCache::new(config, fetcher, block_stream, "uniswapv2")?;
+        cache.subscribe_to_reorgs(reorgs);
+        Ok(Self(cache))
     }
 }
 
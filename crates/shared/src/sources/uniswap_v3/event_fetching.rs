@@ -90,13 +90,22 @@ impl EventRetrieving for UniswapV3PoolEventFetcher {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct RecentEventsCache {
+/// In-memory cache of recent events, ordered by when they were emitted.
+#[derive(Debug)]
+pub struct RecentEventsCache<E = UniswapV3Event> {
     /// (block number, event log index) used as a Key
-    events: BTreeMap<(u64, usize), Event<UniswapV3Event>>,
+    events: BTreeMap<(u64, usize), Event<E>>,
 }
 
-impl RecentEventsCache {
+impl<E> Default for RecentEventsCache<E> {
+    fn default() -> Self {
+        Self {
+            events: Default::default(),
+        }
+    }
+}
+
+impl<E: Clone> RecentEventsCache<E> {
     /// Removes all events up to the specified block, excluding the specified
     /// block.
     pub fn remove_events_older_than_block(&mut self, delete_up_to_block_number: u64) {
@@ -108,7 +117,7 @@ impl RecentEventsCache {
         self.events.split_off(&(delete_from_block_number, 0));
     }
 
-    pub fn get_events(&self, block_range: RangeInclusive<u64>) -> Vec<Event<UniswapV3Event>> {
+    pub fn get_events(&self, block_range: RangeInclusive<u64>) -> Vec<Event<E>> {
         self.events
             .range((*block_range.start(), 0)..=(*block_range.end(), usize::MAX))
             .map(|(_, event)| event)
@@ -118,17 +127,17 @@ impl RecentEventsCache {
 }
 
 #[async_trait::async_trait]
-impl EventStoring<UniswapV3Event> for RecentEventsCache {
+impl<E: Clone + Send + Sync> EventStoring<E> for RecentEventsCache<E> {
     async fn replace_events(
         &mut self,
-        events: Vec<Event<UniswapV3Event>>,
+        events: Vec<Event<E>>,
         range: RangeInclusive<u64>,
     ) -> Result<()> {
         self.remove_events_newer_than_block(*range.start());
         self.append_events(events).await
     }
 
-    async fn append_events(&mut self, events: Vec<Event<UniswapV3Event>>) -> Result<()> {
+    async fn append_events(&mut self, events: Vec<Event<E>>) -> Result<()> {
         for event in events {
             let event_meta = event.meta.as_ref().context("event meta is empty")?;
             self.events
@@ -169,7 +178,7 @@ mod tests {
 
     #[test]
     fn remove_events_older_than_block_test_empty() {
-        let mut cache = RecentEventsCache::default();
+        let mut cache = RecentEventsCache::<UniswapV3Event>::default();
         cache.remove_events_older_than_block(5);
     }
 
@@ -189,7 +198,7 @@ mod tests {
 
     #[test]
     fn remove_events_newer_than_block_test_empty() {
-        let mut cache = RecentEventsCache::default();
+        let mut cache = RecentEventsCache::<UniswapV3Event>::default();
         cache.remove_events_newer_than_block(5);
     }
 
@@ -209,7 +218,7 @@ mod tests {
 
     #[test]
     fn get_events_test_empty() {
-        let cache = RecentEventsCache::default();
+        let cache = RecentEventsCache::<UniswapV3Event>::default();
         let events = cache.get_events(RangeInclusive::try_new(5u64, 5).unwrap());
         assert!(events.is_empty());
     }
@@ -273,7 +282,7 @@ mod tests {
 
     #[tokio::test]
     async fn last_event_block_test_empty() {
-        let cache = RecentEventsCache::default();
+        let cache = RecentEventsCache::<UniswapV3Event>::default();
         let result = cache.last_event_block().await;
         assert!(result.is_err());
     }
@@ -0,0 +1,529 @@
+//! On-chain Uniswap V3 pool discovery and state fetching.
+//!
+//! This is an alternative to the subgraph API for chains where the subgraph
+//! does not exist or is unreliable. Pools are discovered by indexing the
+//! factory's `PoolCreated` events and their state, including all initialized
+//! ticks, is read directly from the pool contracts.
+
+use {
+    super::{
+        graph_api::Token,
+        pool_fetching::{POOL_SWAP_GAS_COST, PoolInfo, PoolState, PoolStats},
+    },
+    crate::event_handling::{EventRetrieving, EventStoring},
+    anyhow::{Context, Result, ensure},
+    contracts::{ERC20, IUniswapV3Factory, UniswapV3Pool},
+    ethcontract::{
+        BlockId,
+        Event,
+        H160,
+        H256,
+        RawLog,
+        U256,
+        common::abi::{Error, Function, Token as AbiToken},
+        contract::ParseLog,
+        dyns::DynAllEventsBuilder,
+        errors::ExecutionError,
+    },
+    ethrpc::{
+        Web3,
+        block_stream::RangeInclusive,
+        multicall::{Call, MulticallExt},
+    },
+    futures::{TryFutureExt as _, future},
+    hex_literal::hex,
+    model::TokenPair,
+    num::{BigInt, rational::Ratio},
+    std::collections::{BTreeMap, HashMap, HashSet},
+};
+
+const POOL_CREATED_TOPIC: H256 = H256(hex!(
+    "783cca1c0412dd0d695e784568c96da2e9c22ff989357a2e8b1d9b2b4e6b7118"
+));
+
+/// The minimum tick supported by Uniswap V3 pools.
+const MIN_TICK: i32 = -887272;
+/// The maximum tick supported by Uniswap V3 pools.
+const MAX_TICK: i32 = -MIN_TICK;
+
+/// The maximum number of calls to batch into a single multicall.
+const MULTICALL_BATCH_SIZE: usize = 500;
+
+/// A pool creation event emitted by the Uniswap V3 factory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolCreated {
+    pub token0: H160,
+    pub token1: H160,
+    /// The pool fee in hundredths of a basis point.
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub pool: H160,
+}
+
+impl ParseLog for PoolCreated {
+    fn parse_log(log: RawLog) -> Result<Self, ExecutionError> {
+        let invalid = || ExecutionError::from(Error::InvalidData);
+        if log.topics.len() != 4 || log.topics[0] != POOL_CREATED_TOPIC || log.data.len() != 64 {
+            return Err(invalid());
+        }
+
+        let address = |word: &[u8]| H160::from_slice(&word[12..]);
+        Ok(Self {
+            token0: address(log.topics[1].as_bytes()),
+            token1: address(log.topics[2].as_bytes()),
+            fee: U256::from_big_endian(log.topics[3].as_bytes()).low_u32(),
+            tick_spacing: U256::from_big_endian(&log.data[..32]).low_u32() as i32,
+            pool: address(&log.data[32..]),
+        })
+    }
+}
+
+/// Retrieves the `PoolCreated` events of a Uniswap V3 factory.
+pub struct FactoryContract(pub IUniswapV3Factory);
+
+impl EventRetrieving for FactoryContract {
+    type Event = PoolCreated;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = DynAllEventsBuilder::new(
+            self.0.raw_instance().web3(),
+            self.0.address(),
+            self.0.deployment_information(),
+        );
+        events.filter = events.filter.topic0(POOL_CREATED_TOPIC.into());
+        events
+    }
+
+    fn block_page_size(&self) -> u64 {
+        // Pools get created rarely, so we can afford to query large block
+        // ranges at once.
+        5_000
+    }
+}
+
+/// A pool registered with the factory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegisteredPool {
+    pub address: H160,
+    pub tokens: (H160, H160),
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub block_created: u64,
+}
+
+/// In-memory index of all pools created by a Uniswap V3 factory.
+#[derive(Debug, Default)]
+pub struct PoolRegistry {
+    pools: HashMap<H160, RegisteredPool>,
+    pools_by_token_pair: HashMap<TokenPair, HashSet<H160>>,
+}
+
+impl PoolRegistry {
+    /// Returns the addresses of all pools trading any of the specified pairs.
+    pub fn pool_ids_for_token_pairs(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<H160> {
+        token_pairs
+            .iter()
+            .filter_map(|pair| self.pools_by_token_pair.get(pair))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the registered pool with the specified address.
+    pub fn get(&self, address: &H160) -> Option<&RegisteredPool> {
+        self.pools.get(address)
+    }
+
+    /// Returns the number of registered pools.
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Returns whether no pools are registered.
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    fn insert(&mut self, event: Event<PoolCreated>) -> Result<()> {
+        let block_created = event.meta.context("event meta is empty")?.block_number;
+        let PoolCreated {
+            token0,
+            token1,
+            fee,
+            tick_spacing,
+            pool,
+        } = event.data;
+        let Some(pair) = TokenPair::new(token0, token1) else {
+            return Ok(());
+        };
+
+        self.pools_by_token_pair
+            .entry(pair)
+            .or_default()
+            .insert(pool);
+        self.pools.insert(
+            pool,
+            RegisteredPool {
+                address: pool,
+                tokens: (token0, token1),
+                fee,
+                tick_spacing,
+                block_created,
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes all pools created in the specified block or later.
+    fn remove_pools_newer_than_block(&mut self, block: u64) {
+        self.pools.retain(|_, pool| pool.block_created < block);
+        for pools in self.pools_by_token_pair.values_mut() {
+            pools.retain(|address| self.pools.contains_key(address));
+        }
+        self.pools_by_token_pair
+            .retain(|_, pools| !pools.is_empty());
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<PoolCreated> for PoolRegistry {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<PoolCreated>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        self.remove_pools_newer_than_block(*range.start());
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<PoolCreated>>) -> Result<()> {
+        for event in events {
+            self.insert(event)?;
+        }
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        Ok(self
+            .pools
+            .values()
+            .map(|pool| pool.block_created)
+            .max()
+            .unwrap_or_default())
+    }
+
+    async fn persist_last_indexed_block(&mut self, _block: u64) -> Result<()> {
+        // storage is only in-memory so we don't need to persist anything here
+        Ok(())
+    }
+}
+
+/// Reads the full state of the specified pool at the specified block.
+pub async fn fetch_pool(web3: &Web3, pool: &RegisteredPool, block_number: u64) -> Result<PoolInfo> {
+    let block = BlockId::Number(block_number.into());
+    let abi = &UniswapV3Pool::raw_contract().interface.abi;
+    let slot0 = abi.function("slot0")?;
+    let liquidity = abi.function("liquidity")?;
+
+    let (state, decimals0, decimals1, liquidity_net) = futures::try_join!(
+        multicall(
+            web3,
+            vec![
+                encode_call(pool.address, slot0, &[]),
+                encode_call(pool.address, liquidity, &[]),
+            ],
+            block,
+        ),
+        ERC20::at(web3, pool.tokens.0)
+            .decimals()
+            .block(block)
+            .call()
+            .map_err(anyhow::Error::from),
+        ERC20::at(web3, pool.tokens.1)
+            .decimals()
+            .block(block)
+            .call()
+            .map_err(anyhow::Error::from),
+        fetch_liquidity_net(web3, pool, block),
+    )?;
+    let slot0 = slot0.decode_output(&state[0])?;
+    let liquidity = liquidity.decode_output(&state[1])?;
+
+    let sqrt_price = slot0
+        .first()
+        .cloned()
+        .and_then(AbiToken::into_uint)
+        .context("invalid slot0 price")?;
+    let tick = slot0
+        .get(1)
+        .cloned()
+        .and_then(AbiToken::into_int)
+        .context("invalid slot0 tick")?;
+    let liquidity = liquidity
+        .first()
+        .cloned()
+        .and_then(AbiToken::into_uint)
+        .context("invalid liquidity")?;
+
+    Ok(PoolInfo {
+        address: pool.address,
+        tokens: vec![
+            Token {
+                id: pool.tokens.0,
+                decimals: decimals0,
+            },
+            Token {
+                id: pool.tokens.1,
+                decimals: decimals1,
+            },
+        ],
+        state: PoolState {
+            sqrt_price,
+            liquidity,
+            tick: BigInt::from(tick.low_u32() as i32),
+            liquidity_net,
+            fee: Ratio::new(pool.fee, 1_000_000u32),
+        },
+        gas_stats: PoolStats {
+            mean_gas: POOL_SWAP_GAS_COST.into(),
+        },
+    })
+}
+
+/// Reads the net liquidity of all initialized ticks of a pool by scanning
+/// its tick bitmap.
+async fn fetch_liquidity_net(
+    web3: &Web3,
+    pool: &RegisteredPool,
+    block: BlockId,
+) -> Result<BTreeMap<BigInt, BigInt>> {
+    ensure!(pool.tick_spacing > 0, "invalid tick spacing");
+    let abi = &UniswapV3Pool::raw_contract().interface.abi;
+    let tick_bitmap = abi.function("tickBitmap")?;
+    let ticks = abi.function("ticks")?;
+
+    let words = bitmap_word_range(pool.tick_spacing);
+    let bitmaps = multicall(
+        web3,
+        words
+            .clone()
+            .map(|word| encode_call(pool.address, tick_bitmap, &[int_token(word.into())]))
+            .collect(),
+        block,
+    )
+    .await?;
+
+    let mut initialized = Vec::new();
+    for (word, bitmap) in words.zip(bitmaps) {
+        let bitmap = decode_word(&bitmap).context("invalid tick bitmap")?;
+        initialized.extend(initialized_ticks(word, bitmap, pool.tick_spacing));
+    }
+
+    let infos = multicall(
+        web3,
+        initialized
+            .iter()
+            .map(|tick| encode_call(pool.address, ticks, &[int_token(*tick)]))
+            .collect(),
+        block,
+    )
+    .await?;
+
+    initialized
+        .into_iter()
+        .zip(infos)
+        .filter_map(|(tick, info)| {
+            // `liquidityNet` is the second value returned by `ticks(int24)`.
+            let net = info
+                .get(32..64)
+                .map(|word| U256::from_big_endian(word).low_u128() as i128);
+            match net {
+                Some(0) => None,
+                Some(net) => Some(Ok((BigInt::from(tick), BigInt::from(net)))),
+                None => Some(Err(anyhow::anyhow!("invalid tick info"))),
+            }
+        })
+        .collect()
+}
+
+/// Returns the range of tick bitmap words that can contain initialized ticks
+/// for the specified tick spacing.
+pub(crate) fn bitmap_word_range(tick_spacing: i32) -> std::ops::RangeInclusive<i16> {
+    let word = |tick: i32| (tick / tick_spacing).div_euclid(256) as i16;
+    word(MIN_TICK)..=word(MAX_TICK)
+}
+
+/// Returns the initialized ticks of a bitmap word.
+pub(crate) fn initialized_ticks(
+    word: i16,
+    bitmap: U256,
+    tick_spacing: i32,
+) -> impl Iterator<Item = i32> {
+    (0..256)
+        .filter(move |bit| bitmap.bit(*bit))
+        .map(move |bit| (i32::from(word) * 256 + bit as i32) * tick_spacing)
+}
+
+fn encode_call(to: H160, function: &Function, params: &[AbiToken]) -> Call {
+    Call {
+        to,
+        data: function
+            .encode_input(params)
+            .expect("valid function parameters"),
+        ..Default::default()
+    }
+}
+
+/// Encodes a signed integer as an ABI token.
+fn int_token(value: i32) -> AbiToken {
+    let magnitude = U256::from(value.unsigned_abs());
+    AbiToken::Int(if value < 0 {
+        (!magnitude).overflowing_add(U256::one()).0
+    } else {
+        magnitude
+    })
+}
+
+fn decode_word(data: &[u8]) -> Option<U256> {
+    (data.len() == 32).then(|| U256::from_big_endian(data))
+}
+
+/// Executes the specified calls in multicall batches at the specified block.
+async fn multicall(web3: &Web3, calls: Vec<Call>, block: BlockId) -> Result<Vec<Vec<u8>>> {
+    let batches = future::join_all(calls.chunks(MULTICALL_BATCH_SIZE).map(|batch| async move {
+        web3.eth()
+            .multicall(batch.to_vec(), Default::default(), Some(block))
+            .await
+    }))
+    .await;
+
+    batches
+        .into_iter()
+        .flatten()
+        .map(|result| result.context("pool call reverted"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::EventMetadata};
+
+    #[test]
+    fn parses_pool_created_log() {
+        // The creation of the mainnet USDC/WETH 0.05% pool.
+        let log = RawLog {
+            topics: vec![
+                POOL_CREATED_TOPIC,
+                H256(hex!(
+                    "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+                )),
+                H256(hex!(
+                    "000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+                )),
+                H256(hex!(
+                    "00000000000000000000000000000000000000000000000000000000000001f4"
+                )),
+            ],
+            data: hex!(
+                "000000000000000000000000000000000000000000000000000000000000000a
+                 00000000000000000000000088e6a0c2ddd26feeb64f039a2c41296fcb3f5640"
+            )
+            .to_vec(),
+        };
+
+        assert_eq!(
+            PoolCreated::parse_log(log).unwrap(),
+            PoolCreated {
+                token0: H160(hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")),
+                token1: H160(hex!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")),
+                fee: 500,
+                tick_spacing: 10,
+                pool: H160(hex!("88e6a0c2ddd26feeb64f039a2c41296fcb3f5640")),
+            }
+        );
+    }
+
+    #[test]
+    fn bitmap_words_cover_all_ticks() {
+        assert_eq!(bitmap_word_range(1), -3466..=3465);
+        assert_eq!(bitmap_word_range(10), -347..=346);
+        assert_eq!(bitmap_word_range(60), -58..=57);
+        assert_eq!(bitmap_word_range(200), -18..=17);
+    }
+
+    #[test]
+    fn finds_initialized_ticks() {
+        let bitmap = U256::one() | (U256::one() << 5) | (U256::one() << 255);
+        assert_eq!(
+            initialized_ticks(0, bitmap, 10).collect::<Vec<_>>(),
+            [0, 50, 2550]
+        );
+        assert_eq!(
+            initialized_ticks(-1, bitmap, 60).collect::<Vec<_>>(),
+            [-15360, -15060, -60]
+        );
+    }
+
+    #[test]
+    fn encodes_negative_ints() {
+        assert_eq!(int_token(1), AbiToken::Int(U256::one()));
+        assert_eq!(int_token(-1), AbiToken::Int(U256::MAX));
+        assert_eq!(int_token(-256), AbiToken::Int(U256::MAX - 255));
+    }
+
+    #[tokio::test]
+    async fn registry_handles_reorgs() {
+        let event = |pool: u64, tokens: (u64, u64), block_number: u64| Event {
+            data: PoolCreated {
+                token0: H160::from_low_u64_be(tokens.0),
+                token1: H160::from_low_u64_be(tokens.1),
+                fee: 500,
+                tick_spacing: 10,
+                pool: H160::from_low_u64_be(pool),
+            },
+            meta: Some(EventMetadata {
+                block_number,
+                ..Default::default()
+            }),
+        };
+        let pair = |a: u64, b: u64| {
+            TokenPair::new(H160::from_low_u64_be(a), H160::from_low_u64_be(b)).unwrap()
+        };
+
+        let mut registry = PoolRegistry::default();
+        registry
+            .append_events(vec![
+                event(10, (1, 2), 1),
+                event(11, (1, 2), 2),
+                event(12, (2, 3), 3),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(registry.last_event_block().await.unwrap(), 3);
+        assert_eq!(
+            registry.pool_ids_for_token_pairs(&HashSet::from([pair(1, 2), pair(2, 3)])),
+            HashSet::from([
+                H160::from_low_u64_be(10),
+                H160::from_low_u64_be(11),
+                H160::from_low_u64_be(12),
+            ])
+        );
+
+        registry
+            .replace_events(
+                vec![event(13, (3, 4), 2)],
+                RangeInclusive::try_new(2, 3).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.pool_ids_for_token_pairs(&HashSet::from([pair(1, 2), pair(2, 3)])),
+            HashSet::from([H160::from_low_u64_be(10)])
+        );
+        assert_eq!(
+            registry.pool_ids_for_token_pairs(&HashSet::from([pair(3, 4)])),
+            HashSet::from([H160::from_low_u64_be(13)])
+        );
+    }
+}
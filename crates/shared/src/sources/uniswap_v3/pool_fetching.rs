@@ -2,13 +2,15 @@ use {
     super::{
         event_fetching::{RecentEventsCache, UniswapV3Event, UniswapV3PoolEventFetcher},
         graph_api::{PoolData, Token, UniV3SubgraphClient},
+        onchain::{self, FactoryContract, PoolRegistry},
     },
     crate::{
-        event_handling::{EventHandler, EventStoring, MAX_REORG_BLOCK_COUNT},
+        event_handling::{EventHandler, EventStoring, MAX_REORG_BLOCK_COUNT, indexing_start_block},
         maintenance::Maintaining,
         recent_block_cache::Block,
     },
     anyhow::{Context, Result},
+    contracts::IUniswapV3Factory,
     ethcontract::{Event, H160, U256},
     ethrpc::{
         Web3,
@@ -28,6 +30,10 @@ use {
     },
 };
 
+/// The average gas cost of a Uniswap V3 swap, as estimated by
+/// <https://dune.com/queries/1044812>.
+pub(super) const POOL_SWAP_GAS_COST: u64 = 108_163;
+
 #[async_trait::async_trait]
 pub trait PoolFetching: Send + Sync {
     async fn fetch(
@@ -101,7 +107,7 @@ impl TryFrom<PoolData> for PoolInfo {
                 fee: Ratio::new(pool.fee_tier.as_u32(), 1_000_000u32),
             },
             gas_stats: PoolStats {
-                mean_gas: U256::from(108_163), // as estimated by https://dune.com/queries/1044812
+                mean_gas: POOL_SWAP_GAS_COST.into(),
             },
         })
     }
@@ -118,10 +124,23 @@ struct PoolsCheckpoint {
     missing_pools: HashSet<H160>,
 }
 
+/// Where the existing pools and their initial state come from.
+enum PoolSource {
+    Subgraph {
+        graph_api: UniV3SubgraphClient,
+        /// H160 is pool id while TokenPair is a pair or tokens for each pool.
+        pools_by_token_pair: HashMap<TokenPair, HashSet<H160>>,
+    },
+    /// Pools are discovered by indexing the factory events and their state is
+    /// read from the pool contracts.
+    OnChain {
+        web3: Web3,
+        registry: tokio::sync::Mutex<EventHandler<FactoryContract, PoolRegistry>>,
+    },
+}
+
 struct PoolsCheckpointHandler {
-    graph_api: UniV3SubgraphClient,
-    /// H160 is pool id while TokenPair is a pair or tokens for each pool.
-    pools_by_token_pair: HashMap<TokenPair, HashSet<H160>>,
+    source: PoolSource,
     /// Pools state on a specific block number in history considered reorg safe
     pools_checkpoint: Mutex<PoolsCheckpoint>,
 }
@@ -178,21 +197,81 @@ impl PoolsCheckpointHandler {
         });
 
         Ok(Self {
-            graph_api,
-            pools_by_token_pair,
+            source: PoolSource::Subgraph {
+                graph_api,
+                pools_by_token_pair,
+            },
             pools_checkpoint,
         })
     }
 
+    /// Indexes all pools created by the specified factory. Unlike with the
+    /// subgraph, no pool state is fetched upfront: pools get initialized
+    /// lazily once they are requested.
+    pub async fn new_on_chain(
+        factory: IUniswapV3Factory,
+        web3: Web3,
+        block_retriever: Arc<dyn BlockRetrieving>,
+    ) -> Result<Self> {
+        let start = indexing_start_block(block_retriever.as_ref(), factory.raw_instance()).await?;
+        let mut registry = EventHandler::new(
+            block_retriever.clone(),
+            FactoryContract(factory),
+            PoolRegistry::default(),
+            start,
+        );
+        registry
+            .update_events()
+            .await
+            .context("failed to index Uniswap V3 pools")?;
+        let block_number = block_retriever
+            .current_block()
+            .await?
+            .number
+            .saturating_sub(MAX_REORG_BLOCK_COUNT);
+        tracing::debug!(
+            block = %block_number, pools = %registry.store().len(),
+            "initialized registered pools",
+        );
+
+        Ok(Self {
+            source: PoolSource::OnChain {
+                web3,
+                registry: tokio::sync::Mutex::new(registry),
+            },
+            pools_checkpoint: Mutex::new(PoolsCheckpoint {
+                block_number,
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Returns the ids of all pools trading any of the specified token pairs.
+    async fn pool_ids(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<H160> {
+        match &self.source {
+            PoolSource::Subgraph {
+                pools_by_token_pair,
+                ..
+            } => token_pairs
+                .iter()
+                .filter_map(|pair| pools_by_token_pair.get(pair))
+                .flatten()
+                .copied()
+                .collect(),
+            PoolSource::OnChain { registry, .. } => registry
+                .lock()
+                .await
+                .store()
+                .pool_ids_for_token_pairs(token_pairs),
+        }
+    }
+
     /// For a given list of token pairs, fetches the pools for the ones that
     /// exist in the checkpoint. For the ones that don't exist, flag as
     /// missing and expect to exist after the next maintenance run.
-    fn get(&self, token_pairs: &HashSet<TokenPair>) -> (HashMap<H160, PoolInfo>, u64) {
-        let mut pool_ids = token_pairs
-            .iter()
-            .filter_map(|pair| self.pools_by_token_pair.get(pair))
-            .flatten()
-            .peekable();
+    async fn get(&self, token_pairs: &HashSet<TokenPair>) -> (HashMap<H160, PoolInfo>, u64) {
+        let pool_ids = self.pool_ids(token_pairs).await;
+        let mut pool_ids = pool_ids.iter().peekable();
 
         tracing::trace!("get checkpoint for pool_ids: {:?}", pool_ids);
 
@@ -230,10 +309,7 @@ impl PoolsCheckpointHandler {
 
         let pool_ids = missing_pools.into_iter().collect::<Vec<_>>();
         let start = std::time::Instant::now();
-        let pools = self
-            .graph_api
-            .get_pools_with_ticks_by_ids(&pool_ids, block_number)
-            .await;
+        let pools = self.fetch_pools(&pool_ids, block_number).await;
         tracing::debug!(
             requested_pools = pool_ids.len(),
             time = ?start.elapsed(),
@@ -243,8 +319,8 @@ impl PoolsCheckpointHandler {
 
         let mut checkpoint = self.pools_checkpoint.lock().unwrap();
         for pool in pools? {
-            checkpoint.missing_pools.remove(&pool.id);
-            checkpoint.pools.insert(pool.id, pool.try_into()?);
+            checkpoint.missing_pools.remove(&pool.address);
+            checkpoint.pools.insert(pool.address, pool);
         }
 
         tracing::debug!("number of cached pools is {}", checkpoint.pools.len());
@@ -256,6 +332,68 @@ impl PoolsCheckpointHandler {
         }
         Ok(())
     }
+
+    /// Fetches the state and ticks of the specified pools at the specified
+    /// block.
+    async fn fetch_pools(&self, pool_ids: &[H160], block_number: u64) -> Result<Vec<PoolInfo>> {
+        match &self.source {
+            PoolSource::Subgraph { graph_api, .. } => graph_api
+                .get_pools_with_ticks_by_ids(pool_ids, block_number)
+                .await?
+                .into_iter()
+                .map(PoolInfo::try_from)
+                .collect(),
+            PoolSource::OnChain { web3, registry } => {
+                let pools = {
+                    let registry = registry.lock().await;
+                    pool_ids
+                        .iter()
+                        .filter_map(|id| registry.store().get(id).cloned())
+                        .collect::<Vec<_>>()
+                };
+                let results = futures::future::join_all(
+                    pools
+                        .iter()
+                        .map(|pool| onchain::fetch_pool(web3, pool, block_number)),
+                )
+                .await;
+                Ok(pools
+                    .iter()
+                    .zip(results)
+                    .filter_map(|(pool, result)| {
+                        // pools created after the checkpoint block can't be
+                        // fetched yet, they will be once the checkpoint moves
+                        result
+                            .inspect_err(|err| {
+                                tracing::debug!(pool = ?pool.address, ?err, "failed to fetch pool")
+                            })
+                            .ok()
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    async fn run_maintenance(&self) -> Result<()> {
+        let pools = async {
+            match &self.source {
+                PoolSource::Subgraph { .. } => Ok(()),
+                PoolSource::OnChain { registry, .. } => registry.run_maintenance().await,
+            }
+        };
+        let (result1, result2) = futures::join!(pools, self.update_missing_pools());
+        result1?;
+        // since failure in updating the missing pools is not critical for
+        // UniswapV3PoolFetcher maintenance and future liquidity fetch calls,
+        // then there is no need to return error
+        if let Err(err) = result2 {
+            tracing::warn!(
+                "UniswapV3PoolFetcher failed to update missing pools: {}",
+                err
+            );
+        }
+        Ok(())
+    }
 }
 
 pub struct UniswapV3PoolFetcher {
@@ -277,7 +415,29 @@ impl UniswapV3PoolFetcher {
         let web3 = ethrpc::instrumented::instrument_with_label(&web3, "uniswapV3".into());
         let checkpoint =
             PoolsCheckpointHandler::new(subgraph_url, client, max_pools_to_initialize).await?;
+        Self::with_checkpoint(checkpoint, web3, block_retriever).await
+    }
+
+    /// Creates a pool fetcher that discovers pools by indexing the
+    /// `PoolCreated` events of the specified factory instead of relying on the
+    /// Uniswap V3 subgraph.
+    pub async fn new_on_chain(
+        factory: IUniswapV3Factory,
+        web3: Web3,
+        block_retriever: Arc<dyn BlockRetrieving>,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "uniswapV3".into());
+        let checkpoint =
+            PoolsCheckpointHandler::new_on_chain(factory, web3.clone(), block_retriever.clone())
+                .await?;
+        Self::with_checkpoint(checkpoint, web3, block_retriever).await
+    }
 
+    async fn with_checkpoint(
+        checkpoint: PoolsCheckpointHandler,
+        web3: Web3,
+        block_retriever: Arc<dyn BlockRetrieving>,
+    ) -> Result<Self> {
         let init_block = checkpoint.pools_checkpoint.lock().unwrap().block_number;
         let init_block = block_retriever.block(init_block).await?;
 
@@ -374,7 +534,7 @@ impl PoolFetching for UniswapV3PoolFetcher {
 
         // this is the only place where this function uses checkpoint - no data racing
         // between maintenance
-        let (mut checkpoint, checkpoint_block_number) = self.checkpoint.get(token_pairs);
+        let (mut checkpoint, checkpoint_block_number) = self.checkpoint.get(token_pairs).await;
 
         if block_number > checkpoint_block_number {
             let block_range = RangeInclusive::try_new(checkpoint_block_number + 1, block_number)?;
@@ -472,18 +632,10 @@ impl Maintaining for UniswapV3PoolFetcher {
     async fn run_maintenance(&self) -> Result<()> {
         let (result1, result2) = futures::join!(
             self.events.run_maintenance(),
-            self.checkpoint.update_missing_pools()
+            self.checkpoint.run_maintenance()
         );
         result1?;
-        // since failure in updating the missing pools is not critical for
-        // UniswapV3PoolFetcher maintenance and future liquidity fetch calls,
-        // then there is no need to return error
-        if let Err(err) = result2 {
-            tracing::warn!(
-                "UniswapV3PoolFetcher failed to update missing pools: {}",
-                err
-            );
-        }
+        result2?;
         self.move_checkpoint_to_future().await
     }
 
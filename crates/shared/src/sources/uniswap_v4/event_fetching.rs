@@ -0,0 +1,473 @@
+use {
+    super::pool_fetching::{PoolId, PoolKey},
+    crate::event_handling::{EventRetrieving, EventStoring},
+    anyhow::{Context, Result},
+    contracts::UniswapV4PoolManager,
+    ethcontract::{
+        Event,
+        H160,
+        H256,
+        RawLog,
+        U256,
+        common::abi::Error,
+        contract::ParseLog,
+        dyns::DynAllEventsBuilder,
+        errors::ExecutionError,
+    },
+    ethrpc::block_stream::RangeInclusive,
+    hex_literal::hex,
+    model::TokenPair,
+    std::collections::{HashMap, HashSet},
+};
+
+const INITIALIZE_TOPIC: H256 = H256(hex!(
+    "dd466e674ea557f56295e2d0218a125ea4b4f0f6f3307b95f85e6110838d6438"
+));
+const MODIFY_LIQUIDITY_TOPIC: H256 = H256(hex!(
+    "f208f4912782fd25c7f114ca3723a2d5dd6f3bcc3ac8db5af63baa85f711d5ec"
+));
+const SWAP_TOPIC: H256 = H256(hex!(
+    "40e9cecb9f5f1f1c5b9c97dec2917b7ee92e57ba5563708daca94dd84ad7112f"
+));
+
+/// A pool initialization event emitted by the pool manager.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Initialize {
+    pub id: PoolId,
+    pub key: PoolKey,
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+}
+
+impl ParseLog for Initialize {
+    fn parse_log(log: RawLog) -> Result<Self, ExecutionError> {
+        if log.topics.len() != 4 || log.topics[0] != INITIALIZE_TOPIC || log.data.len() != 5 * 32 {
+            return Err(invalid_data());
+        }
+
+        Ok(Self {
+            id: log.topics[1],
+            key: PoolKey {
+                currency0: address(log.topics[2].as_bytes()),
+                currency1: address(log.topics[3].as_bytes()),
+                fee: uint(word(&log.data, 0)).low_u32(),
+                tick_spacing: int(word(&log.data, 1)) as i32,
+                hooks: address(word(&log.data, 2)),
+            },
+            sqrt_price_x96: uint(word(&log.data, 3)),
+            tick: int(word(&log.data, 4)) as i32,
+        })
+    }
+}
+
+/// A change of the liquidity of a pool.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ModifyLiquidity {
+    pub id: PoolId,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    /// The liquidity added to (positive) or removed from (negative) the
+    /// position.
+    pub liquidity_delta: i128,
+}
+
+/// A swap against a pool.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Swap {
+    pub id: PoolId,
+    pub sqrt_price_x96: U256,
+    pub liquidity: u128,
+    pub tick: i32,
+}
+
+/// The pool manager events that change the state of an existing pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UniswapV4Event {
+    ModifyLiquidity(ModifyLiquidity),
+    Swap(Swap),
+}
+
+impl UniswapV4Event {
+    /// Returns the id of the pool the event belongs to.
+    pub fn pool_id(&self) -> PoolId {
+        match self {
+            Self::ModifyLiquidity(event) => event.id,
+            Self::Swap(event) => event.id,
+        }
+    }
+}
+
+impl ParseLog for UniswapV4Event {
+    fn parse_log(log: RawLog) -> Result<Self, ExecutionError> {
+        if log.topics.len() != 3 {
+            return Err(invalid_data());
+        }
+
+        let topic = log.topics[0];
+        if topic == MODIFY_LIQUIDITY_TOPIC && log.data.len() == 4 * 32 {
+            Ok(Self::ModifyLiquidity(ModifyLiquidity {
+                id: log.topics[1],
+                tick_lower: int(word(&log.data, 0)) as i32,
+                tick_upper: int(word(&log.data, 1)) as i32,
+                liquidity_delta: int(word(&log.data, 2)),
+            }))
+        } else if topic == SWAP_TOPIC && log.data.len() == 6 * 32 {
+            Ok(Self::Swap(Swap {
+                id: log.topics[1],
+                sqrt_price_x96: uint(word(&log.data, 2)),
+                liquidity: uint(word(&log.data, 3)).low_u128(),
+                tick: int(word(&log.data, 4)) as i32,
+            }))
+        } else {
+            Err(invalid_data())
+        }
+    }
+}
+
+fn invalid_data() -> ExecutionError {
+    ExecutionError::from(Error::InvalidData)
+}
+
+fn word(data: &[u8], index: usize) -> &[u8] {
+    &data[index * 32..(index + 1) * 32]
+}
+
+fn address(word: &[u8]) -> H160 {
+    H160::from_slice(&word[12..])
+}
+
+fn uint(word: &[u8]) -> U256 {
+    U256::from_big_endian(word)
+}
+
+/// Decodes a signed integer of at most 128 bits. ABI encoding sign extends
+/// values to 256 bits, so the lower bits are the two's complement
+/// representation of the value.
+fn int(word: &[u8]) -> i128 {
+    uint(word).low_u128() as i128
+}
+
+/// Retrieves the `Initialize` events of a Uniswap V4 pool manager.
+pub struct InitializeEventFetcher(pub UniswapV4PoolManager);
+
+impl EventRetrieving for InitializeEventFetcher {
+    type Event = Initialize;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = DynAllEventsBuilder::new(
+            self.0.raw_instance().web3(),
+            self.0.address(),
+            self.0.deployment_information(),
+        );
+        events.filter = events.filter.topic0(INITIALIZE_TOPIC.into());
+        events
+    }
+
+    fn block_page_size(&self) -> u64 {
+        // Pools get initialized rarely, so we can afford to query large block
+        // ranges at once.
+        5_000
+    }
+}
+
+/// Retrieves the pool manager events that change the state of existing pools.
+pub struct UniswapV4PoolEventFetcher(pub UniswapV4PoolManager);
+
+impl EventRetrieving for UniswapV4PoolEventFetcher {
+    type Event = UniswapV4Event;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = DynAllEventsBuilder::new(
+            self.0.raw_instance().web3(),
+            self.0.address(),
+            self.0.deployment_information(),
+        );
+        events.filter = events
+            .filter
+            .topic0(vec![MODIFY_LIQUIDITY_TOPIC, SWAP_TOPIC].into());
+        events
+    }
+}
+
+/// A pool initialized in the pool manager.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegisteredPool {
+    pub id: PoolId,
+    pub key: PoolKey,
+    /// The pool's currencies with the native token replaced by its wrapped
+    /// counterpart, since that is what the settlement contract holds.
+    pub tokens: (H160, H160),
+    pub block_initialized: u64,
+}
+
+/// In-memory index of all pools initialized in a Uniswap V4 pool manager.
+#[derive(Debug)]
+pub struct PoolRegistry {
+    weth: H160,
+    pools: HashMap<PoolId, RegisteredPool>,
+    pools_by_token_pair: HashMap<TokenPair, HashSet<PoolId>>,
+}
+
+impl PoolRegistry {
+    pub fn new(weth: H160) -> Self {
+        Self {
+            weth,
+            pools: Default::default(),
+            pools_by_token_pair: Default::default(),
+        }
+    }
+
+    /// Returns the ids of all pools trading any of the specified pairs.
+    pub fn pool_ids_for_token_pairs(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<PoolId> {
+        token_pairs
+            .iter()
+            .filter_map(|pair| self.pools_by_token_pair.get(pair))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Returns the registered pool with the specified id.
+    pub fn get(&self, id: &PoolId) -> Option<&RegisteredPool> {
+        self.pools.get(id)
+    }
+
+    /// Returns the number of registered pools.
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Returns whether no pools are registered.
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    fn insert(&mut self, event: Event<Initialize>) -> Result<()> {
+        let block_initialized = event.meta.context("event meta is empty")?.block_number;
+        let Initialize { id, key, .. } = event.data;
+        let tokens = key.tokens(self.weth);
+        // Pools between the native token and its wrapped counterpart can't
+        // be traded by the settlement contract.
+        let Some(pair) = TokenPair::new(tokens.0, tokens.1) else {
+            return Ok(());
+        };
+
+        self.pools_by_token_pair.entry(pair).or_default().insert(id);
+        self.pools.insert(
+            id,
+            RegisteredPool {
+                id,
+                key,
+                tokens,
+                block_initialized,
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes all pools initialized in the specified block or later.
+    fn remove_pools_newer_than_block(&mut self, block: u64) {
+        self.pools.retain(|_, pool| pool.block_initialized < block);
+        for pools in self.pools_by_token_pair.values_mut() {
+            pools.retain(|id| self.pools.contains_key(id));
+        }
+        self.pools_by_token_pair
+            .retain(|_, pools| !pools.is_empty());
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<Initialize> for PoolRegistry {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<Initialize>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        self.remove_pools_newer_than_block(*range.start());
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<Initialize>>) -> Result<()> {
+        for event in events {
+            self.insert(event)?;
+        }
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        Ok(self
+            .pools
+            .values()
+            .map(|pool| pool.block_initialized)
+            .max()
+            .unwrap_or_default())
+    }
+
+    async fn persist_last_indexed_block(&mut self, _block: u64) -> Result<()> {
+        // storage is only in-memory so we don't need to persist anything here
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::EventMetadata};
+
+    #[test]
+    fn parses_initialize_log() {
+        // The key of the mainnet ETH/USDC 0.05% pool, initialized at an
+        // arbitrary price.
+        let log = RawLog {
+            topics: vec![
+                INITIALIZE_TOPIC,
+                H256(hex!(
+                    "21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"
+                )),
+                H256::zero(),
+                H256(hex!(
+                    "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+                )),
+            ],
+            data: hex!(
+                "00000000000000000000000000000000000000000000000000000000000001f4
+                 000000000000000000000000000000000000000000000000000000000000000a
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 000000000000000000000000000000000000000000043b0aac2f5ea14d6f0f8f
+                 fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffd0c2d"
+            )
+            .to_vec(),
+        };
+
+        assert_eq!(
+            Initialize::parse_log(log).unwrap(),
+            Initialize {
+                id: H256(hex!(
+                    "21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"
+                )),
+                key: PoolKey {
+                    currency0: H160::zero(),
+                    currency1: H160(hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")),
+                    fee: 500,
+                    tick_spacing: 10,
+                    hooks: H160::zero(),
+                },
+                sqrt_price_x96: U256::from_str_radix("43b0aac2f5ea14d6f0f8f", 16).unwrap(),
+                tick: -193491,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_pool_events() {
+        let id = H256::repeat_byte(1);
+        let sender = H256::from(H160::repeat_byte(2));
+
+        let modify_liquidity = RawLog {
+            topics: vec![MODIFY_LIQUIDITY_TOPIC, id, sender],
+            data: hex!(
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff6
+                 0000000000000000000000000000000000000000000000000000000000000014
+                 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff9c
+                 0000000000000000000000000000000000000000000000000000000000000000"
+            )
+            .to_vec(),
+        };
+        assert_eq!(
+            UniswapV4Event::parse_log(modify_liquidity).unwrap(),
+            UniswapV4Event::ModifyLiquidity(ModifyLiquidity {
+                id,
+                tick_lower: -10,
+                tick_upper: 20,
+                liquidity_delta: -100,
+            })
+        );
+
+        let swap = RawLog {
+            topics: vec![SWAP_TOPIC, id, sender],
+            data: hex!(
+                "0000000000000000000000000000000000000000000000000000000000000064
+                 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff9c
+                 0000000000000000000000000000000000000000000000000000000000000003
+                 0000000000000000000000000000000000000000000000000000000000000004
+                 fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffb
+                 00000000000000000000000000000000000000000000000000000000000001f4"
+            )
+            .to_vec(),
+        };
+        assert_eq!(
+            UniswapV4Event::parse_log(swap).unwrap(),
+            UniswapV4Event::Swap(Swap {
+                id,
+                sqrt_price_x96: 3.into(),
+                liquidity: 4,
+                tick: -5,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn registry_maps_native_token_and_handles_reorgs() {
+        let weth = H160::from_low_u64_be(1);
+        let event = |id: u8, currencies: (u64, u64), block_number: u64| Event {
+            data: Initialize {
+                id: H256::repeat_byte(id),
+                key: PoolKey {
+                    currency0: H160::from_low_u64_be(currencies.0),
+                    currency1: H160::from_low_u64_be(currencies.1),
+                    fee: 500,
+                    tick_spacing: 10,
+                    hooks: H160::zero(),
+                },
+                sqrt_price_x96: 1.into(),
+                tick: 0,
+            },
+            meta: Some(EventMetadata {
+                block_number,
+                ..Default::default()
+            }),
+        };
+        let pair = |a: u64, b: u64| {
+            TokenPair::new(H160::from_low_u64_be(a), H160::from_low_u64_be(b)).unwrap()
+        };
+
+        let mut registry = PoolRegistry::new(weth);
+        registry
+            .append_events(vec![
+                // native token pool
+                event(1, (0, 2), 1),
+                event(2, (1, 2), 2),
+                // native/wrapped native pool
+                event(3, (0, 1), 2),
+                event(4, (2, 3), 3),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.last_event_block().await.unwrap(), 3);
+        assert_eq!(
+            registry.get(&H256::repeat_byte(1)).unwrap().tokens,
+            (weth, H160::from_low_u64_be(2))
+        );
+        assert_eq!(
+            registry.pool_ids_for_token_pairs(&HashSet::from([pair(1, 2)])),
+            HashSet::from([H256::repeat_byte(1), H256::repeat_byte(2)])
+        );
+
+        registry
+            .replace_events(
+                vec![event(5, (3, 4), 2)],
+                RangeInclusive::try_new(2, 3).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.pool_ids_for_token_pairs(&HashSet::from([pair(1, 2), pair(2, 3)])),
+            HashSet::from([H256::repeat_byte(1)])
+        );
+        assert_eq!(
+            registry.pool_ids_for_token_pairs(&HashSet::from([pair(3, 4)])),
+            HashSet::from([H256::repeat_byte(5)])
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Uniswap V4 baseline liquidity source implementation.
+//!
+//! All Uniswap V4 pools live in a single `PoolManager` contract and are
+//! identified by the hash of their [`pool_fetching::PoolKey`]. Pools are
+//! discovered by indexing the `Initialize` events of the pool manager and
+//! their state is read straight from its storage.
+pub mod event_fetching;
+pub mod pool_fetching;
+mod state;
@@ -0,0 +1,590 @@
+use {
+    super::{
+        event_fetching::{
+            InitializeEventFetcher,
+            PoolRegistry,
+            UniswapV4Event,
+            UniswapV4PoolEventFetcher,
+        },
+        state,
+    },
+    crate::{
+        event_handling::{EventHandler, EventStoring, MAX_REORG_BLOCK_COUNT, indexing_start_block},
+        maintenance::Maintaining,
+        recent_block_cache::Block,
+        sources::uniswap_v3::{
+            event_fetching::RecentEventsCache,
+            pool_fetching::{PoolState, PoolStats},
+        },
+    },
+    anyhow::{Context, Result},
+    contracts::UniswapV4PoolManager,
+    ethcontract::{Event, H160, H256, U256, common::abi::Token},
+    ethrpc::{
+        Web3,
+        block_stream::{BlockRetrieving, RangeInclusive},
+    },
+    model::TokenPair,
+    num::{BigInt, Zero},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+    },
+    web3::signing::keccak256,
+};
+
+/// A rough estimate of the gas cost of a Uniswap V4 swap through the Universal
+/// Router. This includes transferring the input tokens to the router and
+/// sweeping any remaining input tokens back to the settlement contract.
+pub const POOL_SWAP_GAS_COST: u64 = 150_000;
+
+/// The `fee` value of a pool key signalling that the LP fee is set
+/// dynamically by the pool's hooks.
+pub const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+
+/// Hook permissions are encoded in the lowest bits of the hooks address.
+/// These are the permissions that allow hooks to run custom logic around
+/// swaps.
+const BEFORE_SWAP_FLAG: u16 = 1 << 7;
+const AFTER_SWAP_FLAG: u16 = 1 << 6;
+const BEFORE_SWAP_RETURNS_DELTA_FLAG: u16 = 1 << 3;
+const AFTER_SWAP_RETURNS_DELTA_FLAG: u16 = 1 << 2;
+
+/// Uniswap V4 pools are identified by the hash of their [`PoolKey`].
+pub type PoolId = H256;
+
+/// The immutable parameters identifying a Uniswap V4 pool.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PoolKey {
+    /// The lower currency of the pool, where the zero address is the native
+    /// token.
+    pub currency0: H160,
+    pub currency1: H160,
+    /// The LP fee in hundredths of a basis point, or [`DYNAMIC_FEE_FLAG`].
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: H160,
+}
+
+impl PoolKey {
+    /// Computes the id of the pool.
+    pub fn id(&self) -> PoolId {
+        H256(keccak256(&ethcontract::common::abi::encode(&[
+            Token::Address(self.currency0),
+            Token::Address(self.currency1),
+            Token::Uint(self.fee.into()),
+            Token::Int(signed(self.tick_spacing)),
+            Token::Address(self.hooks),
+        ])))
+    }
+
+    /// Returns the pool's currencies with the native token replaced by the
+    /// specified wrapped native token.
+    pub fn tokens(&self, weth: H160) -> (H160, H160) {
+        let token = |currency: H160| if currency.is_zero() { weth } else { currency };
+        (token(self.currency0), token(self.currency1))
+    }
+
+    /// Returns whether the LP fee is set dynamically by the pool's hooks.
+    pub fn has_dynamic_fee(&self) -> bool {
+        self.fee == DYNAMIC_FEE_FLAG
+    }
+
+    /// Returns whether the pool's hooks can influence the outcome of swaps.
+    /// For such pools the swap amounts can't be computed from the pool state
+    /// alone, so solvers need to decide whether they want to use them.
+    pub fn hooks_affect_swaps(&self) -> bool {
+        let flags = u16::from_be_bytes([self.hooks[18], self.hooks[19]]);
+        self.has_dynamic_fee()
+            || flags
+                & (BEFORE_SWAP_FLAG
+                    | AFTER_SWAP_FLAG
+                    | BEFORE_SWAP_RETURNS_DELTA_FLAG
+                    | AFTER_SWAP_RETURNS_DELTA_FLAG)
+                != 0
+    }
+}
+
+/// Encodes a signed integer as an ABI word.
+fn signed(value: i32) -> U256 {
+    let magnitude = U256::from(value.unsigned_abs());
+    if value < 0 {
+        (!magnitude).overflowing_add(U256::one()).0
+    } else {
+        magnitude
+    }
+}
+
+#[async_trait::async_trait]
+pub trait PoolFetching: Send + Sync {
+    async fn fetch(
+        &self,
+        token_pairs: &HashSet<TokenPair>,
+        at_block: Block,
+    ) -> Result<Vec<PoolInfo>>;
+}
+
+/// Pool data in a format prepared for solvers.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolInfo {
+    pub id: PoolId,
+    pub key: PoolKey,
+    /// The pool's currencies with the native token replaced by its wrapped
+    /// counterpart.
+    pub tokens: (H160, H160),
+    pub state: PoolState,
+    pub gas_stats: PoolStats,
+}
+
+#[derive(Default)]
+struct PoolsCheckpoint {
+    /// Pools state.
+    pools: HashMap<PoolId, PoolInfo>,
+    /// Block number for which `pools` field was populated.
+    block_number: u64,
+    /// Pools that don't exist in `pools` field, therefore need to be
+    /// initialized and moved to `pools` in the next maintainance run
+    missing_pools: HashSet<PoolId>,
+}
+
+/// Fetches Uniswap V4 pools by indexing the `Initialize` events of the pool
+/// manager and reading the state of the requested pools from its storage.
+///
+/// Like for Uniswap V3, the pool states are kept at a checkpoint block that is
+/// considered reorg safe and the events of the more recent blocks are applied
+/// on top of them when fetching.
+pub struct UniswapV4PoolFetcher {
+    manager: UniswapV4PoolManager,
+    registry: tokio::sync::Mutex<EventHandler<InitializeEventFetcher, PoolRegistry>>,
+    /// Pools state on a specific block number in history considered reorg safe
+    checkpoint: Mutex<PoolsCheckpoint>,
+    /// Recent events used on top of the checkpoint to get the `latest_block`
+    /// pools state.
+    events: tokio::sync::Mutex<
+        EventHandler<UniswapV4PoolEventFetcher, RecentEventsCache<UniswapV4Event>>,
+    >,
+}
+
+impl UniswapV4PoolFetcher {
+    /// Indexes all pools of the specified pool manager. No pool state is
+    /// fetched upfront: pools get initialized lazily once they are requested.
+    pub async fn new(
+        manager: UniswapV4PoolManager,
+        weth: H160,
+        web3: Web3,
+        block_retriever: Arc<dyn BlockRetrieving>,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "uniswapV4".into());
+        let manager = UniswapV4PoolManager::with_deployment_info(
+            &web3,
+            manager.address(),
+            manager.deployment_information(),
+        );
+        let start = indexing_start_block(block_retriever.as_ref(), manager.raw_instance()).await?;
+        let mut registry = EventHandler::new(
+            block_retriever.clone(),
+            InitializeEventFetcher(manager.clone()),
+            PoolRegistry::new(weth),
+            start,
+        );
+        registry
+            .update_events()
+            .await
+            .context("failed to index Uniswap V4 pools")?;
+
+        let block_number = block_retriever
+            .current_block()
+            .await?
+            .number
+            .saturating_sub(MAX_REORG_BLOCK_COUNT);
+        tracing::debug!(
+            block = %block_number, pools = %registry.store().len(),
+            "initialized registered pools",
+        );
+        let init_block = block_retriever.block(block_number).await?;
+        let events = EventHandler::new(
+            block_retriever,
+            UniswapV4PoolEventFetcher(manager.clone()),
+            RecentEventsCache::default(),
+            Some(init_block),
+        );
+
+        Ok(Self {
+            manager,
+            registry: tokio::sync::Mutex::new(registry),
+            checkpoint: Mutex::new(PoolsCheckpoint {
+                block_number,
+                ..Default::default()
+            }),
+            events: tokio::sync::Mutex::new(events),
+        })
+    }
+
+    /// For a given list of token pairs, returns the pools that exist in the
+    /// checkpoint. The ones that don't exist are flagged as missing and
+    /// expected to exist after the next maintenance run.
+    async fn checkpoint(
+        &self,
+        token_pairs: &HashSet<TokenPair>,
+    ) -> (HashMap<PoolId, PoolInfo>, u64) {
+        let pool_ids = self
+            .registry
+            .lock()
+            .await
+            .store()
+            .pool_ids_for_token_pairs(token_pairs);
+
+        let mut checkpoint = self.checkpoint.lock().unwrap();
+        let mut pools = HashMap::new();
+        for id in pool_ids {
+            match checkpoint.pools.get(&id) {
+                Some(pool) => {
+                    pools.insert(id, pool.clone());
+                }
+                None => {
+                    checkpoint.missing_pools.insert(id);
+                }
+            }
+        }
+        (pools, checkpoint.block_number)
+    }
+
+    /// Fetches the state of missing pools and moves them from `missing_pools`
+    /// to `pools`.
+    async fn update_missing_pools(&self) -> Result<()> {
+        let (missing_pools, block_number) = {
+            let checkpoint = self.checkpoint.lock().unwrap();
+            if checkpoint.missing_pools.is_empty() {
+                return Ok(());
+            }
+            (checkpoint.missing_pools.clone(), checkpoint.block_number)
+        };
+        tracing::debug!("currently missing pools are {:?}", missing_pools);
+
+        let pools = {
+            let registry = self.registry.lock().await;
+            missing_pools
+                .iter()
+                .filter_map(|id| registry.store().get(id).cloned())
+                .collect::<Vec<_>>()
+        };
+        let results = futures::future::join_all(
+            pools
+                .iter()
+                .map(|pool| state::fetch_pool(&self.manager, pool, block_number)),
+        )
+        .await;
+
+        let mut checkpoint = self.checkpoint.lock().unwrap();
+        for (pool, result) in pools.iter().zip(results) {
+            match result {
+                Ok(pool) => {
+                    checkpoint.missing_pools.remove(&pool.id);
+                    checkpoint.pools.insert(pool.id, pool);
+                }
+                // pools initialized after the checkpoint block can't be
+                // fetched yet, they will be once the checkpoint moves
+                Err(err) => tracing::debug!(pool = ?pool.id, ?err, "failed to fetch pool"),
+            }
+        }
+
+        tracing::debug!("number of cached pools is {}", checkpoint.pools.len());
+        if !checkpoint.missing_pools.is_empty() {
+            tracing::warn!(
+                "not all missing pools updated: {:?}",
+                checkpoint.missing_pools
+            );
+        }
+        Ok(())
+    }
+
+    /// Moves the checkpoint to the block `latest_block - MAX_REORG_BLOCK_COUNT`
+    async fn move_checkpoint_to_future(&self) -> Result<()> {
+        let last_event_block = self.events.lock().await.store().last_event_block().await?;
+        let old_checkpoint_block = self.checkpoint.lock().unwrap().block_number;
+        let new_checkpoint_block = std::cmp::max(
+            last_event_block.saturating_sub(MAX_REORG_BLOCK_COUNT),
+            old_checkpoint_block,
+        );
+
+        if new_checkpoint_block > old_checkpoint_block {
+            {
+                let block_range =
+                    RangeInclusive::try_new(old_checkpoint_block + 1, new_checkpoint_block)?;
+                let events = self.events.lock().await.store().get_events(block_range);
+                let mut checkpoint = self.checkpoint.lock().unwrap();
+                append_events(&mut checkpoint.pools, events);
+                checkpoint.block_number = new_checkpoint_block;
+                tracing::debug!(
+                    "checkpoint block number updated to {}",
+                    checkpoint.block_number
+                );
+            }
+
+            // clear events with block number lower than `new_checkpoint_block`
+            self.events
+                .lock()
+                .await
+                .store_mut()
+                .remove_events_older_than_block(new_checkpoint_block);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl PoolFetching for UniswapV4PoolFetcher {
+    async fn fetch(
+        &self,
+        token_pairs: &HashSet<TokenPair>,
+        at_block: Block,
+    ) -> Result<Vec<PoolInfo>> {
+        let block_number = match at_block {
+            Block::Recent => self
+                .events
+                .lock()
+                .await
+                .store()
+                .last_event_block()
+                .await
+                .unwrap_or_default(),
+            Block::Number(number) => number,
+        };
+
+        // sanity check if events are up to date
+        let last_handled_block = self
+            .events
+            .lock()
+            .await
+            .last_handled_block()
+            .unwrap_or_default()
+            .0;
+        if block_number > last_handled_block {
+            tracing::debug!(
+                "can't get liquidity for block {} since the last handled block is {}",
+                block_number,
+                last_handled_block
+            );
+            if let Err(err) = self.events.run_maintenance().await {
+                tracing::debug!("failed to update events on fetch because {}", err);
+                return Ok(Default::default());
+            }
+        }
+
+        let (mut pools, checkpoint_block_number) = self.checkpoint(token_pairs).await;
+        if block_number > checkpoint_block_number {
+            let block_range = RangeInclusive::try_new(checkpoint_block_number + 1, block_number)?;
+            let events = self.events.lock().await.store().get_events(block_range);
+            append_events(&mut pools, events);
+        }
+
+        // return only pools which current liquidity is positive
+        Ok(pools
+            .into_values()
+            .filter(|pool| pool.state.liquidity > U256::zero())
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for UniswapV4PoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        let (result1, result2, result3) = futures::join!(
+            self.registry.run_maintenance(),
+            self.events.run_maintenance(),
+            self.update_missing_pools(),
+        );
+        result1?;
+        result2?;
+        // since failure in updating the missing pools is not critical for
+        // maintenance and future liquidity fetch calls, then there is no
+        // need to return error
+        if let Err(err) = result3 {
+            tracing::warn!(
+                "UniswapV4PoolFetcher failed to update missing pools: {}",
+                err
+            );
+        }
+        self.move_checkpoint_to_future().await
+    }
+
+    fn name(&self) -> &str {
+        "UniswapV4PoolFetcher"
+    }
+}
+
+/// For a given checkpoint, append events to get a new checkpoint
+fn append_events(pools: &mut HashMap<PoolId, PoolInfo>, events: Vec<Event<UniswapV4Event>>) {
+    for event in events {
+        let Some(pool) = pools
+            .get_mut(&event.data.pool_id())
+            .map(|pool| &mut pool.state)
+        else {
+            continue;
+        };
+        match event.data {
+            UniswapV4Event::ModifyLiquidity(modify) => {
+                let delta = BigInt::from(modify.liquidity_delta);
+                let tick_lower = BigInt::from(modify.tick_lower);
+                let tick_upper = BigInt::from(modify.tick_upper);
+
+                // liquidity tracks the liquidity on recent tick,
+                // only need to update it if the position includes the recent tick.
+                if tick_lower <= pool.tick && pool.tick < tick_upper {
+                    let liquidity = modify.liquidity_delta.unsigned_abs();
+                    if modify.liquidity_delta >= 0 {
+                        pool.liquidity += liquidity.into();
+                    } else {
+                        pool.liquidity = pool.liquidity.saturating_sub(liquidity.into());
+                    }
+                }
+
+                *pool.liquidity_net.entry(tick_lower.clone()).or_default() += &delta;
+                *pool.liquidity_net.entry(tick_upper.clone()).or_default() -= &delta;
+
+                // remove 0 entries to save bandwidth
+                for tick in [tick_lower, tick_upper] {
+                    if pool.liquidity_net[&tick].is_zero() {
+                        pool.liquidity_net.remove(&tick);
+                    }
+                }
+            }
+            UniswapV4Event::Swap(swap) => {
+                pool.tick = BigInt::from(swap.tick);
+                pool.liquidity = swap.liquidity.into();
+                pool.sqrt_price = swap.sqrt_price_x96;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::sources::uniswap_v4::event_fetching::{ModifyLiquidity, Swap},
+        ethcontract::EventMetadata,
+        hex_literal::hex,
+        std::collections::BTreeMap,
+    };
+
+    #[test]
+    fn computes_pool_id() {
+        // The mainnet ETH/USDC 0.05% pool.
+        let key = PoolKey {
+            currency0: H160::zero(),
+            currency1: H160(hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")),
+            fee: 500,
+            tick_spacing: 10,
+            hooks: H160::zero(),
+        };
+        assert_eq!(
+            key.id(),
+            H256(hex!(
+                "21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"
+            ))
+        );
+    }
+
+    #[test]
+    fn detects_hooks_affecting_swaps() {
+        let key = |fee: u32, hooks: u64| PoolKey {
+            fee,
+            hooks: H160::from_low_u64_be(hooks),
+            ..Default::default()
+        };
+
+        assert!(!key(500, 0).hooks_affect_swaps());
+        // only liquidity hooks
+        assert!(!key(500, 0x1000 | 0x0800 | 0x0200).hooks_affect_swaps());
+        assert!(key(DYNAMIC_FEE_FLAG, 0).hooks_affect_swaps());
+        assert!(key(500, 0x0080).hooks_affect_swaps());
+        assert!(key(500, 0x0040).hooks_affect_swaps());
+        assert!(key(500, 0x0008).hooks_affect_swaps());
+        assert!(key(500, 0x0004).hooks_affect_swaps());
+    }
+
+    #[test]
+    fn maps_native_token() {
+        let weth = H160::from_low_u64_be(1);
+        let token = H160::from_low_u64_be(2);
+        let key = PoolKey {
+            currency0: H160::zero(),
+            currency1: token,
+            ..Default::default()
+        };
+        assert_eq!(key.tokens(weth), (weth, token));
+    }
+
+    #[test]
+    fn append_events_updates_pool_state() {
+        let id = H256::repeat_byte(1);
+        let pool = PoolInfo {
+            id,
+            state: PoolState {
+                liquidity: 10.into(),
+                tick: 5.into(),
+                liquidity_net: BTreeMap::from([(0.into(), 10.into()), (10.into(), (-10).into())]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pools = HashMap::from([(id, pool)]);
+        let event = |data| Event {
+            data,
+            meta: Some(EventMetadata::default()),
+        };
+
+        append_events(
+            &mut pools,
+            vec![
+                // in range
+                event(UniswapV4Event::ModifyLiquidity(ModifyLiquidity {
+                    id,
+                    tick_lower: 0,
+                    tick_upper: 20,
+                    liquidity_delta: 5,
+                })),
+                // out of range
+                event(UniswapV4Event::ModifyLiquidity(ModifyLiquidity {
+                    id,
+                    tick_lower: 10,
+                    tick_upper: 20,
+                    liquidity_delta: 3,
+                })),
+                // removes all liquidity at the lower tick
+                event(UniswapV4Event::ModifyLiquidity(ModifyLiquidity {
+                    id,
+                    tick_lower: 0,
+                    tick_upper: 10,
+                    liquidity_delta: -15,
+                })),
+                // unknown pool
+                event(UniswapV4Event::Swap(Swap {
+                    id: H256::repeat_byte(2),
+                    ..Default::default()
+                })),
+            ],
+        );
+        let state = &pools[&id].state;
+        assert_eq!(state.liquidity, U256::zero());
+        assert_eq!(
+            state.liquidity_net,
+            BTreeMap::from([(10.into(), 8.into()), (20.into(), (-8).into())])
+        );
+
+        append_events(
+            &mut pools,
+            vec![event(UniswapV4Event::Swap(Swap {
+                id,
+                sqrt_price_x96: 1.into(),
+                liquidity: 2,
+                tick: 3,
+            }))],
+        );
+        let state = &pools[&id].state;
+        assert_eq!(state.sqrt_price, 1.into());
+        assert_eq!(state.liquidity, 2.into());
+        assert_eq!(state.tick, 3.into());
+    }
+}
@@ -0,0 +1,192 @@
+//! Reading pool state from the storage of the Uniswap V4 pool manager.
+//!
+//! The pool manager exposes its storage through `extsload`, the slots used
+//! here mirror the ones of the `StateLibrary` of the Uniswap V4 core
+//! contracts.
+
+use {
+    super::{
+        event_fetching::RegisteredPool,
+        pool_fetching::{POOL_SWAP_GAS_COST, PoolId, PoolInfo},
+    },
+    crate::sources::uniswap_v3::{
+        onchain::{bitmap_word_range, initialized_ticks},
+        pool_fetching::{PoolState, PoolStats},
+    },
+    anyhow::{Result, ensure},
+    contracts::UniswapV4PoolManager,
+    ethcontract::{BlockId, Bytes, U256},
+    num::{BigInt, rational::Ratio},
+    std::collections::BTreeMap,
+    web3::signing::keccak256,
+};
+
+/// The storage slot of the `pools` mapping of the pool manager.
+const POOLS_SLOT: u64 = 6;
+/// Offsets of the pool state fields relative to the pool's state slot.
+const LIQUIDITY_OFFSET: u64 = 3;
+const TICKS_OFFSET: u64 = 4;
+const TICK_BITMAP_OFFSET: u64 = 5;
+
+/// The maximum number of storage slots to read in a single call.
+const EXTSLOAD_BATCH_SIZE: usize = 1_000;
+
+/// Reads the full state of the specified pool at the specified block.
+pub async fn fetch_pool(
+    manager: &UniswapV4PoolManager,
+    pool: &RegisteredPool,
+    block_number: u64,
+) -> Result<PoolInfo> {
+    ensure!(pool.key.tick_spacing > 0, "invalid tick spacing");
+    let block = BlockId::Number(block_number.into());
+    let state_slot = pool_state_slot(pool.id);
+
+    let words = bitmap_word_range(pool.key.tick_spacing);
+    let mut slots = vec![state_slot, offset(state_slot, LIQUIDITY_OFFSET)];
+    slots.extend(
+        words
+            .clone()
+            .map(|word| mapping_slot(word.into(), offset(state_slot, TICK_BITMAP_OFFSET))),
+    );
+    let values = extsload(manager, slots, block).await?;
+
+    let slot0 = values[0];
+    let sqrt_price = slot0 & ((U256::one() << 160) - 1);
+    ensure!(!sqrt_price.is_zero(), "pool is not initialized");
+    let tick = sign_extend_24((slot0 >> 160).low_u32());
+    let lp_fee = (slot0 >> 208).low_u32() & 0xffffff;
+    let liquidity = values[1] & U256::from(u128::MAX);
+
+    let initialized = words
+        .zip(&values[2..])
+        .flat_map(|(word, bitmap)| initialized_ticks(word, *bitmap, pool.key.tick_spacing))
+        .collect::<Vec<_>>();
+    let tick_infos = extsload(
+        manager,
+        initialized
+            .iter()
+            .map(|tick| mapping_slot(*tick, offset(state_slot, TICKS_OFFSET)))
+            .collect(),
+        block,
+    )
+    .await?;
+    let liquidity_net = initialized
+        .into_iter()
+        .zip(tick_infos)
+        .filter_map(|(tick, info)| {
+            // `liquidityNet` is stored in the upper half of the first slot of
+            // the tick info, next to `liquidityGross`.
+            let net = (info >> 128).low_u128() as i128;
+            (net != 0).then(|| (BigInt::from(tick), BigInt::from(net)))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    Ok(PoolInfo {
+        id: pool.id,
+        key: pool.key,
+        tokens: pool.tokens,
+        state: PoolState {
+            sqrt_price,
+            liquidity,
+            tick: BigInt::from(tick),
+            liquidity_net,
+            fee: Ratio::new(lp_fee, 1_000_000u32),
+        },
+        gas_stats: PoolStats {
+            mean_gas: POOL_SWAP_GAS_COST.into(),
+        },
+    })
+}
+
+/// Returns the slot of the state of the specified pool in the `pools`
+/// mapping.
+fn pool_state_slot(id: PoolId) -> U256 {
+    let mut buffer = [0u8; 64];
+    buffer[..32].copy_from_slice(id.as_bytes());
+    U256::from(POOLS_SLOT).to_big_endian(&mut buffer[32..]);
+    U256::from_big_endian(&keccak256(&buffer))
+}
+
+/// Returns the slot of the value for a signed integer key in the mapping
+/// stored at the specified slot.
+fn mapping_slot(key: i32, mapping: U256) -> U256 {
+    let mut buffer = [0u8; 64];
+    // signed keys are sign extended to 256 bits
+    buffer[..32].fill(if key < 0 { 0xff } else { 0 });
+    buffer[28..32].copy_from_slice(&key.to_be_bytes());
+    mapping.to_big_endian(&mut buffer[32..]);
+    U256::from_big_endian(&keccak256(&buffer))
+}
+
+fn offset(slot: U256, offset: u64) -> U256 {
+    slot + U256::from(offset)
+}
+
+fn sign_extend_24(value: u32) -> i32 {
+    ((value << 8) as i32) >> 8
+}
+
+/// Reads the specified storage slots of the pool manager at the specified
+/// block.
+async fn extsload(
+    manager: &UniswapV4PoolManager,
+    slots: Vec<U256>,
+    block: BlockId,
+) -> Result<Vec<U256>> {
+    let batches = futures::future::try_join_all(slots.chunks(EXTSLOAD_BATCH_SIZE).map(|batch| {
+        manager
+            .extsload(
+                batch
+                    .iter()
+                    .map(|slot| {
+                        let mut bytes = [0u8; 32];
+                        slot.to_big_endian(&mut bytes);
+                        Bytes(bytes)
+                    })
+                    .collect(),
+            )
+            .block(block)
+            .call()
+    }))
+    .await?;
+
+    Ok(batches
+        .into_iter()
+        .flatten()
+        .map(|value| U256::from_big_endian(&value.0))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::H256, hex_literal::hex};
+
+    #[test]
+    fn computes_storage_slots() {
+        // The mainnet ETH/USDC 0.05% pool.
+        let id = H256(hex!(
+            "21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"
+        ));
+        let state_slot = pool_state_slot(id);
+        assert_eq!(
+            state_slot,
+            U256::from_big_endian(&hex!(
+                "da8cac368d67cd2f2d8aaa5cc531768e0fa3b1d205c5c5de60da078e1f59bdfc"
+            ))
+        );
+        assert_eq!(
+            mapping_slot(-10, offset(state_slot, TICKS_OFFSET)),
+            U256::from_big_endian(&hex!(
+                "24527d16364dd34248bef0b6a0d30cca2358973c62a817b78401ada29a396141"
+            ))
+        );
+    }
+
+    #[test]
+    fn sign_extends_ticks() {
+        assert_eq!(sign_extend_24(0x000001), 1);
+        assert_eq!(sign_extend_24(0xffffff), -1);
+        assert_eq!(sign_extend_24(0xfd0c2d), -193491);
+        assert_eq!(sign_extend_24(0x0d89b4), 887220);
+    }
+}
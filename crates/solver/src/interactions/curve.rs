@@ -0,0 +1,110 @@
+use {
+    contracts::{CurveCryptoSwapPool, CurveStableSwapPool},
+    ethcontract::Bytes,
+    primitive_types::U256,
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// A Curve pool contract. StableSwap and CryptoSwap pools use different
+/// integer types for the coin indices in their `exchange` functions.
+#[derive(Clone, Debug)]
+pub enum CurvePool {
+    Stable(CurveStableSwapPool),
+    Crypto(CurveCryptoSwapPool),
+}
+
+/// Sells exactly `amount_in` of coin `i` for at least `min_amount_out` of coin
+/// `j`. Curve pools don't support exact output swaps, so any output in excess
+/// of `min_amount_out` stays in the settlement contract.
+#[derive(Clone, Debug)]
+pub struct CurveExchangeInteraction {
+    pub pool: CurvePool,
+    pub i: usize,
+    pub j: usize,
+    pub amount_in: U256,
+    pub min_amount_out: U256,
+}
+
+impl Interaction for CurveExchangeInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let (address, method) = match &self.pool {
+            CurvePool::Stable(pool) => (
+                pool.address(),
+                pool.exchange(
+                    self.i as i128,
+                    self.j as i128,
+                    self.amount_in,
+                    self.min_amount_out,
+                )
+                .tx,
+            ),
+            CurvePool::Crypto(pool) => (
+                pool.address(),
+                pool.exchange(
+                    self.i.into(),
+                    self.j.into(),
+                    self.amount_in,
+                    self.min_amount_out,
+                )
+                .tx,
+            ),
+        };
+        let calldata = method.data.expect("no calldata").0;
+        (address, 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, hex_literal::hex, primitive_types::H160};
+
+    #[test]
+    fn encode_stable_exchange() {
+        let interaction = CurveExchangeInteraction {
+            pool: CurvePool::Stable(dummy_contract!(CurveStableSwapPool, [0x01; 20])),
+            i: 0,
+            j: 2,
+            amount_in: 1_000.into(),
+            min_amount_out: 999.into(),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x01; 20]));
+        assert_eq!(value, U256::zero());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "3df02124
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000002
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 00000000000000000000000000000000000000000000000000000000000003e7"
+            )
+        );
+    }
+
+    #[test]
+    fn encode_crypto_exchange() {
+        let interaction = CurveExchangeInteraction {
+            pool: CurvePool::Crypto(dummy_contract!(CurveCryptoSwapPool, [0x02; 20])),
+            i: 1,
+            j: 0,
+            amount_in: 42.into(),
+            min_amount_out: 1_337.into(),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x02; 20]));
+        assert_eq!(value, U256::zero());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "5b41b908
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 000000000000000000000000000000000000000000000000000000000000002a
+                 0000000000000000000000000000000000000000000000000000000000000539"
+            )
+        );
+    }
+}
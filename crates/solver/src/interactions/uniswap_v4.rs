@@ -0,0 +1,273 @@
+//! Uniswap V4 swaps through the Universal Router.
+//!
+//! The router settles swaps with the tokens it holds, so the input tokens
+//! have to be transferred to it before executing the swap. Any input tokens
+//! that are left over are swept back to the caller. Pools trading the native
+//! token are swapped with wrapped native tokens, which the router unwraps and
+//! wraps as needed.
+
+use {
+    contracts::UniswapV4UniversalRouter,
+    ethcontract::{
+        Bytes,
+        common::abi::{Token, encode},
+    },
+    hex_literal::hex,
+    primitive_types::{H160, U256},
+    shared::{
+        interaction::{EncodedInteraction, Interaction},
+        sources::uniswap_v4::pool_fetching::PoolKey,
+    },
+};
+
+/// Universal Router commands.
+mod command {
+    pub const SWEEP: u8 = 0x04;
+    pub const WRAP_ETH: u8 = 0x0b;
+    pub const UNWRAP_WETH: u8 = 0x0c;
+    pub const V4_SWAP: u8 = 0x10;
+}
+
+/// Uniswap V4 router actions.
+mod action {
+    pub const SWAP_EXACT_OUT_SINGLE: u8 = 0x08;
+    pub const SETTLE: u8 = 0x0b;
+    pub const TAKE: u8 = 0x0e;
+}
+
+/// Recipient placeholder for the caller of the router.
+const MSG_SENDER: H160 = H160(hex!("0000000000000000000000000000000000000001"));
+/// Recipient placeholder for the router itself.
+const ADDRESS_THIS: H160 = H160(hex!("0000000000000000000000000000000000000002"));
+/// Amount placeholder for settling or taking the full open delta of a
+/// currency.
+const OPEN_DELTA: u64 = 0;
+
+/// Buys exactly `amount_out` for at most `amount_in_max` from a Uniswap V4
+/// pool. Expects the router to already hold `amount_in_max` input tokens.
+#[derive(Clone, Debug)]
+pub struct UniswapV4SwapInteraction {
+    pub router: UniswapV4UniversalRouter,
+    pub key: PoolKey,
+    /// Whether `currency0` is sold for `currency1`.
+    pub zero_for_one: bool,
+    pub amount_out: U256,
+    pub amount_in_max: U256,
+    pub deadline: U256,
+}
+
+impl UniswapV4SwapInteraction {
+    fn currencies(&self) -> (H160, H160) {
+        if self.zero_for_one {
+            (self.key.currency0, self.key.currency1)
+        } else {
+            (self.key.currency1, self.key.currency0)
+        }
+    }
+
+    fn swap(&self, recipient: H160) -> Vec<u8> {
+        let (input, output) = self.currencies();
+        let swap = encode(&[Token::Tuple(vec![
+            Token::Tuple(vec![
+                Token::Address(self.key.currency0),
+                Token::Address(self.key.currency1),
+                Token::Uint(self.key.fee.into()),
+                Token::Int(signed(self.key.tick_spacing)),
+                Token::Address(self.key.hooks),
+            ]),
+            Token::Bool(self.zero_for_one),
+            Token::Uint(self.amount_out),
+            Token::Uint(self.amount_in_max),
+            // hook data
+            Token::Bytes(Vec::new()),
+        ])]);
+        // the router pays for the swap with the tokens it holds
+        let settle = encode(&[
+            Token::Address(input),
+            Token::Uint(OPEN_DELTA.into()),
+            Token::Bool(false),
+        ]);
+        let take = encode(&[
+            Token::Address(output),
+            Token::Address(recipient),
+            Token::Uint(OPEN_DELTA.into()),
+        ]);
+
+        encode(&[
+            Token::Bytes(vec![
+                action::SWAP_EXACT_OUT_SINGLE,
+                action::SETTLE,
+                action::TAKE,
+            ]),
+            Token::Array(vec![
+                Token::Bytes(swap),
+                Token::Bytes(settle),
+                Token::Bytes(take),
+            ]),
+        ])
+    }
+
+    /// Returns the router commands and their inputs for the swap.
+    fn commands(&self) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let (input, output) = self.currencies();
+        let sweep = |token: H160| {
+            (
+                command::SWEEP,
+                encode(&[
+                    Token::Address(token),
+                    Token::Address(MSG_SENDER),
+                    Token::Uint(0.into()),
+                ]),
+            )
+        };
+        // wraps the router's entire native token balance
+        let wrap = (
+            command::WRAP_ETH,
+            encode(&[Token::Address(MSG_SENDER), Token::Uint(U256::one() << 255)]),
+        );
+        let unwrap = (
+            command::UNWRAP_WETH,
+            encode(&[Token::Address(ADDRESS_THIS), Token::Uint(0.into())]),
+        );
+
+        let commands = if input.is_zero() {
+            vec![unwrap, (command::V4_SWAP, self.swap(MSG_SENDER)), wrap]
+        } else if output.is_zero() {
+            vec![
+                (command::V4_SWAP, self.swap(ADDRESS_THIS)),
+                wrap,
+                sweep(input),
+            ]
+        } else {
+            vec![(command::V4_SWAP, self.swap(MSG_SENDER)), sweep(input)]
+        };
+        commands.into_iter().unzip()
+    }
+}
+
+impl Interaction for UniswapV4SwapInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let (commands, inputs) = self.commands();
+        let method = self.router.execute(
+            Bytes(commands),
+            inputs.into_iter().map(Bytes).collect(),
+            self.deadline,
+        );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.router.address(), 0.into(), Bytes(calldata))
+    }
+}
+
+/// Encodes a signed integer as an ABI word.
+fn signed(value: i32) -> U256 {
+    let magnitude = U256::from(value.unsigned_abs());
+    if value < 0 {
+        (!magnitude).overflowing_add(U256::one()).0
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract};
+
+    fn interaction(currency0: H160, zero_for_one: bool) -> UniswapV4SwapInteraction {
+        UniswapV4SwapInteraction {
+            router: dummy_contract!(UniswapV4UniversalRouter, [0x01; 20]),
+            key: PoolKey {
+                currency0,
+                currency1: H160([0x03; 20]),
+                fee: 500,
+                tick_spacing: -10,
+                hooks: H160::zero(),
+            },
+            zero_for_one,
+            amount_out: 5.into(),
+            amount_in_max: 6.into(),
+            deadline: U256::MAX,
+        }
+    }
+
+    #[test]
+    fn encode_swap() {
+        let interaction = interaction(H160([0x02; 20]), true);
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, interaction.router.address());
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "3593564c
+                 0000000000000000000000000000000000000000000000000000000000000060
+                 00000000000000000000000000000000000000000000000000000000000000a0
+                 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+                 0000000000000000000000000000000000000000000000000000000000000002
+                 1004000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000002
+                 0000000000000000000000000000000000000000000000000000000000000040
+                 00000000000000000000000000000000000000000000000000000000000003e0
+                 0000000000000000000000000000000000000000000000000000000000000380
+                 0000000000000000000000000000000000000000000000000000000000000040
+                 0000000000000000000000000000000000000000000000000000000000000080
+                 0000000000000000000000000000000000000000000000000000000000000003
+                 080b0e0000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000003
+                 0000000000000000000000000000000000000000000000000000000000000060
+                 00000000000000000000000000000000000000000000000000000000000001e0
+                 0000000000000000000000000000000000000000000000000000000000000260
+                 0000000000000000000000000000000000000000000000000000000000000160
+                 0000000000000000000000000000000000000000000000000000000000000020
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 0000000000000000000000000303030303030303030303030303030303030303
+                 00000000000000000000000000000000000000000000000000000000000001f4
+                 fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff6
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000005
+                 0000000000000000000000000000000000000000000000000000000000000006
+                 0000000000000000000000000000000000000000000000000000000000000120
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000060
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000060
+                 0000000000000000000000000303030303030303030303030303030303030303
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000060
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000000"
+            )
+        );
+    }
+
+    #[test]
+    fn wraps_and_unwraps_native_token() {
+        let native = H160::zero();
+        let token = H160([0x03; 20]);
+
+        // selling the native token
+        let (commands, inputs) = interaction(native, true).commands();
+        assert_eq!(
+            commands,
+            [command::UNWRAP_WETH, command::V4_SWAP, command::WRAP_ETH]
+        );
+        assert_eq!(inputs[0][12..32], ADDRESS_THIS.0);
+        assert_eq!(inputs[2][12..32], MSG_SENDER.0);
+
+        // buying the native token
+        let (commands, inputs) = interaction(native, false).commands();
+        assert_eq!(
+            commands,
+            [command::V4_SWAP, command::WRAP_ETH, command::SWEEP]
+        );
+        assert_eq!(inputs[1][12..32], MSG_SENDER.0);
+        assert_eq!(inputs[2][12..32], token.0);
+
+        // the native token is taken by the router so it can be wrapped
+        assert_eq!(inputs[0], interaction(native, false).swap(ADDRESS_THIS),);
+    }
+}
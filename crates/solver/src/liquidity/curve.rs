@@ -0,0 +1,221 @@
+//! Module for providing Curve pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{
+            CurveExchangeInteraction,
+            CurvePool,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances, Approval},
+        },
+        liquidity::{AmmOrderExecution, CurvePoolOrder, Liquidity, SettlementHandling},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Context, Result},
+    contracts::{CurveCryptoSwapPool, CurveStableSwapPool},
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::curve::{self, PoolKind, pool_fetching::CurvePoolFetching},
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for Curve pools.
+pub struct CurveLiquidity {
+    web3: Web3,
+    pool_fetcher: Arc<dyn CurvePoolFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+}
+
+impl CurveLiquidity {
+    pub fn new(web3: Web3, pool_fetcher: Arc<dyn CurvePoolFetching>, settlement: H160) -> Self {
+        let allowance_manager = AllowanceManager::new(web3.clone(), settlement);
+        Self {
+            web3,
+            pool_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for CurveLiquidity {
+    /// Returns relevant Curve pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+
+        // Each Curve pool pulls the input tokens itself, so allowances are
+        // required per pool.
+        let allowances = futures::future::try_join_all(pools.iter().map(|pool| {
+            self.allowance_manager
+                .get_allowances(pool.tokens.iter().copied().collect(), pool.address)
+        }))
+        .await?;
+
+        Ok(pools
+            .into_iter()
+            .zip(allowances)
+            .map(|(pool, allowances)| {
+                Liquidity::Curve(CurvePoolOrder {
+                    settlement_handling: Arc::new(SettlementHandler::new(
+                        &self.web3, &pool, allowances,
+                    )),
+                    pool,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    pool: CurvePool,
+    tokens: Vec<H160>,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(web3: &Web3, pool: &curve::Pool, allowances: Allowances) -> Self {
+        let contract = match pool.kind {
+            PoolKind::Stable(_) => CurvePool::Stable(CurveStableSwapPool::at(web3, pool.address)),
+            PoolKind::Crypto(_) => CurvePool::Crypto(CurveCryptoSwapPool::at(web3, pool.address)),
+        };
+        Self {
+            pool: contract,
+            tokens: pool.tokens.clone(),
+            allowances,
+        }
+    }
+
+    pub fn address(&self) -> H160 {
+        match &self.pool {
+            CurvePool::Stable(pool) => pool.address(),
+            CurvePool::Crypto(pool) => pool.address(),
+        }
+    }
+
+    /// Creates the interactions for selling all of `input` for at least
+    /// `output`.
+    pub fn swap(
+        &self,
+        input: TokenAmount,
+        output: TokenAmount,
+    ) -> Result<(Option<Approval>, CurveExchangeInteraction)> {
+        let index = |token| {
+            self.tokens
+                .iter()
+                .position(|t| *t == token)
+                .context("token not in Curve pool")
+        };
+        let interaction = CurveExchangeInteraction {
+            pool: self.pool.clone(),
+            i: index(input.token)?,
+            j: index(output.token)?,
+            amount_in: input.amount,
+            min_amount_out: output.amount,
+        };
+        let approval = self.allowances.approve_token(input)?;
+        Ok((approval, interaction))
+    }
+}
+
+impl SettlementHandling<CurvePoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let (approval, swap) = self.swap(execution.input_max, execution.output)?;
+        if let Some(approval) = approval {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ethrpc::dummy,
+        primitive_types::U256,
+        shared::sources::curve::stable_swap,
+        std::collections::HashMap,
+    };
+
+    fn pool() -> curve::Pool {
+        curve::Pool {
+            address: H160([0x01; 20]),
+            tokens: vec![H160([0x02; 20]), H160([0x03; 20])],
+            balances: vec![U256::exp10(24), U256::exp10(24)],
+            kind: PoolKind::Stable(stable_swap::Parameters {
+                amplification: 10_000.into(),
+                fee: 4_000_000.into(),
+                rates: vec![U256::exp10(18), U256::exp10(18)],
+            }),
+        }
+    }
+
+    #[test]
+    fn swap_approves_pool_when_needed() {
+        let pool = pool();
+        let handler = SettlementHandler::new(
+            &dummy::web3(),
+            &pool,
+            Allowances::new(
+                pool.address,
+                HashMap::from([(pool.tokens[0], 100.into()), (pool.tokens[1], 100.into())]),
+            ),
+        );
+
+        let (approval, swap) = handler
+            .swap(
+                TokenAmount::new(pool.tokens[0], 100),
+                TokenAmount::new(pool.tokens[1], 99),
+            )
+            .unwrap();
+        assert_eq!(approval, None);
+        assert_eq!((swap.i, swap.j), (0, 1));
+
+        let (approval, swap) = handler
+            .swap(
+                TokenAmount::new(pool.tokens[1], 101),
+                TokenAmount::new(pool.tokens[0], 100),
+            )
+            .unwrap();
+        assert_eq!(
+            approval,
+            Some(Approval {
+                token: pool.tokens[1],
+                spender: pool.address,
+            })
+        );
+        assert_eq!((swap.i, swap.j), (1, 0));
+    }
+
+    #[test]
+    fn swap_rejects_unknown_tokens() {
+        let pool = pool();
+        let handler =
+            SettlementHandler::new(&dummy::web3(), &pool, Allowances::empty(pool.address));
+        assert!(
+            handler
+                .swap(
+                    TokenAmount::new(H160([0x04; 20]), 100),
+                    TokenAmount::new(pool.tokens[0], 99),
+                )
+                .is_err()
+        );
+    }
+}
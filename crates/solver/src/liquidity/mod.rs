@@ -1,8 +1,10 @@
 pub mod balancer_v2;
+pub mod curve;
 pub mod order_converter;
 pub mod slippage;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
+pub mod uniswap_v4;
 pub mod zeroex;
 
 #[cfg(test)]
@@ -45,6 +47,8 @@ pub enum Liquidity {
     BalancerStable(StablePoolOrder),
     LimitOrder(LimitOrder),
     Concentrated(ConcentratedLiquidity),
+    Curve(CurvePoolOrder),
+    UniswapV4(UniswapV4PoolOrder),
 }
 
 /// A trait associating some liquidity model to how it is executed and encoded
@@ -393,6 +397,56 @@ impl Settleable for ConcentratedLiquidity {
     }
 }
 
+/// Curve StableSwap or CryptoSwap pool.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct CurvePoolOrder {
+    pub pool: shared::sources::curve::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for CurvePoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Curve pool {:?}", self.pool.address)
+    }
+}
+
+impl Settleable for CurvePoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
+/// Uniswap V4 pool. Like Uniswap V3 pools it has concentrated liquidity, but
+/// it may additionally be attached to hooks that run custom logic.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct UniswapV4PoolOrder {
+    pub tokens: TokenPair,
+    pub pool: shared::sources::uniswap_v4::pool_fetching::PoolInfo,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for UniswapV4PoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Uniswap V4 pool {:?}", self.pool.id)
+    }
+}
+
+impl Settleable for UniswapV4PoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use {super::*, maplit::btreemap, std::sync::Mutex};
@@ -0,0 +1,215 @@
+//! Module for providing Uniswap V4 pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{Erc20TransferInteraction, UniswapV4SwapInteraction},
+        liquidity::{AmmOrderExecution, Liquidity, SettlementHandling, UniswapV4PoolOrder},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Context, Result, ensure},
+    contracts::{ERC20, UniswapV4UniversalRouter},
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::uniswap_v4::pool_fetching::{PoolFetching, PoolKey},
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+// 1h timeout for Uniswap V4 interactions
+const TIMEOUT: u32 = 3600;
+
+/// A liquidity provider for Uniswap V4 pools.
+pub struct UniswapV4Liquidity {
+    router: UniswapV4UniversalRouter,
+    web3: Web3,
+    pool_fetcher: Arc<dyn PoolFetching>,
+}
+
+impl UniswapV4Liquidity {
+    pub fn new(
+        router: UniswapV4UniversalRouter,
+        web3: Web3,
+        pool_fetcher: Arc<dyn PoolFetching>,
+    ) -> Self {
+        Self {
+            router,
+            web3,
+            pool_fetcher,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for UniswapV4Liquidity {
+    /// Returns relevant Uniswap V4 pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        self.pool_fetcher
+            .fetch(&pairs, block)
+            .await?
+            .into_iter()
+            .map(|pool| {
+                Ok(Liquidity::UniswapV4(UniswapV4PoolOrder {
+                    tokens: TokenPair::new(pool.tokens.0, pool.tokens.1)
+                        .context("cant create pair")?,
+                    settlement_handling: Arc::new(SettlementHandler {
+                        router: self.router.clone(),
+                        web3: self.web3.clone(),
+                        key: pool.key,
+                        tokens: pool.tokens,
+                    }),
+                    pool,
+                }))
+            })
+            .collect()
+    }
+}
+
+pub struct SettlementHandler {
+    pub router: UniswapV4UniversalRouter,
+    web3: Web3,
+    key: PoolKey,
+    /// The pool's currencies with the native token replaced by its wrapped
+    /// counterpart.
+    tokens: (H160, H160),
+}
+
+impl SettlementHandler {
+    pub fn new(
+        router: UniswapV4UniversalRouter,
+        web3: Web3,
+        key: PoolKey,
+        tokens: (H160, H160),
+    ) -> Self {
+        Self {
+            router,
+            web3,
+            key,
+            tokens,
+        }
+    }
+
+    /// Creates the interactions for buying exactly `output` for at most
+    /// `input_max`. The router pays for the swap with tokens it holds, so
+    /// `input_max` gets transferred to it first.
+    pub fn swap(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> Result<(Erc20TransferInteraction, UniswapV4SwapInteraction)> {
+        let zero_for_one = input_max.token == self.tokens.0;
+        ensure!(
+            (input_max.token, output.token) == self.tokens
+                || (output.token, input_max.token) == self.tokens,
+            "tokens not in Uniswap V4 pool"
+        );
+
+        let transfer = Erc20TransferInteraction {
+            token: ERC20::at(&self.web3, input_max.token),
+            to: self.router.address(),
+            amount: input_max.amount,
+        };
+        let swap = UniswapV4SwapInteraction {
+            router: self.router.clone(),
+            key: self.key,
+            zero_for_one,
+            amount_out: output.amount,
+            amount_in_max: input_max.amount,
+            deadline: model::time::now_in_epoch_seconds()
+                .saturating_add(TIMEOUT)
+                .into(),
+        };
+        Ok((transfer, swap))
+    }
+}
+
+impl SettlementHandling<UniswapV4PoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    // Creates the required interactions to convert the given input into
+    // output. Assumes slippage is already applied to the `input_max` field.
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let (transfer, swap) = self.swap(execution.input_max, execution.output)?;
+        encoder
+            .append_to_execution_plan_internalizable(Arc::new(transfer), execution.internalizable);
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, ethrpc::dummy};
+
+    fn handler(currency0: H160, tokens: (H160, H160)) -> SettlementHandler {
+        SettlementHandler::new(
+            dummy_contract!(UniswapV4UniversalRouter, [0x01; 20]),
+            dummy::web3(),
+            PoolKey {
+                currency0,
+                currency1: tokens.1,
+                fee: 500,
+                tick_spacing: 10,
+                hooks: H160::zero(),
+            },
+            tokens,
+        )
+    }
+
+    #[test]
+    fn swap_transfers_input_to_router() {
+        let tokens = (H160([0x02; 20]), H160([0x03; 20]));
+        let handler = handler(tokens.0, tokens);
+
+        let (transfer, swap) = handler
+            .swap(
+                TokenAmount::new(tokens.1, 100),
+                TokenAmount::new(tokens.0, 99),
+            )
+            .unwrap();
+        assert_eq!(transfer.token.address(), tokens.1);
+        assert_eq!(transfer.to, handler.router.address());
+        assert_eq!(transfer.amount, 100.into());
+        assert!(!swap.zero_for_one);
+        assert_eq!(swap.amount_in_max, 100.into());
+        assert_eq!(swap.amount_out, 99.into());
+    }
+
+    #[test]
+    fn swap_transfers_wrapped_native_token() {
+        let weth = H160([0x02; 20]);
+        let tokens = (weth, H160([0x03; 20]));
+        let handler = handler(H160::zero(), tokens);
+
+        let (transfer, swap) = handler
+            .swap(TokenAmount::new(weth, 100), TokenAmount::new(tokens.1, 99))
+            .unwrap();
+        assert_eq!(transfer.token.address(), weth);
+        assert!(swap.zero_for_one);
+        assert_eq!(swap.key.currency0, H160::zero());
+    }
+
+    #[test]
+    fn swap_rejects_unknown_tokens() {
+        let tokens = (H160([0x02; 20]), H160([0x03; 20]));
+        let handler = handler(tokens.0, tokens);
+        assert!(
+            handler
+                .swap(
+                    TokenAmount::new(H160([0x04; 20]), 100),
+                    TokenAmount::new(tokens.0, 99),
+                )
+                .is_err()
+        );
+    }
+}
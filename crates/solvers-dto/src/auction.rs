@@ -131,6 +131,20 @@ pub enum FeePolicy {
     },
     #[serde(rename_all = "camelCase")]
     Volume { factor: f64 },
+    #[serde(rename_all = "camelCase")]
+    TieredSurplus {
+        tiers: Vec<SurplusTier>,
+        max_volume_factor: f64,
+    },
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurplusTier {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub threshold: U256,
+    pub factor: f64,
 }
 
 #[serde_as]
@@ -166,6 +180,8 @@ pub enum Liquidity {
     WeightedProduct(WeightedProductPool),
     Stable(StablePool),
     ConcentratedLiquidity(ConcentratedLiquidityPool),
+    UniswapV4(UniswapV4Pool),
+    Curve(CurvePool),
     LimitOrder(ForeignLimitOrder),
 }
 
@@ -264,6 +280,94 @@ pub struct ConcentratedLiquidityPool {
     pub fee: BigDecimal,
 }
 
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniswapV4Pool {
+    pub id: String,
+    /// The identifier of the pool within the Uniswap V4 pool manager.
+    pub pool_id: H256,
+    pub router: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gas_estimate: U256,
+    pub tokens: Vec<H160>,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub sqrt_price: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    pub liquidity: u128,
+    pub tick: i32,
+    #[serde_as(as = "HashMap<DisplayFromStr, DisplayFromStr>")]
+    pub liquidity_net: HashMap<i32, i128>,
+    pub fee: BigDecimal,
+    pub hooks: H160,
+    /// Whether the pool's hooks can change the outcome of swaps, in which case
+    /// the pool state doesn't suffice for computing swap amounts.
+    pub hooks_affect_swaps: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurvePool {
+    pub id: String,
+    pub address: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gas_estimate: U256,
+    /// The pool coins in the order of their indices in the pool contract.
+    pub tokens: Vec<CurveReserve>,
+    pub invariant: CurveInvariant,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveReserve {
+    pub token: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub balance: U256,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CurveInvariant {
+    StableSwap(CurveStableSwap),
+    CryptoSwap(CurveCryptoSwap),
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveStableSwap {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub amplification: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub fee: U256,
+    #[serde_as(as = "Vec<HexOrDecimalU256>")]
+    pub rates: Vec<U256>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveCryptoSwap {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub a: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gamma: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub d: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub price_scale: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub mid_fee: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub out_fee: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub fee_gamma: U256,
+    #[serde_as(as = "[HexOrDecimalU256; 2]")]
+    pub precisions: [U256; 2],
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -72,6 +72,11 @@ pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
         liquidity: auction
             .liquidity
             .iter()
+            // Swaps through pools with hooks that modify swap amounts can't be
+            // simulated from the pool state alone, so they are not supported.
+            .filter(|liquidity| {
+                !matches!(liquidity, Liquidity::UniswapV4(pool) if pool.hooks_affect_swaps)
+            })
             .map(|liquidity| match liquidity {
                 Liquidity::ConstantProduct(liquidity) => {
                     constant_product_pool::to_domain(liquidity)
@@ -83,6 +88,8 @@ pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
                 Liquidity::ConcentratedLiquidity(liquidity) => {
                     concentrated_liquidity_pool::to_domain(liquidity)
                 }
+                Liquidity::UniswapV4(liquidity) => uniswap_v4_pool::to_domain(liquidity),
+                Liquidity::Curve(liquidity) => curve_pool::to_domain(liquidity),
                 Liquidity::LimitOrder(liquidity) => Ok(foreign_limit_order::to_domain(liquidity)),
             })
             .try_collect()?,
@@ -243,6 +250,99 @@ mod concentrated_liquidity_pool {
     }
 }
 
+mod uniswap_v4_pool {
+    use {super::*, itertools::Itertools};
+
+    pub fn to_domain(pool: &UniswapV4Pool) -> Result<liquidity::Liquidity, Error> {
+        let tokens = {
+            let (a, b) = pool
+                .tokens
+                .iter()
+                .copied()
+                .map(eth::TokenAddress)
+                .collect_tuple()
+                .ok_or("invalid number of Uniswap V4 pool tokens")?;
+            liquidity::TokenPair::new(a, b).ok_or("duplicate Uniswap V4 pool token address")?
+        };
+
+        // Uniswap V4 pools behave like concentrated liquidity pools as long as
+        // their hooks don't interfere with swaps.
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(pool.id.clone()),
+            // Uniswap V4 pools don't have their own contract, swaps go
+            // through the router instead.
+            address: pool.router,
+            gas: eth::Gas(pool.gas_estimate),
+            state: liquidity::State::Concentrated(liquidity::concentrated::Pool {
+                tokens,
+                sqrt_price: liquidity::concentrated::SqrtPrice(pool.sqrt_price),
+                liquidity: liquidity::concentrated::Amount(pool.liquidity),
+                tick: liquidity::concentrated::Tick(pool.tick),
+                liquidity_net: pool
+                    .liquidity_net
+                    .iter()
+                    .map(|(tick, liquidity)| {
+                        (
+                            liquidity::concentrated::Tick(*tick),
+                            liquidity::concentrated::LiquidityNet(*liquidity),
+                        )
+                    })
+                    .collect(),
+                fee: liquidity::concentrated::Fee(
+                    conv::decimal_to_rational(&pool.fee).ok_or("invalid Uniswap V4 pool fee")?,
+                ),
+            }),
+        })
+    }
+}
+
+mod curve_pool {
+    use super::*;
+
+    pub fn to_domain(pool: &CurvePool) -> Result<liquidity::Liquidity, Error> {
+        let reserves = liquidity::curve::Reserves::new(
+            pool.tokens
+                .iter()
+                .map(|reserve| eth::Asset {
+                    token: eth::TokenAddress(reserve.token),
+                    amount: reserve.balance,
+                })
+                .collect(),
+        )
+        .ok_or("invalid Curve pool reserves")?;
+
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(pool.id.clone()),
+            address: pool.address,
+            gas: eth::Gas(pool.gas_estimate),
+            state: liquidity::State::Curve(liquidity::curve::Pool {
+                reserves,
+                invariant: match &pool.invariant {
+                    CurveInvariant::StableSwap(params) => {
+                        liquidity::curve::Invariant::StableSwap(liquidity::curve::StableSwap {
+                            amplification: params.amplification,
+                            fee: params.fee,
+                            rates: params.rates.clone(),
+                        })
+                    }
+                    CurveInvariant::CryptoSwap(params) => {
+                        liquidity::curve::Invariant::CryptoSwap(liquidity::curve::CryptoSwap {
+                            a: params.a,
+                            gamma: params.gamma,
+                            d: params.d,
+                            price_scale: params.price_scale,
+                            mid_fee: params.mid_fee,
+                            out_fee: params.out_fee,
+                            fee_gamma: params.fee_gamma,
+                            precisions: params.precisions,
+                        })
+                    }
+                },
+            }),
+        })
+    }
+}
+
 mod foreign_limit_order {
     use super::*;
 
@@ -11,8 +11,12 @@ use {
     std::collections::{HashMap, HashSet},
 };
 
+/// The maximum number of paths that are considered when splitting an order.
+const MAX_SPLIT_PATHS: usize = 64;
+
 pub struct Solver<'a> {
     base_tokens: BaseTokens,
+    split_parts: usize,
     onchain_liquidity: HashMap<TokenPair, Vec<OnchainLiquidity>>,
     liquidity: HashMap<liquidity::Id, &'a liquidity::Liquidity>,
 }
@@ -21,10 +25,12 @@ impl<'a> Solver<'a> {
     pub fn new(
         weth: &eth::WethAddress,
         base_tokens: &HashSet<eth::TokenAddress>,
+        split_parts: usize,
         liquidity: &'a [liquidity::Liquidity],
     ) -> Self {
         Self {
             base_tokens: to_boundary_base_tokens(weth, base_tokens),
+            split_parts,
             onchain_liquidity: to_boundary_liquidity(liquidity),
             liquidity: liquidity
                 .iter()
@@ -33,14 +39,29 @@ impl<'a> Solver<'a> {
         }
     }
 
-    pub fn route(&self, request: solver::Request, max_hops: usize) -> Option<solver::Route<'a>> {
+    /// Finds the best route for the specified request. When a gas cost is
+    /// given, routes are compared by their output net of the cost of the gas
+    /// needed to execute them, so that a marginally better route that is much
+    /// more expensive to execute is not preferred.
+    ///
+    /// The request is also split over multiple paths if that results in a
+    /// better route.
+    pub fn route(
+        &self,
+        request: solver::Request,
+        max_hops: usize,
+        gas_cost: Option<solver::GasCost>,
+    ) -> Option<solver::Route<'a>> {
         let candidates = self.base_tokens.path_candidates_with_hops(
             request.sell.token.0,
             request.buy.token.0,
             max_hops,
         );
+        let cost = |route: &solver::Route| {
+            gas_cost.map_or(U256::zero(), |gas_cost| gas_cost.token_amount(route.gas()))
+        };
 
-        let (segments, _) = match request.side {
+        let single = match request.side {
             order::Side::Buy => candidates
                 .iter()
                 .filter_map(|path| {
@@ -62,9 +83,12 @@ impl<'a> Solver<'a> {
                         return None;
                     }
 
-                    (sell.value <= request.sell.amount).then_some((segments, sell))
+                    (sell.value <= request.sell.amount)
+                        .then(|| solver::Route::new(segments))
+                        .flatten()
+                        .map(|route| (route, sell.value))
                 })
-                .min_by_key(|(_, sell)| sell.value)?,
+                .min_by_key(|(route, sell)| sell.saturating_add(cost(route))),
             order::Side::Sell => candidates
                 .iter()
                 .filter_map(|path| {
@@ -86,12 +110,183 @@ impl<'a> Solver<'a> {
                         return None;
                     }
 
-                    (buy.value >= request.buy.amount).then_some((segments, buy))
+                    (buy.value >= request.buy.amount)
+                        .then(|| solver::Route::new(segments))
+                        .flatten()
+                        .map(|route| (route, buy.value))
                 })
-                .max_by_key(|(_, buy)| buy.value)?,
+                .max_by_key(|(route, buy)| buy.saturating_sub(cost(route))),
+        }
+        .map(|(route, _)| route);
+
+        let split = self.split(&request, &candidates, gas_cost);
+        match (single, split) {
+            (Some(single), Some(split)) => {
+                let better = match request.side {
+                    order::Side::Buy => {
+                        split.input().amount.saturating_add(cost(&split))
+                            < single.input().amount.saturating_add(cost(&single))
+                    }
+                    order::Side::Sell => {
+                        split.output().amount.saturating_sub(cost(&split))
+                            > single.output().amount.saturating_sub(cost(&single))
+                    }
+                };
+                Some(if better { split } else { single })
+            }
+            (single, split) => single.or(split),
+        }
+    }
+
+    /// Splits the request over multiple paths of pools that don't share any
+    /// liquidity. The traded amount is divided into parts and each part is
+    /// routed over the path with the best marginal price for it, accounting
+    /// for the gas cost of using an additional path. This approximates an
+    /// allocation where the marginal prices of all used paths are equal.
+    ///
+    /// Returns `None` if fewer than two paths end up being used.
+    fn split(
+        &self,
+        request: &solver::Request,
+        candidates: &HashSet<Vec<H160>>,
+        gas_cost: Option<solver::GasCost>,
+    ) -> Option<solver::Route<'a>> {
+        if self.split_parts <= 1 {
+            return None;
+        }
+        let total = match request.side {
+            order::Side::Buy => request.buy.amount,
+            order::Side::Sell => request.sell.amount,
         };
+        let part = total / self.split_parts;
+        if part.is_zero() {
+            return None;
+        }
+
+        let mut candidates = candidates.iter().collect::<Vec<_>>();
+        candidates.sort_by_key(|path| (path.len(), path.to_vec()));
+        let paths = candidates
+            .into_iter()
+            .flat_map(|path| self.pool_paths(path))
+            .take(MAX_SPLIT_PATHS)
+            .collect::<Vec<_>>();
+        if paths.len() < 2 {
+            return None;
+        }
+        let costs = paths
+            .iter()
+            .map(|path| {
+                let gas = path.iter().map(|pool| pool.gas_cost()).sum::<usize>();
+                gas_cost.map_or(U256::zero(), |gas_cost| {
+                    gas_cost.token_amount(eth::Gas(gas.into()))
+                })
+            })
+            .collect::<Vec<_>>();
+        // Marginal amounts are offset by the highest cost, so that they can't
+        // become negative when accounting for gas costs.
+        let max_cost = costs.iter().max().copied().unwrap_or_default();
 
-        solver::Route::new(segments)
+        let mut allocations = vec![U256::zero(); paths.len()];
+        let mut used = HashSet::new();
+        for i in 0..self.split_parts {
+            let amount = if i + 1 == self.split_parts {
+                total - part * (self.split_parts - 1)
+            } else {
+                part
+            };
+            let available = |j: &usize| {
+                !allocations[*j].is_zero() || paths[*j].iter().all(|pool| !used.contains(&pool.id))
+            };
+            let cost = |j: usize| {
+                if allocations[j].is_zero() {
+                    costs[j]
+                } else {
+                    U256::zero()
+                }
+            };
+
+            let (best, _) = match request.side {
+                order::Side::Buy => (0..paths.len())
+                    .filter(available)
+                    .filter_map(|j| {
+                        let before = match allocations[j].is_zero() {
+                            true => U256::zero(),
+                            false => amount_in(&paths[j], request.buy.token.0, allocations[j])?,
+                        };
+                        let after = amount_in(
+                            &paths[j],
+                            request.buy.token.0,
+                            allocations[j].checked_add(amount)?,
+                        )?;
+                        Some((j, after.saturating_sub(before).saturating_add(cost(j))))
+                    })
+                    .min_by_key(|(_, sell)| *sell)?,
+                order::Side::Sell => (0..paths.len())
+                    .filter(available)
+                    .filter_map(|j| {
+                        let before = match allocations[j].is_zero() {
+                            true => U256::zero(),
+                            false => amount_out(&paths[j], request.sell.token.0, allocations[j])?,
+                        };
+                        let after = amount_out(
+                            &paths[j],
+                            request.sell.token.0,
+                            allocations[j].checked_add(amount)?,
+                        )?;
+                        let buy = after.saturating_sub(before).saturating_add(max_cost);
+                        Some((j, buy.saturating_sub(cost(j))))
+                    })
+                    .max_by_key(|(_, buy)| *buy)?,
+            };
+
+            if allocations[best].is_zero() {
+                used.extend(paths[best].iter().map(|pool| &pool.id));
+            }
+            allocations[best] += amount;
+        }
+
+        let routes = paths
+            .iter()
+            .zip(&allocations)
+            .filter(|(_, allocation)| !allocation.is_zero())
+            .map(|(path, allocation)| {
+                let sell = match request.side {
+                    order::Side::Buy => amount_in(path, request.buy.token.0, *allocation)?,
+                    order::Side::Sell => *allocation,
+                };
+                solver::Route::new(self.traverse_path(path, request.sell.token.0, sell)?)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if routes.len() < 2 {
+            return None;
+        }
+
+        let route = solver::Route::split(routes)?;
+        (route.input().amount <= request.sell.amount && route.output().amount >= request.buy.amount)
+            .then_some(route)
+    }
+
+    /// Returns all combinations of pools that can be used for trading along
+    /// the specified path of tokens.
+    fn pool_paths(&self, path: &[H160]) -> Vec<Vec<&OnchainLiquidity>> {
+        path.windows(2)
+            .try_fold(vec![Vec::new()], |paths, hop| {
+                let pools = self
+                    .onchain_liquidity
+                    .get(&TokenPair::new(hop[0], hop[1])?)?;
+                Some(
+                    paths
+                        .iter()
+                        .flat_map(|path| {
+                            pools
+                                .iter()
+                                .map(move |pool| path.iter().copied().chain([pool]).collect())
+                        })
+                        .take(MAX_SPLIT_PATHS)
+                        .collect(),
+                )
+            })
+            .unwrap_or_default()
     }
 
     fn traverse_path(
@@ -123,7 +318,11 @@ impl<'a> Solver<'a> {
                     token: eth::TokenAddress(buy_token),
                     amount: buy_amount,
                 },
-                gas: eth::Gas(liquidity.gas_cost().into()),
+                gas: eth::Gas(
+                    liquidity
+                        .swap_gas_cost(buy_token, (sell_amount, sell_token))
+                        .into(),
+                ),
             });
 
             sell_token = buy_token;
@@ -133,6 +332,27 @@ impl<'a> Solver<'a> {
     }
 }
 
+/// Computes the output of selling the specified amount along a path of pools.
+fn amount_out(path: &[&OnchainLiquidity], mut token: H160, mut amount: U256) -> Option<U256> {
+    for pool in path {
+        let out_token = pool.token_pair.other(&token)?;
+        amount = pool.get_amount_out(out_token, (amount, token))?;
+        token = out_token;
+    }
+    Some(amount)
+}
+
+/// Computes the input needed for buying the specified amount along a path of
+/// pools.
+fn amount_in(path: &[&OnchainLiquidity], mut token: H160, mut amount: U256) -> Option<U256> {
+    for pool in path.iter().rev() {
+        let in_token = pool.token_pair.other(&token)?;
+        amount = pool.get_amount_in(in_token, (amount, token))?;
+        token = in_token;
+    }
+    Some(amount)
+}
+
 fn to_boundary_liquidity(
     liquidity: &[liquidity::Liquidity],
 ) -> HashMap<TokenPair, Vec<OnchainLiquidity>> {
@@ -192,6 +412,36 @@ fn to_boundary_liquidity(
                         }
                     }
                 }
+                liquidity::State::Concentrated(pool) => {
+                    if let Some(boundary_pool) =
+                        boundary::liquidity::concentrated::to_boundary_pool(pool, liquidity.gas)
+                    {
+                        onchain_liquidity
+                            .entry(boundary_pool.tokens)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair: boundary_pool.tokens,
+                                source: LiquiditySource::Concentrated(boundary_pool),
+                            });
+                    }
+                }
+                liquidity::State::Curve(pool) => {
+                    if let Some(boundary_pool) =
+                        boundary::liquidity::curve::to_boundary_pool(liquidity.address, pool)
+                    {
+                        for pair in pool.reserves.token_pairs() {
+                            let token_pair = to_boundary_token_pair(&pair);
+                            onchain_liquidity.entry(token_pair).or_default().push(
+                                OnchainLiquidity {
+                                    id: liquidity.id.clone(),
+                                    token_pair,
+                                    source: LiquiditySource::Curve(boundary_pool.clone()),
+                                },
+                            );
+                        }
+                    }
+                }
                 liquidity::State::LimitOrder(limit_order) => {
                     if let Some(token_pair) =
                         TokenPair::new(limit_order.maker.token.0, limit_order.taker.token.0)
@@ -206,8 +456,6 @@ fn to_boundary_liquidity(
                             })
                     }
                 }
-                // The baseline solver does not currently support other AMMs.
-                _ => {}
             };
             onchain_liquidity
         })
@@ -225,15 +473,33 @@ enum LiquiditySource {
     ConstantProduct(boundary::liquidity::constant_product::Pool),
     WeightedProduct(boundary::liquidity::weighted_product::Pool),
     Stable(boundary::liquidity::stable::Pool),
+    Concentrated(boundary::liquidity::concentrated::Pool),
+    Curve(boundary::liquidity::curve::Pool),
     LimitOrder(liquidity::limit_order::LimitOrder),
 }
 
+impl OnchainLiquidity {
+    /// Returns the approximate amount of gas needed for swapping the specified
+    /// input through this liquidity. This is constant for most sources, but
+    /// concentrated liquidity gets more expensive with every tick crossed.
+    fn swap_gas_cost(&self, out_token: H160, input: (U256, H160)) -> usize {
+        match &self.source {
+            LiquiditySource::Concentrated(pool) => pool
+                .swap_gas_cost(out_token, input)
+                .unwrap_or_else(|| pool.gas_cost()),
+            _ => self.gas_cost(),
+        }
+    }
+}
+
 impl BaselineSolvable for OnchainLiquidity {
     fn get_amount_out(&self, out_token: H160, input: (U256, H160)) -> Option<U256> {
         match &self.source {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::Stable(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Concentrated(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Curve(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::LimitOrder(limit_order) => {
                 limit_order.get_amount_out(out_token, input)
             }
@@ -245,6 +511,8 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::Stable(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Concentrated(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Curve(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::LimitOrder(limit_order) => limit_order.get_amount_in(in_token, out),
         }
     }
@@ -254,6 +522,8 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.gas_cost(),
             LiquiditySource::WeightedProduct(pool) => pool.gas_cost(),
             LiquiditySource::Stable(pool) => pool.gas_cost(),
+            LiquiditySource::Concentrated(pool) => pool.gas_cost(),
+            LiquiditySource::Curve(pool) => pool.gas_cost(),
             LiquiditySource::LimitOrder(limit_order) => limit_order.gas_cost(),
         }
     }
@@ -0,0 +1,672 @@
+//! Uniswap V3 swap math for routing through concentrated liquidity pools.
+//!
+//! This is a port of the parts of the Uniswap V3 core contracts (`TickMath`,
+//! `SqrtPriceMath` and `SwapMath`) that are needed for computing swap amounts
+//! while crossing initialized ticks. Rounding follows the contracts exactly so
+//! that the computed amounts match what the pool would do on-chain.
+
+use {
+    crate::{
+        domain::{eth, liquidity},
+        util::math::div_ceil,
+    },
+    ethereum_types::{H160, U256, U512},
+    model::TokenPair,
+    shared::baseline_solver::BaselineSolvable,
+    std::{
+        collections::BTreeMap,
+        ops::Bound::{Excluded, Unbounded},
+    },
+};
+
+/// The minimum tick that may be passed to [`sqrt_ratio_at_tick`].
+const MIN_TICK: i32 = -887272;
+/// The maximum tick that may be passed to [`sqrt_ratio_at_tick`].
+const MAX_TICK: i32 = -MIN_TICK;
+
+/// The denominator of fees expressed in pips (hundredths of a basis point).
+const FEE_DENOMINATOR: u32 = 1_000_000;
+
+/// Additional gas used for every initialized tick that is crossed by a swap.
+/// Crossing a tick updates the fee growth accumulators and active liquidity
+/// of the pool, which makes multi-tick swaps noticeably more expensive.
+const GAS_PER_TICK_CROSSED: u64 = 25_000;
+
+/// The minimum value that can be returned from [`sqrt_ratio_at_tick`].
+fn min_sqrt_ratio() -> U256 {
+    U256::from(4295128739_u64)
+}
+
+/// The maximum value that can be returned from [`sqrt_ratio_at_tick`].
+fn max_sqrt_ratio() -> U256 {
+    U256::from_dec_str("1461446703485210103287273052203988822378723970342").unwrap()
+}
+
+/// A Uniswap V3 pool in a representation suitable for computing swaps.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub tokens: TokenPair,
+    sqrt_price: U256,
+    liquidity: u128,
+    tick: i32,
+    liquidity_net: BTreeMap<i32, i128>,
+    /// The pool fee in pips.
+    fee: U256,
+    /// Gas needed for a swap that does not cross any initialized ticks.
+    gas: u64,
+}
+
+/// Converts a domain pool into a boundary concentrated liquidity pool. Returns
+/// `None` if the domain pool cannot be represented as a boundary pool.
+pub fn to_boundary_pool(pool: &liquidity::concentrated::Pool, gas: eth::Gas) -> Option<Pool> {
+    let (token0, token1) = pool.tokens.get();
+    let tokens = TokenPair::new(token0.0, token1.0).expect("tokens are distinct by construction");
+
+    let fee = pool
+        .fee
+        .0
+        .numer()
+        .checked_mul(FEE_DENOMINATOR.into())?
+        .checked_div(*pool.fee.0.denom())?;
+    if fee >= FEE_DENOMINATOR.into() {
+        return None;
+    }
+
+    if !(min_sqrt_ratio()..max_sqrt_ratio()).contains(&pool.sqrt_price.0)
+        || !(MIN_TICK..=MAX_TICK).contains(&pool.tick.0)
+    {
+        return None;
+    }
+
+    Some(Pool {
+        tokens,
+        sqrt_price: pool.sqrt_price.0,
+        liquidity: pool.liquidity.0,
+        tick: pool.tick.0,
+        liquidity_net: pool
+            .liquidity_net
+            .iter()
+            .map(|(tick, net)| (tick.0, net.0))
+            .collect(),
+        fee,
+        gas: gas.0.try_into().ok()?,
+    })
+}
+
+impl Pool {
+    /// Returns the approximate amount of gas needed to swap the specified
+    /// input through the pool, including the cost of crossed ticks.
+    pub fn swap_gas_cost(
+        &self,
+        out_token: H160,
+        (in_amount, in_token): (U256, H160),
+    ) -> Option<usize> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        let swap = self.swap(zero_for_one, Specified::ExactIn(in_amount))?;
+        let ticks_crossed = u64::try_from(swap.ticks_crossed).ok()?;
+        let gas = self
+            .gas
+            .checked_add(ticks_crossed.checked_mul(GAS_PER_TICK_CROSSED)?)?;
+        usize::try_from(gas).ok()
+    }
+
+    /// Returns whether swapping `in_token` for `out_token` moves the price
+    /// from token 0 to token 1, or `None` if the tokens do not belong to the
+    /// pool.
+    fn zero_for_one(&self, in_token: H160, out_token: H160) -> Option<bool> {
+        let (token0, token1) = self.tokens.get();
+        match (in_token, out_token) {
+            (i, o) if i == token0 && o == token1 => Some(true),
+            (i, o) if i == token1 && o == token0 => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Simulates a swap through the pool. Returns `None` if the pool does not
+    /// have enough liquidity to fully execute the swap, or on arithmetic
+    /// errors.
+    fn swap(&self, zero_for_one: bool, specified: Specified) -> Option<Swap> {
+        let (exact_in, amount) = match specified {
+            Specified::ExactIn(amount) => (true, amount),
+            Specified::ExactOut(amount) => (false, amount),
+        };
+        if amount.is_zero() {
+            return None;
+        }
+
+        // Use the most extreme price limit allowed, the same way the Uniswap
+        // router does when no explicit limit is specified.
+        let limit = if zero_for_one {
+            min_sqrt_ratio() + 1
+        } else {
+            max_sqrt_ratio() - 1
+        };
+
+        let mut remaining = amount;
+        let mut calculated = U256::zero();
+        let mut sqrt_price = self.sqrt_price;
+        let mut tick = self.tick;
+        let mut liquidity = self.liquidity;
+        let mut ticks_crossed = 0;
+
+        while !remaining.is_zero() && sqrt_price != limit {
+            let next = if zero_for_one {
+                self.liquidity_net.range(..=tick).next_back()
+            } else {
+                self.liquidity_net.range((Excluded(tick), Unbounded)).next()
+            };
+            let (tick_next, liquidity_net) = match next {
+                Some((tick, net)) => ((*tick).clamp(MIN_TICK, MAX_TICK), Some(*net)),
+                None if zero_for_one => (MIN_TICK, None),
+                None => (MAX_TICK, None),
+            };
+
+            let sqrt_price_next_tick = sqrt_ratio_at_tick(tick_next)?;
+            let target = if zero_for_one {
+                sqrt_price_next_tick.max(limit)
+            } else {
+                sqrt_price_next_tick.min(limit)
+            };
+
+            let step =
+                compute_swap_step(sqrt_price, target, liquidity, remaining, exact_in, self.fee)?;
+            sqrt_price = step.sqrt_price_next;
+            if exact_in {
+                remaining = remaining.checked_sub(step.amount_in.checked_add(step.fee_amount)?)?;
+                calculated = calculated.checked_add(step.amount_out)?;
+            } else {
+                remaining = remaining.checked_sub(step.amount_out)?;
+                calculated =
+                    calculated.checked_add(step.amount_in.checked_add(step.fee_amount)?)?;
+            }
+
+            // Note that we don't need to recompute the current tick when the
+            // step ends in between two initialized ticks, since that only
+            // happens when the swap is complete.
+            if sqrt_price == sqrt_price_next_tick {
+                if let Some(net) = liquidity_net {
+                    let net = if zero_for_one {
+                        net.checked_neg()?
+                    } else {
+                        net
+                    };
+                    liquidity = liquidity.checked_add_signed(net)?;
+                    ticks_crossed += 1;
+                }
+                tick = if zero_for_one {
+                    tick_next - 1
+                } else {
+                    tick_next
+                };
+            }
+        }
+
+        if !remaining.is_zero() {
+            return None;
+        }
+
+        let (amount_in, amount_out) = if exact_in {
+            (amount, calculated)
+        } else {
+            (calculated, amount)
+        };
+        Some(Swap {
+            amount_in,
+            amount_out,
+            ticks_crossed,
+        })
+    }
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        self.swap(zero_for_one, Specified::ExactIn(in_amount))
+            .map(|swap| swap.amount_out)
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        self.swap(zero_for_one, Specified::ExactOut(out_amount))
+            .map(|swap| swap.amount_in)
+    }
+
+    fn gas_cost(&self) -> usize {
+        usize::try_from(self.gas).unwrap_or(usize::MAX)
+    }
+}
+
+/// The amount specified for a swap.
+#[derive(Clone, Copy, Debug)]
+enum Specified {
+    ExactIn(U256),
+    ExactOut(U256),
+}
+
+/// The result of a simulated swap.
+#[derive(Clone, Copy, Debug)]
+struct Swap {
+    amount_in: U256,
+    amount_out: U256,
+    ticks_crossed: usize,
+}
+
+/// The result of a single swap step within a tick range.
+#[derive(Clone, Copy, Debug)]
+struct Step {
+    sqrt_price_next: U256,
+    amount_in: U256,
+    amount_out: U256,
+    fee_amount: U256,
+}
+
+/// Computes the result of swapping some amount in or out within a single
+/// tick range. Port of `SwapMath.computeSwapStep`.
+fn compute_swap_step(
+    sqrt_price_current: U256,
+    sqrt_price_target: U256,
+    liquidity: u128,
+    amount_remaining: U256,
+    exact_in: bool,
+    fee: U256,
+) -> Option<Step> {
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+    let fee_complement = U256::from(FEE_DENOMINATOR) - fee;
+
+    let (sqrt_price_next, amount_to_target) = if exact_in {
+        let amount_remaining_less_fee =
+            mul_div(amount_remaining, fee_complement, FEE_DENOMINATOR.into())?;
+        let amount_in = if zero_for_one {
+            amount0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+        } else {
+            amount1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+        };
+        let sqrt_price_next = if amount_remaining_less_fee >= amount_in {
+            sqrt_price_target
+        } else {
+            next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        };
+        (sqrt_price_next, amount_in)
+    } else {
+        let amount_out = if zero_for_one {
+            amount1_delta(sqrt_price_target, sqrt_price_current, liquidity, false)?
+        } else {
+            amount0_delta(sqrt_price_current, sqrt_price_target, liquidity, false)?
+        };
+        let sqrt_price_next = if amount_remaining >= amount_out {
+            sqrt_price_target
+        } else {
+            next_sqrt_price_from_output(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?
+        };
+        (sqrt_price_next, amount_out)
+    };
+
+    let max = sqrt_price_next == sqrt_price_target;
+    let (amount_in, mut amount_out) = if zero_for_one {
+        (
+            if max && exact_in {
+                amount_to_target
+            } else {
+                amount0_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?
+            },
+            if max && !exact_in {
+                amount_to_target
+            } else {
+                amount1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?
+            },
+        )
+    } else {
+        (
+            if max && exact_in {
+                amount_to_target
+            } else {
+                amount1_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?
+            },
+            if max && !exact_in {
+                amount_to_target
+            } else {
+                amount0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?
+            },
+        )
+    };
+
+    // Cap the output amount to not exceed the remaining output amount.
+    if !exact_in && amount_out > amount_remaining {
+        amount_out = amount_remaining;
+    }
+
+    let fee_amount = if exact_in && !max {
+        // We didn't reach the target, so take the remainder of the maximum
+        // input as fee.
+        amount_remaining.checked_sub(amount_in)?
+    } else {
+        mul_div_rounding_up(amount_in, fee, fee_complement)?
+    };
+
+    Some(Step {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+/// Computes the square root price at the specified tick as a Q64.96 number.
+/// Port of `TickMath.getSqrtRatioAtTick`.
+fn sqrt_ratio_at_tick(tick: i32) -> Option<U256> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs_tick = tick.unsigned_abs();
+
+    const FACTORS: [u128; 19] = [
+        0xfff97272373d413259a46990580e213a,
+        0xfff2e50f5f656932ef12357cf3c7fdcc,
+        0xffe5caca7e10e4e61c3624eaa0941cd0,
+        0xffcb9843d60f6159c9db58835c926644,
+        0xff973b41fa98c081472e6896dfb254c0,
+        0xff2ea16466c96a3843ec78b326b52861,
+        0xfe5dee046a99a2a811c461f1969c3053,
+        0xfcbe86c7900a88aedcffc83b479aa3a4,
+        0xf987a7253ac413176f2b074cf7815e54,
+        0xf3392b0822b70005940c7a398e4b70f3,
+        0xe7159475a2c29b7443b29c7fa6e889d9,
+        0xd097f3bdfd2022b8845ad8f792aa5825,
+        0xa9f746462d870fdf8a65dc1f90e061e5,
+        0x70d869a156d2a1b890bb3df62baf32f7,
+        0x31be135f97d08fd981231505542fcfa6,
+        0x9aa508b5b7a84e1c677de54f3e99bc9,
+        0x5d6af8dedb81196699c329225ee604,
+        0x2216e584f5fa1ea926041bedfe98,
+        0x48a170391f7dc42444e8fa2,
+    ];
+
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from(0xfffcb933bd6fad37aa2d162d1a594001_u128)
+    } else {
+        U256::one() << 128
+    };
+    for (i, factor) in FACTORS.iter().enumerate() {
+        if abs_tick & (0x2 << i) != 0 {
+            ratio = (ratio * U256::from(*factor)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Round up when converting from Q128.128 to Q64.96 so that the result is
+    // always consistent with `getTickAtSqrtRatio`.
+    let round_up = if ratio.low_u32() == 0 { 0 } else { 1 };
+    Some((ratio >> 32) + round_up)
+}
+
+/// Computes the token 0 amount between two prices for the given liquidity.
+/// Port of `SqrtPriceMath.getAmount0Delta`.
+fn amount0_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (a, b) = if a > b { (b, a) } else { (a, b) };
+    if a.is_zero() {
+        return None;
+    }
+
+    let numerator1 = U256::from(liquidity) << 96;
+    let numerator2 = b - a;
+    if round_up {
+        div_ceil(mul_div_rounding_up(numerator1, numerator2, b)?, a)
+    } else {
+        mul_div(numerator1, numerator2, b)?.checked_div(a)
+    }
+}
+
+/// Computes the token 1 amount between two prices for the given liquidity.
+/// Port of `SqrtPriceMath.getAmount1Delta`.
+fn amount1_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (a, b) = if a > b { (b, a) } else { (a, b) };
+
+    let liquidity = U256::from(liquidity);
+    if round_up {
+        mul_div_rounding_up(liquidity, b - a, q96())
+    } else {
+        mul_div(liquidity, b - a, q96())
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromInput`.
+fn next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if zero_for_one {
+        next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromOutput`.
+fn next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if zero_for_one {
+        next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount0RoundingUp`.
+fn next_sqrt_price_from_amount0_rounding_up(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if amount.is_zero() {
+        return Some(sqrt_price);
+    }
+
+    let numerator1 = U256::from(liquidity) << 96;
+    if add {
+        if let Some(denominator) = amount
+            .checked_mul(sqrt_price)
+            .and_then(|product| numerator1.checked_add(product))
+        {
+            return mul_div_rounding_up(numerator1, sqrt_price, denominator);
+        }
+        div_ceil(numerator1, (numerator1 / sqrt_price).checked_add(amount)?)
+    } else {
+        let product = amount.checked_mul(sqrt_price)?;
+        let denominator = numerator1
+            .checked_sub(product)
+            .filter(|denominator| !denominator.is_zero())?;
+        mul_div_rounding_up(numerator1, sqrt_price, denominator)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount1RoundingDown`.
+fn next_sqrt_price_from_amount1_rounding_down(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    let max_u160 = (U256::one() << 160) - 1;
+    let liquidity = U256::from(liquidity);
+    if add {
+        let quotient = if amount <= max_u160 {
+            (amount << 96).checked_div(liquidity)?
+        } else {
+            mul_div(amount, q96(), liquidity)?
+        };
+        sqrt_price.checked_add(quotient)
+    } else {
+        let quotient = if amount <= max_u160 {
+            div_ceil(amount << 96, liquidity)?
+        } else {
+            mul_div_rounding_up(amount, q96(), liquidity)?
+        };
+        sqrt_price
+            .checked_sub(quotient)
+            .filter(|sqrt_price| !sqrt_price.is_zero())
+    }
+}
+
+/// The Q64.96 fixed point representation of 1.
+fn q96() -> U256 {
+    U256::one() << 96
+}
+
+/// Computes `a * b / denominator` with full precision, rounding down. Returns
+/// `None` on division by zero or if the result overflows.
+fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    U256::try_from(a.full_mul(b) / U512::from(denominator)).ok()
+}
+
+/// Computes `a * b / denominator` with full precision, rounding up. Returns
+/// `None` on division by zero or if the result overflows.
+fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let (quotient, remainder) = a.full_mul(b).div_mod(U512::from(denominator));
+    let result = U256::try_from(quotient).ok()?;
+    if remainder.is_zero() {
+        Some(result)
+    } else {
+        result.checked_add(U256::one())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, shared::addr};
+
+    fn pool(liquidity_net: &[(i32, i128)]) -> Pool {
+        Pool {
+            tokens: TokenPair::new(
+                addr!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+                addr!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+            )
+            .unwrap(),
+            sqrt_price: q96(),
+            liquidity: 1_000_000_000_000_000_000,
+            tick: 0,
+            liquidity_net: liquidity_net.iter().copied().collect(),
+            fee: 3000.into(),
+            gas: 108_163,
+        }
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick_bounds() {
+        assert_eq!(sqrt_ratio_at_tick(MIN_TICK).unwrap(), min_sqrt_ratio());
+        assert_eq!(sqrt_ratio_at_tick(MAX_TICK).unwrap(), max_sqrt_ratio());
+        assert_eq!(sqrt_ratio_at_tick(0).unwrap(), q96());
+        assert!(sqrt_ratio_at_tick(MIN_TICK - 1).is_none());
+        assert!(sqrt_ratio_at_tick(MAX_TICK + 1).is_none());
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick_values() {
+        for (tick, expected) in [
+            (-50, "79030349367926598376800521322"),
+            (50, "79426470787362580746886972461"),
+            (100, "79625275426524748796330556128"),
+            (-100, "78833030112140176575862854579"),
+            (887220, "1457652066949847389969617340386294118487833376468"),
+        ] {
+            assert_eq!(
+                sqrt_ratio_at_tick(tick).unwrap(),
+                U256::from_dec_str(expected).unwrap(),
+                "tick {tick}",
+            );
+        }
+    }
+
+    #[test]
+    fn swap_within_single_tick_range() {
+        let (token0, token1) = pool(&[]).tokens.get();
+        let pool = pool(&[(-60, 1), (60, -1)]);
+
+        let amount_in = U256::exp10(15);
+        let amount_out = pool.get_amount_out(token1, (amount_in, token0)).unwrap();
+        assert_eq!(amount_out, U256::from_dec_str("996006981039903").unwrap());
+        assert_eq!(
+            pool.swap_gas_cost(token1, (amount_in, token0)).unwrap(),
+            108_163
+        );
+
+        let amount_in = pool.get_amount_in(token0, (amount_out, token1)).unwrap();
+        assert_eq!(amount_in, U256::exp10(15));
+    }
+
+    #[test]
+    fn swap_crossing_ticks() {
+        let (token0, token1) = pool(&[]).tokens.get();
+        let pool = pool(&[
+            (-120, 0),
+            (-60, -500_000_000_000_000_000),
+            (60, 500_000_000_000_000_000),
+        ]);
+
+        let amount_in = U256::exp10(16);
+        let amount_out = pool.get_amount_out(token0, (amount_in, token1)).unwrap();
+        assert_eq!(amount_out, U256::from_dec_str("9887424946393952").unwrap());
+        assert_eq!(
+            pool.swap_gas_cost(token0, (amount_in, token1)).unwrap(),
+            108_163 + 25_000,
+        );
+
+        let amount_in = pool.get_amount_in(token1, (amount_out, token0)).unwrap();
+        assert_eq!(amount_in, U256::exp10(16));
+    }
+
+    #[test]
+    fn swap_exceeding_liquidity() {
+        let (token0, token1) = pool(&[]).tokens.get();
+        let pool = pool(&[(-60, 1_000_000_000_000_000_000)]);
+
+        assert!(
+            pool.get_amount_out(token1, (U256::exp10(18), token0))
+                .is_none()
+        );
+        assert!(
+            pool.get_amount_in(token0, (U256::exp10(18), token1))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn wrong_tokens() {
+        let (token0, _) = pool(&[]).tokens.get();
+        let pool = pool(&[]);
+        let other = addr!("def1ca1fb7fbcdc777520aa7f396b4e015f497ab");
+
+        assert!(
+            pool.get_amount_out(other, (U256::exp10(15), token0))
+                .is_none()
+        );
+        assert!(
+            pool.get_amount_in(other, (U256::exp10(15), token0))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,50 @@
+pub use shared::sources::curve::Pool;
+use {
+    crate::domain::liquidity,
+    ethereum_types::H160,
+    shared::sources::curve::{PoolKind, crypto_swap, stable_swap},
+};
+
+/// Converts a domain pool into a [`shared`] Curve pool. Returns `None` if the
+/// domain pool cannot be represented as a boundary pool.
+pub fn to_boundary_pool(address: H160, pool: &liquidity::curve::Pool) -> Option<Pool> {
+    let (tokens, balances) = pool
+        .reserves
+        .iter()
+        .map(|reserve| (reserve.token.0, reserve.amount))
+        .unzip();
+    let kind = match &pool.invariant {
+        liquidity::curve::Invariant::StableSwap(params) => {
+            if params.rates.len() != pool.reserves.iter().count() {
+                return None;
+            }
+            PoolKind::Stable(stable_swap::Parameters {
+                amplification: params.amplification,
+                fee: params.fee,
+                rates: params.rates.clone(),
+            })
+        }
+        liquidity::curve::Invariant::CryptoSwap(params) => {
+            if pool.reserves.iter().count() != 2 {
+                return None;
+            }
+            PoolKind::Crypto(crypto_swap::Parameters {
+                a: params.a,
+                gamma: params.gamma,
+                d: params.d,
+                price_scale: params.price_scale,
+                mid_fee: params.mid_fee,
+                out_fee: params.out_fee,
+                fee_gamma: params.fee_gamma,
+                precisions: params.precisions,
+            })
+        }
+    };
+
+    Some(Pool {
+        address,
+        tokens,
+        balances,
+        kind,
+    })
+}
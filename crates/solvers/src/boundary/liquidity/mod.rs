@@ -1,4 +1,6 @@
+pub mod concentrated;
 pub mod constant_product;
+pub mod curve;
 mod limit_order;
 pub mod stable;
 pub mod weighted_product;
@@ -0,0 +1,75 @@
+use {
+    crate::domain::{eth, liquidity},
+    ethereum_types::U256,
+    itertools::Itertools as _,
+};
+
+/// The state of a Curve pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub reserves: Reserves,
+    pub invariant: Invariant,
+}
+
+/// Curve pool reserves, ordered by the coin indices of the pool contract.
+#[derive(Clone, Debug)]
+pub struct Reserves(Vec<eth::Asset>);
+
+impl Reserves {
+    /// Returns a new reserve instance for specified reserve entries. Returns
+    /// `None` if there are fewer than two entries or duplicate tokens.
+    ///
+    /// Note that, unlike other pools, the order of the reserves is preserved
+    /// as it determines the coin indices used when swapping.
+    pub fn new(reserves: Vec<eth::Asset>) -> Option<Self> {
+        if reserves.len() < 2 || !reserves.iter().map(|r| r.token).all_unique() {
+            return None;
+        }
+        Some(Self(reserves))
+    }
+
+    /// Returns an iterator over the token reserves.
+    pub fn iter(&self) -> impl Iterator<Item = eth::Asset> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns an iterator over the tokens pairs handled by the pool reserves.
+    pub fn token_pairs(&self) -> impl Iterator<Item = liquidity::TokenPair> + '_ {
+        self.0
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| liquidity::TokenPair::new(a.token, b.token).expect("a != b"))
+    }
+}
+
+/// The invariant used by the pool along with its parameters.
+#[derive(Clone, Debug)]
+pub enum Invariant {
+    StableSwap(StableSwap),
+    CryptoSwap(CryptoSwap),
+}
+
+/// StableSwap pool parameters.
+#[derive(Clone, Debug)]
+pub struct StableSwap {
+    /// The amplification coefficient, multiplied by the `A_PRECISION` of 100.
+    pub amplification: U256,
+    /// The swap fee with 10 decimals of precision.
+    pub fee: U256,
+    /// The rates used to normalize each coin's balance to 18 decimals, scaled
+    /// by `1e18`.
+    pub rates: Vec<U256>,
+}
+
+/// CryptoSwap pool parameters.
+#[derive(Clone, Debug)]
+pub struct CryptoSwap {
+    pub a: U256,
+    pub gamma: U256,
+    pub d: U256,
+    pub price_scale: U256,
+    pub mid_fee: U256,
+    pub out_fee: U256,
+    pub fee_gamma: U256,
+    pub precisions: [U256; 2],
+}
@@ -0,0 +1,361 @@
+//! Matching of coincidences of wants (CoWs).
+//!
+//! Sell orders trading the same token pair in opposite directions are settled
+//! against each other at a uniform clearing price. The side selling more than
+//! the other one can absorb (valued at the auction's reference prices) is the
+//! excess side, and only its remainder gets routed over on-chain liquidity.
+//! The clearing price is the best price for the excess side at which this
+//! route still covers what its orders are owed.
+
+use {
+    super::{GasCost, Inner, Request, Route},
+    crate::{
+        boundary,
+        domain::{auction, eth, order, solution},
+        util,
+    },
+    ethereum_types::{U256, U512},
+    std::collections::BTreeMap,
+};
+
+/// The gas needed for every trade of a solution in addition to the first one,
+/// i.e. transferring its sell tokens in and its buy tokens out.
+const ADDITIONAL_TRADE_GAS: u64 = 2 * solution::ERC20_TRANSFER;
+
+impl Inner {
+    /// Matches sell orders trading against each other. The matches of token
+    /// pairs that don't share any tokens are combined into a single solution,
+    /// the others are returned as separate solutions.
+    pub(super) fn solve_cows(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+    ) -> Vec<solution::Solution> {
+        let mut pairs = BTreeMap::<_, [Vec<_>; 2]>::new();
+        for order in &auction.orders {
+            // Flashloans are only supported for single order solutions.
+            if order.side != order::Side::Sell || order.flashloan_hint.is_some() {
+                continue;
+            }
+            let (sell, buy) = (order.sell.token, order.buy.token);
+            if sell < buy {
+                pairs.entry((sell, buy)).or_default()[0].push(order);
+            } else {
+                pairs.entry((buy, sell)).or_default()[1].push(order);
+            }
+        }
+
+        let mut batches = Vec::<Batch>::new();
+        for ((a, b), [a_orders, b_orders]) in pairs {
+            if a_orders.is_empty() || b_orders.is_empty() {
+                continue;
+            }
+            let Some(cow) = self.match_pair(
+                auction,
+                boundary_solver,
+                [(a, a_orders.as_slice()), (b, b_orders.as_slice())],
+            ) else {
+                continue;
+            };
+            tracing::debug!(?a, ?b, trades = cow.trades.len(), "matched CoW");
+            match batches.iter_mut().find(|batch| batch.accepts(&cow)) {
+                Some(batch) => batch.cows.push(cow),
+                None => batches.push(Batch { cows: vec![cow] }),
+            }
+        }
+
+        batches
+            .into_iter()
+            .map(|batch| batch.into_solution(self.solution_gas_offset))
+            .collect()
+    }
+
+    /// Matches the orders of a token pair, given as the token sold by each
+    /// side along with its orders.
+    fn match_pair(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        sides: [(eth::TokenAddress, &[&order::Order]); 2],
+    ) -> Option<Cow> {
+        let cow = self.try_match(auction, boundary_solver, sides, eth::Gas::default())?;
+        if cow.route_gas.0.is_zero() {
+            return Some(cow);
+        }
+        // Solver fees were computed without knowing the gas needed for routing
+        // the remainder, so match again now that it is known.
+        self.try_match(auction, boundary_solver, sides, cow.route_gas)
+    }
+
+    fn try_match(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        sides: [(eth::TokenAddress, &[&order::Order]); 2],
+        route_gas: eth::Gas,
+    ) -> Option<Cow> {
+        // Every order pays for an equal share of the solution's gas.
+        let orders = sides[0].1.len() + sides[1].1.len();
+        let gas = eth::Gas(
+            route_gas
+                .0
+                .saturating_add(U256::from(ADDITIONAL_TRADE_GAS) * (orders - 1)),
+        ) + self.solution_gas_offset;
+        let fee = eth::Ether(gas.0.checked_mul(auction.gas_price.0.0)? / orders);
+
+        let [mut a, mut b] = sides.map(|(token, orders)| {
+            let price = self.reference_price(&auction.tokens, &token)?;
+            Some(Side {
+                token,
+                price,
+                orders: orders
+                    .iter()
+                    .filter_map(|order| Candidate::new(order, price, fee))
+                    .collect(),
+            })
+        });
+        let (a, b) = (a.as_mut()?, b.as_mut()?);
+        let (a_price, b_price) = (a.price, b.price);
+        a.orders
+            .retain(|candidate| candidate.satisfied_at(a_price, b_price));
+        b.orders
+            .retain(|candidate| candidate.satisfied_at(b_price, a_price));
+
+        loop {
+            if a.orders.is_empty() || b.orders.is_empty() {
+                return None;
+            }
+            let a_value = a.total()?.full_mul(a.price.0.0);
+            let b_value = b.total()?.full_mul(b.price.0.0);
+            let (excess, deficit) = if a_value >= b_value {
+                (&mut *a, &mut *b)
+            } else {
+                (&mut *b, &mut *a)
+            };
+            if let Clearing::Matched(cow) = self.clear(auction, boundary_solver, excess, deficit)? {
+                return Some(cow);
+            }
+        }
+    }
+
+    /// Computes the clearing price for the orders of both sides. Orders with
+    /// limit prices that can't be satisfied are removed, in which case the
+    /// clearing needs to be retried with the remaining orders.
+    fn clear(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        excess: &mut Side,
+        deficit: &mut Side,
+    ) -> Option<Clearing> {
+        // The clearing price is represented by the total amount of deficit
+        // tokens paid to the excess side for all of its tokens. The lowest
+        // price is the one at which the deficit side buys all tokens of the
+        // excess side, the highest one is bounded by the limit prices of the
+        // deficit side.
+        let excess_total = excess.total()?;
+        let deficit_total = deficit.total()?;
+        let bound = |candidate: &Candidate| {
+            mul_div(candidate.executed, excess_total, candidate.order.buy.amount)
+                .unwrap_or(U256::MAX)
+        };
+        let (mut low, mut high) = (deficit_total, deficit.orders.iter().map(bound).min()?);
+        if high < low {
+            let (i, _) = deficit
+                .orders
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, candidate)| bound(candidate))?;
+            deficit.orders.remove(i);
+            return Some(Clearing::Retry);
+        }
+
+        // Routes the excess side's remainder at a given price. Returns `None`
+        // if the remainder can't be routed for enough deficit tokens.
+        let route = |paid: U256| {
+            let received = deficit
+                .orders
+                .iter()
+                .try_fold(U256::zero(), |sum, candidate| {
+                    sum.checked_add(mul_div(candidate.executed, excess_total, paid)?)
+                })?;
+            let paid_out = excess
+                .orders
+                .iter()
+                .try_fold(U256::zero(), |sum, candidate| {
+                    sum.checked_add(mul_div(candidate.executed, paid, excess_total)?)
+                })?;
+            let missing = paid_out.saturating_sub(deficit_total);
+            if missing.is_zero() {
+                return Some(None);
+            }
+
+            let request = Request {
+                sell: eth::Asset {
+                    token: excess.token,
+                    amount: excess_total.checked_sub(received)?,
+                },
+                buy: eth::Asset {
+                    token: deficit.token,
+                    amount: missing,
+                },
+                side: order::Side::Sell,
+            };
+            let gas_cost = GasCost::new(auction.gas_price, deficit.price);
+            boundary_solver
+                .route(request, self.max_hops, Some(gas_cost))
+                .map(Some)
+        };
+
+        // Nothing needs to be routed at the lowest price, so search for the
+        // highest price at which the remainder can still be routed.
+        let mut best = route(low)?;
+        while low < high {
+            let mid = low + util::math::div_ceil(high - low, 2.into())?;
+            match route(mid) {
+                Some(route) => {
+                    low = mid;
+                    best = route;
+                }
+                None => high = mid - U256::one(),
+            }
+        }
+        let paid = low;
+
+        let count = excess.orders.len();
+        excess.orders.retain(|candidate| {
+            mul_div(candidate.executed, paid, excess_total)
+                .is_some_and(|bought| bought >= candidate.order.buy.amount)
+        });
+        if excess.orders.len() < count {
+            return Some(Clearing::Retry);
+        }
+
+        Some(Clearing::Matched(Cow {
+            prices: [(excess.token, paid), (deficit.token, excess_total)],
+            trades: excess
+                .orders
+                .iter()
+                .chain(&deficit.orders)
+                .map(|candidate| {
+                    solution::Fulfillment::new(
+                        candidate.order.clone(),
+                        candidate.executed,
+                        candidate.fee,
+                    )
+                })
+                .collect::<Option<_>>()?,
+            interactions: best.as_ref().map(Route::interactions).unwrap_or_default(),
+            route_gas: best.map(|route| route.gas()).unwrap_or_default(),
+        }))
+    }
+}
+
+/// The orders selling one of the tokens of a token pair.
+struct Side<'a> {
+    token: eth::TokenAddress,
+    price: auction::Price,
+    orders: Vec<Candidate<'a>>,
+}
+
+impl Side<'_> {
+    /// The total amount of tokens sold by the side's orders.
+    fn total(&self) -> Option<U256> {
+        self.orders.iter().try_fold(U256::zero(), |sum, candidate| {
+            sum.checked_add(candidate.executed)
+        })
+    }
+}
+
+/// An order that can be part of a CoW.
+struct Candidate<'a> {
+    order: &'a order::Order,
+    /// The sold amount, excluding the solver fee.
+    executed: U256,
+    fee: solution::Fee,
+}
+
+impl<'a> Candidate<'a> {
+    /// Creates a candidate for fully executing the order, charging the
+    /// specified fee if the order requires a solver fee.
+    fn new(order: &'a order::Order, sell_price: auction::Price, fee: eth::Ether) -> Option<Self> {
+        let fee = if order.solver_determines_fee() {
+            solution::Fee::Surplus(sell_price.ether_value(fee)?.into())
+        } else {
+            solution::Fee::Protocol
+        };
+        let executed = order
+            .sell
+            .amount
+            .checked_sub(fee.surplus().unwrap_or_default())?;
+        (!executed.is_zero()).then_some(Self {
+            order,
+            executed,
+            fee,
+        })
+    }
+
+    /// Returns whether the order's limit price is satisfied when trading at
+    /// the specified prices.
+    fn satisfied_at(&self, sell_price: auction::Price, buy_price: auction::Price) -> bool {
+        self.executed.full_mul(sell_price.0.0) >= self.order.buy.amount.full_mul(buy_price.0.0)
+    }
+}
+
+enum Clearing {
+    Matched(Cow),
+    Retry,
+}
+
+/// The matched orders of a token pair.
+struct Cow {
+    prices: [(eth::TokenAddress, U256); 2],
+    trades: Vec<solution::Fulfillment>,
+    interactions: Vec<solution::Interaction>,
+    route_gas: eth::Gas,
+}
+
+/// CoWs of token pairs that don't share any tokens, which can therefore be
+/// settled in a single solution.
+struct Batch {
+    cows: Vec<Cow>,
+}
+
+impl Batch {
+    fn accepts(&self, cow: &Cow) -> bool {
+        self.cows
+            .iter()
+            .flat_map(|other| &other.prices)
+            .all(|(token, _)| cow.prices.iter().all(|(other, _)| token != other))
+    }
+
+    fn into_solution(self, gas_offset: eth::SignedGas) -> solution::Solution {
+        let trades = self.cows.iter().map(|cow| cow.trades.len()).sum::<usize>();
+        let gas = self
+            .cows
+            .iter()
+            .fold(U256::zero(), |gas, cow| gas.saturating_add(cow.route_gas.0))
+            .saturating_add(U256::from(ADDITIONAL_TRADE_GAS) * (trades - 1));
+
+        let mut solution = solution::Solution {
+            gas: Some(eth::Gas(gas) + gas_offset),
+            ..Default::default()
+        };
+        for cow in self.cows {
+            solution.prices.0.extend(cow.prices);
+            solution
+                .trades
+                .extend(cow.trades.into_iter().map(solution::Trade::Fulfillment));
+            solution.interactions.extend(cow.interactions);
+        }
+        solution
+    }
+}
+
+/// Computes `a * b / c`, rounding down.
+fn mul_div(a: U256, b: U256, c: U256) -> Option<U256> {
+    if c.is_zero() {
+        return None;
+    }
+    (a.full_mul(b) / U512::from(c)).try_into().ok()
+}
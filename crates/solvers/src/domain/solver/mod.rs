@@ -0,0 +1,536 @@
+//! "Baseline" solver implementation.
+//!
+//! The baseline solver is a simple solver implementation that finds the best
+//! path of at most length `max_hops + 1` over a set of on-chain liquidity.
+//! Paths are compared by their output net of gas costs, valued at the
+//! auction's reference prices. Large orders can additionally be split over
+//! multiple paths, where each part of the order is routed over the path with
+//! the best marginal price for it.
+//!
+//! Optionally, sell orders trading against each other are matched first (see
+//! [`cows`]), in which case only the amounts that can't be matched get routed
+//! over on-chain liquidity.
+
+use {
+    crate::{
+        boundary,
+        domain::{
+            auction,
+            eth,
+            liquidity,
+            order::{self, Order},
+            solution,
+        },
+        infra::metrics,
+        util,
+    },
+    ethereum_types::U256,
+    std::{cmp, collections::HashSet, sync::Arc},
+};
+
+mod cows;
+
+pub struct Solver(Arc<Inner>);
+
+/// The amount of time we aim the solver to finish before the final deadline is
+/// reached.
+const DEADLINE_SLACK: chrono::Duration = chrono::Duration::milliseconds(500);
+
+pub struct Config {
+    pub weth: eth::WethAddress,
+    pub base_tokens: Vec<eth::TokenAddress>,
+    pub max_hops: usize,
+    pub max_partial_attempts: usize,
+    pub route_split_parts: usize,
+    pub solution_gas_offset: eth::SignedGas,
+    pub native_token_price_estimation_amount: eth::U256,
+    pub match_cows: bool,
+}
+
+struct Inner {
+    weth: eth::WethAddress,
+
+    /// Set of tokens to additionally consider as intermediary hops when
+    /// path-finding. This allows paths of the kind `TOKEN1 -> WETH -> TOKEN2`
+    /// to be considered.
+    base_tokens: HashSet<eth::TokenAddress>,
+
+    /// Maximum number of hops that can be considered in a trading path. A hop
+    /// is an intermediary token within a trading path. For example:
+    /// - A value of 0 indicates that only a direct trade is allowed: `A -> B`
+    /// - A value of 1 indicates that a single intermediary token can appear
+    ///   within a trading path: `A -> B -> C`
+    /// - A value of 2 indicates: `A -> B -> C -> D`
+    /// - etc.
+    max_hops: usize,
+
+    /// The maximum number of attempts to solve a partially fillable order.
+    /// The largest executable amount is searched for with a binary search
+    /// over the order's amount, so each attempt halves the search interval.
+    max_partial_attempts: usize,
+
+    /// The number of parts that orders are divided into when splitting them
+    /// over multiple paths. A value of 1 disables splitting.
+    route_split_parts: usize,
+
+    /// Units of gas that get added to the gas estimate for executing a
+    /// computed trade route to arrive at a gas estimate for a whole settlement.
+    solution_gas_offset: eth::SignedGas,
+
+    /// The amount of the native token to use to estimate native price of a
+    /// token
+    native_token_price_estimation_amount: eth::U256,
+
+    /// Whether to match orders trading against each other before routing them
+    /// individually.
+    match_cows: bool,
+}
+
+impl Solver {
+    /// Creates a new baseline solver for the specified configuration.
+    pub fn new(config: Config) -> Self {
+        Self(Arc::new(Inner {
+            weth: config.weth,
+            base_tokens: config.base_tokens.into_iter().collect(),
+            max_hops: config.max_hops,
+            max_partial_attempts: config.max_partial_attempts,
+            route_split_parts: config.route_split_parts,
+            solution_gas_offset: config.solution_gas_offset,
+            native_token_price_estimation_amount: config.native_token_price_estimation_amount,
+            match_cows: config.match_cows,
+        }))
+    }
+
+    /// Solves the specified auction, returning a vector of all possible
+    /// solutions.
+    pub async fn solve(&self, auction: auction::Auction) -> Vec<solution::Solution> {
+        metrics::solve(&auction);
+        let deadline = auction.deadline.clone();
+        // Make sure to push the CPU-heavy code to a separate thread in order to
+        // not lock up the [`tokio`] runtime and cause it to slow down handling
+        // the real async things. For larger settlements, this can block in the
+        // 100s of ms.
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let remaining = auction
+            .deadline
+            .clone()
+            .reduce(DEADLINE_SLACK)
+            .remaining()
+            .unwrap_or_default();
+
+        let inner = self.0.clone();
+        let span = tracing::Span::current();
+        let background_work = async move {
+            let _entered = span.enter();
+            inner.solve(auction, sender);
+        };
+
+        if tokio::time::timeout(remaining, tokio::spawn(background_work))
+            .await
+            .is_err()
+        {
+            tracing::debug!("reached timeout while solving orders");
+        }
+
+        let mut solutions = vec![];
+        while let Ok(solution) = receiver.try_recv() {
+            solutions.push(solution);
+        }
+        metrics::solved(&deadline, &solutions);
+        solutions
+    }
+}
+
+impl Inner {
+    fn solve(
+        &self,
+        auction: auction::Auction,
+        sender: tokio::sync::mpsc::UnboundedSender<solution::Solution>,
+    ) {
+        let boundary_solver = boundary::baseline::Solver::new(
+            &self.weth,
+            &self.base_tokens,
+            self.route_split_parts,
+            &auction.liquidity,
+        );
+
+        // Orders that are settled as part of a CoW are not solved again
+        // individually.
+        let mut matched = HashSet::new();
+        if self.match_cows {
+            for (i, solution) in self
+                .solve_cows(&auction, &boundary_solver)
+                .into_iter()
+                .enumerate()
+            {
+                matched.extend(solution.trades.iter().filter_map(|trade| match trade {
+                    solution::Trade::Fulfillment(fulfillment) => Some(fulfillment.order().uid),
+                    solution::Trade::Jit(_) => None,
+                }));
+                let solution = solution
+                    .with_id(solution::Id((auction.orders.len() + i) as u64))
+                    .with_buffers_internalizations(&auction.tokens);
+                if sender.send(solution).is_err() {
+                    tracing::debug!("deadline hit, receiver dropped");
+                    return;
+                }
+            }
+        }
+
+        for (i, order) in auction.orders.into_iter().enumerate() {
+            if matched.contains(&order.uid) {
+                continue;
+            }
+            let sell_token = order.sell.token;
+            let sell_token_price = match auction.tokens.reference_price(&sell_token) {
+                Some(price) => price,
+                None if sell_token == self.weth.0.into() => {
+                    // Early return if the sell token is native token
+                    auction::Price(eth::Ether(eth::U256::exp10(18)))
+                }
+                None => {
+                    // Estimate the price of the sell token in the native token
+                    let native_price_request = self.native_price_request(&order);
+                    match boundary_solver.route(native_price_request, self.max_hops, None) {
+                        Some(route) => {
+                            // how many units of buy_token are bought for one unit of sell_token
+                            // (buy_amount / sell_amount).
+                            let price = self.native_token_price_estimation_amount.to_f64_lossy()
+                                / route.input().amount.to_f64_lossy();
+                            let Some(price) = to_normalized_price(price) else {
+                                continue;
+                            };
+
+                            auction::Price(eth::Ether(price))
+                        }
+                        _ => {
+                            // This is to allow quotes to be generated for tokens for which the sell
+                            // token price is not available, so we default to fee=0
+                            auction::Price(eth::Ether(eth::U256::MAX))
+                        }
+                    }
+                }
+            };
+
+            // Routes are compared by the amount of the token that the order
+            // optimises for, i.e. the buy token for sell orders and the sell
+            // token for buy orders, net of their gas costs.
+            let route_token_price = match order.side {
+                order::Side::Sell => self.reference_price(&auction.tokens, &order.buy.token),
+                order::Side::Buy => Some(sell_token_price),
+            };
+            let gas_cost = route_token_price.map(|price| GasCost::new(auction.gas_price, price));
+
+            let solution = self.solve_order(&order, &sender, |request| {
+                let route = boundary_solver.route(request, self.max_hops, gas_cost)?;
+                let interactions = route.interactions();
+
+                // The baseline solver generates a path with swapping
+                // for exact output token amounts. This leads to
+                // potential rounding errors for buy orders, where we
+                // can buy slightly more than intended. Fix this by
+                // capping the output amount to the order's buy amount
+                // for buy orders.
+                let mut output = route.output();
+                if let order::Side::Buy = order.side {
+                    output.amount = cmp::min(output.amount, order.buy.amount);
+                }
+
+                let gas = route.gas() + self.solution_gas_offset;
+                let fee = sell_token_price
+                    .ether_value(eth::Ether(gas.0.checked_mul(auction.gas_price.0.0)?))?
+                    .into();
+
+                Some(
+                    solution::Single {
+                        order: order.clone(),
+                        input: route.input(),
+                        output,
+                        interactions,
+                        gas,
+                    }
+                    .into_solution(fee)?
+                    .with_id(solution::Id(i as u64))
+                    .with_buffers_internalizations(&auction.tokens),
+                )
+            });
+            if let Some(solution) = solution {
+                if sender.send(solution).is_err() {
+                    tracing::debug!("deadline hit, receiver dropped");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns the reference price of a token, falling back to the price of
+    /// the native token for WETH.
+    fn reference_price(
+        &self,
+        tokens: &auction::Tokens,
+        token: &eth::TokenAddress,
+    ) -> Option<auction::Price> {
+        tokens.reference_price(token).or_else(|| {
+            (*token == self.weth.0.into()).then(|| auction::Price(eth::Ether(eth::U256::exp10(18))))
+        })
+    }
+
+    /// Solves the order for the largest executed amount that `solve` finds a
+    /// solution for. Orders that aren't partially fillable are only solved for
+    /// their full amount. For partially fillable orders, the executed amount
+    /// is searched for with a binary search using at most
+    /// `max_partial_attempts` attempts in total.
+    fn solve_order(
+        &self,
+        order: &Order,
+        sender: &tokio::sync::mpsc::UnboundedSender<solution::Solution>,
+        solve: impl Fn(Request) -> Option<solution::Solution>,
+    ) -> Option<solution::Solution> {
+        let attempt = |executed: U256| {
+            let request = Self::request_for_amount(order, executed)?;
+            tracing::trace!(order =% order.uid, ?request, "finding route");
+            solve(request)
+        };
+
+        let total = match order.side {
+            order::Side::Buy => order.buy.amount,
+            order::Side::Sell => order.sell.amount,
+        };
+        if let Some(solution) = attempt(total) {
+            return Some(solution);
+        }
+        if !order.partially_fillable {
+            return None;
+        }
+
+        // Smaller amounts get better prices, so an amount that can't be
+        // executed is an upper bound for the amounts that can. Note that this
+        // assumes that the solver fee, which only depends on gas and not on
+        // the executed amount, is small compared to the order. Amounts too
+        // small to cover it can't be executed either, so for orders where
+        // only a small fraction can be filled at all, the search may miss it.
+        let (mut low, mut high) = (U256::zero(), total);
+        let mut best = None;
+        for _ in 1..self.max_partial_attempts {
+            // The receiver gets dropped once the deadline is reached.
+            if sender.is_closed() {
+                break;
+            }
+            let mid = low + (high - low) / 2;
+            if mid == low {
+                break;
+            }
+            match attempt(mid) {
+                Some(solution) => {
+                    best = Some(solution);
+                    low = mid;
+                }
+                None => high = mid,
+            }
+        }
+        best
+    }
+
+    /// Returns the request for executing the specified amount of an order at
+    /// its limit price. The amount is the sell amount for sell orders and the
+    /// buy amount for buy orders.
+    fn request_for_amount(order: &Order, executed: U256) -> Option<Request> {
+        let (sell, buy) = match order.side {
+            order::Side::Buy if executed == order.buy.amount => {
+                (order.sell.amount, order.buy.amount)
+            }
+            order::Side::Sell if executed == order.sell.amount => {
+                (order.sell.amount, order.buy.amount)
+            }
+            // Amounts are rounded such that the order's limit price holds.
+            order::Side::Buy => (
+                order.sell.amount.checked_mul(executed)? / order.buy.amount,
+                executed,
+            ),
+            order::Side::Sell => (
+                executed,
+                util::math::div_ceil(order.buy.amount.checked_mul(executed)?, order.sell.amount)?,
+            ),
+        };
+        (!sell.is_zero() && !buy.is_zero()).then_some(Request {
+            sell: eth::Asset {
+                token: order.sell.token,
+                amount: sell,
+            },
+            buy: eth::Asset {
+                token: order.buy.token,
+                amount: buy,
+            },
+            side: order.side,
+        })
+    }
+
+    fn native_price_request(&self, order: &Order) -> Request {
+        let sell = eth::Asset {
+            token: order.sell.token,
+            // Note that we intentionally do not use [`eth::U256::max_value()`]
+            // as an order with this would cause overflows with the smart
+            // contract, so buy orders requiring excessively large sell amounts
+            // would not work anyway. Instead we use `2 ** 144`, the rationale
+            // being that Uniswap V2 pool reserves are 112-bit integers. Noting
+            // that `256 - 112 = 144`, this means that we can use it to trade a full
+            // `type(uint112).max` without overflowing a `uint256` on the smart
+            // contract level. Requiring to trade more than `type(uint112).max`
+            // is unlikely and would not work with Uniswap V2 anyway.
+            amount: eth::U256::one() << 144,
+        };
+
+        let buy = eth::Asset {
+            token: self.weth.0.into(),
+            amount: self.native_token_price_estimation_amount,
+        };
+
+        Request {
+            sell,
+            buy,
+            side: order::Side::Buy,
+        }
+    }
+}
+
+fn to_normalized_price(price: f64) -> Option<U256> {
+    let uint_max = 2.0_f64.powi(256);
+
+    let price_in_eth = 1e18 * price;
+    if price_in_eth.is_normal() && price_in_eth >= 1. && price_in_eth < uint_max {
+        Some(U256::from_f64_lossy(price_in_eth))
+    } else {
+        None
+    }
+}
+
+/// A baseline routing request.
+#[derive(Debug)]
+pub struct Request {
+    pub sell: eth::Asset,
+    pub buy: eth::Asset,
+    pub side: order::Side,
+}
+
+/// The cost of gas expressed in a token, used to compare routes by their
+/// output net of execution costs.
+#[derive(Clone, Copy, Debug)]
+pub struct GasCost {
+    gas_price: auction::GasPrice,
+    token_price: auction::Price,
+}
+
+impl GasCost {
+    pub fn new(gas_price: auction::GasPrice, token_price: auction::Price) -> Self {
+        Self {
+            gas_price,
+            token_price,
+        }
+    }
+
+    /// Returns the amount of the token that is equivalent in value to the
+    /// cost of the specified gas. Saturates on overflow, making the route
+    /// prohibitively expensive.
+    pub fn token_amount(&self, gas: eth::Gas) -> U256 {
+        gas.0
+            .checked_mul(self.gas_price.0.0)
+            .and_then(|cost| self.token_price.ether_value(eth::Ether(cost)))
+            .unwrap_or(U256::MAX)
+    }
+}
+
+/// A trading route. The traded amount can be split over multiple paths that
+/// all start with the same input token and end with the same output token.
+#[derive(Debug)]
+pub struct Route<'a> {
+    paths: Vec<Vec<Segment<'a>>>,
+}
+
+/// A segment in a trading route.
+#[derive(Debug)]
+pub struct Segment<'a> {
+    pub liquidity: &'a liquidity::Liquidity,
+    // TODO: There is no type-level guarantee here that both `input.token` and
+    // `output.token` are valid for the liquidity in this segment. This is
+    // unfortunate because this type leaks out of this module (currently into
+    // the `boundary::baseline` module) but should no longer need to be `pub`
+    // once the `boundary::baseline` module gets refactored into the domain
+    // logic, so I think it is fine for now.
+    pub input: eth::Asset,
+    pub output: eth::Asset,
+    pub gas: eth::Gas,
+}
+
+impl<'a> Route<'a> {
+    pub fn new(segments: Vec<Segment<'a>>) -> Option<Self> {
+        if segments.is_empty() {
+            return None;
+        }
+        Some(Self {
+            paths: vec![segments],
+        })
+    }
+
+    /// Creates a route splitting the traded amount over multiple routes.
+    /// Returns `None` if the routes don't trade the same tokens.
+    pub fn split(routes: Vec<Route<'a>>) -> Option<Self> {
+        let first = routes.first()?;
+        let (input, output) = (first.input().token, first.output().token);
+        if routes
+            .iter()
+            .any(|route| route.input().token != input || route.output().token != output)
+        {
+            return None;
+        }
+        Some(Self {
+            paths: routes.into_iter().flat_map(|route| route.paths).collect(),
+        })
+    }
+
+    pub fn input(&self) -> eth::Asset {
+        eth::Asset {
+            token: self.paths[0][0].input.token,
+            amount: self.paths.iter().fold(U256::zero(), |acc, path| {
+                acc.saturating_add(path[0].input.amount)
+            }),
+        }
+    }
+
+    pub fn output(&self) -> eth::Asset {
+        let last = |path: &Vec<Segment<'a>>| {
+            path.last()
+                .expect("route paths have at least one segment by construction")
+                .output
+        };
+        eth::Asset {
+            token: last(&self.paths[0]).token,
+            amount: self.paths.iter().fold(U256::zero(), |acc, path| {
+                acc.saturating_add(last(path).amount)
+            }),
+        }
+    }
+
+    pub fn gas(&self) -> eth::Gas {
+        eth::Gas(self.segments().fold(U256::zero(), |acc, segment| {
+            acc.saturating_add(segment.gas.0)
+        }))
+    }
+
+    fn segments(&self) -> impl Iterator<Item = &Segment<'a>> {
+        self.paths.iter().flatten()
+    }
+
+    fn interactions(&self) -> Vec<solution::Interaction> {
+        self.segments()
+            .map(|segment| {
+                solution::Interaction::Liquidity(solution::LiquidityInteraction {
+                    liquidity: segment.liquidity.clone(),
+                    input: segment.input,
+                    output: segment.output,
+                    // TODO does the baseline solver know about this optimization?
+                    internalize: false,
+                })
+            })
+            .collect()
+    }
+}
@@ -35,10 +35,17 @@ struct Config {
     /// path.
     max_hops: usize,
 
-    /// The maximum number of pieces to divide partially fillable limit orders
-    /// when trying to solve it against baseline liquidity.
+    /// The maximum number of attempts for finding the largest amount of a
+    /// partially fillable order that can be solved against baseline liquidity.
     max_partial_attempts: usize,
 
+    /// The number of parts that orders are divided into when splitting them
+    /// over multiple paths. Each part gets routed over the path with the best
+    /// marginal price for it, so more parts allow for more precise splits at
+    /// the cost of more computation. A value of 1 disables splitting.
+    #[serde(default = "default_route_split_parts")]
+    route_split_parts: usize,
+
     /// Units of gas that get added to the gas estimate for executing a
     /// computed trade route to arrive at a gas estimate for a whole settlement.
     #[serde(default = "default_gas_offset")]
@@ -48,6 +55,12 @@ struct Config {
     /// token
     #[serde_as(as = "serialize::U256")]
     native_token_price_estimation_amount: eth::U256,
+
+    /// Whether to match sell orders trading the same token pair in opposite
+    /// directions against each other, only routing the remainder over
+    /// on-chain liquidity.
+    #[serde(default)]
+    match_cows: bool,
 }
 
 /// Load the driver configuration from a TOML file.
@@ -82,8 +95,10 @@ pub async fn load(path: &Path) -> solver::Config {
             .collect(),
         max_hops: config.max_hops,
         max_partial_attempts: config.max_partial_attempts,
+        route_split_parts: config.route_split_parts,
         solution_gas_offset: config.solution_gas_offset.into(),
         native_token_price_estimation_amount: config.native_token_price_estimation_amount,
+        match_cows: config.match_cows,
     }
 }
 
@@ -110,3 +125,8 @@ where
 fn default_gas_offset() -> i64 {
     SETTLEMENT_OVERHEAD.try_into().unwrap()
 }
+
+/// Orders are not split over multiple paths by default.
+fn default_route_split_parts() -> usize {
+    1
+}
@@ -0,0 +1,128 @@
+//! Test case that verifies that the baseline solver matches orders trading
+//! against each other, only routing the remainder over on-chain liquidity.
+
+use {crate::tests, serde_json::json};
+
+fn order(uid: u8, sell_token: &str, buy_token: &str, sell: &str, buy: &str) -> serde_json::Value {
+    json!({
+        "uid": format!("0x{}", hex::encode([uid; 56])),
+        "sellToken": sell_token,
+        "buyToken": buy_token,
+        "sellAmount": sell,
+        "fullSellAmount": sell,
+        "buyAmount": buy,
+        "fullBuyAmount": buy,
+        "feePolicies": [],
+        "validTo": 0,
+        "kind": "sell",
+        "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+        "partiallyFillable": false,
+        "preInteractions": [],
+        "postInteractions": [],
+        "sellTokenSource": "erc20",
+        "buyTokenDestination": "erc20",
+        "class": "market",
+        "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+        "signingScheme": "presign",
+        "signature": "0x",
+    })
+}
+
+#[tokio::test]
+async fn test() {
+    let engine = tests::SolverEngine::new(
+        "baseline",
+        tests::Config::File("config/example.baseline.toml".into()),
+    )
+    .await;
+
+    const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    const COW: &str = "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB";
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                WETH: {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                },
+                COW: {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "53125132573502",
+                    "availableBalance": "0",
+                    "trusted": true
+                }
+            },
+            "orders": [
+                // Sells 1 WETH, worth slightly more than the COW sold below.
+                order(1, WETH, COW, "1000000000000000000", "18000000000000000000000"),
+                order(2, COW, WETH, "18000000000000000000000", "900000000000000000"),
+            ],
+            "liquidity": [
+                // A pool trading at the reference price.
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        WETH: {
+                            "balance": "100000000000000000000"
+                        },
+                        COW: {
+                            "balance": "1882350000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0x97b744df0b59d93A866304f97431D8EfAd29a08d",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                }
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    // Both orders are settled in a single solution, so neither of them is
+    // solved individually.
+    let solutions = solution["solutions"].as_array().unwrap();
+    assert_eq!(solutions.len(), 1);
+    let solution = &solutions[0];
+
+    let trades = solution["trades"].as_array().unwrap();
+    assert_eq!(trades.len(), 2);
+    for (trade, executed) in trades
+        .iter()
+        .zip(["1000000000000000000", "18000000000000000000000"])
+    {
+        assert_eq!(trade["executedAmount"], executed);
+    }
+
+    // Only the WETH that isn't bought by the COW seller is routed over the
+    // pool, which is a small fraction of the order.
+    let interactions = solution["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 1);
+    assert_eq!(interactions[0]["inputToken"], WETH.to_lowercase());
+    let routed = interactions[0]["inputAmount"]
+        .as_str()
+        .unwrap()
+        .parse::<u128>()
+        .unwrap();
+    assert!(routed < 100_000_000_000_000_000);
+
+    // The orders are settled at uniform clearing prices that satisfy both of
+    // their limit prices, close to the price of the pool.
+    let price = |token: &str| {
+        solution["prices"][token.to_lowercase()]
+            .as_str()
+            .unwrap()
+            .parse::<f64>()
+            .unwrap()
+    };
+    let cow_per_weth = price(WETH) / price(COW);
+    assert!((18_600.0..18_823.5).contains(&cow_per_weth));
+}
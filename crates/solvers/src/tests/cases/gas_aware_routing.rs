@@ -0,0 +1,150 @@
+//! Test case that verifies that the baseline solver takes gas costs into
+//! account when choosing between routes. A two-hop route that yields slightly
+//! more than a direct swap is only chosen when gas is cheap enough for the
+//! additional hop to pay for itself.
+
+use {crate::tests, serde_json::json};
+
+fn auction(effective_gas_price: &str) -> serde_json::Value {
+    json!({
+        "id": "1",
+        "tokens": {
+            "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                "decimals": 18,
+                "symbol": "WETH",
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": true
+            },
+            "0x6B175474E89094C44Da98b954EedeAC495271d0F": {
+                "decimals": 18,
+                "symbol": "DAI",
+                "referencePrice": "333333333333333",
+                "availableBalance": "0",
+                "trusted": true
+            },
+            "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                "decimals": 18,
+                "symbol": "COW",
+                "referencePrice": "53125132573502",
+                "availableBalance": "0",
+                "trusted": true
+            }
+        },
+        "orders": [
+            {
+                "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a",
+                "sellToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                "buyToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                "sellAmount": "1000000000000000000",
+                "fullSellAmount": "1000000000000000000",
+                "buyAmount": "18000000000000000000000",
+                "fullBuyAmount": "18000000000000000000000",
+                "feePolicies": [],
+                "validTo": 0,
+                "kind": "sell",
+                "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                "partiallyFillable": false,
+                "preInteractions": [],
+                "postInteractions": [],
+                "sellTokenSource": "erc20",
+                "buyTokenDestination": "erc20",
+                "class": "market",
+                "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                "signingScheme": "presign",
+                "signature": "0x",
+            }
+        ],
+        "liquidity": [
+            {
+                "kind": "constantProduct",
+                "tokens": {
+                    "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                        "balance": "100000000000000000000"
+                    },
+                    "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                        "balance": "1882300000000000000000000"
+                    }
+                },
+                "fee": "0.003",
+                "id": "0",
+                "address": "0x97b744df0b59d93A866304f97431D8EfAd29a08d",
+                "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                "gasEstimate": "110000"
+            },
+            {
+                "kind": "constantProduct",
+                "tokens": {
+                    "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                        "balance": "100000000000000000000"
+                    },
+                    "0x6B175474E89094C44Da98b954EedeAC495271d0F": {
+                        "balance": "300000000000000000000000"
+                    }
+                },
+                "fee": "0.003",
+                "id": "1",
+                "address": "0xA478c2975Ab1Ea89e8196811F51A7B7Ade33eB11",
+                "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                "gasEstimate": "110000"
+            },
+            {
+                "kind": "constantProduct",
+                "tokens": {
+                    "0x6B175474E89094C44Da98b954EedeAC495271d0F": {
+                        "balance": "300000000000000000000000"
+                    },
+                    "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                        "balance": "1908000000000000000000000"
+                    }
+                },
+                "fee": "0.003",
+                "id": "2",
+                "address": "0x1111111111111111111111111111111111111111",
+                "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                "gasEstimate": "110000"
+            }
+        ],
+        "effectiveGasPrice": effective_gas_price,
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    })
+}
+
+fn liquidity_ids(solution: &serde_json::Value) -> Vec<&str> {
+    solution["solutions"][0]["interactions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|interaction| interaction["id"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test() {
+    let engine = tests::SolverEngine::new(
+        "baseline",
+        tests::Config::String(
+            r#"
+                chain-id = "1"
+                base-tokens = ["0x6B175474E89094C44Da98b954EedeAC495271d0F"]
+                max-hops = 1
+                max-partial-attempts = 1
+                native-token-price-estimation-amount = "1000000000000000000"
+            "#
+            .to_owned(),
+        ),
+    )
+    .await;
+
+    // At 100 Gwei, the additional hop costs roughly 113 COW, which is more
+    // than the 14 COW that the route via DAI yields on top of the direct swap.
+    let solution = engine.solve(auction("100000000000")).await;
+    assert_eq!(liquidity_ids(&solution), ["0"]);
+
+    // At 1 Gwei, the additional hop only costs roughly 1 COW.
+    let solution = engine.solve(auction("1000000000")).await;
+    assert_eq!(liquidity_ids(&solution), ["1", "2"]);
+}
@@ -2,7 +2,10 @@
 
 mod bal_liquidity;
 mod buy_order_rounding;
+mod cow_matching;
 mod direct_swap;
+mod gas_aware_routing;
 mod internalization;
 mod limit_order_quoting;
 mod partial_fill;
+mod split_routing;
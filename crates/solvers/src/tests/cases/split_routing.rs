@@ -0,0 +1,116 @@
+//! Test case that verifies that the baseline solver splits large orders over
+//! multiple pools when that yields more than routing the whole order over a
+//! single pool.
+
+use {crate::tests, serde_json::json};
+
+const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+const COW: &str = "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB";
+
+fn pool(id: &str, address: &str) -> serde_json::Value {
+    json!({
+        "kind": "constantProduct",
+        "tokens": {
+            WETH: {
+                "balance": "100000000000000000000"
+            },
+            COW: {
+                "balance": "1882300000000000000000000"
+            }
+        },
+        "fee": "0.003",
+        "id": id,
+        "address": address,
+        "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+        "gasEstimate": "110000"
+    })
+}
+
+#[tokio::test]
+async fn test() {
+    let engine = tests::SolverEngine::new(
+        "baseline",
+        tests::Config::String(
+            r#"
+                chain-id = "1"
+                base-tokens = []
+                max-hops = 0
+                max-partial-attempts = 1
+                native-token-price-estimation-amount = "1000000000000000000"
+                route-split-parts = 10
+            "#
+            .to_owned(),
+        ),
+    )
+    .await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                WETH: {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                },
+                COW: {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "53125132573502",
+                    "availableBalance": "0",
+                    "trusted": true
+                }
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": WETH,
+                    "buyToken": COW,
+                    "sellAmount": "10000000000000000000",
+                    "fullSellAmount": "10000000000000000000",
+                    "buyAmount": "175000000000000000000000",
+                    "fullBuyAmount": "175000000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [
+                pool("0", "0x97b744df0b59d93A866304f97431D8EfAd29a08d"),
+                pool("1", "0x1111111111111111111111111111111111111111"),
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    // Routing the whole order over one of the pools yields roughly 170,600
+    // COW, which doesn't satisfy the order. Splitting it evenly over both
+    // pools yields roughly 178,700 COW.
+    let solution = &solution["solutions"][0];
+    let interactions = solution["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 2);
+    for interaction in interactions {
+        assert_eq!(interaction["inputToken"], WETH.to_lowercase());
+        assert_eq!(interaction["inputAmount"], "5000000000000000000");
+    }
+    assert_eq!(
+        solution["trades"][0]["executedAmount"],
+        "10000000000000000000"
+    );
+}
@@ -0,0 +1,10 @@
+-- Contains the reference score of every winning solver of an auction.
+-- With multiple winners per auction, each winner gets its own reference score which is computed
+-- from the auction as if the solver had not participated.
+CREATE TABLE reference_scores
+(
+    auction_id      bigint  NOT NULL,
+    solver          bytea   NOT NULL,
+    reference_score numeric NOT NULL,
+    PRIMARY KEY (auction_id, solver)
+);
@@ -0,0 +1,59 @@
+-- Publishes changes in the life cycle of orders on the `order_updates`
+-- notification channel so that API clients can get them pushed instead of
+-- polling for them.
+
+CREATE FUNCTION notify_order_event() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('order_updates', json_build_object(
+        'type', 'event',
+        'uid', '0x' || encode(NEW.order_uid, 'hex'),
+        'label', NEW.label,
+        'timestamp', NEW.timestamp
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Filtered and invalid events get emitted for every auction an order is not
+-- part of, which is too noisy to be useful for clients.
+CREATE TRIGGER order_events_notify
+AFTER INSERT ON order_events
+FOR EACH ROW WHEN (NEW.label NOT IN ('filtered', 'invalid'))
+EXECUTE FUNCTION notify_order_event();
+
+CREATE FUNCTION notify_trade() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('order_updates', json_build_object(
+        'type', 'trade',
+        'uid', '0x' || encode(NEW.order_uid, 'hex'),
+        'blockNumber', NEW.block_number,
+        'logIndex', NEW.log_index,
+        'sellAmount', NEW.sell_amount::text,
+        'buyAmount', NEW.buy_amount::text,
+        'feeAmount', NEW.fee_amount::text
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trades_notify
+AFTER INSERT ON trades
+FOR EACH ROW
+EXECUTE FUNCTION notify_trade();
+
+CREATE FUNCTION notify_invalidation() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('order_updates', json_build_object(
+        'type', 'invalidation',
+        'uid', '0x' || encode(NEW.order_uid, 'hex'),
+        'blockNumber', NEW.block_number,
+        'logIndex', NEW.log_index
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER invalidations_notify
+AFTER INSERT ON invalidations
+FOR EACH ROW
+EXECUTE FUNCTION notify_invalidation();
@@ -0,0 +1,26 @@
+-- Conditional orders (ComposableCoW) that get polled by the orderbook every
+-- block in order to place the orders they produce.
+CREATE TABLE conditional_orders (
+    -- Hash of the parameters as computed by ComposableCoW. Different owners
+    -- can authorize conditional orders with the same parameters.
+    id bytea NOT NULL,
+    owner bytea NOT NULL,
+    handler bytea NOT NULL,
+    salt bytea NOT NULL,
+    static_input bytea NOT NULL,
+    creation_timestamp timestamptz NOT NULL,
+    -- Whether the conditional order still gets polled.
+    active boolean NOT NULL,
+    PRIMARY KEY (owner, id)
+);
+
+CREATE INDEX conditional_orders_active ON conditional_orders USING BTREE (active);
+
+-- The orders that got placed for conditional orders.
+CREATE TABLE conditional_order_parts (
+    order_uid bytea PRIMARY KEY,
+    owner bytea NOT NULL,
+    conditional_order bytea NOT NULL
+);
+
+CREATE INDEX conditional_order_parts_by_conditional_order ON conditional_order_parts USING BTREE (owner, conditional_order);
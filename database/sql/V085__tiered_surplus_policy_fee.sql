@@ -0,0 +1,8 @@
+-- Add `tiered_surplus` policy fee kind
+ALTER TYPE PolicyKind ADD VALUE 'tieredsurplus';
+
+-- Add tiered surplus fee columns
+ALTER TABLE fee_policies
+    ADD COLUMN tiered_surplus_thresholds numeric(78,0)[],
+    ADD COLUMN tiered_surplus_factors double precision[],
+    ADD COLUMN tiered_surplus_max_volume_factor double precision;